@@ -4,12 +4,27 @@ complete -c exa -s '?' -l 'help'    -d "Show list of command-line options"
 
 # Display options
 complete -c exa -s '1' -l 'oneline'      -d "Display one entry per line"
+complete -c exa -s '0' -l 'zero'         -d "Display entries separated by NUL bytes"
+complete -c exa        -l 'csv'          -d "Display the long view's columns as comma-separated values"
+complete -c exa        -l 'tsv'          -d "Display the long view's columns as tab-separated values"
+complete -c exa        -l 'stat'         -d "Display every detail of each file as a block of labelled lines"
+complete -c exa        -l 'paging'       -d "When to send output through a pager" -x -a "
+    auto\t'When the output is taller than the terminal'
+    never\t'Never use a pager'
+"
 complete -c exa -s 'l' -l 'long'         -d "Display extended file metadata as a table"
 complete -c exa -s 'G' -l 'grid'         -d "Display entries in a grid"
 complete -c exa -s 'x' -l 'across'       -d "Sort the grid across, rather than downwards"
+complete -c exa -s 'w' -l 'width'        -d "Set the width of the terminal" -x
+complete -c exa -l 'min-columns' -d "Lay the grid out in at least this many columns" -x
+complete -c exa -l 'max-columns' -d "Lay the grid out in at most this many columns" -x
 complete -c exa -s 'R' -l 'recurse'      -d "Recurse into directories"
 complete -c exa -s 'T' -l 'tree'         -d "Recurse into directories as a tree"
-complete -c exa -s 'F' -l 'classify'     -d "Display type indicator by file names"
+complete -c exa -s 'F' -l 'classify'     -d "When to display type indicators by file names" -f -a "
+    always\t'Always display type indicators'
+    auto\t'Display type indicators if standard output is a terminal'
+    never\t'Never display type indicators'
+"
 complete -c exa        -l 'color' \
                        -l 'colour'       -d "When to use terminal colours" -x -a "
     always\t'Always use colour'
@@ -17,23 +32,77 @@ complete -c exa        -l 'color' \
     never\t'Never use colour'
 "
 complete -c exa        -l 'color-scale' \
-                       -l 'colour-scale' -d "Highlight levels of file sizes distinctly"
-complete -c exa        -l 'icons'        -d "Display icons"
+                       -l 'colour-scale' -d "Highlight levels of file sizes or ages distinctly" -f -a "
+    size\t'Highlight levels of file sizes'
+    age\t'Fade timestamps as they get older'
+    all\t'Highlight both sizes and ages'
+"
+complete -c exa        -l 'age-colors' \
+                       -l 'age-colours'  -d "Colour timestamps by how long ago they were"
+complete -c exa        -l 'theme'        -d "Which built-in colours to use" -x -a "
+    dark\t'Colours for dark backgrounds'
+    light\t'Colours for light backgrounds'
+"
+complete -c exa        -l 'theme-file'   -d "Load interface colours from a file" -r -F
+complete -c exa        -l 'dim-hidden'   -d "Dim the names of hidden files"
+complete -c exa        -l 'icons'        -d "When to display icons" -x -a "
+    always\t'Always display icons'
+    auto\t'Display icons if standard output is a terminal'
+    never\t'Never display icons'
+    minimal\t'Always display ASCII icons'
+"
 complete -c exa        -l 'no-icons'     -d "Don't display icons"
+complete -c exa        -l 'hyperlink'    -d "Display entries as hyperlinks"
+complete -c exa        -l 'quoting-style' -d "How to quote file names" -x -a "
+    literal\t'Display file names as they are'
+    shell\t'Quote file names with special characters in them'
+    shell-always\t'Quote every file name'
+    c\t'Quote every file name like a C string'
+    escape\t'Escape spaces and backslashes'
+"
+complete -c exa        -l 'absolute'     -d "Display absolute paths instead of names" -f -a "
+    on\t'Display absolute paths'
+    off\t'Display file names'
+    follow\t'Display absolute paths with symlinks resolved'
+"
+complete -c exa        -l 'no-symlinks'  -d "Don't display the targets of symlinks"
+complete -c exa        -l 'show-symlink-targets' -d "Display the targets of symlinks in every view"
 
 # Filtering and sorting options
 complete -c exa -l 'group-directories-first' -d "Sort directories before other files"
+complete -c exa -l 'group-directories-last' -d "Sort directories after other files"
 complete -c exa -l 'git-ignore'           -d "Ignore files mentioned in '.gitignore'"
+complete -c exa -l 'ext'                  -d "Only list files with these extensions" -r
+complete -c exa -l 'ignore-glob-files'    -d "Ignore files mentioned in '.ignore' and '.fdignore'"
 complete -c exa -s 'a' -l 'all'       -d "Show hidden and 'dot' files"
 complete -c exa -s 'd' -l 'list-dirs' -d "List directories like regular files"
 complete -c exa -s 'L' -l 'level'     -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c exa        -l 'depth'     -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c exa -l 'collapse-exts'    -d "Fold files sharing an extension in a tree" -x
+complete -c exa -l 'collapse-single-child' -d "List chains of directories with one directory in each on one line"
+complete -c exa -l 'one-file-system'  -d "Don't recurse into other file systems"
+complete -c exa -l 'follow-symlinks'  -d "Recurse into symlinks to directories"
+complete -c exa -l 'dereference-command-line' -d "Follow symlinks to directories given as arguments"
+complete -c exa -l 'peek-archives'    -d "List the contents of zip and tar files"
+complete -c exa -l 'paths-from'       -d "List the paths in a file" -r -F
+complete -c exa -l 'prune'            -d "Hide directories with nothing in them to list"
+complete -c exa -l 'flat'             -d "Recurse into directories, listing every file by its path at once"
+complete -c exa -l 'files-first'      -d "When recursing, list each level before descending any further"
+complete -c exa -l 'reverse-tree'     -d "List the entries at each level of a tree the other way round"
+complete -c exa -l 'tree-style'       -d "Which characters to draw the tree with" -x -a "
+    unicode\t'Draw the tree with box-drawing characters'
+    ascii\t'Draw the tree with ASCII characters'
+"
 complete -c exa -s 'r' -l 'reverse'   -d "Reverse the sort order"
-complete -c exa -s 's' -l 'sort'      -d "Which field to sort by" -x -a "
+complete -c exa        -l 'then'      -d "Which fields to sort by when files tie" -x
+complete -c exa -s 's' -l 'sort'      -d "Which fields to sort by" -x -a "
     accessed\t'Sort by file accessed time'
     age\t'Sort by file modified time (newest first)'
     changed\t'Sort by changed time'
     created\t'Sort by file modified time'
     date\t'Sort by file modified time'
+    dir\t'Sort directories before other files'
+    dirsize\t'Sort directories by their number of entries'
     ext\t'Sort by file extension'
     Ext\t'Sort by file extension (uppercase first)'
     extension\t'Sort by file extension'
@@ -41,28 +110,53 @@ complete -c exa -s 's' -l 'sort'      -d "Which field to sort by" -x -a "
     filename\t'Sort by filename'
     Filename\t'Sort by filename (uppercase first)'
     inode\t'Sort by file inode'
+    kind\t'Sort by kind of file, such as image or document'
+    length\t'Sort by the length of the file name'
     modified\t'Sort by file modified time'
     name\t'Sort by filename'
     Name\t'Sort by filename (uppercase first)'
+    name-len\t'Sort by the length of the file name'
     newest\t'Sort by file modified time (newest first)'
     none\t'Do not sort files at all'
     oldest\t'Sort by file modified time'
+    random\t'Shuffle files into a random order'
     size\t'Sort by file size'
     time\t'Sort by file modified time'
-    type\t'Sort by file type'
+    type\t'Sort by kind of file, such as image or document'
 "
 
 complete -c exa -s 'I' -l 'ignore-glob' -d "Ignore files that match these glob patterns" -r
 complete -c exa -s 'D' -l 'only-dirs'   -d "List only directories"
+complete -c exa -s 'f' -l 'only-files'  -d "List only files"
+complete -c exa -l 'newer-than' -d "Only list files modified after this time" -x
+complete -c exa -l 'older-than' -d "Only list files modified before this time" -x
+complete -c exa -l 'bigger-than' -d "Only list files bigger than this size" -x
+complete -c exa -l 'smaller-than' -d "Only list files smaller than this size" -x
+complete -c exa -l 'max-entries' -d "List at most this many entries from each directory" -x
 
 # Long view options
 complete -c exa -s 'b' -l 'binary'   -d "List file sizes with binary prefixes"
 complete -c exa -s 'B' -l 'bytes'    -d "List file sizes in bytes, without any prefixes"
+complete -c exa        -l 'si'       -d "List file sizes with SI units, such as kB"
+complete -c exa        -l 'iec'      -d "List file sizes with IEC units, such as KiB"
+complete -c exa        -l 'comma'    -d "Put commas between thousands in byte sizes"
+complete -c exa -l 'size-percent' -d "List file sizes as a percentage of the total" -f -a "
+    dir\t'Use the total of each directory'
+    global\t'Use the total of the whole listing'
+"
 complete -c exa -s 'g' -l 'group'    -d "List each file's group"
 complete -c exa -s 'h' -l 'header'   -d "Add a header row to each column"
+complete -c exa -l 'repeat-header' -d "Repeat the header row every N rows" -x
+complete -c exa -l 'no-headers-between-dirs' -d "Only show the header row above the first directory"
+complete -c exa -l 'summary'       -d "End with the number of files and directories and their total size"
 complete -c exa -s 'H' -l 'links'    -d "List each file's number of hard links"
+complete -c exa        -l 'hard-link-groups' -d "Tag the files that are hard links to each other"
 complete -c exa -s 'i' -l 'inode'    -d "List each file's inode number"
 complete -c exa -s 'S' -l 'blocks'   -d "List each file's number of filesystem blocks"
+complete -c exa -l 'block-size'      -d "Count blocks in units of this many bytes" -x
+complete -c exa -l 'blocksize-human' -d "Show the space blocks take up on disk as a size"
+complete -c exa -l 'total-size'      -d "List the recursive total size of directories"
+complete -c exa -l 'dir-counts'      -d "List the number of entries in directories as their size"
 complete -c exa -s 't' -l 'time'     -d "Which timestamp field to list" -x -a "
     modified\t'Display modified time'
     changed\t'Display changed time'
@@ -79,13 +173,28 @@ complete -c exa        -l 'time-style'    -d "How to format timestamps" -x -a "
     iso\t'Display brief ISO timestamps'
     long-iso\t'Display longer ISO timestaps, up to the minute'
     full-iso\t'Display full ISO timestamps, up to the nanosecond'
+    precise\t'Display full ISO timestamps with their offset, up to the nanosecond'
+    relative\t'Display how long ago each timestamp was'
+    relative:30d\t'Display how long ago each timestamp was, or the date if over 30 days'
 "
+complete -c exa        -l 'dereference'   -d "List the details of the files symlinks point to"
 complete -c exa        -l 'no-permissions' -d "Suppress the permissions field"
 complete -c exa        -l 'octal-permissions' -d "List each file's permission in octal format"
+complete -c exa        -l 'mounts'       -d "List the type of file system each file is on"
+complete -c exa        -l 'mime'         -d "List each file's MIME type"
 complete -c exa        -l 'no-filesize'    -d "Suppress the filesize field"
 complete -c exa        -l 'no-user'        -d "Suppress the user field"
 complete -c exa        -l 'no-time'        -d "Suppress the time field"
+complete -c exa        -l 'columns'        -d "List only these columns, in this order" -x -a "perms octal links inode blocks user group size git date name"
+complete -c exa        -l 'only'           -d "List only these columns" -x -a "perms octal links inode blocks user group size git date name"
 
 # Optional extras
-complete -c exa -l 'git' -d "List each file's Git status, if tracked"
+complete -c exa -l 'git' -d "List each file's Git status, if tracked" -f -a "
+    short\t'Show the index and worktree statuses in one column'
+    long\t'Show the index and worktree statuses in separate columns'
+"
+complete -c exa -l 'git-repos' -d "List the branch and status of each Git repository"
+complete -c exa -l 'git-highlight' -d "Colour file names by their Git status"
+complete -c exa -l 'no-git-repos-recurse' -d "Skip Git statuses in very large repositories"
 complete -c exa -s '@' -l 'extended' -d "List each file's extended attributes and sizes"
+complete -c exa -l 'xattr-names' -d "List the names of each file's extended attributes in a column"
@@ -0,0 +1,1024 @@
+//! Looking inside archives, so they can be listed as though they were
+//! directories.
+//!
+//! Only an archive’s index gets read — the central directory at the end of
+//! a zip file, or the header in front of each file in a tar file — and
+//! nothing ever gets decompressed, which is why compressed tar files can’t
+//! be looked inside. Nothing gets written anywhere, either: the entries are
+//! kept in memory, and the files listed from inside an archive take their
+//! sizes, permissions, and modification times from them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::*;
+
+use crate::fs::File;
+use crate::info::filetype::{FileExtensions, FileType};
+
+
+/// The longest that anything read into memory from an archive can be, such
+/// as the tar headers that describe the next entry, a symlink’s target, or
+/// a zip file’s central directory, so an archive can’t make exa allocate
+/// as much as it claims to need. This is enough for a central directory
+/// with tens of thousands of entries.
+const MAX_METADATA_LEN: u64 = 8 * 1024 * 1024;
+
+/// The biggest that an entry can claim to be. Nothing that big gets read,
+/// but sizes past this are a sign of a corrupt archive.
+const MAX_ENTRY_SIZE: u64 = 1 << 40;
+
+/// The most symlinks inside an archive that get followed one after the
+/// other, so links that point to each other don’t go round forever.
+const MAX_LINK_HOPS: usize = 40;
+
+/// The kinds of archive that exa knows how to read the index of.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Format {
+
+    /// A zip file, which has a central directory at its end.
+    Zip,
+
+    /// An uncompressed tar file, which has a header before each entry.
+    Tar,
+}
+
+impl Format {
+
+    /// Works out which format the given file is in, if it’s an archive
+    /// that can be looked inside. Archives that exa can’t read, such as
+    /// compressed tar files, have no format.
+    pub fn of(file: &File<'_>) -> Option<Self> {
+        if FileExtensions.file_type(file) != Some(FileType::Compressed) {
+            return None;
+        }
+
+        match file.ext.as_deref() {
+            Some("zip")  => Some(Self::Zip),
+            Some("tar")  => Some(Self::Tar),
+            _            => None,
+        }
+    }
+
+    /// Reads the list of entries out of the given archive.
+    pub fn entries<R: Read + Seek>(self, reader: R) -> io::Result<Vec<Entry>> {
+        let entries = match self {
+            Self::Zip  => zip_entries(reader)?,
+            Self::Tar  => tar_entries(reader)?,
+        };
+
+        if entries.iter().any(|e| e.size > MAX_ENTRY_SIZE) {
+            return Err(corrupt("archive entry is too big"));
+        }
+
+        Ok(entries)
+    }
+}
+
+
+/// One file, directory, or link inside an archive.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry {
+
+    /// The entry’s path, relative to the root of the archive.
+    pub path: PathBuf,
+
+    /// What kind of entry this is.
+    pub kind: EntryKind,
+
+    /// The size of the entry’s contents, once they’re uncompressed.
+    pub size: u64,
+
+    /// When the entry was last modified, in seconds since the Unix epoch,
+    /// if the archive recorded it.
+    pub modified: Option<i64>,
+
+    /// The entry’s Unix permission bits, if the archive recorded them.
+    pub mode: Option<u32>,
+}
+
+/// The kinds of entry that get listed. Anything that isn’t a directory or
+/// a symlink, such as a device file, gets listed as a regular file.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink(PathBuf),
+}
+
+
+/// Every entry in an archive, arranged so that the contents of each of its
+/// directories can be found. The root of the archive is the first node.
+struct Index {
+
+    /// The path of the archive, as it was given on the command-line.
+    path: PathBuf,
+
+    /// The metadata of the archive file itself, which the files inside it
+    /// use for anything the archive doesn’t record, such as their owner.
+    metadata: fs::Metadata,
+
+    /// A number that no other archive has, which goes into the identities
+    /// of its directories.
+    serial: u64,
+
+    /// The entries, each with its position among them of its parent and of
+    /// its children.
+    nodes: Vec<Node>,
+
+    /// The position of each entry, by its path inside the archive.
+    positions: HashMap<PathBuf, usize>,
+}
+
+struct Node {
+    entry: Entry,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+impl Index {
+
+    /// Arranges the given entries. Entries with paths that would lead out
+    /// of the archive are skipped, any directories that entries are in
+    /// that don’t have entries of their own get added, and an entry with
+    /// the same path as an earlier one replaces it, as it would if the
+    /// archive were extracted.
+    fn new(path: PathBuf, metadata: fs::Metadata, entries: Vec<Entry>) -> Self {
+        static SERIAL: AtomicU64 = AtomicU64::new(0);
+
+        let root = Entry { path: PathBuf::new(), kind: EntryKind::Directory, size: 0, modified: None, mode: None };
+        let mut index = Self {
+            path,
+            metadata,
+            serial: SERIAL.fetch_add(1, Ordering::Relaxed),
+            nodes: vec![ Node { entry: root, parent: None, children: Vec::new() } ],
+            positions: HashMap::new(),
+        };
+        index.positions.insert(PathBuf::new(), 0);
+
+        for entry in entries {
+            match safe_path(&entry.path) {
+                Some(path)  => index.insert(Entry { path, .. entry }),
+                None        => warn!("Skipping archive entry with unsafe path {}", entry.path.display()),
+            }
+        }
+
+        index
+    }
+
+    fn insert(&mut self, entry: Entry) {
+        if let Some(&position) = self.positions.get(&entry.path) {
+            self.nodes[position].entry = entry;
+            return;
+        }
+
+        let parent = self.directory(entry.path.parent().unwrap_or_else(|| Path::new("")));
+        let position = self.nodes.len();
+        self.positions.insert(entry.path.clone(), position);
+        self.nodes[parent].children.push(position);
+        self.nodes.push(Node { entry, parent: Some(parent), children: Vec::new() });
+    }
+
+    /// The position of the directory with the given path, adding it if
+    /// there isn’t one yet.
+    fn directory(&mut self, path: &Path) -> usize {
+        if let Some(&position) = self.positions.get(path) {
+            self.nodes[position].entry.kind = EntryKind::Directory;
+            return position;
+        }
+
+        self.insert(Entry { path: path.to_path_buf(), kind: EntryKind::Directory, size: 0, modified: None, mode: None });
+        self.positions[path]
+    }
+}
+
+
+/// A place inside an archive that’s been looked inside: either its root,
+/// which gets listed in the archive’s place, or one of its entries. These
+/// are cheap to clone, as the entries themselves are shared.
+#[derive(Clone)]
+pub struct Archived {
+    index: Arc<Index>,
+    position: usize,
+}
+
+impl Archived {
+
+    /// Reads the index of the archive at the given path, returning its
+    /// root.
+    pub fn peek(archive: &Path, format: Format) -> io::Result<Self> {
+        let file = fs::File::open(archive)?;
+        let metadata = file.metadata()?;
+        let entries = format.entries(io::BufReader::new(file))?;
+        Ok(Self::from_entries(archive, metadata, entries))
+    }
+
+    /// Arranges entries that have already been read out of the archive at
+    /// the given path, which has the given metadata, returning its root.
+    pub fn from_entries(archive: &Path, metadata: fs::Metadata, entries: Vec<Entry>) -> Self {
+        let index = Index::new(archive.to_path_buf(), metadata, entries);
+        Self { index: Arc::new(index), position: 0 }
+    }
+
+    fn node(&self) -> &Node {
+        &self.index.nodes[self.position]
+    }
+
+    fn at(&self, position: usize) -> Self {
+        Self { index: Arc::clone(&self.index), position }
+    }
+
+    /// The path to list this with: the archive’s own path, followed by the
+    /// entry’s path inside of it.
+    pub fn path(&self) -> PathBuf {
+        let path = &self.node().entry.path;
+        if path.as_os_str().is_empty() { self.index.path.clone() }
+                                  else { self.index.path.join(path) }
+    }
+
+    /// The metadata of the archive this is inside of.
+    pub fn archive_metadata(&self) -> &fs::Metadata {
+        &self.index.metadata
+    }
+
+    pub fn kind(&self) -> &EntryKind {
+        &self.node().entry.kind
+    }
+
+    pub fn size(&self) -> u64 {
+        self.node().entry.size
+    }
+
+    /// When this was last modified, if the archive recorded it.
+    pub fn modified(&self) -> Option<SystemTime> {
+        let seconds = self.node().entry.modified?;
+        let duration = Duration::from_secs(seconds.unsigned_abs());
+        if seconds < 0 { UNIX_EPOCH.checked_sub(duration) }
+                  else { UNIX_EPOCH.checked_add(duration) }
+    }
+
+    /// The permission bits of this entry, if the archive recorded them.
+    pub fn mode(&self) -> Option<u32> {
+        self.node().entry.mode.map(|mode| mode & 0o7777)
+    }
+
+    /// The entries directly inside this directory, in the order they were
+    /// in the archive.
+    pub fn children(&self) -> impl Iterator<Item = Self> + '_ {
+        self.node().children.iter().map(move |&position| self.at(position))
+    }
+
+    /// The entry directly inside this directory with the given name.
+    pub fn child(&self, name: &str) -> Option<Self> {
+        let path = self.node().entry.path.join(name);
+        self.index.positions.get(&path).map(|&position| self.at(position))
+    }
+
+    /// The directory this is inside of, or nothing if this is the root.
+    pub fn parent(&self) -> Option<Self> {
+        self.node().parent.map(|position| self.at(position))
+    }
+
+    /// If this is a symlink, follows it, and any links it leads to, to the
+    /// entry at the end. Links with targets that would lead out of the
+    /// archive, or that don’t lead anywhere, return nothing: whatever they
+    /// point to on the filesystem has nothing to do with the archive.
+    pub fn target(&self) -> Option<Self> {
+        let mut current = self.clone();
+        let mut hops = 0;
+
+        while let EntryKind::Symlink(target) = current.kind() {
+            if hops == MAX_LINK_HOPS {
+                return None;
+            }
+
+            hops += 1;
+            let mut path = current.node().entry.path.parent().map(Path::to_path_buf).unwrap_or_default();
+            for component in target.components() {
+                match component {
+                    Component::Normal(c)                         => path.push(c),
+                    Component::CurDir                            => {},
+                    Component::ParentDir                         => if ! path.pop() { return None },
+                    Component::RootDir | Component::Prefix(_)    => return None,
+                }
+            }
+
+            current = self.at(*self.index.positions.get(&path)?);
+        }
+
+        Some(current)
+    }
+
+    /// The total size of every file underneath this directory.
+    pub fn total_size(&self, dotfiles: bool) -> u64 {
+        self.children()
+            .filter(|child| dotfiles || ! File::filename(&child.node().entry.path).starts_with('.'))
+            .map(|child| match child.kind() {
+                EntryKind::Directory  => child.total_size(dotfiles),
+                EntryKind::File       => child.size(),
+                EntryKind::Symlink(_) => 0,
+            })
+            .sum()
+    }
+
+    /// A device and inode number pair for this entry, so that directories
+    /// inside archives can be told apart like the ones on the filesystem
+    /// are. No real device has the highest device number, so these can’t
+    /// be mistaken for real directories.
+    pub fn identity(&self) -> (u64, u64) {
+        (u64::MAX, (self.index.serial << 32) | self.position as u64)
+    }
+}
+
+/// Strips the leading `./` and `/` from an entry’s path, returning nothing
+/// if it contains a `..` that could lead out of the archive.
+fn safe_path(path: &Path) -> Option<PathBuf> {
+    let mut safe = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(c)                         => safe.push(c),
+            Component::CurDir | Component::RootDir       => {},
+            Component::ParentDir | Component::Prefix(_)  => return None,
+        }
+    }
+
+    if safe.as_os_str().is_empty() { None } else { Some(safe) }
+}
+
+fn corrupt(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+
+// ---- Zip files ----
+
+/// Reads the entries from a zip file’s central directory. Its position is
+/// found using the “end of central directory” record at the end of the
+/// file, which can be followed by a comment up to 64 KiB long.
+fn zip_entries<R: Read + Seek>(mut reader: R) -> io::Result<Vec<Entry>> {
+    const EOCD_LEN: usize = 22;
+
+    let len = reader.seek(SeekFrom::End(0))?;
+    let tail_len = len.min((EOCD_LEN + 0xFFFF) as u64);
+    reader.seek(SeekFrom::Start(len - tail_len))?;
+    let mut tail = vec![0; tail_len as usize];
+    reader.read_exact(&mut tail)?;
+
+    if tail.len() < EOCD_LEN {
+        return Err(corrupt("not a zip file"));
+    }
+
+    let eocd = (0 ..= tail.len() - EOCD_LEN).rev()
+                   .find(|&i| tail[i ..].starts_with(b"PK\x05\x06"))
+                   .ok_or_else(|| corrupt("not a zip file"))?;
+
+    let mut count = u64::from(le16(&tail, eocd + 10));
+    let mut cd_len = u64::from(le32(&tail, eocd + 12));
+    let mut cd_start = u64::from(le32(&tail, eocd + 16));
+
+    // Zip64 files keep the real values in another record, which is found
+    // through a locator just before the usual one.
+    if (count == 0xFFFF || cd_len == 0xFFFF_FFFF || cd_start == 0xFFFF_FFFF)
+        && eocd >= 20 && tail[eocd - 20 ..].starts_with(b"PK\x06\x07")
+    {
+        let mut record = [0; 56];
+        reader.seek(SeekFrom::Start(le64(&tail, eocd - 20 + 8)))?;
+        reader.read_exact(&mut record)?;
+
+        if ! record.starts_with(b"PK\x06\x06") {
+            return Err(corrupt("bad zip64 central directory"));
+        }
+
+        count = le64(&record, 32);
+        cd_len = le64(&record, 40);
+        cd_start = le64(&record, 48);
+    }
+
+    if cd_start.checked_add(cd_len).map_or(true, |end| end > len) {
+        return Err(corrupt("zip central directory is out of bounds"));
+    }
+
+    if cd_len > MAX_METADATA_LEN {
+        return Err(corrupt("zip central directory is too long"));
+    }
+
+    let mut cd = vec![0; cd_len as usize];
+    reader.seek(SeekFrom::Start(cd_start))?;
+    reader.read_exact(&mut cd)?;
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    for _ in 0 .. count {
+        let (mut entry, len) = zip_entry(&cd[pos ..])?;
+
+        // Symlinks have their targets as their contents, which can only
+        // be read without decompressing anything if they were stored.
+        let is_link = entry.mode.map_or(false, |m| m & 0o170_000 == 0o120_000);
+        if is_link && le16(&cd, pos + 10) == 0 {
+            let target = zip_contents(&mut reader, le32(&cd, pos + 42), le32(&cd, pos + 20))?;
+            entry.kind = EntryKind::Symlink(PathBuf::from(String::from_utf8_lossy(&target).into_owned()));
+        }
+
+        entries.push(entry);
+        pos += len;
+    }
+
+    Ok(entries)
+}
+
+/// Reads the contents of an uncompressed entry, which come after its local
+/// header at the given offset.
+fn zip_contents<R: Read + Seek>(reader: &mut R, offset: u32, len: u32) -> io::Result<Vec<u8>> {
+    if u64::from(len) > MAX_METADATA_LEN {
+        return Err(corrupt("zip entry contents are too long"));
+    }
+
+    let mut header = [0; 30];
+    reader.seek(SeekFrom::Start(u64::from(offset)))?;
+    reader.read_exact(&mut header)?;
+
+    if ! header.starts_with(b"PK\x03\x04") {
+        return Err(corrupt("bad zip local header"));
+    }
+
+    let skip = i64::from(le16(&header, 26)) + i64::from(le16(&header, 28));
+    reader.seek(SeekFrom::Current(skip))?;
+
+    let mut contents = Vec::new();
+    reader.take(u64::from(len)).read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads one entry from the start of the central directory, returning it
+/// along with how many bytes it took up.
+fn zip_entry(bytes: &[u8]) -> io::Result<(Entry, usize)> {
+    const HEADER_LEN: usize = 46;
+
+    if bytes.len() < HEADER_LEN || ! bytes.starts_with(b"PK\x01\x02") {
+        return Err(corrupt("bad zip central directory entry"));
+    }
+
+    let made_by = le16(bytes, 4);
+    let mut size = u64::from(le32(bytes, 24));
+    let name_len = usize::from(le16(bytes, 28));
+    let extra_len = usize::from(le16(bytes, 30));
+    let comment_len = usize::from(le16(bytes, 32));
+    let external_attrs = le32(bytes, 38);
+
+    let total_len = HEADER_LEN + name_len + extra_len + comment_len;
+    if bytes.len() < total_len {
+        return Err(corrupt("bad zip central directory entry"));
+    }
+
+    let name = String::from_utf8_lossy(&bytes[HEADER_LEN .. HEADER_LEN + name_len]).into_owned();
+    let mut modified = Some(dos_time(le16(bytes, 14), le16(bytes, 12)));
+
+    let mut extra = &bytes[HEADER_LEN + name_len .. HEADER_LEN + name_len + extra_len];
+    while extra.len() >= 4 {
+        let id = le16(extra, 0);
+        let field_len = usize::from(le16(extra, 2));
+        let field = &extra[4 .. (4 + field_len).min(extra.len())];
+
+        match id {
+            // Zip64 sizes, present when the normal one doesn’t fit.
+            0x0001 if size == 0xFFFF_FFFF && field.len() >= 8 => {
+                size = le64(field, 0);
+            }
+
+            // The “extended timestamp” field, which is in UTC, unlike
+            // the DOS time that’s always present.
+            0x5455 if field.len() >= 5 && field[0] & 1 != 0 => {
+                modified = Some(i64::from(le32(field, 1) as i32));
+            }
+
+            _ => {}
+        }
+
+        extra = &extra[(4 + field_len).min(extra.len()) ..];
+    }
+
+    // Permissions are only kept by zip files made on Unix.
+    let mode = if made_by >> 8 == 3 { Some(external_attrs >> 16) } else { None };
+    let is_dir = name.ends_with('/') || mode.map_or(false, |m| m & 0o170_000 == 0o040_000);
+
+    let entry = Entry {
+        path: PathBuf::from(name),
+        kind: if is_dir { EntryKind::Directory } else { EntryKind::File },
+        size: if is_dir { 0 } else { size },
+        modified,
+        mode,
+    };
+
+    Ok((entry, total_len))
+}
+
+/// Converts a DOS date and time to seconds since the epoch. These don’t
+/// have a time zone, so they get treated as UTC.
+fn dos_time(date: u16, time: u16) -> i64 {
+    let year = 1980 + i64::from(date >> 9);
+    let month = i64::from((date >> 5) & 0xF);
+    let day = i64::from(date & 0x1F);
+
+    let hours = i64::from(time >> 11);
+    let minutes = i64::from((time >> 5) & 0x3F);
+    let seconds = i64::from(time & 0x1F) * 2;
+
+    days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + seconds
+}
+
+/// The number of days between the epoch and the given date in the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn le16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([ bytes[at], bytes[at + 1] ])
+}
+
+fn le32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([ bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3] ])
+}
+
+fn le64(bytes: &[u8], at: usize) -> u64 {
+    u64::from(le32(bytes, at)) | u64::from(le32(bytes, at + 4)) << 32
+}
+
+
+// ---- Tar files ----
+
+/// Reads the entries from the headers in a tar file, skipping over the
+/// contents in between them. As well as plain ustar headers, this handles
+/// the long names that GNU tar writes, and the ones in pax headers.
+fn tar_entries<R: Read + Seek>(mut reader: R) -> io::Result<Vec<Entry>> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut entries = Vec::new();
+    let mut long_name = None;
+    let mut long_link = None;
+    let mut pax = Pax::default();
+
+    loop {
+        let mut header = [0; 512];
+        match reader.read_exact(&mut header) {
+            Ok(())                                             => {},
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                if entries.is_empty() { return Err(corrupt("not a tar file")) }
+                break;
+            }
+            Err(e)                                             => return Err(e),
+        }
+
+        // The archive ends with blocks of zeroes.
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        if ! tar_checksum_matches(&header) {
+            return Err(corrupt("bad tar header checksum"));
+        }
+
+        let size = tar_number(&header[124 .. 136]).ok_or_else(|| corrupt("bad tar entry size"))?;
+        let padded_size = size.checked_add(511).ok_or_else(|| corrupt("bad tar entry size"))? / 512 * 512;
+        let type_flag = header[156];
+
+        if reader.stream_position()?.checked_add(padded_size).map_or(true, |end| end > len) {
+            return Err(corrupt("tar entry is out of bounds"));
+        }
+
+        // These headers have contents that describe the next entry, rather
+        // than being entries themselves.
+        if matches!(type_flag, b'L' | b'K' | b'x') {
+            if size > MAX_METADATA_LEN {
+                return Err(corrupt("tar metadata header is too long"));
+            }
+
+            let mut data = vec![0; size as usize];
+            reader.read_exact(&mut data)?;
+            reader.seek(SeekFrom::Current((padded_size - size) as i64))?;
+
+            match type_flag {
+                b'L'  => long_name = Some(c_string(&data)),
+                b'K'  => long_link = Some(c_string(&data)),
+                _     => pax = Pax::parse(&data),
+            }
+            continue;
+        }
+
+        reader.seek(SeekFrom::Current(padded_size as i64))?;
+
+        // Global pax headers, and anything else without a path of its
+        // own, don’t get listed.
+        if matches!(type_flag, b'g' | b'V') {
+            continue;
+        }
+
+        let mut name = c_string(&header[0 .. 100]);
+        if &header[257 .. 262] == b"ustar" {
+            let prefix = c_string(&header[345 .. 500]);
+            if ! prefix.is_empty() {
+                name = prefix + "/" + &name;
+            }
+        }
+
+        let name = pax.path.take().or_else(|| long_name.take()).unwrap_or(name);
+        let link = pax.linkpath.take().or_else(|| long_link.take()).unwrap_or_else(|| c_string(&header[157 .. 257]));
+
+        let kind = match type_flag {
+            b'5'                     => EntryKind::Directory,
+            b'2'                     => EntryKind::Symlink(PathBuf::from(link)),
+            _ if name.ends_with('/') => EntryKind::Directory,
+            _                        => EntryKind::File,
+        };
+
+        entries.push(Entry {
+            path: PathBuf::from(name),
+            size: if kind == EntryKind::File { pax.size.take().unwrap_or(size) } else { 0 },
+            kind,
+            modified: pax.mtime.take().or_else(|| tar_number(&header[136 .. 148]).map(|t| t as i64)),
+            mode: tar_number(&header[100 .. 108]).map(|m| m as u32),
+        });
+
+        pax = Pax::default();
+    }
+
+    Ok(entries)
+}
+
+/// The checksum is the sum of every byte in the header, with the checksum
+/// field itself counted as spaces.
+fn tar_checksum_matches(header: &[u8; 512]) -> bool {
+    let sum: u64 = header.iter().enumerate()
+                         .map(|(i, &b)| if (148 .. 156).contains(&i) { u64::from(b' ') } else { u64::from(b) })
+                         .sum();
+
+    tar_number(&header[148 .. 156]) == Some(sum)
+}
+
+/// Reads a number from a header field. These are usually written in octal
+/// digits, but large numbers are written as big-endian binary with the
+/// top bit of the first byte set.
+fn tar_number(field: &[u8]) -> Option<u64> {
+    if field.first().map_or(false, |&b| b & 0x80 != 0) {
+        return Some(field[1 ..].iter().fold(u64::from(field[0] & 0x7F), |n, &b| n << 8 | u64::from(b)));
+    }
+
+    let digits = std::str::from_utf8(field).ok()?;
+    let digits = digits.trim_matches(|c: char| c == '\0' || c == ' ');
+    if digits.is_empty() {
+        return Some(0);
+    }
+
+    u64::from_str_radix(digits, 8).ok()
+}
+
+/// Reads a string that ends at the first null byte, if there is one.
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[.. end]).into_owned()
+}
+
+/// The values from a pax extended header that override the ones in the
+/// next entry’s header.
+#[derive(Default, Debug)]
+struct Pax {
+    path: Option<String>,
+    linkpath: Option<String>,
+    size: Option<u64>,
+    mtime: Option<i64>,
+}
+
+impl Pax {
+
+    /// Parses the records in a pax header, which are each in the form
+    /// “length key=value\n”, where the length includes the whole record.
+    fn parse(data: &[u8]) -> Self {
+        let mut pax = Self::default();
+        let mut rest = data;
+
+        while let Some(space) = rest.iter().position(|&b| b == b' ') {
+            let len = match std::str::from_utf8(&rest[.. space]).ok().and_then(|l| l.parse::<usize>().ok()) {
+                Some(len) if len > space && len <= rest.len()  => len,
+                _                                               => break,
+            };
+
+            let record = String::from_utf8_lossy(&rest[space + 1 .. len]);
+            if let Some((key, value)) = record.trim_end_matches('\n').split_once('=') {
+                match key {
+                    "path"      => pax.path = Some(value.into()),
+                    "linkpath"  => pax.linkpath = Some(value.into()),
+                    "size"      => pax.size = value.parse().ok(),
+                    "mtime"     => pax.mtime = value.split('.').next().and_then(|s| s.parse().ok()),
+                    _           => {},
+                }
+            }
+
+            rest = &rest[len ..];
+        }
+
+        pax
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Builds a tar header for an entry, with a correct checksum.
+    fn tar_header(name: &str, type_flag: u8, size: u64, mtime: u64) -> Vec<u8> {
+        let mut header = vec![0; 512];
+        header[.. name.len()].copy_from_slice(name.as_bytes());
+        header[100 .. 107].copy_from_slice(b"0000644");
+        octal(&mut header[124 .. 135], size);
+        octal(&mut header[136 .. 147], mtime);
+        header[156] = type_flag;
+        header[257 .. 263].copy_from_slice(b"ustar\0");
+
+        header[148 .. 156].copy_from_slice(b"        ");
+        let sum: u64 = header.iter().map(|&b| u64::from(b)).sum();
+        octal(&mut header[148 .. 154], sum);
+        header[154] = 0;
+        header
+    }
+
+    /// Fills a header field with the octal digits of a number.
+    fn octal(field: &mut [u8], mut number: u64) {
+        for digit in field.iter_mut().rev() {
+            *digit = b'0' + (number % 8) as u8;
+            number /= 8;
+        }
+    }
+
+    fn tar_file(headers: &[(&str, u8, u64)]) -> Vec<u8> {
+        let mut tar = Vec::new();
+        for &(name, type_flag, size) in headers {
+            tar.extend(tar_header(name, type_flag, size, 1_600_000_000));
+            tar.extend(vec![b'x'; size as usize]);
+            tar.extend(vec![0; (512 - size as usize % 512) % 512]);
+        }
+        tar.extend(vec![0; 1024]);
+        tar
+    }
+
+    /// Builds a zip file with only a central directory, as that’s all that
+    /// gets read.
+    fn zip_file(names: &[(&str, u32)]) -> Vec<u8> {
+        let mut cd = Vec::new();
+        for &(name, size) in names {
+            cd.extend(b"PK\x01\x02");
+            cd.extend(&(3_u16 << 8).to_le_bytes());     // made on Unix
+            cd.extend(&[0; 6]);
+            cd.extend(&0x6000_u16.to_le_bytes());       // 12:00:00
+            cd.extend(&0x5021_u16.to_le_bytes());       // 2020-01-01
+            cd.extend(&[0; 8]);
+            cd.extend(&size.to_le_bytes());
+            cd.extend(&(name.len() as u16).to_le_bytes());
+            cd.extend(&[0; 8]);
+            cd.extend(&(0o100_644_u32 << 16).to_le_bytes());
+            cd.extend(&[0; 4]);
+            cd.extend(name.as_bytes());
+        }
+
+        let mut zip = cd.clone();
+        zip.extend(b"PK\x05\x06");
+        zip.extend(&[0; 6]);
+        zip.extend(&(names.len() as u16).to_le_bytes());
+        zip.extend(&(cd.len() as u32).to_le_bytes());
+        zip.extend(&0_u32.to_le_bytes());
+        zip.extend(&[0; 2]);
+        zip
+    }
+
+    #[test]
+    fn tar_entries_listed() {
+        let tar = tar_file(&[ ("dir/", b'5', 0), ("dir/file.txt", b'0', 700) ]);
+        let entries = Format::Tar.entries(Cursor::new(tar)).unwrap();
+
+        assert_eq!(entries, vec![
+            Entry { path: "dir/".into(), kind: EntryKind::Directory, size: 0, modified: Some(1_600_000_000), mode: Some(0o644) },
+            Entry { path: "dir/file.txt".into(), kind: EntryKind::File, size: 700, modified: Some(1_600_000_000), mode: Some(0o644) },
+        ]);
+    }
+
+    #[test]
+    fn tar_long_name() {
+        let long = "a/".repeat(80) + "file";
+        let mut tar = tar_header("././@LongLink", b'L', long.len() as u64 + 1, 0);
+        tar.extend(long.as_bytes());
+        tar.extend(vec![0; 512 - long.len() % 512]);
+        tar.extend(tar_file(&[ ("a/a/a/truncated", b'0', 3) ]));
+
+        let entries = Format::Tar.entries(Cursor::new(tar)).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, PathBuf::from(long));
+    }
+
+    #[test]
+    fn tar_bad_checksum() {
+        let mut tar = tar_file(&[ ("file", b'0', 10) ]);
+        tar[0] = b'g';
+        assert!(Format::Tar.entries(Cursor::new(tar)).is_err());
+    }
+
+    #[test]
+    fn tar_metadata_too_long() {
+        let mut tar = tar_header("././@LongLink", b'L', MAX_METADATA_LEN + 1, 0);
+        tar.extend(vec![b'a'; MAX_METADATA_LEN as usize + 512]);
+        tar.extend(vec![0; 1024]);
+        assert!(Format::Tar.entries(Cursor::new(tar)).is_err());
+    }
+
+    #[test]
+    fn tar_out_of_bounds() {
+        let mut tar = tar_header("file", b'0', 1 << 30, 0);
+        tar.extend(vec![0; 1024]);
+        assert!(Format::Tar.entries(Cursor::new(tar)).is_err());
+    }
+
+    #[test]
+    fn tar_too_big() {
+        let record = b"25 size=2000000000000000\n";
+        let mut tar = tar_header("pax", b'x', record.len() as u64, 0);
+        tar.extend(record);
+        tar.extend(vec![0; 512 - record.len()]);
+        tar.extend(tar_file(&[ ("file", b'0', 3) ]));
+        assert!(Format::Tar.entries(Cursor::new(tar)).is_err());
+    }
+
+    #[test]
+    fn tar_empty() {
+        assert!(Format::Tar.entries(Cursor::new(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn pax_records() {
+        let pax = Pax::parse(b"21 path=some/file.rs\n19 mtime=1234.5678\n");
+        assert_eq!(pax.path, Some("some/file.rs".into()));
+        assert_eq!(pax.mtime, Some(1234));
+    }
+
+    #[test]
+    fn zip_entries_listed() {
+        let zip = zip_file(&[ ("src/", 0), ("src/main.rs", 1234) ]);
+        let entries = Format::Zip.entries(Cursor::new(zip)).unwrap();
+
+        assert_eq!(entries, vec![
+            Entry { path: "src/".into(), kind: EntryKind::Directory, size: 0, modified: Some(1_577_880_000), mode: Some(0o100_644) },
+            Entry { path: "src/main.rs".into(), kind: EntryKind::File, size: 1234, modified: Some(1_577_880_000), mode: Some(0o100_644) },
+        ]);
+    }
+
+    #[test]
+    fn zip_truncated() {
+        let zip = zip_file(&[ ("src/main.rs", 1234) ]);
+        assert!(Format::Zip.entries(Cursor::new(&zip[10 ..])).is_err());
+    }
+
+    #[test]
+    fn not_a_zip() {
+        assert!(Format::Zip.entries(Cursor::new(b"hello".to_vec())).is_err());
+    }
+
+    #[test]
+    fn unsafe_paths() {
+        assert_eq!(safe_path(Path::new("./a/b")), Some(PathBuf::from("a/b")));
+        assert_eq!(safe_path(Path::new("/etc/passwd")), Some(PathBuf::from("etc/passwd")));
+        assert_eq!(safe_path(Path::new("a/../../b")), None);
+        assert_eq!(safe_path(Path::new("./")), None);
+    }
+
+    #[test]
+    fn zip_central_directory_too_long() {
+        let cd_len = MAX_METADATA_LEN as u32 + 1;
+        let mut zip = vec![0; cd_len as usize];
+        zip.extend(b"PK\x05\x06");
+        zip.extend(&[0; 6]);
+        zip.extend(&1_u16.to_le_bytes());
+        zip.extend(&cd_len.to_le_bytes());
+        zip.extend(&0_u32.to_le_bytes());
+        zip.extend(&[0; 2]);
+        assert!(Format::Zip.entries(Cursor::new(zip)).is_err());
+    }
+
+    fn entry(path: &str, kind: EntryKind, size: u64) -> Entry {
+        Entry { path: path.into(), kind, size, modified: Some(1_000_000_000), mode: Some(0o4755) }
+    }
+
+    fn archived(entries: Vec<Entry>) -> Archived {
+        Archived::from_entries(Path::new("test.tar"), fs::metadata(std::env::temp_dir()).unwrap(), entries)
+    }
+
+    fn names(dir: &Archived) -> Vec<String> {
+        dir.children().map(|child| File::filename(&child.path())).collect()
+    }
+
+    #[test]
+    fn indexed_entries() {
+        let root = archived(vec![
+            entry("dir/file", EntryKind::File, 4096),
+            entry("../escape", EntryKind::File, 1),
+            entry("./top", EntryKind::File, 10),
+        ]);
+
+        assert_eq!(root.path(), PathBuf::from("test.tar"));
+        assert_eq!(names(&root), vec![ "dir", "top" ]);
+        assert_eq!(root.total_size(true), 4106);
+
+        let dir = root.child("dir").unwrap();
+        assert_eq!(dir.kind(), &EntryKind::Directory);
+        assert_eq!(dir.modified(), None);
+        assert_eq!(dir.parent().unwrap().path(), root.path());
+
+        let file = dir.child("file").unwrap();
+        assert_eq!(file.path(), PathBuf::from("test.tar/dir/file"));
+        assert_eq!(file.size(), 4096);
+        assert_eq!(file.mode(), Some(0o4755));
+        assert_eq!(file.modified(), Some(UNIX_EPOCH + Duration::from_secs(1_000_000_000)));
+        assert_ne!(file.identity(), dir.identity());
+    }
+
+    #[test]
+    fn later_entries_replace_earlier_ones() {
+        let root = archived(vec![
+            entry("dir/file", EntryKind::File, 1),
+            entry("dir/", EntryKind::Directory, 0),
+            entry("dir/file", EntryKind::File, 2),
+        ]);
+
+        let dir = root.child("dir").unwrap();
+        assert_eq!(dir.modified(), Some(UNIX_EPOCH + Duration::from_secs(1_000_000_000)));
+        assert_eq!(names(&dir), vec![ "file" ]);
+        assert_eq!(dir.child("file").unwrap().size(), 2);
+    }
+
+    #[test]
+    fn link_targets() {
+        let root = archived(vec![
+            entry("dir/file", EntryKind::File, 1),
+            entry("dir/sibling", EntryKind::Symlink("./file".into()), 0),
+            entry("dir/up", EntryKind::Symlink("..".into()), 0),
+            entry("dir/chain", EntryKind::Symlink("sibling".into()), 0),
+            entry("dir/loop", EntryKind::Symlink("loop".into()), 0),
+            entry("dir/missing", EntryKind::Symlink("nothing".into()), 0),
+            entry("dir/escape", EntryKind::Symlink("../../outside".into()), 0),
+            entry("absolute", EntryKind::Symlink("/etc".into()), 0),
+        ]);
+
+        let dir = root.child("dir").unwrap();
+        let target = |name: &str| dir.child(name).unwrap().target().map(|t| t.path());
+        assert_eq!(target("sibling"), Some(PathBuf::from("test.tar/dir/file")));
+        assert_eq!(target("up"), Some(PathBuf::from("test.tar")));
+        assert_eq!(target("chain"), Some(PathBuf::from("test.tar/dir/file")));
+        assert_eq!(target("loop"), None);
+        assert_eq!(target("missing"), None);
+        assert_eq!(target("escape"), None);
+        assert_eq!(root.child("absolute").unwrap().target().map(|t| t.path()), None);
+    }
+
+    #[test]
+    fn listed_like_directories() {
+        use crate::fs::{Dir, DotFilter};
+
+        let root = archived(vec![
+            entry("dir/file", EntryKind::File, 4096),
+            entry(".hidden", EntryKind::File, 1),
+            entry("link", EntryKind::Symlink("dir".into()), 0),
+        ]);
+
+        let top = File::from_archive(root.clone(), None, String::from("test.tar"));
+        assert!(top.is_directory());
+        assert_eq!(top.child_count(), Some(3));
+
+        let dir = top.to_dir().unwrap();
+        let files = dir.files(DotFilter::DotfilesAndDots, None, false).map(Result::unwrap).collect::<Vec<_>>();
+        let names = files.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec![ ".", "..", "dir", ".hidden", "link" ]);
+        assert!(files[0].is_directory());
+        assert_eq!(files[1].path, PathBuf::from("."));
+
+        let link = &files[4];
+        assert!(link.is_link());
+        assert!(link.points_to_directory());
+        assert_eq!(link.to_dir().unwrap().path, PathBuf::from("test.tar/dir"));
+
+        let inner = Dir::from_archive(root.child("dir").unwrap());
+        let file = inner.files(DotFilter::JustFiles, None, false).next().unwrap().unwrap();
+        assert_eq!(file.path, PathBuf::from("test.tar/dir/file"));
+        assert_eq!(file.length(), 4096);
+        assert_eq!(file.modified_time(), Some(UNIX_EPOCH + Duration::from_secs(1_000_000_000)));
+    }
+}
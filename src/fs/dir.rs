@@ -2,12 +2,19 @@ use crate::fs::feature::git::GitCache;
 use crate::fs::fields::GitStatus;
 use std::io;
 use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
+use std::sync::{Mutex, PoisonError};
+use std::vec::IntoIter as VecIntoIter;
 
+use lazy_static::lazy_static;
 use log::*;
+use scoped_threadpool::Pool;
 
 use crate::fs::File;
+use crate::fs::archive::Archived;
 
 
 /// A **Dir** provides a cached list of the file paths in a directory that’s
@@ -23,6 +30,18 @@ pub struct Dir {
 
     /// The path that was read.
     pub path: PathBuf,
+
+    /// The device and inode numbers of the directory, if they were already
+    /// known from the metadata of the file it was read from.
+    known_identity: Option<(u64, u64)>,
+
+    /// The device and inode numbers of the directories that were descended
+    /// into to reach this one while recursing, from the top down.
+    ancestors: Vec<(u64, u64)>,
+
+    /// The directory inside an archive that this is, if it isn’t one on
+    /// the filesystem.
+    archived: Option<Archived>,
 }
 
 impl Dir {
@@ -42,27 +61,115 @@ impl Dir {
                           .map(|result| result.map(|entry| entry.path()))
                           .collect::<Result<_, _>>()?;
 
-        Ok(Self { contents, path })
+        Ok(Self { contents, path, known_identity: None, ancestors: Vec::new(), archived: None })
+    }
+
+    /// Create a new Dir object for a directory inside an archive, filled
+    /// with the entries directly inside it.
+    pub fn from_archive(archived: Archived) -> Self {
+        info!("Reading archive directory {}", archived.path().display());
+
+        let contents = archived.children().map(|child| child.path()).collect();
+        Self { contents, path: archived.path(), known_identity: Some(archived.identity()), ancestors: Vec::new(), archived: Some(archived) }
+    }
+
+    /// The directory inside an archive that this is, if it is one.
+    pub fn archived(&self) -> Option<&Archived> {
+        self.archived.as_ref()
     }
 
     /// Produce an iterator of IO results of trying to read all the files in
     /// this directory.
+    ///
+    /// Directories with lots of files in them get all their files statted
+    /// at once, spread across threads, before any of them are produced.
+    /// Smaller ones get statted one at a time as they’re iterated over.
     pub fn files<'dir, 'ig>(&'dir self, dots: DotFilter, git: Option<&'ig GitCache>, git_ignoring: bool) -> Files<'dir, 'ig> {
+        let stats = if self.contents.len() >= PARALLEL_STAT_THRESHOLD && self.archived.is_none() {
+            Some(stat_visible(&self.contents, dots.shows_dotfiles()).into_iter())
+        }
+        else {
+            None
+        };
+
         Files {
             inner:     self.contents.iter(),
             dir:       self,
             dotfiles:  dots.shows_dotfiles(),
             dots:      dots.dots(),
+            stats,
             git,
             git_ignoring,
         }
     }
 
+    /// Counts the files in this directory that a listing with the given
+    /// filter would show, without reading any of their metadata. The `.`
+    /// and `..` entries aren’t counted, as they aren’t really its children.
+    pub fn count(&self, dots: DotFilter) -> usize {
+        self.contents.iter()
+            .filter(|path| is_visible(&File::filename(path), dots.shows_dotfiles()))
+            .count()
+    }
+
     /// Whether this directory contains a file with the given path.
     pub fn contains(&self, path: &Path) -> bool {
         self.contents.iter().any(|p| p.as_path() == path)
     }
 
+    /// The ID of the device this directory is on, or nothing if it can’t be
+    /// found out. Directories inside an archive are on the same device as
+    /// the archive.
+    #[cfg(unix)]
+    pub fn device_id(&self) -> Option<u64> {
+        match &self.archived {
+            Some(archived)  => Some(archived.archive_metadata().dev()),
+            None            => fs::metadata(&self.path).ok().map(|m| m.dev()),
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn device_id(&self) -> Option<u64> {
+        None
+    }
+
+    /// Records the device and inode numbers of this directory, which were
+    /// already known, so getting its identity doesn’t stat it again.
+    pub fn with_identity(self, known_identity: Option<(u64, u64)>) -> Self {
+        Self { known_identity, .. self }
+    }
+
+    /// The device and inode numbers of this directory, which are the same
+    /// however the directory was reached, even through a symlink.
+    #[cfg(unix)]
+    pub fn identity(&self) -> Option<(u64, u64)> {
+        self.known_identity.or_else(|| fs::metadata(&self.path).ok().map(|m| (m.dev(), m.ino())))
+    }
+
+    #[cfg(windows)]
+    pub fn identity(&self) -> Option<(u64, u64)> {
+        self.known_identity
+    }
+
+    /// Takes the given directory, read from inside this one while
+    /// recursing, as being one level further down. Returns `None` if it’s
+    /// this directory or one of the ones above it — as happens with a bind
+    /// mount or a symlink that leads back up — since it would otherwise be
+    /// descended into forever. A directory reached down two different paths
+    /// isn’t its own ancestor, so it still gets listed both times.
+    pub fn enter(&self, child: Self) -> Option<Self> {
+        let mut ancestors = self.ancestors.clone();
+        ancestors.extend(self.identity());
+
+        let known_identity = child.identity();
+        if known_identity.map_or(false, |id| ancestors.contains(&id)) {
+            debug!("Not descending into {} again", child.path.display());
+            return None;
+        }
+
+        Some(Self { known_identity, ancestors, .. child })
+    }
+
     /// Append a path onto the path specified by this directory.
     pub fn join(&self, child: &Path) -> PathBuf {
         self.path.join(child)
@@ -70,6 +177,53 @@ impl Dir {
 }
 
 
+/// The number of files a directory needs to have before they get statted in
+/// parallel. Below this, starting the threads takes longer than the stat
+/// calls they’d save.
+const PARALLEL_STAT_THRESHOLD: usize = 512;
+
+lazy_static! {
+
+    /// The threads that big directories get statted on. They only get
+    /// started the first time one is read, and are kept around after that,
+    /// so recursing through lots of them doesn’t start new ones every time.
+    static ref STAT_POOL: Mutex<Pool> = {
+        let n_cpus = match num_cpus::get() {
+            0 => 1,
+            n => n,
+        };
+
+        Mutex::new(Pool::new(n_cpus as u32))
+    };
+}
+
+/// Reads the metadata of each of the given paths that’s visible, splitting
+/// them between a pool of threads. The results are in the same order as
+/// the paths, with nothing in the places of the ones that aren’t visible.
+fn stat_visible(paths: &[PathBuf], dotfiles: bool) -> Vec<Option<io::Result<fs::Metadata>>> {
+    let mut pool = STAT_POOL.lock().unwrap_or_else(PoisonError::into_inner);
+    let n_threads = pool.thread_count() as usize;
+
+    let chunk_size = paths.len() / n_threads + 1;
+    let mut stats = paths.iter().map(|_| None).collect::<Vec<_>>();
+
+    debug!("Statting {} files on {} threads", paths.len(), n_threads);
+    pool.scoped(|scoped| {
+        for (paths, stats) in paths.chunks(chunk_size).zip(stats.chunks_mut(chunk_size)) {
+            scoped.execute(move || {
+                for (path, stat) in paths.iter().zip(stats) {
+                    if is_visible(&File::filename(path), dotfiles) {
+                        *stat = Some(fs::symlink_metadata(path));
+                    }
+                }
+            });
+        }
+    });
+
+    stats
+}
+
+
 /// Iterator over reading the contents of a directory as `File` objects.
 pub struct Files<'dir, 'ig> {
 
@@ -86,6 +240,10 @@ pub struct Files<'dir, 'ig> {
     /// any files have been listed.
     dots: DotsNext,
 
+    /// The metadata of each of the paths, in the same order, if they’ve
+    /// already been statted all at once.
+    stats: Option<VecIntoIter<Option<io::Result<fs::Metadata>>>>,
+
     git: Option<&'ig GitCache>,
 
     git_ignoring: bool,
@@ -93,6 +251,15 @@ pub struct Files<'dir, 'ig> {
 
 impl<'dir, 'ig> Files<'dir, 'ig> {
     fn parent(&self) -> PathBuf {
+        // The root of an archive has the directory the archive is in as its
+        // parent, which can’t be reached by going through the archive.
+        if self.dir.archived.as_ref().map_or(false, |a| a.parent().is_none()) {
+            return match self.dir.path.parent() {
+                Some(p) if ! p.as_os_str().is_empty()  => p.to_path_buf(),
+                _                                      => PathBuf::from("."),
+            };
+        }
+
         // We can’t use `Path#parent` here because all it does is remove the
         // last path component, which is no good for us if the path is
         // relative. For example, while the parent of `/testcases/files` is
@@ -106,15 +273,10 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
     fn next_visible_file(&mut self) -> Option<Result<File<'dir>, (PathBuf, io::Error)>> {
         loop {
             if let Some(path) = self.inner.next() {
-                let filename = File::filename(path);
-                if ! self.dotfiles && filename.starts_with('.') {
-                    continue;
-                }
+                let stat = self.stats.as_mut().and_then(Iterator::next).flatten();
 
-                // Also hide _prefix files on Windows because it's used by old applications
-                // as an alternative to dot-prefix files.
-                #[cfg(windows)]
-                if ! self.dotfiles && filename.starts_with('_') {
+                let filename = File::filename(path);
+                if ! is_visible(&filename, self.dotfiles) {
                     continue;
                 }
 
@@ -125,8 +287,18 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
                     }
                 }
 
-                return Some(File::from_args(path.clone(), self.dir, filename)
-                                 .map_err(|e| (path.clone(), e)))
+                let file = match (&self.dir.archived, stat) {
+                    (Some(archived), _) => {
+                        archived.child(&filename)
+                                .map(|child| File::from_archive(child, self.dir, filename))
+                                .ok_or_else(|| io::ErrorKind::NotFound.into())
+                    }
+                    (None, Some(Ok(metadata)))  => Ok(File::with_metadata(path.clone(), self.dir, filename, metadata)),
+                    (None, Some(Err(e)))        => Err(e),
+                    (None, None)                => File::from_args(path.clone(), self.dir, filename),
+                };
+
+                return Some(file.map_err(|e| (path.clone(), e)))
             }
 
             return None
@@ -134,6 +306,24 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
     }
 }
 
+/// Whether a file with the given name gets listed, depending on whether
+/// dotfiles are being shown.
+fn is_visible(filename: &str, dotfiles: bool) -> bool {
+    if ! dotfiles && filename.starts_with('.') {
+        return false;
+    }
+
+    // Also hide _prefix files on Windows because it's used by old applications
+    // as an alternative to dot-prefix files.
+    #[cfg(windows)]
+    if ! dotfiles && filename.starts_with('_') {
+        return false;
+    }
+
+    true
+}
+
+
 /// The dot directories that need to be listed before actual files, if any.
 /// If these aren’t being printed, then `FilesNext` is used to skip them.
 enum DotsNext {
@@ -215,3 +405,78 @@ impl DotFilter {
         }
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+
+    /// Makes a directory with enough files in it to get them statted in
+    /// parallel, and reads it, before removing one of every hundred files so
+    /// statting them fails.
+    fn big_dir(name: &str) -> Dir {
+        let root = temp_dir(&[ "big-dir-", name ].concat());
+
+        for i in 0 .. PARALLEL_STAT_THRESHOLD * 2 {
+            fs::write(root.join(i.to_string()), "").unwrap();
+        }
+        fs::write(root.join(".hidden"), "").unwrap();
+
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        for i in (0 .. PARALLEL_STAT_THRESHOLD * 2).step_by(100) {
+            fs::remove_file(root.join(i.to_string())).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn order_preserved() {
+        let dir = big_dir("order");
+        let names = dir.files(DotFilter::JustFiles, None, false)
+                       .map(|f| match f {
+                           Ok(file)        => file.name,
+                           Err((path, _))  => File::filename(&path),
+                       })
+                       .collect::<Vec<_>>();
+
+        let expected = dir.contents.iter()
+                          .map(|path| File::filename(path))
+                          .filter(|name| name != ".hidden")
+                          .collect::<Vec<_>>();
+
+        assert_eq!(names, expected);
+        fs::remove_dir_all(&dir.path).unwrap();
+    }
+
+    #[test]
+    fn errors_surface() {
+        let dir = big_dir("errors");
+        let mut failures = dir.files(DotFilter::JustFiles, None, false)
+                              .filter_map(Result::err)
+                              .map(|(path, e)| (File::filename(&path), e.kind()))
+                              .collect::<Vec<_>>();
+        failures.sort();
+
+        let mut expected = (0 .. PARALLEL_STAT_THRESHOLD * 2).step_by(100)
+                               .map(|i| (i.to_string(), io::ErrorKind::NotFound))
+                               .collect::<Vec<_>>();
+        expected.sort();
+
+        assert_eq!(failures, expected);
+        fs::remove_dir_all(&dir.path).unwrap();
+    }
+
+    #[test]
+    fn dotfiles_statted() {
+        let dir = big_dir("dotfiles");
+        let hidden = dir.files(DotFilter::Dotfiles, None, false)
+                        .filter_map(Result::ok)
+                        .filter(|f| f.name == ".hidden")
+                        .count();
+
+        assert_eq!(hidden, 1);
+        fs::remove_dir_all(&dir.path).unwrap();
+    }
+}
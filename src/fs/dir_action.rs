@@ -1,5 +1,9 @@
 //! What to do when encountering a directory?
 
+
+use crate::fs::File;
+
+
 /// The action to take when trying to list a file that turns out to be a
 /// directory.
 ///
@@ -19,6 +23,9 @@
 /// into them and print out their contents. The recurse mode does this by
 /// having extra output blocks at the end, while the tree mode will show
 /// directories inline, with their contents immediately underneath.
+///
+/// Passing `--list-dirs` alongside either recursive mode limits the
+/// recursion to directories, giving a view of just the directory structure.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum DirAction {
 
@@ -58,7 +65,7 @@ impl DirAction {
 
 
 /// The options that determine how to recurse into a directory.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
 pub struct RecurseOptions {
 
     /// Whether recursion should be done as a tree or as multiple individual
@@ -68,6 +75,51 @@ pub struct RecurseOptions {
     /// The maximum number of times that recursion should descend to, if one
     /// is specified.
     pub max_depth: Option<usize>,
+
+    /// Whether only directories should be listed while recursing, which
+    /// happens when `--list-dirs` is combined with one of the recursive
+    /// modes. Files at every level are skipped.
+    pub dirs_only: bool,
+
+    /// The number of files sharing an extension that a directory has to
+    /// contain before they get folded into a single summary line, if files
+    /// should be folded at all. This only happens in tree mode.
+    pub collapse_exts: Option<usize>,
+
+    /// Whether recursion should stay on the file system it started on,
+    /// rather than descending into directories that have other file systems
+    /// mounted on them. The directories themselves still get listed.
+    pub one_file_system: bool,
+
+    /// Whether symlinks that point to directories should be descended into
+    /// as though they were directories themselves.
+    pub follow_symlinks: bool,
+
+    /// Whether directories that have nothing in them to list, once every
+    /// filter has been applied, should be left out, along with any
+    /// directories that only contain those.
+    pub prune: bool,
+
+    /// Whether the files in every directory should be gathered into one
+    /// listing, showing each file’s path, rather than listing each
+    /// directory separately under its own heading.
+    pub flat: bool,
+
+    /// Whether the files in each directory of a tree should be listed in
+    /// the reverse of their sorted order. This only happens in tree mode,
+    /// and works separately from reversing the sort itself.
+    pub reverse_tree: bool,
+
+    /// Whether a directory whose only entry is another directory should
+    /// share a row with it, and so on down the chain, rather than taking
+    /// up a level of the tree each. This only happens in tree mode.
+    pub collapse_single_child: bool,
+
+    /// Whether every directory at one depth should be listed before any of
+    /// the directories inside them, rather than each directory being
+    /// followed straight away by the ones inside it. This never happens in
+    /// tree mode.
+    pub files_first: bool,
 }
 
 impl RecurseOptions {
@@ -79,4 +131,114 @@ impl RecurseOptions {
             Some(d)  => d <= depth
         }
     }
+
+    /// Returns whether the given file is a directory that can be descended
+    /// into, which includes symlinks to directories when they’re followed.
+    pub fn is_recursable(self, file: &File<'_>) -> bool {
+        file.is_directory() || (self.follow_symlinks && file.points_to_directory())
+    }
+
+    /// Returns whether the given directory shouldn’t be descended into
+    /// because it’s on a different file system to the directory it’s in,
+    /// which has the given device ID. This is only ever the case when
+    /// recursion is limited to one file system.
+    pub fn is_other_file_system(self, parent_device: Option<u64>, child: &File<'_>) -> bool {
+        self.one_file_system && parent_device.is_some() && child.device_id() != parent_device
+    }
+
+    /// Removes every file that should not be listed during recursion from
+    /// the given vector, which means everything but directories when only
+    /// directories are being listed.
+    pub fn filter_child_files(self, files: &mut Vec<File<'_>>) {
+        if self.dirs_only {
+            files.retain(File::is_directory);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::Dir;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    fn one_file_system() -> RecurseOptions {
+        RecurseOptions { one_file_system: true, .. RecurseOptions::default() }
+    }
+
+    #[test]
+    fn depth_limit() {
+        let two_deep = RecurseOptions { max_depth: Some(2), .. RecurseOptions::default() };
+        assert!(! two_deep.is_too_deep(1));
+        assert!(two_deep.is_too_deep(2));
+        assert!(two_deep.is_too_deep(3));
+    }
+
+    #[test]
+    fn no_depth_limit() {
+        let everything = RecurseOptions::default();
+        assert!(! everything.is_too_deep(usize::MAX));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn same_device_is_recursed() {
+        let root = temp_dir("one-fs");
+        fs::create_dir(root.join("child")).unwrap();
+
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        let child = File::from_args(root.join("child"), Some(&dir), None).unwrap();
+        assert!(! one_file_system().is_other_file_system(dir.device_id(), &child));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn other_device_is_not() {
+        let root = temp_dir("other-fs");
+        fs::create_dir(root.join("child")).unwrap();
+
+        let child = File::from_args(root.join("child"), None, None).unwrap();
+        let device = child.device_id().map(|d| d.wrapping_add(1));
+        assert!(one_file_system().is_other_file_system(device, &child));
+
+        let everything = RecurseOptions::default();
+        assert!(! everything.is_other_file_system(device, &child));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlink_loop() {
+        let root = temp_dir("symlink-loop");
+        fs::create_dir(root.join("child")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("child").join("parent")).unwrap();
+
+        let top = Dir::read_dir(root.clone()).unwrap();
+        let child = top.enter(Dir::read_dir(root.join("child")).unwrap()).unwrap();
+        assert!(child.enter(Dir::read_dir(root.join("child").join("parent")).unwrap()).is_none());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn same_dir_down_other_paths() {
+        let root = temp_dir("other-paths");
+        fs::create_dir_all(root.join("a").join("shared")).unwrap();
+        fs::create_dir(root.join("b")).unwrap();
+        std::os::unix::fs::symlink(root.join("a").join("shared"), root.join("b").join("shared")).unwrap();
+
+        let top = Dir::read_dir(root.clone()).unwrap();
+        let a = top.enter(Dir::read_dir(root.join("a")).unwrap()).unwrap();
+        let b = top.enter(Dir::read_dir(root.join("b")).unwrap()).unwrap();
+        assert!(a.enter(Dir::read_dir(root.join("a").join("shared")).unwrap()).is_some());
+        assert!(b.enter(Dir::read_dir(root.join("b").join("shared")).unwrap()).is_some());
+
+        fs::remove_dir_all(root).unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 //! Getting the Git status of files and directories.
 
+use std::collections::HashSet;
 use std::ffi::OsStr;
 #[cfg(target_family = "unix")]
 use std::os::unix::ffi::OsStrExt;
@@ -22,19 +23,115 @@ pub struct GitCache {
 
     /// Paths that we’ve confirmed do not have Git repositories underneath them.
     misses: Vec<PathBuf>,
+
+    /// Repositories inside the working trees of the ones above, which get
+    /// discovered as the files in them get looked up.
+    nested: Mutex<NestedRepos>,
+
+    /// The number of files a repository can have in its index before its
+    /// statuses stop being looked up, if there’s a limit.
+    file_limit: Option<usize>,
+}
+
+/// The repositories found inside other repositories’ working trees. Git
+/// doesn’t look inside these from the outside, so they have to be asked
+/// about their own files, with their own ignore rules.
+#[derive(Default)]
+struct NestedRepos {
+
+    /// The nested repositories found so far.
+    repos: Vec<GitRepo>,
+
+    /// Directories that have been checked, and don’t have a repository of
+    /// their own. Nearly every directory being listed ends up in here, so
+    /// unlike the other misses, these get hashed.
+    misses: HashSet<PathBuf>,
 }
 
 impl GitCache {
+
+    /// Stops the statuses of repositories with more than the given number
+    /// of files from being looked up, with every file in them being given
+    /// an unknown status instead.
+    pub fn with_file_limit(self, file_limit: Option<usize>) -> Self {
+        Self { file_limit, ..self }
+    }
+
     pub fn has_anything_for(&self, index: &Path) -> bool {
         self.repos.iter().any(|e| e.has_path(index))
     }
 
+    /// Gets the Git status of the given path from the innermost repository
+    /// it’s in. A directory that’s the root of a nested repository gets its
+    /// status from the repository around it, which is the one tracking it.
     pub fn get(&self, index: &Path, prefix_lookup: bool) -> f::Git {
-        self.repos.iter()
-            .find(|e| e.has_path(index))
-            .map(|repo| repo.search(index, prefix_lookup))
+        let parent = index.parent().unwrap_or(index);
+        self.with_innermost_repo(index, parent, |repo| repo.search(index, prefix_lookup, self.file_limit))
             .unwrap_or_default()
     }
+
+    /// Gets the name of the branch that’s checked out in the repository the
+    /// given path is in, or the abbreviated ID of the commit if the head is
+    /// detached. Returns nothing if the path isn’t in a repository.
+    pub fn head_name(&self, index: &Path) -> Option<String> {
+        self.with_innermost_repo(index, index, |repo| repo.head.clone())
+            .flatten()
+    }
+
+    /// Runs the given function with the innermost repository that the given
+    /// path is in, if it’s in one at all. The deepest of the repositories
+    /// discovered from the arguments gets picked, then the directories
+    /// between it and the path get checked for nested repositories, from
+    /// the `search_from` directory upwards.
+    fn with_innermost_repo<T>(&self, index: &Path, search_from: &Path, action: impl FnOnce(&GitRepo) -> T) -> Option<T> {
+        let repo = self.repos.iter()
+            .filter(|e| e.has_path(index))
+            .max_by_key(|e| e.workdir.components().count())?;
+
+        let top = repo.path_containing(index)?;
+        let mut nested = self.nested.lock().unwrap();
+        for dir in search_from.ancestors().take_while(|d| d.starts_with(top) && *d != top) {
+            if let Some(nested_repo) = nested.find(dir) {
+                return Some(action(nested_repo));
+            }
+        }
+
+        Some(action(repo))
+    }
+}
+
+impl NestedRepos {
+
+    /// Gets the repository whose working tree is the given directory,
+    /// discovering it if this is the first time the directory has been
+    /// checked. Only directories with a `.git` entry in them get handed to
+    /// Git, as asking it about the others would find the outer repository.
+    fn find(&mut self, dir: &Path) -> Option<&GitRepo> {
+        if self.misses.contains(dir) {
+            return None;
+        }
+
+        if let Some(index) = self.repos.iter().position(|e| e.original_path == dir) {
+            return Some(&self.repos[index]);
+        }
+
+        if ! dir.join(".git").exists() {
+            self.misses.insert(dir.to_path_buf());
+            return None;
+        }
+
+        match GitRepo::discover(dir.to_path_buf()) {
+            Ok(r) => {
+                debug!("Discovered nested Git repo in {}", dir.display());
+                self.repos.push(r);
+                self.repos.last()
+            }
+            Err(miss) => {
+                self.misses.insert(miss);
+                None
+            }
+        }
+    }
 }
 
 use std::iter::FromIterator;
@@ -46,6 +143,8 @@ impl FromIterator<PathBuf> for GitCache {
         let mut git = Self {
             repos: Vec::with_capacity(iter.size_hint().0),
             misses: Vec::new(),
+            nested: Mutex::default(),
+            file_limit: None,
         };
 
         for path in iter {
@@ -98,14 +197,21 @@ pub struct GitRepo {
     /// Any other paths that were checked only to result in this same
     /// repository.
     extra_paths: Vec<PathBuf>,
+
+    /// The name of the branch that’s checked out, or the abbreviated ID of
+    /// the commit if the head is detached, read when the repository was
+    /// discovered.
+    head: Option<String>,
 }
 
 /// A repository’s queried state.
 enum GitContents {
 
-    /// All the interesting Git stuff goes through this.
+    /// All the interesting Git stuff goes through this, along with the
+    /// number of files that have been looked up one at a time so far.
     Before {
         repo: git2::Repository,
+        lookups: usize,
     },
 
     /// Temporary value used in `repo_to_statuses` so we can move the
@@ -117,6 +223,10 @@ enum GitContents {
     After {
         statuses: Git,
     },
+
+    /// The repository had too many files in it for its statuses to be
+    /// worth looking up.
+    TooBig,
 }
 
 impl GitRepo {
@@ -128,16 +238,41 @@ impl GitRepo {
     /// Git statuses is only done once, and gets cached so we don’t need to
     /// re-query the entire repository the times after that.
     ///
+    /// Until then, the first few files get looked up on their own, so a
+    /// listing that only shows a handful of the files in a big repository
+    /// never has to wait for all of them to be checked. Directories need the
+    /// statuses of everything inside them, so they always query the lot.
+    ///
     /// The temporary `Processing` enum variant is used after the `git2`
     /// repository is moved out, but before the results have been moved in!
     /// See <https://stackoverflow.com/q/45985827/3484614>
-    fn search(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+    ///
+    /// If the repository has more files in its index than the limit, it
+    /// doesn’t get queried at all, and every path gets an unknown status.
+    fn search(&self, index: &Path, prefix_lookup: bool, file_limit: Option<usize>) -> f::Git {
         use std::mem::replace;
 
         let mut contents = self.contents.lock().unwrap();
-        if let GitContents::After { ref statuses } = *contents {
-            debug!("Git repo {:?} has been found in cache", &self.workdir);
-            return statuses.status(index, prefix_lookup);
+        match *contents {
+            GitContents::After { ref statuses } => {
+                debug!("Git repo {:?} has been found in cache", &self.workdir);
+                return statuses.status(index, prefix_lookup);
+            }
+            GitContents::TooBig => {
+                return f::Git::unknown();
+            }
+            GitContents::Before { ref repo, lookups: 0 } if file_limit.map_or(false, |limit| file_count(repo) > limit) => {
+                info!("Skipping Git statuses for repo {} because it has too many files", self.workdir.display());
+                *contents = GitContents::TooBig;
+                return f::Git::unknown();
+            }
+            GitContents::Before { ref repo, ref mut lookups } if ! prefix_lookup && *lookups < SINGLE_FILE_LOOKUPS => {
+                *lookups += 1;
+                if let Some(status) = single_file_status(repo, &self.workdir, index) {
+                    return status;
+                }
+            }
+            _ => {}
         }
 
         debug!("Querying Git repo {:?} for the first time", &self.workdir);
@@ -158,6 +293,17 @@ impl GitRepo {
         path.starts_with(&self.original_path) || self.extra_paths.iter().any(|e| path.starts_with(e))
     }
 
+    /// The longest of the paths checked to discover this repository that
+    /// the given path is inside, which is where looking for nested
+    /// repositories should stop.
+    fn path_containing(&self, path: &Path) -> Option<&Path> {
+        std::iter::once(&self.original_path)
+            .chain(&self.extra_paths)
+            .filter(|e| path.starts_with(e))
+            .max_by_key(|e| e.components().count())
+            .map(PathBuf::as_path)
+    }
+
     /// Searches for a Git repository at any point above the given path.
     /// Returns the original buffer if none is found.
     fn discover(path: PathBuf) -> Result<Self, PathBuf> {
@@ -172,8 +318,9 @@ impl GitRepo {
 
         if let Some(workdir) = repo.workdir() {
             let workdir = workdir.to_path_buf();
-            let contents = Mutex::new(GitContents::Before { repo });
-            Ok(Self { contents, workdir, original_path: path, extra_paths: Vec::new() })
+            let head = head_name(&repo);
+            let contents = Mutex::new(GitContents::Before { repo, lookups: 0 });
+            Ok(Self { contents, workdir, original_path: path, extra_paths: Vec::new(), head })
         }
         else {
             warn!("Repository has no workdir?");
@@ -188,7 +335,7 @@ impl GitContents {
     /// (consuming the value) if it has. This is needed because the entire
     /// enum variant gets replaced when a repo is queried (see above).
     fn inner_repo(self) -> git2::Repository {
-        if let Self::Before { repo } = self {
+        if let Self::Before { repo, .. } = self {
             repo
         }
         else {
@@ -197,6 +344,34 @@ impl GitContents {
     }
 }
 
+/// The number of files that get looked up one at a time before the whole
+/// repository gets queried instead. Each lookup checks the index and the
+/// ignore rules for its file, so past a point, getting everything at once
+/// works out quicker.
+const SINGLE_FILE_LOOKUPS: usize = 64;
+
+/// Looks up the status of a single file, without getting the statuses of
+/// the rest of the repository. This returns nothing for any path that Git
+/// can’t match to exactly one file, such as a directory, which leaves the
+/// whole repository to be queried instead.
+fn single_file_status(repo: &git2::Repository, workdir: &Path, file: &Path) -> Option<f::Git> {
+    let path = reorient(file);
+    let relative = path.strip_prefix(workdir).ok()?;
+
+    debug!("Querying Git repo {} for the status of {}", workdir.display(), relative.display());
+    let status = repo.status_file(relative).ok()?;
+
+    let staged = index_status(status);
+    let unstaged = working_tree_status(status);
+    Some(f::Git { staged, unstaged })
+}
+
+/// The number of files in a repository’s index, which is much quicker to
+/// find out than its statuses, as none of the files have to be checked.
+fn file_count(repo: &git2::Repository) -> usize {
+    repo.index().map(|index| index.len()).unwrap_or(0)
+}
+
 /// Iterates through a repository’s statuses, consuming it and returning the
 /// mapping of files to their Git status.
 /// We will have already used the working directory at this point, so it gets
@@ -292,6 +467,66 @@ impl Git {
 }
 
 
+/// Summarises the state of the Git repository whose working tree has the
+/// given directory as its root: the checked-out branch, whether anything
+/// has changed, and how far the branch has diverged from its upstream.
+/// Returns nothing for a directory that isn’t the root of a repository.
+pub fn repo_summary(path: &Path) -> Option<f::GitRepo> {
+    let repo = git2::Repository::open(path).ok()?;
+    if repo.is_bare() {
+        return None;
+    }
+
+    info!("Summarising Git repo at {:?}", path);
+    let head = repo.head().ok();
+    let head_name = head_name(&repo);
+
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let dirty = match repo.statuses(Some(&mut options)) {
+        Ok(es)  => ! es.is_empty(),
+        Err(e)  => {
+            error!("Error looking up Git statuses: {:?}", e);
+            false
+        }
+    };
+
+    let (ahead, behind) = head.as_ref()
+                              .filter(|h| h.is_branch())
+                              .and_then(|h| ahead_behind(&repo, h))
+                              .unwrap_or((0, 0));
+
+    Some(f::GitRepo { head: head_name.unwrap_or_else(|| String::from("HEAD")), dirty, ahead, behind })
+}
+
+/// Gets the name of the branch that’s checked out in the given repository,
+/// or the abbreviated ID of the commit if the head is detached.
+fn head_name(repo: &git2::Repository) -> Option<String> {
+    match repo.head() {
+        Ok(h) if h.is_branch()  => h.shorthand().map(String::from),
+        Ok(h)                   => h.target().map(|oid| oid.to_string()[.. 7].to_string()),
+        Err(_)                  => unborn_branch(repo),
+    }
+}
+
+/// Gets the name of the branch that the head points to when that branch
+/// has no commits yet, as happens in a newly-created repository.
+fn unborn_branch(repo: &git2::Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    Some(target.trim_start_matches("refs/heads/").to_string())
+}
+
+/// Counts how many commits the given branch is ahead of and behind its
+/// upstream, if it has one.
+fn ahead_behind(repo: &git2::Repository, head: &git2::Reference<'_>) -> Option<(usize, usize)> {
+    let local = head.target()?;
+    let branch = repo.find_branch(head.shorthand()?, git2::BranchType::Local).ok()?;
+    let upstream = branch.upstream().ok()?.get().target()?;
+    repo.graph_ahead_behind(local, upstream).ok()
+}
+
+
 /// Converts a path to an absolute path based on the current directory.
 /// Paths need to be absolute for them to be compared properly, otherwise
 /// you’d ask a repo about “./README.md” but it only knows about
@@ -343,3 +578,204 @@ fn index_status(status: git2::Status) -> f::GitStatus {
         _                                                => f::GitStatus::NotModified,
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+    use crate::fs::test::temp_dir;
+
+    /// Makes an outer repository that ignores `.log` files, with an inner
+    /// one inside it that ignores `.tmp` files and tracks a `.log` file.
+    fn nested_repos(name: &str) -> PathBuf {
+        let root = temp_dir(&[ "git-nested-", name ].concat());
+        let outer = root.join("outer");
+        let inner = outer.join("inner");
+        fs::create_dir_all(&inner).unwrap();
+
+        let outer_repo = git2::Repository::init(&outer).unwrap();
+        outer_repo.set_head("refs/heads/outer").unwrap();
+        fs::write(outer.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(outer.join("a.log"), "").unwrap();
+
+        let inner_repo = git2::Repository::init(&inner).unwrap();
+        inner_repo.set_head("refs/heads/inner").unwrap();
+        fs::write(inner.join(".gitignore"), "*.tmp\n").unwrap();
+        fs::write(inner.join("keep.log"), "").unwrap();
+        fs::write(inner.join("junk.tmp"), "").unwrap();
+
+        let mut index = inner_repo.index().unwrap();
+        index.add_path(Path::new("keep.log")).unwrap();
+        index.write().unwrap();
+
+        root
+    }
+
+    fn status(cache: &GitCache, path: &Path, prefix_lookup: bool) -> (bool, bool) {
+        let git = cache.get(path, prefix_lookup);
+        (git.staged == f::GitStatus::New, git.unstaged == f::GitStatus::Ignored)
+    }
+
+    #[test]
+    fn outer_ignores_outside() {
+        let root = nested_repos("outside");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>();
+        assert_eq!(status(&cache, &root.join("outer/a.log"), false), (false, true));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn outer_ignores_not_inside() {
+        let root = nested_repos("not-inside");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>();
+        assert_eq!(status(&cache, &root.join("outer/inner/keep.log"), false), (true, false));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn inner_ignores_inside() {
+        let root = nested_repos("inside");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>();
+        assert_eq!(status(&cache, &root.join("outer/inner/junk.tmp"), false), (false, true));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn inner_root_from_outer() {
+        let root = nested_repos("root");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>();
+        let git = cache.get(&root.join("outer/inner"), true);
+        assert!(git.unstaged == f::GitStatus::New);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn deepest_argument() {
+        let root = nested_repos("deepest");
+        let cache = vec![ root.join("outer/inner"), root.join("outer") ].into_iter().collect::<GitCache>();
+        assert_eq!(status(&cache, &root.join("outer/inner/junk.tmp"), false), (false, true));
+        assert_eq!(status(&cache, &root.join("outer/a.log"), false), (false, true));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    /// Makes a repository with files in every state: committed, modified,
+    /// staged, untracked, and ignored, both on their own and in directories.
+    fn every_status(name: &str) -> PathBuf {
+        let root = temp_dir(&[ "git-statuses-", name ].concat());
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::create_dir_all(root.join("untracked")).unwrap();
+
+        let repo = git2::Repository::init(&root).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        fs::write(root.join("committed.txt"), "one").unwrap();
+        fs::write(root.join("modified.txt"), "one").unwrap();
+        fs::write(root.join("src/lib.rs"), "one").unwrap();
+
+        let mut index = repo.index().unwrap();
+        for path in &[ ".gitignore", "committed.txt", "modified.txt", "src/lib.rs" ] {
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("exa", "exa@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[]).unwrap();
+
+        fs::write(root.join("modified.txt"), "two").unwrap();
+        fs::write(root.join("src/lib.rs"), "two").unwrap();
+        fs::write(root.join("staged.txt"), "one").unwrap();
+        fs::write(root.join("both.txt"), "one").unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.add_path(Path::new("both.txt")).unwrap();
+        index.write().unwrap();
+        fs::write(root.join("both.txt"), "two").unwrap();
+
+        fs::write(root.join("new.txt"), "").unwrap();
+        fs::write(root.join("untracked/inside.txt"), "").unwrap();
+        fs::write(root.join("debug.log"), "").unwrap();
+        fs::write(root.join("build/out.o"), "").unwrap();
+
+        root
+    }
+
+    const EVERY_STATUS: &[&str] = &[
+        "committed.txt", "modified.txt", "staged.txt", "both.txt", "new.txt",
+        "debug.log", "src", "src/lib.rs", "untracked", "untracked/inside.txt",
+        "build", "build/out.o",
+    ];
+
+    #[test]
+    fn single_files_match_whole_repo() {
+        let root = every_status("match");
+        let lazy = std::iter::once(root.clone()).collect::<GitCache>();
+        let eager = std::iter::once(root.clone()).collect::<GitCache>();
+        eager.get(&root, true);
+
+        for path in EVERY_STATUS {
+            for prefix_lookup in &[ false, true ] {
+                let one = lazy.get(&root.join(path), *prefix_lookup);
+                let all = eager.get(&root.join(path), *prefix_lookup);
+                assert!((one.staged, one.unstaged) == (all.staged, all.unstaged), "{} differs", root.join(path).display());
+            }
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn single_files_looked_up_alone() {
+        let root = every_status("alone");
+        let cache = std::iter::once(root.clone()).collect::<GitCache>();
+        let git = cache.get(&root.join("both.txt"), false);
+        assert!(git.staged == f::GitStatus::New && git.unstaged == f::GitStatus::Modified);
+
+        let contents = cache.repos[0].contents.lock().unwrap();
+        assert!(matches!(*contents, GitContents::Before { lookups: 1, .. }));
+        drop(contents);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn directories_query_whole_repo() {
+        let root = every_status("dirs");
+        let cache = std::iter::once(root.clone()).collect::<GitCache>();
+        let git = cache.get(&root.join("src"), true);
+        assert!(git.staged == f::GitStatus::NotModified && git.unstaged == f::GitStatus::Modified);
+
+        let contents = cache.repos[0].contents.lock().unwrap();
+        assert!(matches!(*contents, GitContents::After { .. }));
+        drop(contents);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn too_many_files() {
+        let root = nested_repos("limit");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>().with_file_limit(Some(0));
+        let git = cache.get(&root.join("outer/inner/keep.log"), false);
+        assert!(git.staged == f::GitStatus::Unknown && git.unstaged == f::GitStatus::Unknown);
+        assert_eq!(status(&cache, &root.join("outer/a.log"), false), (false, true));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn few_enough_files() {
+        let root = nested_repos("under-limit");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>().with_file_limit(Some(1));
+        assert_eq!(status(&cache, &root.join("outer/inner/keep.log"), false), (true, false));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn head_names() {
+        let root = nested_repos("heads");
+        let cache = std::iter::once(root.join("outer")).collect::<GitCache>();
+        assert_eq!(cache.head_name(&root.join("outer")), Some("outer".into()));
+        assert_eq!(cache.head_name(&root.join("outer/inner")), Some("inner".into()));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
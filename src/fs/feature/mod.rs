@@ -22,6 +22,10 @@ pub mod git {
     }
 
     impl GitCache {
+        pub fn with_file_limit(self, _limit: Option<usize>) -> Self {
+            self
+        }
+
         pub fn has_anything_for(&self, _index: &Path) -> bool {
             false
         }
@@ -29,5 +33,13 @@ pub mod git {
         pub fn get(&self, _index: &Path, _prefix_lookup: bool) -> f::Git {
             unreachable!();
         }
+
+        pub fn head_name(&self, _index: &Path) -> Option<String> {
+            None
+        }
+    }
+
+    pub fn repo_summary(_path: &Path) -> Option<f::GitRepo> {
+        None
     }
 }
@@ -109,6 +109,23 @@ pub fn list_attrs(lister: &lister::Lister, path: &Path) -> io::Result<Vec<Attrib
 }
 
 
+/// Whether the file at the given path has any Linux capabilities set.
+/// These are stored in the `security.capability` extended attribute, so
+/// we only need to check whether it exists, not what’s in it.
+#[cfg(target_os = "linux")]
+pub fn has_capabilities(path: &Path) -> bool {
+    use std::ffi::CString;
+
+    let c_path = match path.to_str().and_then(|s| CString::new(s).ok()) {
+        Some(cstring) => cstring,
+        None          => return false,
+    };
+
+    let lister = lister::Lister::new(FollowSymlinks::Yes);
+    lister.getxattr(&c_path, b"security.capability\0") > 0
+}
+
+
 #[cfg(target_os = "macos")]
 mod lister {
     use super::FollowSymlinks;
@@ -104,6 +104,8 @@ pub struct PermissionsPlus {
     #[cfg(windows)]
     pub attributes:  Attributes,
     pub xattrs:      bool,
+    #[cfg(target_os = "linux")]
+    pub capabilities: bool,
 }
 
 
@@ -141,7 +143,7 @@ pub struct Inode(pub ino_t);
 #[derive(Copy, Clone)]
 pub enum Blocks {
 
-    /// This file has the given number of blocks.
+    /// This file has the given number of 512-byte blocks.
     Some(blkcnt_t),
 
     /// This file isn’t of a type that can take up blocks.
@@ -237,6 +239,10 @@ pub enum GitStatus {
 
     /// A file that’s updated but unmerged.
     Conflicted,
+
+    /// A file whose status wasn’t looked up, because its repository has
+    /// too many files in it.
+    Unknown,
 }
 
 
@@ -259,3 +265,35 @@ impl Default for Git {
         }
     }
 }
+
+impl Git {
+
+    /// Create a Git status for a file in a repository that was skipped.
+    pub fn unknown() -> Self {
+        Self {
+            staged: GitStatus::Unknown,
+            unstaged: GitStatus::Unknown,
+        }
+    }
+}
+
+
+/// A summary of the state of a Git repository, for a directory that’s the
+/// root of its working tree.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct GitRepo {
+
+    /// The name of the branch that’s checked out, or the abbreviated ID of
+    /// the commit if the head is detached.
+    pub head: String,
+
+    /// Whether anything has changed in the working tree or the index since
+    /// the last commit, including new files that aren’t being ignored.
+    pub dirty: bool,
+
+    /// How many commits the branch has that its upstream doesn’t.
+    pub ahead: usize,
+
+    /// How many commits the branch’s upstream has that it doesn’t.
+    pub behind: usize,
+}
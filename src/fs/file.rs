@@ -2,14 +2,16 @@
 
 use std::io;
 #[cfg(unix)]
-use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use log::*;
+use once_cell::sync::OnceCell;
 
+use crate::fs::archive::{Archived, EntryKind};
 use crate::fs::dir::Dir;
 use crate::fs::fields as f;
 
@@ -48,6 +50,9 @@ pub struct File<'dir> {
     /// This too is queried multiple times, and is *not* cached by the OS, as
     /// it could easily change between invocations — but exa is so short-lived
     /// it’s better to just cache it.
+    ///
+    /// Files inside archives have the metadata of the archive itself, which
+    /// gets used for anything the archive doesn’t record.
     pub metadata: std::fs::Metadata,
 
     /// A reference to the directory that contains this file, if any.
@@ -66,25 +71,60 @@ pub struct File<'dir> {
     /// directory’s children, and are in fact added specifically by exa; this
     /// means that they should be skipped when recursing.
     pub is_all_all: bool,
+
+    /// The entry inside an archive that this file is, if it isn’t on the
+    /// filesystem. Its kind, size, permissions, and modification time take
+    /// the place of the ones in the metadata.
+    pub archived: Option<Archived>,
+
+    /// This file’s created timestamp, once it’s been looked up. Getting it
+    /// can take a system call of its own, and sorting by it asks for it on
+    /// every comparison, so it only gets looked up the first time.
+    created: OnceCell<Option<SystemTime>>,
 }
 
 impl<'dir> File<'dir> {
     pub fn from_args<PD, FN>(path: PathBuf, parent_dir: PD, filename: FN) -> io::Result<File<'dir>>
+    where PD: Into<Option<&'dir Dir>>,
+          FN: Into<Option<String>>
+    {
+        debug!("Statting file {:?}", &path);
+        let metadata = std::fs::symlink_metadata(&path)?;
+
+        Ok(File::with_metadata(path, parent_dir, filename, metadata))
+    }
+
+    /// Create a new File from metadata that’s already been read, such as
+    /// when all the files in a big directory get statted at once.
+    pub fn with_metadata<PD, FN>(path: PathBuf, parent_dir: PD, filename: FN, metadata: std::fs::Metadata) -> File<'dir>
     where PD: Into<Option<&'dir Dir>>,
           FN: Into<Option<String>>
     {
         let parent_dir = parent_dir.into();
         let name       = filename.into().unwrap_or_else(|| File::filename(&path));
         let ext        = File::ext(&path);
-
-        debug!("Statting file {:?}", &path);
-        let metadata   = std::fs::symlink_metadata(&path)?;
         let is_all_all = false;
 
-        Ok(File { name, ext, path, metadata, parent_dir, is_all_all })
+        File { name, ext, path, metadata, parent_dir, is_all_all, archived: None, created: OnceCell::new() }
+    }
+
+    /// Create a new File for an entry inside an archive, which has been
+    /// read from the archive’s index rather than from the filesystem.
+    pub fn from_archive<PD, FN>(archived: Archived, parent_dir: PD, filename: FN) -> File<'dir>
+    where PD: Into<Option<&'dir Dir>>,
+          FN: Into<Option<String>>
+    {
+        let metadata = archived.archive_metadata().clone();
+        let file = File::with_metadata(archived.path(), parent_dir, filename, metadata);
+        File { archived: Some(archived), .. file }
     }
 
     pub fn new_aa_current(parent_dir: &'dir Dir) -> io::Result<File<'dir>> {
+        if let Some(archived) = parent_dir.archived() {
+            let file = File::from_archive(archived.clone(), parent_dir, String::from("."));
+            return Ok(File { is_all_all: true, .. file });
+        }
+
         let path       = parent_dir.path.clone();
         let ext        = File::ext(&path);
 
@@ -93,10 +133,15 @@ impl<'dir> File<'dir> {
         let is_all_all = true;
         let parent_dir = Some(parent_dir);
 
-        Ok(File { path, parent_dir, metadata, ext, name: ".".into(), is_all_all })
+        Ok(File { path, parent_dir, metadata, ext, name: ".".into(), is_all_all, archived: None, created: OnceCell::new() })
     }
 
     pub fn new_aa_parent(path: PathBuf, parent_dir: &'dir Dir) -> io::Result<File<'dir>> {
+        if let Some(up) = parent_dir.archived().and_then(Archived::parent) {
+            let file = File::from_archive(up, parent_dir, String::from(".."));
+            return Ok(File { is_all_all: true, .. file });
+        }
+
         let ext        = File::ext(&path);
 
         debug!("Statting file {:?}", &path);
@@ -104,7 +149,15 @@ impl<'dir> File<'dir> {
         let is_all_all = true;
         let parent_dir = Some(parent_dir);
 
-        Ok(File { path, parent_dir, metadata, ext, name: "..".into(), is_all_all })
+        Ok(File { path, parent_dir, metadata, ext, name: "..".into(), is_all_all, archived: None, created: OnceCell::new() })
+    }
+
+    /// Cuts this file loose from the directory it was read from, so it can
+    /// outlive it. Like the files passed in on the command-line, it then
+    /// gets displayed with its whole path.
+    pub fn detach<'any>(self) -> File<'any> {
+        let File { name, ext, path, metadata, is_all_all, archived, created, .. } = self;
+        File { name, ext, path, metadata, parent_dir: None, is_all_all, archived, created }
     }
 
     /// A file’s name is derived from its string. This needs to handle directories
@@ -139,7 +192,28 @@ impl<'dir> File<'dir> {
 
     /// Whether this file is a directory on the filesystem.
     pub fn is_directory(&self) -> bool {
-        self.metadata.is_dir()
+        match &self.archived {
+            Some(archived)  => *archived.kind() == EntryKind::Directory,
+            None            => self.metadata.is_dir(),
+        }
+    }
+
+    /// The number of entries in this directory, not counting the `.` and
+    /// `..` entries, or `None` if this file isn’t a directory or it can’t
+    /// be read.
+    ///
+    /// This reads the directory each time it’s called, so the result should
+    /// be kept around if it’s going to be needed more than once.
+    pub fn child_count(&self) -> Option<usize> {
+        if ! self.is_directory() {
+            return None;
+        }
+
+        if let Some(archived) = &self.archived {
+            return Some(archived.children().count());
+        }
+
+        std::fs::read_dir(&self.path).ok().map(Iterator::count)
     }
 
     /// Whether this file is a directory, or a symlink pointing to a directory.
@@ -164,14 +238,41 @@ impl<'dir> File<'dir> {
     ///
     /// Returns an IO error upon failure, but this shouldn’t be used to check
     /// if a `File` is a directory or not! For that, just use `is_directory()`.
+    ///
+    /// Directories inside archives have their contents taken from the
+    /// archive’s index, following any symlinks that lead to them.
     pub fn to_dir(&self) -> io::Result<Dir> {
-        Dir::read_dir(self.path.clone())
+        if let Some(archived) = &self.archived {
+            return match archived.target() {
+                Some(target) if *target.kind() == EntryKind::Directory  => Ok(Dir::from_archive(target)),
+                _  => Err(io::Error::new(io::ErrorKind::Other, "Not a directory inside the archive")),
+            };
+        }
+
+        Dir::read_dir(self.path.clone()).map(|dir| dir.with_identity(self.dir_identity()))
+    }
+
+    /// The device and inode numbers of this file, taken from the metadata
+    /// it already has, if it’s a directory itself. A symlink’s metadata is
+    /// the link’s own, so the directory it points to has to be statted.
+    #[cfg(unix)]
+    fn dir_identity(&self) -> Option<(u64, u64)> {
+        if self.is_directory() { Some((self.metadata.dev(), self.metadata.ino())) }
+                          else { None }
+    }
+
+    #[cfg(windows)]
+    fn dir_identity(&self) -> Option<(u64, u64)> {
+        None
     }
 
     /// Whether this file is a regular file on the filesystem — that is, not a
     /// directory, a link, or anything else treated specially.
     pub fn is_file(&self) -> bool {
-        self.metadata.is_file()
+        match &self.archived {
+            Some(archived)  => *archived.kind() == EntryKind::File,
+            None            => self.metadata.is_file(),
+        }
     }
 
     /// Whether this file is both a regular file *and* executable for the
@@ -180,12 +281,15 @@ impl<'dir> File<'dir> {
     #[cfg(unix)]
     pub fn is_executable_file(&self) -> bool {
         let bit = modes::USER_EXECUTE;
-        self.is_file() && (self.metadata.permissions().mode() & bit) == bit
+        self.is_file() && (self.mode() & bit) == bit
     }
 
     /// Whether this file is a symlink on the filesystem.
     pub fn is_link(&self) -> bool {
-        self.metadata.file_type().is_symlink()
+        match &self.archived {
+            Some(archived)  => matches!(archived.kind(), EntryKind::Symlink(_)),
+            None            => self.metadata.file_type().is_symlink(),
+        }
     }
 
     /// Whether this file is a named pipe on the filesystem.
@@ -216,7 +320,7 @@ impl<'dir> File<'dir> {
     /// Re-prefixes the path pointed to by this file, if it’s a symlink, to
     /// make it an absolute path that can be accessed from whichever
     /// directory exa is being run from.
-    fn reorient_target_path(&self, path: &Path) -> PathBuf {
+    pub fn reorient_target_path(&self, path: &Path) -> PathBuf {
         if path.is_absolute() {
             path.to_path_buf()
         }
@@ -247,6 +351,10 @@ impl<'dir> File<'dir> {
         // this file — which could be absolute or relative — to the path
         // we actually look up and turn into a `File` — which needs to be
         // absolute to be accessible from any directory.
+        if let Some(archived) = &self.archived {
+            return self.archived_link_target(archived);
+        }
+
         debug!("Reading link {:?}", &self.path);
         let path = match std::fs::read_link(&self.path) {
             Ok(p)   => p,
@@ -261,7 +369,7 @@ impl<'dir> File<'dir> {
             Ok(metadata) => {
                 let ext  = File::ext(&path);
                 let name = File::filename(&path);
-                let file = File { parent_dir: None, path, ext, metadata, name, is_all_all: false };
+                let file = File { parent_dir: None, path, ext, metadata, name, is_all_all: false, archived: None, created: OnceCell::new() };
                 FileTarget::Ok(Box::new(file))
             }
             Err(e) => {
@@ -271,6 +379,60 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// Follows a symlink inside an archive to the entry it points to. Links
+    /// that lead out of the archive are always broken, as they could only
+    /// point to something on the filesystem by chance.
+    fn archived_link_target(&self, archived: &Archived) -> FileTarget<'dir> {
+        let path = match archived.kind() {
+            EntryKind::Symlink(target)  => target.clone(),
+            _                           => return FileTarget::Err(io::Error::new(io::ErrorKind::InvalidInput, "Not a symlink")),
+        };
+
+        match archived.target() {
+            Some(target) => {
+                let file = File::from_archive(target, None, File::filename(&path));
+                FileTarget::Ok(Box::new(File { ext: File::ext(&path), path, .. file }))
+            }
+            None => {
+                FileTarget::Broken(path)
+            }
+        }
+    }
+
+    /// If this file is a symlink, returns a copy of it with the metadata of
+    /// the file it points to, so that file’s details can be listed in its
+    /// place. The path and name are kept, so it still gets displayed as the
+    /// link. Returns `None` if this isn’t a link, or if the link is broken.
+    pub fn dereferenced(&self) -> Option<File<'dir>> {
+        if ! self.is_link() {
+            return None;
+        }
+
+        if let Some(archived) = &self.archived {
+            let target = File::from_archive(archived.target()?, self.parent_dir, self.name.clone());
+            return Some(File { path: self.path.clone(), ext: self.ext.clone(), is_all_all: self.is_all_all, .. target });
+        }
+
+        match std::fs::metadata(&self.path) {
+            Ok(metadata) => {
+                Some(File {
+                    name:       self.name.clone(),
+                    ext:        self.ext.clone(),
+                    path:       self.path.clone(),
+                    parent_dir: self.parent_dir,
+                    is_all_all: self.is_all_all,
+                    metadata,
+                    archived:   None,
+                    created:    OnceCell::new(),
+                })
+            }
+            Err(e) => {
+                error!("Error dereferencing link {:?}: {:#?}", &self.path, e);
+                None
+            }
+        }
+    }
+
     /// This file’s number of hard links.
     ///
     /// It also reports whether this is both a regular file, and a file with
@@ -280,7 +442,7 @@ impl<'dir> File<'dir> {
     /// more attentively.
     #[cfg(unix)]
     pub fn links(&self) -> f::Links {
-        let count = self.metadata.nlink();
+        let count = if self.archived.is_some() { 1 } else { self.metadata.nlink() };
 
         f::Links {
             count,
@@ -288,18 +450,36 @@ impl<'dir> File<'dir> {
         }
     }
 
-    /// This file’s inode.
+    /// This file’s inode. Files inside archives have none, so they get
+    /// their position inside the archive instead.
     #[cfg(unix)]
     pub fn inode(&self) -> f::Inode {
-        f::Inode(self.metadata.ino())
+        match &self.archived {
+            Some(archived)  => f::Inode(archived.identity().1),
+            None            => f::Inode(self.metadata.ino()),
+        }
     }
 
-    /// This file’s number of filesystem blocks.
-    ///
-    /// (Not the size of each block, which we don’t actually report on)
+    /// The ID of the device this file is on. Files on the same file system
+    /// share a device ID.
+    #[cfg(unix)]
+    pub fn device_id(&self) -> Option<u64> {
+        Some(self.metadata.dev())
+    }
+
+    #[cfg(windows)]
+    pub fn device_id(&self) -> Option<u64> {
+        None
+    }
+
+    /// This file’s number of filesystem blocks, counted in 512-byte units
+    /// whatever size the file system’s own blocks are.
     #[cfg(unix)]
     pub fn blocks(&self) -> f::Blocks {
-        if self.is_file() || self.is_link() {
+        if self.archived.is_some() {
+            f::Blocks::None
+        }
+        else if self.is_file() || self.is_link() {
             f::Blocks::Some(self.metadata.blocks())
         }
         else {
@@ -345,7 +525,7 @@ impl<'dir> File<'dir> {
             })
         }
         else {
-            f::Size::Some(self.metadata.len())
+            f::Size::Some(self.length())
         }
     }
 
@@ -355,33 +535,34 @@ impl<'dir> File<'dir> {
             f::Size::None
         }
         else {
-            f::Size::Some(self.metadata.len())
+            f::Size::Some(self.length())
+        }
+    }
+
+    /// The length of this file’s contents in bytes, whatever kind of file
+    /// it is.
+    pub fn length(&self) -> u64 {
+        match &self.archived {
+            Some(archived)  => archived.size(),
+            None            => self.metadata.len(),
         }
     }
 
     /// This file’s last modified timestamp, if available on this platform.
+    /// Entries in archives that don’t record one get the archive’s.
     pub fn modified_time(&self) -> Option<SystemTime> {
-        self.metadata.modified().ok()
+        self.archived.as_ref().and_then(Archived::modified)
+            .or_else(|| self.metadata.modified().ok())
     }
 
     /// This file’s last changed timestamp, if available on this platform.
     #[cfg(unix)]
     pub fn changed_time(&self) -> Option<SystemTime> {
-        let (mut sec, mut nanosec) = (self.metadata.ctime(), self.metadata.ctime_nsec());
-
-        if sec < 0 {
-            if nanosec > 0 {
-                sec += 1;
-                nanosec -= 1_000_000_000;
-            }
-
-            let duration = Duration::new(sec.unsigned_abs(), nanosec.unsigned_abs() as u32);
-            Some(UNIX_EPOCH - duration)
-        }
-        else {
-            let duration = Duration::new(sec as u64, nanosec as u32);
-            Some(UNIX_EPOCH + duration)
+        if self.archived.is_some() {
+            return self.modified_time();
         }
+
+        Some(unix_time(self.metadata.ctime(), self.metadata.ctime_nsec()))
     }
 
     #[cfg(windows)]
@@ -391,14 +572,39 @@ impl<'dir> File<'dir> {
 
     /// This file’s last accessed timestamp, if available on this platform.
     pub fn accessed_time(&self) -> Option<SystemTime> {
+        if self.archived.is_some() {
+            return self.modified_time();
+        }
+
         self.metadata.accessed().ok()
     }
 
     /// This file’s created timestamp, if available on this platform.
     pub fn created_time(&self) -> Option<SystemTime> {
+        if self.archived.is_some() {
+            return None;
+        }
+
+        *self.created.get_or_init(|| self.birth_time())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn birth_time(&self) -> Option<SystemTime> {
         self.metadata.created().ok()
     }
 
+    /// On Linux, the birth time is only available through `statx`, which
+    /// gets asked for it directly. If the kernel is too old to have that
+    /// system call, or the file system doesn’t record birth times, this
+    /// falls back to what the standard library can find.
+    #[cfg(target_os = "linux")]
+    fn birth_time(&self) -> Option<SystemTime> {
+        match statx::birth_time(&self.path, ! self.is_link()) {
+            Some((sec, nanosec))  => Some(unix_time(sec, nanosec.into())),
+            None                  => self.metadata.created().ok(),
+        }
+    }
+
     /// This file’s ‘type’.
     ///
     /// This is used a the leftmost character of the permissions column.
@@ -448,7 +654,7 @@ impl<'dir> File<'dir> {
     /// This file’s permissions, with flags for each bit.
     #[cfg(unix)]
     pub fn permissions(&self) -> f::Permissions {
-        let bits = self.metadata.mode();
+        let bits = self.mode();
         let has_bit = |bit| bits & bit == bit;
 
         f::Permissions {
@@ -470,6 +676,31 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// This file’s mode bits. Entries in archives that don’t record them
+    /// get the ones that a new file or directory usually would.
+    #[cfg(unix)]
+    fn mode(&self) -> u32 {
+        match &self.archived {
+            Some(archived) => {
+                archived.mode().unwrap_or(match archived.kind() {
+                    EntryKind::File        => 0o644,
+                    EntryKind::Directory   => 0o755,
+                    EntryKind::Symlink(_)  => 0o777,
+                })
+            }
+            None => {
+                self.metadata.mode()
+            }
+        }
+    }
+
+    /// Whether this file has any Linux capabilities, such as `cap_net_raw`,
+    /// which grant it privileges that aren’t visible in its mode bits.
+    #[cfg(target_os = "linux")]
+    pub fn has_capabilities(&self) -> bool {
+        self.archived.is_none() && crate::fs::feature::xattr::has_capabilities(&self.path)
+    }
+
     #[cfg(windows)]
     pub fn attributes(&self) -> f::Attributes {
         let bits = self.metadata.file_attributes();
@@ -541,6 +772,110 @@ impl<'dir> FileTarget<'dir> {
 }
 
 
+/// Converts a number of seconds and nanoseconds since the Unix epoch, as
+/// returned by the various `stat` calls, into a `SystemTime`. Either of them
+/// can be negative for files from before 1970.
+#[cfg(unix)]
+fn unix_time(mut sec: i64, mut nanosec: i64) -> SystemTime {
+    if sec < 0 {
+        if nanosec > 0 {
+            sec += 1;
+            nanosec -= 1_000_000_000;
+        }
+
+        let duration = Duration::new(sec.unsigned_abs(), nanosec.unsigned_abs() as u32);
+        UNIX_EPOCH - duration
+    }
+    else {
+        let duration = Duration::new(sec as u64, nanosec as u32);
+        UNIX_EPOCH + duration
+    }
+}
+
+
+/// Access to the `statx` system call, which is the only way to get a file’s
+/// birth time on Linux. This goes through `syscall` rather than the libc
+/// wrapper, which only exists in newer versions of glibc, so older systems
+/// get an `ENOSYS` error at runtime instead of failing to link.
+#[cfg(target_os = "linux")]
+mod statx {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    use log::*;
+
+    /// The bit in `stx_mask` that says the birth time was filled in.
+    const STATX_BTIME: u32 = 0x0800;
+
+    /// One of the timestamps in a `statx` result.
+    #[repr(C)]
+    #[derive(Default)]
+    struct StatxTimestamp {
+        tv_sec:    i64,
+        tv_nsec:   u32,
+        _reserved: i32,
+    }
+
+    /// The structure the kernel fills in. Only the mask and the birth time
+    /// are used here, but the whole thing has to be the right size.
+    #[repr(C)]
+    #[derive(Default)]
+    struct Statx {
+        stx_mask:   u32,
+        _fields:    [u32; 15],
+        _stx_atime: StatxTimestamp,
+        stx_btime:  StatxTimestamp,
+        _stx_ctime: StatxTimestamp,
+        _stx_mtime: StatxTimestamp,
+        _spare:     [u64; 16],
+    }
+
+    /// Returns the seconds and nanoseconds of the birth time of the file at
+    /// the given path, or `None` if it isn’t available for any reason.
+    /// Symlinks are only followed if `follow` is set, for files that have
+    /// already had their links dereferenced.
+    pub fn birth_time(path: &Path, follow: bool) -> Option<(i64, u32)> {
+        let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        let mut buf = Statx::default();
+        let buf_ptr: *mut Statx = &mut buf;
+        let flags = if follow { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_statx,
+                libc::AT_FDCWD,
+                c_path.as_ptr(),
+                flags,
+                STATX_BTIME,
+                buf_ptr,
+            )
+        };
+
+        if result != 0 {
+            debug!("statx failed for {:?}: {}", path, std::io::Error::last_os_error());
+            return None;
+        }
+
+        if buf.stx_mask & STATX_BTIME == 0 {
+            return None;
+        }
+
+        Some((buf.stx_btime.tv_sec, buf.stx_btime.tv_nsec))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::Statx;
+
+        #[test]
+        fn kernel_size() {
+            assert_eq!(std::mem::size_of::<Statx>(), 0x100);
+        }
+    }
+}
+
+
 /// More readable aliases for the permission bits exposed by libc.
 #[allow(trivial_numeric_casts)]
 #[cfg(unix)]
@@ -626,3 +961,33 @@ mod filename_test {
         assert_eq!("/", File::filename(Path::new("/")))
     }
 }
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod unix_time_test {
+    use super::unix_time;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn epoch() {
+        assert_eq!(unix_time(0, 0), UNIX_EPOCH);
+    }
+
+    #[test]
+    fn after_epoch() {
+        assert_eq!(unix_time(10, 500), UNIX_EPOCH + Duration::new(10, 500));
+    }
+
+    #[test]
+    fn before_epoch() {
+        assert_eq!(unix_time(-10, 0), UNIX_EPOCH - Duration::new(10, 0));
+    }
+
+    #[test]
+    fn before_epoch_with_nanoseconds() {
+        // -10 seconds plus half a second is 9.5 seconds before the epoch
+        assert_eq!(unix_time(-10, 500_000_000), UNIX_EPOCH - Duration::new(9, 500_000_000));
+    }
+}
+
@@ -1,12 +1,16 @@
 //! Filtering and sorting the list of files before displaying them.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
 use std::iter::FromIterator;
-#[cfg(unix)]
-use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::fs::DotFilter;
 use crate::fs::File;
+use crate::fs::fields as f;
+use crate::info::filetype::FileExtensions;
 
 
 /// The **file filter** processes a list of files before displaying them to
@@ -23,16 +27,29 @@ use crate::fs::File;
 /// The filter also governs sorting the list. After being filtered, pairs of
 /// files are compared and sorted based on the result, with the sort field
 /// performing the comparison.
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
 pub struct FileFilter {
 
     /// Whether directories should be listed first, and other types of file
     /// second. Some users prefer it like this.
     pub list_dirs_first: bool,
 
+    /// Whether directories should be listed last, after every other type
+    /// of file. This can’t be set alongside `list_dirs_first`.
+    pub list_dirs_last: bool,
+
     /// The metadata field to sort by.
     pub sort_field: SortField,
 
+    /// The fields to sort files by when they can’t be told apart by the
+    /// main sort field, in order of priority. These get compared before
+    /// any of the main field’s own tiebreaks, such as the file name.
+    pub secondary_sorts: Vec<SortField>,
+
+    /// The seed to shuffle files with when sorting them randomly, so the
+    /// order can be reproduced. A new one gets picked if this isn’t set.
+    pub random_seed: Option<u64>,
+
     /// Whether to reverse the sorting order. This would sort the largest
     /// files first, or files starting with Z, or the most-recently-changed
     /// ones, depending on the sort field.
@@ -41,6 +58,9 @@ pub struct FileFilter {
     /// Whether to only show directories.
     pub only_dirs: bool,
 
+    /// Whether to only show files, hiding directories.
+    pub only_files: bool,
+
     /// Which invisible “dot” files to include when listing a directory.
     ///
     /// Files starting with a single “.” are used to determine “system” or
@@ -54,25 +74,85 @@ pub struct FileFilter {
     /// [Linux History: How Dot Files Became Hidden Files](https://linux-audit.com/linux-history-how-dot-files-became-hidden-files/)
     pub dot_filter: DotFilter,
 
+    /// The extensions that files must have to be listed, if any were given.
+    pub extensions: ExtensionFilter,
+
     /// Glob patterns to ignore. Any file name that matches *any* of these
     /// patterns won’t be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
 
+    /// If set, only files modified after this time will be displayed.
+    /// Files without a modified time are still displayed.
+    pub newer_than: Option<SystemTime>,
+
+    /// If set, only files modified before this time will be displayed.
+    /// Files without a modified time are never displayed.
+    pub older_than: Option<SystemTime>,
+
+    /// If set, only files bigger than this many bytes will be displayed.
+    /// Directories and devices have no size, so they’re always displayed.
+    pub bigger_than: Option<u64>,
+
+    /// If set, only files smaller than this many bytes will be displayed.
+    /// Directories and devices have no size, so they’re always displayed.
+    pub smaller_than: Option<u64>,
+
     /// Whether to ignore Git-ignored patterns.
     pub git_ignore: GitIgnore,
+
+    /// Whether to ignore files matched by `.ignore` and `.fdignore` files.
+    pub ignore_files: IgnoreFiles,
+
+    /// The most entries to list from each directory, if there’s a limit.
+    /// The rest get summed up in one line after them.
+    pub max_entries: Option<usize>,
 }
 
 impl FileFilter {
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for files found inside a directory.
     pub fn filter_child_files(&self, files: &mut Vec<File<'_>>) {
+        self.filter_child_entries(files);
+        self.remove_directories(files);
+    }
+
+    /// Remove every file in the given vector that does *not* pass the
+    /// filter predicate, like `filter_child_files`, except that directories
+    /// are kept even when only files are being listed, so they can still be
+    /// recursed into.
+    pub fn filter_child_entries(&self, files: &mut Vec<File<'_>>) {
         files.retain(|f| ! self.ignore_patterns.is_ignored(&f.name));
+        files.retain(|f| self.is_in_time_window(f) && self.is_in_size_range(f));
+        files.retain(|f| self.extensions.allows(f));
+
+        if self.ignore_files == IgnoreFiles::CheckAndIgnore {
+            IgnoreRules::filter_files(files);
+        }
 
         if self.only_dirs {
             files.retain(File::is_directory);
         }
     }
 
+    /// Remove every directory in the given vector if only files are being
+    /// listed. Symlinks to directories are not directories themselves, so
+    /// they stay.
+    pub fn remove_directories(&self, files: &mut Vec<File<'_>>) {
+        if self.only_files {
+            files.retain(|f| ! f.is_directory());
+        }
+    }
+
+    /// Cut the given files, which should already be sorted, down to the
+    /// most entries that get listed from a directory, returning the ones
+    /// that were left out.
+    pub fn limit_entries<F>(&self, files: &mut Vec<F>) -> Vec<F> {
+        match self.max_entries {
+            Some(max) if files.len() > max => files.split_off(max),
+            _ => Vec::new(),
+        }
+    }
+
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for file names specified on the command-line.
     ///
@@ -84,22 +164,105 @@ impl FileFilter {
     /// from the glob, even though the globbing is done by the shell!
     pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>) {
         files.retain(|f| {
-            ! self.ignore_patterns.is_ignored(&f.name)
+            ! self.ignore_patterns.is_ignored(&f.name) && self.is_in_time_window(f) && self.is_in_size_range(f) && self.extensions.allows(f)
         });
     }
 
+    /// Whether the given file was modified within the window given by the
+    /// `--newer-than` and `--older-than` options, if there is one.
+    fn is_in_time_window(&self, file: &File<'_>) -> bool {
+        if self.newer_than.is_none() && self.older_than.is_none() {
+            return true;
+        }
+
+        match file.modified_time() {
+            Some(time) => {
+                self.newer_than.map_or(true, |newer| time > newer) &&
+                self.older_than.map_or(true, |older| time < older)
+            }
+            None => {
+                self.older_than.is_none()
+            }
+        }
+    }
+
+    /// Whether the given file’s size is within the range given by the
+    /// `--bigger-than` and `--smaller-than` options, if there is one.
+    fn is_in_size_range(&self, file: &File<'_>) -> bool {
+        match file.size() {
+            f::Size::Some(size) => {
+                self.bigger_than.map_or(true, |bigger| size > bigger) &&
+                self.smaller_than.map_or(true, |smaller| size < smaller)
+            }
+            f::Size::None | f::Size::DeviceIDs(_) => {
+                true
+            }
+        }
+    }
+
+    /// Compares two files by the main sort field, then by each of the
+    /// secondary ones in turn, and only then by the main field’s tiebreak.
+    fn compare_files(&self, a: &File<'_>, b: &File<'_>, counts: &EntryCounts) -> Ordering {
+        let mut order = self.sort_field.compare_key(a, b, counts);
+        for field in &self.secondary_sorts {
+            order = order.then_with(|| field.compare_key(a, b, counts));
+        }
+
+        order.then_with(|| self.sort_field.compare_tiebreak(a, b))
+    }
+
     /// Sort the files in the given vector based on the sort field option.
     pub fn sort_files<'a, F>(&self, files: &mut [F])
     where F: AsRef<File<'a>>
     {
-        files.sort_by(|a, b| {
-            self.sort_field.compare_files(a.as_ref(), b.as_ref())
-        });
+        if self.sort_field == SortField::Unsorted {
+            // Files stay in the order the filesystem returned them, with the
+            // `.` and `..` entries first, so there’s nothing to sort.
+        }
+        else if self.sort_field == SortField::Random {
+            // Files get read in whatever order the filesystem returns them,
+            // so they’re sorted by name first, otherwise the same seed
+            // wouldn’t always produce the same order.
+            files.sort_by(|a, b| natord::compare(&a.as_ref().name, &b.as_ref().name));
+            shuffle(files, self.random_seed.unwrap_or_else(random_seed));
+        }
+        else {
+            let counts = self.entry_counts(files);
+            files.sort_by(|a, b| {
+                self.compare_files(a.as_ref(), b.as_ref(), &counts)
+            });
+        }
 
         if self.reverse {
             files.reverse();
         }
 
+        // Grouping happens after reversing, so `--reverse` reverses the order
+        // of the files within each group without moving the groups around.
+        self.group_directories(files);
+    }
+
+    /// Counts the entries in each of the given directories, if any of the
+    /// sort fields needs them. Counting a directory’s entries means reading
+    /// it, so this happens once per file, rather than once per comparison.
+    fn entry_counts<'a, F>(&self, files: &[F]) -> EntryCounts
+    where F: AsRef<File<'a>>
+    {
+        if self.sort_field != SortField::DirEntryCount && ! self.secondary_sorts.contains(&SortField::DirEntryCount) {
+            return EntryCounts::new();
+        }
+
+        files.iter()
+             .map(|f| (f.as_ref().path.clone(), f.as_ref().child_count().unwrap_or(0)))
+             .collect()
+    }
+
+    /// Moves the directories in the given vector before or after every
+    /// other file, if they should be grouped together, keeping the files in
+    /// each group in the same order as they were.
+    pub fn group_directories<'a, F>(&self, files: &mut [F])
+    where F: AsRef<File<'a>>
+    {
         if self.list_dirs_first {
             // This relies on the fact that `sort_by` is *stable*: it will keep
             // adjacent elements next to each other.
@@ -108,16 +271,56 @@ impl FileFilter {
                     .cmp(&a.as_ref().points_to_directory())
             });
         }
+        else if self.list_dirs_last {
+            files.sort_by(|a, b| {
+                a.as_ref().points_to_directory()
+                    .cmp(&b.as_ref().points_to_directory())
+            });
+        }
+    }
+}
+
+/// The number of entries in each directory being sorted, by its path, for
+/// sorting by `SortField::DirEntryCount`.
+type EntryCounts = HashMap<PathBuf, usize>;
+
+/// Shuffles the given items with the Fisher–Yates shuffle, using the
+/// `SplitMix64` generator, which is small enough not to need a whole crate
+/// but still good enough to make every order equally likely.
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+
+    for i in (1 .. items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
     }
 }
 
+/// Picks a new seed to shuffle with. The standard library’s hash maps
+/// already get randomly seeded for each process, so that gets reused.
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
+}
+
 
 /// User-supplied field to sort by.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum SortField {
 
-    /// Don’t apply any sorting. This is usually used as an optimisation in
-    /// scripts, where the order doesn’t matter.
+    /// Don’t apply any sorting, keeping the order the filesystem returned
+    /// the files in. This is usually used as an optimisation in scripts,
+    /// where the order doesn’t matter. Reversing and grouping directories
+    /// still happen if they were asked for.
     Unsorted,
 
     /// The file name. This is the default sorting.
@@ -163,11 +366,17 @@ pub enum SortField {
     /// The time the file was created (the “btime” or “birthtime”).
     CreatedDate,
 
-    /// The type of the file: directories, links, pipes, regular, files, etc.
-    ///
-    /// Files are ordered according to the `PartialOrd` implementation of
-    /// `fs::fields::Type`, so changing that will change this.
-    FileType,
+    /// The kind of the file, as worked out from its name and extension for
+    /// highlighting it: images, videos, documents, compiled files, and so
+    /// on. Files of the same kind are sorted by name, and files that aren’t
+    /// of any kind are listed last.
+    FileKind,
+
+    /// The number of entries in a directory, with files that aren’t
+    /// directories counting as having none. This is different from the
+    /// size, which for directories is the size of the directory entry
+    /// itself rather than anything to do with its contents.
+    DirEntryCount,
 
     /// The “age” of the file, which is the time it was modified sorted
     /// backwards. The reverse of the `ModifiedDate` ordering!
@@ -183,6 +392,22 @@ pub enum SortField {
     /// The file's name, however if the name of the file begins with `.`
     /// ignore the leading `.` and then sort as Name
     NameMixHidden(SortCase),
+
+    /// The length of the file’s name, counted in characters rather than
+    /// bytes, so names with accents aren’t listed as though they were
+    /// longer than they look. Names of the same length are sorted by name.
+    NameLength,
+
+    /// A random order, which is different each time unless a seed is
+    /// given. This isn’t a comparison between files, so the files get
+    /// shuffled separately instead.
+    Random,
+
+    /// Whether the file is a directory, with directories listed first.
+    /// Unlike grouping the directories, this is just another sort key, so
+    /// it can come after other fields in a list, and gets reversed along
+    /// with everything else.
+    DirsFirst,
 }
 
 /// Whether a field should be sorted case-sensitively or case-insensitively.
@@ -208,45 +433,51 @@ pub enum SortCase {
 impl SortField {
 
     /// Compares two files to determine the order they should be listed in,
-    /// depending on the search field.
+    /// depending on the search field, without breaking any ties.
     ///
     /// The `natord` crate is used here to provide a more *natural* sorting
     /// order than just sorting character-by-character. This splits filenames
     /// into groups between letters and numbers, and then sorts those blocks
     /// together, so `file10` will sort after `file9`, instead of before it
     /// because of the `1`.
-    pub fn compare_files(self, a: &File<'_>, b: &File<'_>) -> Ordering {
+    ///
+    /// Directories’ entry counts come from the given map, which has to have
+    /// been filled in beforehand.
+    fn compare_key(self, a: &File<'_>, b: &File<'_>, counts: &EntryCounts) -> Ordering {
         use self::SortCase::{ABCabc, AaBbCc};
 
         match self {
             Self::Unsorted  => Ordering::Equal,
+            Self::Random    => Ordering::Equal,
 
             Self::Name(ABCabc)  => natord::compare(&a.name, &b.name),
             Self::Name(AaBbCc)  => natord::compare_ignore_case(&a.name, &b.name),
 
-            Self::Size          => a.metadata.len().cmp(&b.metadata.len()),
+            Self::Size          => a.length().cmp(&b.length()),
             #[cfg(unix)]
-            Self::FileInode     => a.metadata.ino().cmp(&b.metadata.ino()),
+            Self::FileInode     => a.inode().0.cmp(&b.inode().0),
             Self::ModifiedDate  => a.modified_time().cmp(&b.modified_time()),
             Self::AccessedDate  => a.accessed_time().cmp(&b.accessed_time()),
             Self::ChangedDate   => a.changed_time().cmp(&b.changed_time()),
             Self::CreatedDate   => a.created_time().cmp(&b.created_time()),
             Self::ModifiedAge   => b.modified_time().cmp(&a.modified_time()),  // flip b and a
 
-            Self::FileType => match a.type_char().cmp(&b.type_char()) { // todo: this recomputes
-                Ordering::Equal  => natord::compare(&*a.name, &*b.name),
-                order            => order,
-            },
+            Self::FileKind => {
+                let a_kind = FileExtensions.file_type(a);
+                let b_kind = FileExtensions.file_type(b);
+                (a_kind.is_none(), a_kind).cmp(&(b_kind.is_none(), b_kind))
+            }
+
+            Self::DirEntryCount => {
+                let count = |f: &File<'_>| counts.get(&f.path).copied().unwrap_or(0);
+                count(a).cmp(&count(b))
+            }
 
-            Self::Extension(ABCabc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare(&*a.name, &*b.name),
-                order            => order,
-            },
+            Self::DirsFirst => b.points_to_directory().cmp(&a.points_to_directory()),  // flip b and a
 
-            Self::Extension(AaBbCc) => match a.ext.cmp(&b.ext) {
-                Ordering::Equal  => natord::compare_ignore_case(&*a.name, &*b.name),
-                order            => order,
-            },
+            Self::Extension(_) => a.ext.cmp(&b.ext),
+
+            Self::NameLength => a.name.chars().count().cmp(&b.name.chars().count()),
 
             Self::NameMixHidden(ABCabc) => natord::compare(
                 Self::strip_dot(&a.name),
@@ -259,6 +490,23 @@ impl SortField {
         }
     }
 
+    /// Compares two files that this field can’t tell apart. Most fields
+    /// that often have ties fall back to the file names; the others leave
+    /// the files in the order they were in. Timestamps tie more often than
+    /// you’d think, such as after a checkout writes every file at once, and
+    /// without this, those files would come out in whatever order the
+    /// filesystem returned them in.
+    pub fn compare_tiebreak(self, a: &File<'_>, b: &File<'_>) -> Ordering {
+        match self {
+            Self::FileKind | Self::DirEntryCount | Self::NameLength | Self::DirsFirst |
+            Self::ModifiedDate | Self::ModifiedAge | Self::AccessedDate |
+            Self::ChangedDate | Self::CreatedDate |
+            Self::Extension(SortCase::ABCabc)  => natord::compare(&*a.name, &*b.name),
+            Self::Extension(SortCase::AaBbCc)  => natord::compare_ignore_case(&*a.name, &*b.name),
+            _                                  => Ordering::Equal,
+        }
+    }
+
     fn strip_dot(n: &str) -> &str {
         match n.strip_prefix('.') {
             Some(s) => s,
@@ -326,19 +574,213 @@ impl IgnorePatterns {
 }
 
 
+/// The **extension filter** is a list of extensions that a file must have
+/// one of to be displayed, such as `rs` and `toml`. Directories are always
+/// let through, so that they can still be recursed into.
+#[derive(PartialEq, Eq, Default, Debug, Clone)]
+pub struct ExtensionFilter {
+    extensions: Vec<String>,
+}
+
+impl<'a> FromIterator<&'a str> for ExtensionFilter {
+
+    /// Create a new filter from the input extensions, which get lowercased
+    /// to match the file’s extension, and have any leading dot removed.
+    /// Blank inputs are skipped.
+    fn from_iter<I>(iter: I) -> Self
+    where I: IntoIterator<Item = &'a str>
+    {
+        let extensions = iter.into_iter()
+            .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|e| ! e.is_empty())
+            .collect();
+
+        Self { extensions }
+    }
+}
+
+impl ExtensionFilter {
+
+    /// Whether no extensions were given, so every file gets displayed.
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Test whether the given file should be displayed.
+    fn allows(&self, file: &File<'_>) -> bool {
+        self.is_empty() || file.is_directory() || self.matches(file.ext.as_deref())
+    }
+
+    /// Test whether a file with the given lowercased extension, if it has
+    /// one at all, is in the list.
+    fn matches(&self, ext: Option<&str>) -> bool {
+        ext.map_or(false, |ext| self.extensions.iter().any(|e| e == ext))
+    }
+}
+
+
 /// Whether to ignore or display files that Git would ignore.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
 pub enum GitIgnore {
 
     /// Ignore files that Git would ignore.
     CheckAndIgnore,
 
     /// Display files, even if Git would ignore them.
+    #[default]
     Off,
 }
 
 
 
+/// Whether to ignore or display files that are matched by the patterns in
+/// `.ignore` and `.fdignore` files, as used by ripgrep and fd.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum IgnoreFiles {
+
+    /// Ignore files that the ignore files match.
+    CheckAndIgnore,
+
+    /// Display files, even if they’re matched.
+    #[default]
+    Off,
+}
+
+/// The names of the files whose patterns get read, in order of precedence:
+/// when both are in the same directory, the later one wins.
+const IGNORE_FILE_NAMES: &[&str] = &[ ".ignore", ".fdignore" ];
+
+
+/// One line of an ignore file, which uses the same syntax as a Git ignore
+/// file: a glob pattern that matches file names, or paths relative to the
+/// ignore file’s directory if it contains a slash, with a leading `!` to
+/// un-ignore files and a trailing `/` to only match directories.
+#[derive(Debug)]
+struct IgnoreRule {
+
+    /// The directory containing the ignore file this rule is from.
+    base: PathBuf,
+
+    pattern: glob::Pattern,
+
+    /// Whether files that match are shown rather than hidden.
+    negated: bool,
+
+    /// Whether the pattern only matches directories.
+    dirs_only: bool,
+
+    /// Whether the pattern matches paths rather than file names.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+
+    /// Parses one line of an ignore file in the given directory, returning
+    /// nothing for blank lines, comments, and invalid patterns.
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest)  => (true, rest),
+            None        => (false, line),
+        };
+
+        let (dirs_only, line) = match line.strip_suffix('/') {
+            Some(rest)  => (true, rest),
+            None        => (false, line),
+        };
+
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+
+        let pattern = glob::Pattern::new(line).ok()?;
+        Some(Self { base: base.to_path_buf(), pattern, negated, dirs_only, anchored })
+    }
+
+    /// Whether this rule applies to the file with the given absolute path.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dirs_only && ! is_dir {
+            return false;
+        }
+
+        let relative = match path.strip_prefix(&self.base) {
+            Ok(r)   => r,
+            Err(_)  => return false,
+        };
+
+        if self.anchored {
+            let options = glob::MatchOptions { require_literal_separator: true, .. glob::MatchOptions::new() };
+            self.pattern.matches_path_with(relative, options)
+        }
+        else {
+            relative.file_name().map_or(false, |name| self.pattern.matches(&name.to_string_lossy()))
+        }
+    }
+}
+
+
+/// The rules from every ignore file in a directory and the directories
+/// above it, in the order they apply, so the last rule to match a file is
+/// the one that decides whether it’s ignored.
+#[derive(Debug, Default)]
+struct IgnoreRules(Vec<IgnoreRule>);
+
+impl IgnoreRules {
+
+    /// Reads the ignore files in the given absolute directory path and in
+    /// each of its parents.
+    fn for_dir(dir: &Path) -> Self {
+        let mut rules = Vec::new();
+
+        let mut ancestors = dir.ancestors().collect::<Vec<_>>();
+        ancestors.reverse();
+
+        for ancestor in ancestors {
+            for name in IGNORE_FILE_NAMES {
+                if let Ok(contents) = fs::read_to_string(ancestor.join(name)) {
+                    rules.extend(contents.lines().filter_map(|line| IgnoreRule::parse(ancestor, line)));
+                }
+            }
+        }
+
+        Self(rules)
+    }
+
+    /// Whether the file with the given absolute path should be hidden.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.0.iter().rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .map_or(false, |rule| ! rule.negated)
+    }
+
+    /// Removes every file in the given vector that an ignore file matches,
+    /// reading the rules for each directory the files are in once.
+    fn filter_files(files: &mut Vec<File<'_>>) {
+        let mut current: Option<(PathBuf, PathBuf, Self)> = None;
+
+        files.retain(|file| {
+            if file.is_all_all {
+                return true;
+            }
+
+            let parent = file.path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+            if current.as_ref().map_or(true, |c| c.0 != parent) {
+                let absolute = fs::canonicalize(if parent.as_os_str().is_empty() { Path::new(".") } else { &parent })
+                                  .unwrap_or_else(|_| parent.clone());
+                let rules = Self::for_dir(&absolute);
+                current = Some((parent, absolute, rules));
+            }
+
+            let (_, absolute, rules) = current.as_ref().unwrap();
+            ! rules.is_ignored(&absolute.join(&file.name), file.is_directory())
+        });
+    }
+}
+
+
 #[cfg(test)]
 mod test_ignores {
     use super::*;
@@ -374,3 +816,641 @@ mod test_ignores {
         assert!(pats.is_ignored("test.mp3"));
     }
 }
+
+
+#[cfg(test)]
+mod test_extensions {
+    use super::*;
+
+    #[test]
+    fn empty_matches_nothing() {
+        let exts = ExtensionFilter::default();
+        assert!(exts.is_empty());
+        assert!(!exts.matches(Some("rs")));
+    }
+
+    #[test]
+    fn matches_a_list() {
+        let exts = ExtensionFilter::from_iter("rs,TOML,.md".split(','));
+        assert!(exts.matches(Some("rs")));
+        assert!(exts.matches(Some("toml")));
+        assert!(exts.matches(Some("md")));
+        assert!(!exts.matches(Some("lock")));
+    }
+
+    #[test]
+    fn no_extension() {
+        let exts = ExtensionFilter::from_iter(vec![ "rs" ]);
+        assert!(!exts.matches(None));
+    }
+
+    #[test]
+    fn blanks_are_skipped() {
+        let exts = ExtensionFilter::from_iter("rs,,".split(','));
+        assert_eq!(exts, ExtensionFilter::from_iter(vec![ "rs" ]));
+        assert!(!exts.matches(Some("")));
+    }
+}
+
+
+/// Fixtures for the tests below that make real files in a directory and
+/// check what the filter does with them.
+#[cfg(test)]
+mod test_files {
+    use super::*;
+    use crate::fs::test::temp_dir;
+
+    /// Creates a directory holding the given files with the given contents,
+    /// along with any directories they go in. Paths ending in a slash are
+    /// created as empty directories instead.
+    pub fn make_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = temp_dir(name);
+
+        for (path, contents) in files {
+            let full = root.join(path);
+            if path.ends_with('/') {
+                fs::create_dir_all(full).unwrap();
+            }
+            else {
+                fs::create_dir_all(full.parent().unwrap()).unwrap();
+                fs::write(full, contents).unwrap();
+            }
+        }
+
+        root
+    }
+
+    /// Reads the files in a directory, in the order the OS returns them.
+    pub fn read_files<'dir>(root: &Path) -> Vec<File<'dir>> {
+        fs::read_dir(root).unwrap()
+            .map(|e| File::from_args(e.unwrap().path(), None, None).unwrap())
+            .collect()
+    }
+
+    /// Returns the names of the files in a directory after sorting them
+    /// with the given filter.
+    pub fn sorted_dir(root: &Path, filter: &FileFilter) -> Vec<String> {
+        let mut files = read_files(root);
+        filter.sort_files(&mut files);
+        files.into_iter().map(|f| f.name).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod test_dir_entry_counts {
+    use super::*;
+    use super::test_files::*;
+
+    fn make_dirs(name: &str, populations: &[(&str, usize)]) -> PathBuf {
+        let root = make_files(name, &[ ("file", "contents") ]);
+
+        for (dir, count) in populations {
+            let dir = root.join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            for i in 0 .. *count {
+                fs::write(dir.join(i.to_string()), "").unwrap();
+            }
+        }
+
+        root
+    }
+
+    fn sorted_names(root: &Path, reverse: bool) -> Vec<String> {
+        sorted_dir(root, &FileFilter { reverse, sort_field: SortField::DirEntryCount, .. FileFilter::default() })
+    }
+
+    #[test]
+    fn fewest_entries_first() {
+        let root = make_dirs("dirsize", &[ ("busy", 5), ("empty", 0), ("quiet", 1), ("quieter", 1) ]);
+        assert_eq!(sorted_names(&root, false), vec![ "empty", "file", "quiet", "quieter", "busy" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn most_entries_first() {
+        let root = make_dirs("dirsize-rev", &[ ("busy", 3), ("empty", 0), ("quiet", 2), ("quieter", 1) ]);
+        assert_eq!(sorted_names(&root, true), vec![ "busy", "quiet", "quieter", "file", "empty" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn counts_as_tiebreak() {
+        let root = make_dirs("dirsize-then", &[ ("busy", 3), ("empty", 0), ("quiet", 2), ("quieter", 1) ]);
+        let filter = FileFilter { sort_field: SortField::DirsFirst, secondary_sorts: vec![ SortField::DirEntryCount ], .. FileFilter::default() };
+        assert_eq!(sorted_dir(&root, &filter), vec![ "empty", "quieter", "quiet", "busy", "file" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn files_have_no_count() {
+        let root = make_dirs("dirsize-file", &[ ("quiet", 2) ]);
+        let file = File::from_args(root.join("file"), None, None).unwrap();
+        let dir = File::from_args(root.join("quiet"), None, None).unwrap();
+        assert_eq!(file.child_count(), None);
+        assert_eq!(dir.child_count(), Some(2));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod test_ignore_files {
+    use super::*;
+    use super::test_files::*;
+
+    fn make_tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        fs::canonicalize(make_files(name, files)).unwrap()
+    }
+
+    fn visible(dir: &Path) -> Vec<String> {
+        let mut files = read_files(dir);
+        IgnoreRules::filter_files(&mut files);
+
+        let mut names = files.into_iter().map(|f| f.name).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn names_and_negation() {
+        let root = make_tree("ignore-names", &[
+            (".ignore",   "# logs\n*.log\n!keep.log\n"),
+            ("a.log",     ""),
+            ("keep.log",  ""),
+            ("a.txt",     ""),
+        ]);
+
+        assert_eq!(visible(&root), vec![ ".ignore", "a.txt", "keep.log" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn nested_files_override() {
+        let root = make_tree("ignore-nested", &[
+            (".ignore",            "*.log\n/top.txt\n"),
+            ("top.txt",            ""),
+            ("sub/top.txt",        ""),
+            ("sub/.fdignore",      "!debug.log\nbuild/\n"),
+            ("sub/debug.log",      ""),
+            ("sub/other.log",      ""),
+            ("sub/build/output",   ""),
+        ]);
+
+        assert_eq!(visible(&root), vec![ ".ignore", "sub" ]);
+        assert_eq!(visible(&root.join("sub")), vec![ ".fdignore", "debug.log", "top.txt" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn anchored_paths() {
+        let root = make_tree("ignore-anchored", &[
+            (".ignore",         "sub/*.txt\n"),
+            ("a.txt",           ""),
+            ("sub/b.txt",       ""),
+            ("sub/deeper/c.txt", ""),
+        ]);
+
+        assert_eq!(visible(&root.join("sub")), vec![ "deeper" ]);
+        assert_eq!(visible(&root.join("sub").join("deeper")), vec![ "c.txt" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn fdignore_wins() {
+        let root = make_tree("ignore-precedence", &[
+            (".ignore",    "!*.tmp\n"),
+            (".fdignore",  "*.tmp\n"),
+            ("a.tmp",      ""),
+        ]);
+
+        assert_eq!(visible(&root), vec![ ".fdignore", ".ignore" ]);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod test_only_files {
+    use super::*;
+    use super::test_files::*;
+
+    #[test]
+    fn directories_disappear() {
+        let root = make_files("only-files", &[ ("dir/", ""), ("file", "") ]);
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("file", root.join("link")).unwrap();
+
+        let mut files = read_files(&root);
+        let filter = FileFilter { only_files: true, .. FileFilter::default() };
+        filter.filter_child_files(&mut files);
+        filter.sort_files(&mut files);
+
+        let names = files.into_iter().map(|f| f.name).collect::<Vec<_>>();
+        if cfg!(unix) {
+            assert_eq!(names, vec![ "file", "link" ]);
+        }
+        else {
+            assert_eq!(names, vec![ "file" ]);
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod test_dirs_grouping {
+    use super::*;
+    use super::test_files::*;
+
+    fn sorted_names(name: &str, reverse: bool, list_dirs_first: bool, list_dirs_last: bool) -> Vec<String> {
+        let root = make_files(&[ "dirs-", name ].concat(), &[ ("b-dir/", ""), ("d-dir/", ""), ("a-file", ""), ("c-file", "") ]);
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("b-dir", root.join("c-link")).unwrap();
+
+        let names = sorted_dir(&root, &FileFilter { list_dirs_first, list_dirs_last, reverse, .. FileFilter::default() });
+        fs::remove_dir_all(root).unwrap();
+        names
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn mixed_together() {
+        assert_eq!(sorted_names("mixed", false, false, false), vec![ "a-file", "b-dir", "c-file", "c-link", "d-dir" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn directories_first() {
+        assert_eq!(sorted_names("first", false, true, false), vec![ "b-dir", "c-link", "d-dir", "a-file", "c-file" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn directories_last() {
+        assert_eq!(sorted_names("last", false, false, true), vec![ "a-file", "c-file", "b-dir", "c-link", "d-dir" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn reversed_directories_first() {
+        assert_eq!(sorted_names("rev-first", true, true, false), vec![ "d-dir", "c-link", "b-dir", "c-file", "a-file" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn reversed_directories_last() {
+        assert_eq!(sorted_names("rev-last", true, false, true), vec![ "c-file", "a-file", "d-dir", "c-link", "b-dir" ]);
+    }
+}
+
+
+#[cfg(test)]
+mod test_dirs_sort_key {
+    use super::*;
+    use super::test_files::*;
+
+    fn sorted_names(name: &str, sort_fields: &[SortField], reverse: bool) -> Vec<String> {
+        let root = make_files(&[ "dir-key-", name ].concat(), &[
+            ("b-dir/",  ""),
+            ("d-dir/",  ""),
+            ("a-file",  "xxxxx"),
+            ("c-file",  "x"),
+            ("e-file",  "xxx"),
+        ]);
+
+        let filter = FileFilter {
+            reverse,
+            sort_field: sort_fields[0],
+            secondary_sorts: sort_fields[1..].to_vec(),
+            .. FileFilter::default()
+        };
+
+        let names = sorted_dir(&root, &filter);
+        fs::remove_dir_all(root).unwrap();
+        names
+    }
+
+    #[test]
+    fn dirs_alone() {
+        assert_eq!(sorted_names("dir", &[ SortField::DirsFirst ], false), vec![ "b-dir", "d-dir", "a-file", "c-file", "e-file" ]);
+    }
+
+    #[test]
+    fn dirs_then_name() {
+        let fields = [ SortField::DirsFirst, SortField::Name(SortCase::AaBbCc) ];
+        assert_eq!(sorted_names("dir-name", &fields, false), vec![ "b-dir", "d-dir", "a-file", "c-file", "e-file" ]);
+    }
+
+    #[test]
+    fn dirs_then_size() {
+        let fields = [ SortField::DirsFirst, SortField::Size ];
+        assert_eq!(sorted_names("dir-size", &fields, false), vec![ "b-dir", "d-dir", "c-file", "e-file", "a-file" ]);
+    }
+
+    #[test]
+    fn size_then_dirs() {
+        let fields = [ SortField::Size, SortField::DirsFirst ];
+        let names = sorted_names("size-dir", &fields, false);
+        assert_eq!(names.iter().filter(|n| n.ends_with("-file")).collect::<Vec<_>>(), vec![ "c-file", "e-file", "a-file" ]);
+    }
+
+    #[test]
+    fn reversed() {
+        let fields = [ SortField::DirsFirst, SortField::Name(SortCase::AaBbCc) ];
+        assert_eq!(sorted_names("dir-rev", &fields, true), vec![ "e-file", "c-file", "a-file", "d-dir", "b-dir" ]);
+    }
+}
+
+
+#[cfg(test)]
+mod test_name_lengths {
+    use super::*;
+    use super::test_files::*;
+
+    fn sorted_names(name: &str, file_names: &[&str]) -> Vec<String> {
+        let files = file_names.iter().map(|n| (*n, "")).collect::<Vec<_>>();
+        let root = make_files(&[ "name-len-", name ].concat(), &files);
+
+        let names = sorted_dir(&root, &FileFilter { sort_field: SortField::NameLength, .. FileFilter::default() });
+        fs::remove_dir_all(root).unwrap();
+        names
+    }
+
+    #[test]
+    fn mixed_lengths() {
+        assert_eq!(sorted_names("mixed", &[ "medium", "a", "longest-one", "abc" ]),
+                   vec![ "a", "abc", "medium", "longest-one" ]);
+    }
+
+    #[test]
+    fn equal_lengths_by_name() {
+        assert_eq!(sorted_names("equal", &[ "file2", "zzz", "file1", "aaa" ]),
+                   vec![ "aaa", "zzz", "file1", "file2" ]);
+    }
+
+    #[test]
+    fn characters_not_bytes() {
+        // “ééé” is six bytes long, but only three characters.
+        assert_eq!(sorted_names("chars", &[ "abcd", "ééé", "ab" ]),
+                   vec![ "ab", "ééé", "abcd" ]);
+    }
+}
+
+
+#[cfg(test)]
+mod test_unsorted {
+    use super::*;
+    use super::test_files::*;
+
+    fn sorted_names(name: &str, reverse: bool, list_dirs_first: bool) -> (Vec<String>, Vec<String>) {
+        let root = make_files(&[ "unsorted-", name ].concat(), &[
+            ("c", ""), ("a", ""), ("e", ""), ("b", ""), ("d", ""), ("z-dir/", ""), ("y-dir/", ""),
+        ]);
+
+        let mut files = read_files(&root);
+        let before = files.iter().map(|f| f.name.clone()).collect();
+
+        let filter = FileFilter { list_dirs_first, reverse, sort_field: SortField::Unsorted, .. FileFilter::default() };
+        filter.sort_files(&mut files);
+        fs::remove_dir_all(root).unwrap();
+        (before, files.into_iter().map(|f| f.name).collect())
+    }
+
+    #[test]
+    fn order_preserved() {
+        let (before, after) = sorted_names("plain", false, false);
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn order_reversed() {
+        let (mut before, after) = sorted_names("reverse", true, false);
+        before.reverse();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn directories_grouped() {
+        let (before, after) = sorted_names("first", false, true);
+        let (dirs, files): (Vec<_>, Vec<_>) = before.into_iter().partition(|name| name.ends_with("-dir"));
+        assert_eq!(after, dirs.into_iter().chain(files).collect::<Vec<_>>());
+    }
+}
+
+
+#[cfg(test)]
+mod test_secondary_sorts {
+    use super::*;
+    use super::test_files::*;
+
+    fn sorted_names(name: &str, sort_field: SortField, secondary_sorts: Vec<SortField>) -> Vec<String> {
+        let root = make_files(&[ "then-", name ].concat(), &[
+            ("a.rs",   "xxxxx"),
+            ("b.rs",   "x"),
+            ("c.txt",  "xxx"),
+            ("d.txt",  "xx"),
+            ("e.txt",  "xx"),
+            ("f",      "xxx"),
+        ]);
+
+        let names = sorted_dir(&root, &FileFilter { sort_field, secondary_sorts, .. FileFilter::default() });
+        fs::remove_dir_all(root).unwrap();
+        names
+    }
+
+    #[test]
+    fn extension_alone() {
+        let ext = SortField::Extension(SortCase::AaBbCc);
+        assert_eq!(sorted_names("ext", ext, Vec::new()), vec![ "f", "a.rs", "b.rs", "c.txt", "d.txt", "e.txt" ]);
+    }
+
+    #[test]
+    fn extension_then_size() {
+        let ext = SortField::Extension(SortCase::AaBbCc);
+        assert_eq!(sorted_names("ext-size", ext, vec![ SortField::Size ]), vec![ "f", "b.rs", "a.rs", "d.txt", "e.txt", "c.txt" ]);
+    }
+
+    #[test]
+    fn size_then_extension() {
+        let ext = SortField::Extension(SortCase::AaBbCc);
+        assert_eq!(sorted_names("size-ext", SortField::Size, vec![ ext, SortField::Name(SortCase::AaBbCc) ]), vec![ "b.rs", "d.txt", "e.txt", "f", "c.txt", "a.rs" ]);
+    }
+
+    #[test]
+    fn size_then_name() {
+        let names = sorted_names("size-name", SortField::Size, vec![ SortField::NameMixHidden(SortCase::AaBbCc) ]);
+        assert_eq!(names, vec![ "b.rs", "d.txt", "e.txt", "c.txt", "f", "a.rs" ]);
+    }
+
+    #[test]
+    fn counts_then_size() {
+        let names = sorted_names("count-size", SortField::DirEntryCount, vec![ SortField::Size ]);
+        assert_eq!(names, vec![ "b.rs", "d.txt", "e.txt", "c.txt", "f", "a.rs" ]);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test_date_ties {
+    use super::*;
+    use super::test_files::*;
+    use crate::fs::test::set_times;
+
+    fn sorted_names(name: &str, sort_field: SortField, reverse: bool) -> Vec<String> {
+        let times = [ ("file10", 1_000), ("b", 1_000), ("file9", 1_000), ("A", 1_000), ("old", 500), ("new", 2_000) ];
+        let root = make_files(&[ "date-ties-", name ].concat(), &times.iter().map(|(n, _)| (*n, "")).collect::<Vec<_>>());
+        for (name, seconds) in &times {
+            set_times(&root.join(name), *seconds);
+        }
+
+        let names = sorted_dir(&root, &FileFilter { sort_field, reverse, .. FileFilter::default() });
+        fs::remove_dir_all(root).unwrap();
+        names
+    }
+
+    #[test]
+    fn modified() {
+        assert_eq!(sorted_names("modified", SortField::ModifiedDate, false), vec![ "old", "A", "b", "file9", "file10", "new" ]);
+    }
+
+    #[test]
+    fn modified_reversed() {
+        assert_eq!(sorted_names("modified-rev", SortField::ModifiedDate, true), vec![ "new", "file10", "file9", "b", "A", "old" ]);
+    }
+
+    #[test]
+    fn age() {
+        assert_eq!(sorted_names("age", SortField::ModifiedAge, false), vec![ "new", "A", "b", "file9", "file10", "old" ]);
+    }
+
+    #[test]
+    fn accessed() {
+        assert_eq!(sorted_names("accessed", SortField::AccessedDate, false), vec![ "old", "A", "b", "file9", "file10", "new" ]);
+    }
+
+    #[test]
+    fn changed_and_created_tiebreaks() {
+        let root = make_files("date-ties-tiebreak", &[ ("file10", ""), ("file9", "") ]);
+
+        let a = File::from_args(root.join("file9"), None, None).unwrap();
+        let b = File::from_args(root.join("file10"), None, None).unwrap();
+        assert_eq!(SortField::ChangedDate.compare_tiebreak(&a, &b), Ordering::Less);
+        assert_eq!(SortField::CreatedDate.compare_tiebreak(&b, &a), Ordering::Greater);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod test_max_entries {
+    use super::*;
+    use super::test_files::*;
+
+    /// Sorts the files in a directory by name and cuts them down to the
+    /// given number, returning the names left along with how many went.
+    fn limited_names(name: &str, reverse: bool, max_entries: Option<usize>) -> (Vec<String>, usize) {
+        let root = make_files(&[ "max-", name ].concat(), &[ ("a", ""), ("b", ""), ("c", ""), ("d", ""), ("e", "") ]);
+
+        let mut files = read_files(&root);
+        let filter = FileFilter { reverse, max_entries, .. FileFilter::default() };
+        filter.sort_files(&mut files);
+        let more = filter.limit_entries(&mut files).len();
+        fs::remove_dir_all(root).unwrap();
+        (files.into_iter().map(|f| f.name).collect(), more)
+    }
+
+    #[test]
+    fn no_limit() {
+        assert_eq!(limited_names("none", false, None), (vec![ "a", "b", "c", "d", "e" ].into_iter().map(String::from).collect(), 0));
+    }
+
+    #[test]
+    fn limited() {
+        assert_eq!(limited_names("limited", false, Some(2)), (vec![ String::from("a"), String::from("b") ], 3));
+    }
+
+    #[test]
+    fn limited_after_reversing() {
+        assert_eq!(limited_names("reversed", true, Some(2)), (vec![ String::from("e"), String::from("d") ], 3));
+    }
+
+    #[test]
+    fn limit_not_reached() {
+        assert_eq!(limited_names("exact", false, Some(5)).1, 0);
+        assert_eq!(limited_names("over", false, Some(9)).1, 0);
+    }
+}
+
+
+#[cfg(test)]
+mod test_shuffle {
+    use super::*;
+
+    fn shuffled(seed: u64) -> Vec<u32> {
+        let mut items = (0 .. 20).collect::<Vec<_>>();
+        shuffle(&mut items, seed);
+        items
+    }
+
+    #[test]
+    fn same_seed_same_order() {
+        assert_eq!(shuffled(42), shuffled(42));
+    }
+
+    #[test]
+    fn different_seeds_different_orders() {
+        assert_ne!(shuffled(42), shuffled(43));
+    }
+
+    #[test]
+    fn actually_shuffled() {
+        assert_ne!(shuffled(42), (0 .. 20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn nothing_lost() {
+        let mut items = shuffled(42);
+        items.sort_unstable();
+        assert_eq!(items, (0 .. 20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_and_single() {
+        let mut empty: Vec<u32> = Vec::new();
+        shuffle(&mut empty, 42);
+        assert!(empty.is_empty());
+
+        let mut single = vec![ 1 ];
+        shuffle(&mut single, 42);
+        assert_eq!(single, vec![ 1 ]);
+    }
+
+    #[test]
+    fn seeded_sort() {
+        use super::test_files::*;
+
+        let names = [ "a", "b", "c", "d", "e", "f", "g", "h" ];
+        let root = make_files("shuffle", &names.iter().map(|n| (*n, "")).collect::<Vec<_>>());
+
+        let mut files = read_files(&root);
+        let filter = FileFilter { sort_field: SortField::Random, random_seed: Some(7), .. FileFilter::default() };
+        filter.sort_files(&mut files);
+        let first = files.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+
+        // The same seed gives the same order, whatever order the files
+        // started out in.
+        files.reverse();
+        filter.sort_files(&mut files);
+        let second = files.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+
+        fs::remove_dir_all(root).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, names);
+    }
+}
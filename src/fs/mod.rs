@@ -4,7 +4,40 @@ pub use self::dir::{Dir, DotFilter};
 mod file;
 pub use self::file::{File, FileTarget};
 
+pub mod archive;
 pub mod dir_action;
 pub mod feature;
 pub mod fields;
 pub mod filter;
+pub mod mounts;
+
+
+#[cfg(test)]
+pub mod test {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Creates an empty directory for a test to put its files in, with the
+    /// given name and the process ID so runs don’t trip over each other.
+    /// Anything left over from an earlier run gets removed first.
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("exa-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    /// Sets the modified and accessed times of the file at the given path
+    /// to the given number of seconds after the epoch.
+    #[cfg(unix)]
+    #[allow(trivial_numeric_casts)]
+    pub fn set_times(path: &Path, seconds: i64) {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let time = libc::timespec { tv_sec: seconds as libc::time_t, tv_nsec: 0 };
+        let times = [ time, time ];
+        assert_eq!(unsafe { libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) }, 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Listing the file systems that are mounted, so each file can be shown
+//! with the type of the one it lives on.
+//!
+//! The mount table only gets read on Linux, through `/proc/mounts`. On other
+//! platforms it’s always empty, so every file’s file system is unknown.
+
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+
+
+/// One entry in the mount table.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MountedFs {
+
+    /// The directory the file system is mounted on.
+    pub dest: PathBuf,
+
+    /// The type of the file system, such as `ext4` or `tmpfs`.
+    pub fstype: String,
+
+    /// The device or other source that was mounted.
+    pub source: String,
+}
+
+lazy_static! {
+    static ref MOUNTS: Vec<MountedFs> = get_mount_points();
+}
+
+/// Returns the mount table, which only gets read the first time it’s
+/// needed.
+pub fn mount_points() -> &'static [MountedFs] {
+    &MOUNTS
+}
+
+/// Reads the mount table from the system.
+#[cfg(target_os = "linux")]
+pub fn get_mount_points() -> Vec<MountedFs> {
+    use log::*;
+
+    match std::fs::read_to_string("/proc/mounts") {
+        Ok(contents) => {
+            parse_mounts(&contents)
+        }
+        Err(e) => {
+            warn!("Couldn't read mount table: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// There’s no mount table to read on this platform.
+#[cfg(not(target_os = "linux"))]
+pub fn get_mount_points() -> Vec<MountedFs> {
+    Vec::new()
+}
+
+/// Parses the contents of `/proc/mounts`, which has one mount on each line
+/// with its source, destination, and type separated by spaces, followed by
+/// fields that aren’t needed.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mounts(contents: &str) -> Vec<MountedFs> {
+    contents.lines().filter_map(|line| {
+        let mut fields = line.split(' ');
+        let source = unescape(fields.next()?);
+        let dest = PathBuf::from(unescape(fields.next()?));
+        let fstype = unescape(fields.next()?);
+        Some(MountedFs { dest, fstype, source })
+    }).collect()
+}
+
+/// Turns the octal escapes that the mount table uses for spaces, tabs,
+/// newlines, and backslashes, such as `\040`, back into those characters.
+fn unescape(field: &str) -> String {
+    let mut result = String::with_capacity(field.len());
+    let mut rest = field;
+
+    while let Some(index) = rest.find('\\') {
+        result.push_str(&rest[.. index]);
+        rest = &rest[index ..];
+
+        match rest.get(1 .. 4).and_then(|octal| u8::from_str_radix(octal, 8).ok()) {
+            Some(byte) => {
+                result.push(char::from(byte));
+                rest = &rest[4 ..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[1 ..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Finds the file system that the given absolute path is on, which is the
+/// one with the longest mount point that contains it. When more than one
+/// file system is mounted on the same directory, the last one hides the
+/// others, so it’s the one that counts.
+pub fn mount_for<'m>(mounts: &'m [MountedFs], path: &Path) -> Option<&'m MountedFs> {
+    mounts.iter()
+          .filter(|m| path.starts_with(&m.dest))
+          .fold(None, |best: Option<&MountedFs>, m| match best {
+              Some(b) if b.dest.components().count() > m.dest.components().count()  => Some(b),
+              _                                                                      => Some(m),
+          })
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static TABLE: &str = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid 0 0
+tmpfs /tmp tmpfs rw 0 0
+server:/home /home/my\\040files nfs rw 0 0
+overlay /tmp overlay rw 0 0
+";
+
+    fn fstype(path: &str) -> Option<String> {
+        let mounts = parse_mounts(TABLE);
+        mount_for(&mounts, Path::new(path)).map(|m| m.fstype.clone())
+    }
+
+    #[test]
+    fn parses_lines() {
+        let mounts = parse_mounts(TABLE);
+        assert_eq!(mounts.len(), 5);
+        assert_eq!(mounts[0], MountedFs { dest: PathBuf::from("/"), fstype: "ext4".into(), source: "/dev/sda1".into() });
+    }
+
+    #[test]
+    fn unescapes_spaces() {
+        assert_eq!(unescape("/home/my\\040files"), "/home/my files");
+        assert_eq!(unescape("back\\\\slash"), "back\\\\slash");
+        assert_eq!(unescape("back\\134slash"), "back\\slash");
+    }
+
+    #[test]
+    fn root() {
+        assert_eq!(fstype("/etc/passwd"), Some("ext4".into()));
+    }
+
+    #[test]
+    fn longest_mount_point_wins() {
+        assert_eq!(fstype("/proc/self"), Some("proc".into()));
+        assert_eq!(fstype("/home/my files/notes"), Some("nfs".into()));
+        assert_eq!(fstype("/home/other"), Some("ext4".into()));
+    }
+
+    #[test]
+    fn mount_point_itself() {
+        assert_eq!(fstype("/proc"), Some("proc".into()));
+    }
+
+    #[test]
+    fn last_mount_hides_earlier_ones() {
+        assert_eq!(fstype("/tmp/file"), Some("overlay".into()));
+    }
+
+    #[test]
+    fn prefixes_are_not_mount_points() {
+        assert_eq!(fstype("/processes"), Some("ext4".into()));
+    }
+
+    #[test]
+    fn unknown() {
+        let mounts = parse_mounts("");
+        assert_eq!(mount_for(&mounts, Path::new("/")), None);
+    }
+}
@@ -14,6 +14,24 @@ use crate::theme::FileColours;
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct FileExtensions;
 
+/// The kind of a file, as worked out from its name and extension. This is
+/// what file names get highlighted by, and what they can be sorted by.
+///
+/// Files get sorted in the order these variants are declared in.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum FileType {
+    Immediate,
+    Image,
+    Video,
+    Music,
+    Lossless,
+    Crypto,
+    Document,
+    Compressed,
+    Compiled,
+    Temp,
+}
+
 impl FileExtensions {
 
     /// An “immediate” file is something that can be run or activated somehow
@@ -102,25 +120,67 @@ impl FileExtensions {
             false
         }
     }
+
+    /// The colour of a source file in one of a handful of well-known
+    /// languages, loosely based on the colour each language is known by,
+    /// so a directory full of code isn’t all the same colour. These only
+    /// apply to files that aren’t any of the other kinds.
+    fn source_colour(&self, file: &File<'_>) -> Option<Style> {
+        use ansi_term::Colour::Fixed;
+
+        Some(match file.ext.as_deref()? {
+            "rs"                                   => Fixed(173).normal(),
+            "py" | "pyi"                           => Fixed(67).normal(),
+            "js" | "mjs" | "cjs" | "jsx"           => Fixed(185).normal(),
+            "ts" | "mts" | "cts" | "tsx"           => Fixed(74).normal(),
+            "go"                                   => Fixed(80).normal(),
+            "c" | "h"                              => Fixed(110).normal(),
+            "cc" | "cpp" | "cxx" | "hh" | "hpp"    => Fixed(168).normal(),
+            "java" | "kt"                          => Fixed(130).normal(),
+            "rb"                                   => Fixed(124).normal(),
+            "sh" | "bash" | "zsh" | "fish"         => Fixed(113).normal(),
+            _                                      => return None,
+        })
+    }
+
+    /// Works out which kind of file this is, if it’s any of them. Some
+    /// files could count as more than one kind, so the first check that
+    /// succeeds wins: a temporary backup of an image is a temporary file.
+    pub fn file_type(&self, file: &File<'_>) -> Option<FileType> {
+        Some(match file {
+            f if self.is_temp(f)        => FileType::Temp,
+            f if self.is_immediate(f)   => FileType::Immediate,
+            f if self.is_image(f)       => FileType::Image,
+            f if self.is_video(f)       => FileType::Video,
+            f if self.is_music(f)       => FileType::Music,
+            f if self.is_lossless(f)    => FileType::Lossless,
+            f if self.is_crypto(f)      => FileType::Crypto,
+            f if self.is_document(f)    => FileType::Document,
+            f if self.is_compressed(f)  => FileType::Compressed,
+            f if self.is_compiled(f)    => FileType::Compiled,
+            _                           => return None,
+        })
+    }
 }
 
 impl FileColours for FileExtensions {
     fn colour_file(&self, file: &File<'_>) -> Option<Style> {
         use ansi_term::Colour::*;
 
-        Some(match file {
-            f if self.is_temp(f)        => Fixed(244).normal(),
-            f if self.is_immediate(f)   => Yellow.bold().underline(),
-            f if self.is_image(f)       => Fixed(133).normal(),
-            f if self.is_video(f)       => Fixed(135).normal(),
-            f if self.is_music(f)       => Fixed(92).normal(),
-            f if self.is_lossless(f)    => Fixed(93).normal(),
-            f if self.is_crypto(f)      => Fixed(109).normal(),
-            f if self.is_document(f)    => Fixed(105).normal(),
-            f if self.is_compressed(f)  => Red.normal(),
-            f if self.is_compiled(f)    => Fixed(137).normal(),
-            _                           => return None,
-        })
+        let kind_colour = self.file_type(file).map(|t| match t {
+            FileType::Temp        => Fixed(244).normal(),
+            FileType::Immediate   => Yellow.bold().underline(),
+            FileType::Image       => Fixed(133).normal(),
+            FileType::Video       => Fixed(135).normal(),
+            FileType::Music       => Fixed(92).normal(),
+            FileType::Lossless    => Fixed(93).normal(),
+            FileType::Crypto      => Fixed(109).normal(),
+            FileType::Document    => Fixed(105).normal(),
+            FileType::Compressed  => Red.normal(),
+            FileType::Compiled    => Fixed(137).normal(),
+        });
+
+        kind_colour.or_else(|| self.source_colour(file))
     }
 }
 
@@ -142,3 +202,90 @@ impl FileIcon for FileExtensions {
         }
     }
 }
+
+
+#[cfg(test)]
+mod colour_test {
+    use super::*;
+    use std::fs;
+    use ansi_term::Colour::*;
+    use crate::fs::test::temp_dir;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    /// Makes files with each of the given names, then returns the colour
+    /// they’d get from the given file colours.
+    fn colours(name: &str, colours: &dyn FileColours, names: &[&str]) -> Vec<Option<Style>> {
+        let root = temp_dir(&[ "filetype-", name ].concat());
+
+        let styles = names.iter()
+            .map(|name| {
+                fs::write(root.join(name), "").unwrap();
+                File::from_args(root.join(name), None, None).unwrap()
+            })
+            .map(|file| colours.colour_file(&file))
+            .collect();
+
+        fs::remove_dir_all(root).unwrap();
+        styles
+    }
+
+    #[test]
+    fn languages() {
+        let styles = colours("languages", &FileExtensions, &[ "main.rs", "app.py", "index.js", "lib.ts", "run.sh" ]);
+        assert_eq!(styles, vec![ Some(Fixed(173).normal()), Some(Fixed(67).normal()), Some(Fixed(185).normal()), Some(Fixed(74).normal()), Some(Fixed(113).normal()) ]);
+    }
+
+    #[test]
+    fn uppercase_extension() {
+        assert_eq!(colours("uppercase", &FileExtensions, &[ "MAIN.RS" ]), vec![ Some(Fixed(173).normal()) ]);
+    }
+
+    #[test]
+    fn file_types_first() {
+        // A backup of a source file is a temporary file, and a build script
+        // is still immediate
+        let styles = colours("types", &FileExtensions, &[ "main.rs.bak", "Gruntfile.js" ]);
+        assert_eq!(styles, vec![ Some(Fixed(244).normal()), Some(Yellow.bold().underline()) ]);
+    }
+
+    #[test]
+    fn unknown_extension() {
+        assert_eq!(colours("unknown", &FileExtensions, &[ "notes.txt", "Makefile.in" ]), vec![ None, None ]);
+    }
+
+    #[test]
+    fn overridden() {
+        let theme = ThemeOptions {
+            use_colours: UseColours::Always,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions { ls: None, exa: Some("*.rs=31".into()), theme_file: None },
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(true);
+
+        let styles = colours("overridden", theme.exts.as_ref(), &[ "main.rs", "app.py" ]);
+        assert_eq!(styles, vec![ Some(Red.normal()), Some(Fixed(67).normal()) ]);
+    }
+
+    #[test]
+    fn glob_case_sensitive() {
+        // Globs are matched against the file’s real name, so they can tell
+        // extensions in different cases apart, even though the built-in
+        // colours can’t.
+        let theme = ThemeOptions {
+            use_colours: UseColours::Always,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions { ls: None, exa: Some("*.JPG=31:*.png=32".into()), theme_file: None },
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(true);
+
+        let image = colours("built-in", &FileExtensions, &[ "photo.jpg" ]).remove(0);
+        assert!(image.is_some());
+
+        let styles = colours("case", theme.exts.as_ref(), &[ "photo.JPG", "photo.jpg", "image.png", "image.PNG" ]);
+        assert_eq!(styles, vec![ Some(Red.normal()), image, Some(Green.normal()), image ]);
+    }
+}
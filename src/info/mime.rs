@@ -0,0 +1,209 @@
+//! Working out the MIME types of files.
+//!
+//! Like the file types used for highlighting, this mostly goes by a file’s
+//! extension, as that doesn’t need the file to be opened. Files without an
+//! extension get the first few bytes of their contents checked for one of
+//! a handful of well-known signatures instead.
+
+use std::io::Read;
+
+use log::*;
+
+use crate::fs::File;
+
+
+/// Works out the MIME type of the given file, if it’s one that exa knows
+/// about. Directories and symlinks get the `inode/` types that the `file`
+/// command uses for them; other special files have no type.
+pub fn mime_type(file: &File<'_>) -> Option<&'static str> {
+    if file.is_directory() {
+        Some("inode/directory")
+    }
+    else if file.is_link() {
+        Some("inode/symlink")
+    }
+    else if ! file.is_file() {
+        None
+    }
+    else if let Some(ext) = &file.ext {
+        from_extension(ext)
+    }
+    else {
+        from_contents(&read_start(file)?)
+    }
+}
+
+/// Returns the MIME type that files with the given lowercase extension
+/// usually have.
+fn from_extension(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        // Images
+        "png"                          => "image/png",
+        "jpg" | "jpeg" | "jpe"         => "image/jpeg",
+        "gif"                          => "image/gif",
+        "bmp"                          => "image/bmp",
+        "tif" | "tiff"                 => "image/tiff",
+        "webp"                         => "image/webp",
+        "svg"                          => "image/svg+xml",
+        "ico"                          => "image/vnd.microsoft.icon",
+        "avif"                         => "image/avif",
+        "heif"                         => "image/heif",
+        "heic"                         => "image/heic",
+        "jxl"                          => "image/jxl",
+
+        // Video
+        "mp4" | "m4v"                  => "video/mp4",
+        "mkv"                          => "video/x-matroska",
+        "webm"                         => "video/webm",
+        "mov"                          => "video/quicktime",
+        "avi"                          => "video/x-msvideo",
+        "mpg" | "mpeg"                 => "video/mpeg",
+        "ogv"                          => "video/ogg",
+        "flv"                          => "video/x-flv",
+        "wmv"                          => "video/x-ms-wmv",
+
+        // Audio
+        "mp3"                          => "audio/mpeg",
+        "m4a" | "aac"                  => "audio/aac",
+        "ogg" | "oga"                  => "audio/ogg",
+        "opus"                         => "audio/opus",
+        "flac"                         => "audio/flac",
+        "wav"                          => "audio/wav",
+        "mka"                          => "audio/x-matroska",
+
+        // Documents
+        "pdf"                          => "application/pdf",
+        "rtf"                          => "application/rtf",
+        "doc"                          => "application/msword",
+        "docx"                         => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls"                          => "application/vnd.ms-excel",
+        "xlsx"                         => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt"                          => "application/vnd.ms-powerpoint",
+        "pptx"                         => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt"                          => "application/vnd.oasis.opendocument.text",
+        "epub"                         => "application/epub+zip",
+
+        // Archives
+        "zip"                          => "application/zip",
+        "tar"                          => "application/x-tar",
+        "gz" | "tgz"                   => "application/gzip",
+        "bz2"                          => "application/x-bzip2",
+        "xz" | "txz"                   => "application/x-xz",
+        "zst"                          => "application/zstd",
+        "7z"                           => "application/x-7z-compressed",
+        "rar"                          => "application/vnd.rar",
+        "deb"                          => "application/vnd.debian.binary-package",
+        "rpm"                          => "application/x-rpm",
+        "iso"                          => "application/x-iso9660-image",
+
+        // Text
+        "txt"                          => "text/plain",
+        "md" | "markdown"              => "text/markdown",
+        "html" | "htm"                 => "text/html",
+        "css"                          => "text/css",
+        "csv"                          => "text/csv",
+        "tsv"                          => "text/tab-separated-values",
+        "xml"                          => "application/xml",
+        "json"                         => "application/json",
+        "toml"                         => "application/toml",
+        "yaml" | "yml"                 => "application/yaml",
+        "js" | "mjs"                   => "text/javascript",
+        "sh"                           => "application/x-sh",
+
+        // Fonts
+        "ttf"                          => "font/ttf",
+        "otf"                          => "font/otf",
+        "woff"                         => "font/woff",
+        "woff2"                        => "font/woff2",
+
+        // Binaries
+        "wasm"                         => "application/wasm",
+        "jar"                          => "application/java-archive",
+        "class"                        => "application/java-vm",
+
+        _                              => return None,
+    })
+}
+
+/// Returns the MIME type of a file that starts with the given bytes, if
+/// they match one of the signatures that exa checks for.
+fn from_contents(start: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n",  "image/png"),
+        (b"\xFF\xD8\xFF",       "image/jpeg"),
+        (b"GIF87a",             "image/gif"),
+        (b"GIF89a",             "image/gif"),
+        (b"%PDF-",              "application/pdf"),
+        (b"PK\x03\x04",         "application/zip"),
+        (b"\x1F\x8B",           "application/gzip"),
+        (b"BZh",                "application/x-bzip2"),
+        (b"\xFD7zXZ\x00",       "application/x-xz"),
+        (b"\x28\xB5\x2F\xFD",   "application/zstd"),
+        (b"\x7FELF",            "application/x-executable"),
+        (b"#!",                 "text/x-script"),
+    ];
+
+    SIGNATURES.iter()
+              .find(|(signature, _)| start.starts_with(signature))
+              .map(|(_, mime)| *mime)
+}
+
+/// Reads enough of the start of the file to check it against each of the
+/// signatures, or fewer bytes if the file is shorter than that.
+fn read_start(file: &File<'_>) -> Option<Vec<u8>> {
+    let mut buf = Vec::with_capacity(8);
+
+    match std::fs::File::open(&file.path) {
+        Ok(f) => {
+            f.take(8).read_to_end(&mut buf).ok()?;
+            Some(buf)
+        }
+        Err(e) => {
+            debug!("Couldn't read {} to work out its type: {}", file.path.display(), e);
+            None
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn known_extension() {
+        assert_eq!(from_extension("png"), Some("image/png"));
+        assert_eq!(from_extension("jpeg"), Some("image/jpeg"));
+    }
+
+    #[test]
+    fn unknown_extension() {
+        assert_eq!(from_extension("exa"), None);
+    }
+
+    #[test]
+    fn png_signature() {
+        assert_eq!(from_contents(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+    }
+
+    #[test]
+    fn elf_signature() {
+        assert_eq!(from_contents(b"\x7FELF\x02\x01\x01"), Some("application/x-executable"));
+    }
+
+    #[test]
+    fn script_signature() {
+        assert_eq!(from_contents(b"#!/bin/sh"), Some("text/x-script"));
+    }
+
+    #[test]
+    fn too_short() {
+        assert_eq!(from_contents(b"\x89P"), None);
+    }
+
+    #[test]
+    fn no_signature() {
+        assert_eq!(from_contents(b"hello"), None);
+        assert_eq!(from_contents(b""), None);
+    }
+}
@@ -22,20 +22,27 @@
 #![allow(clippy::upper_case_acronyms)]
 #![allow(clippy::wildcard_imports)]
 
+use std::collections::HashMap;
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::io::{self, Write, ErrorKind};
-use std::path::{Component, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, Command, Stdio};
 
 use ansi_term::{ANSIStrings, Style};
 
 use log::*;
 
 use crate::fs::{Dir, File};
+use crate::fs::archive::{Archived, Format};
+use crate::fs::dir_action::{DirAction, RecurseOptions};
 use crate::fs::feature::git::GitCache;
 use crate::fs::filter::GitIgnore;
 use crate::options::{Options, Vars, vars, OptionsResult};
-use crate::output::{escape, lines, grid, grid_details, details, View, Mode};
+use crate::output::{escape, lines, grid, grid_details, delimited, details, stat, table, zero, View, Mode, Paging};
+use crate::output::details::Summary;
+use crate::output::file_name::EmbedHyperlinks;
+use crate::output::table::SizePercent;
 use crate::theme::Theme;
 
 mod fs;
@@ -61,22 +68,59 @@ fn main() {
         warn!("Failed to enable ANSI support: {}", e);
     }
 
+    // Options from the config file go first, so the command-line ones win.
+    let defaults = Options::config_args(&LiveVars);
     let args: Vec<_> = env::args_os().skip(1).collect();
-    match Options::parse(args.iter().map(std::convert::AsRef::as_ref), &LiveVars) {
-        OptionsResult::Ok(options, mut input_paths) => {
+    match Options::parse_with_defaults(defaults.iter().map(std::convert::AsRef::as_ref), args.iter().map(std::convert::AsRef::as_ref), &LiveVars) {
+        OptionsResult::Ok(mut options, mut input_paths) => {
+
+            // Paths listed in a file get listed after the arguments, as
+            // though they’d been given as arguments themselves.
+            let listed_paths = match options.paths_from {
+                Some(ref path) => match read_paths_from(path, matches!(options.view.mode, Mode::Zero)) {
+                    Ok(paths)  => paths,
+                    Err(e)     => {
+                        eprintln!("exa: {}: {}", path.display(), e);
+                        exit(exits::RUNTIME_ERROR);
+                    }
+                },
+                None => Vec::new(),
+            };
+            input_paths.extend(listed_paths.iter().map(OsString::as_os_str));
 
-            // List the current directory by default.
+            // List the current directory by default, unless the paths were
+            // meant to come from a file, which could have been empty.
             // (This has to be done here, otherwise git_options won’t see it.)
-            if input_paths.is_empty() {
+            if input_paths.is_empty() && options.paths_from.is_none() {
                 input_paths = vec![ OsStr::new(".") ];
             }
 
             let git = git_options(&options, &input_paths);
-            let writer = io::stdout();
+            let writer = Output::for_paging(&options.view.paging);
 
             let console_width = options.view.width.actual_terminal_width();
-            let theme = options.theme.to_theme(console_width.is_some());
-            let exa = Exa { options, writer, input_paths, theme, console_width, git };
+            let is_tty = options.is_tty(console_width.is_some());
+
+            // Nothing fits in a terminal with no width, so a width of zero
+            // gets the views used when there’s no terminal at all.
+            let console_width = console_width.filter(|width| *width > 0);
+            let theme = options.theme.to_theme(is_tty);
+
+            let show_icons = options.view.file_style.show_icons;
+            options.view.file_style.show_icons = show_icons.for_terminal(is_tty);
+
+            let classify = options.view.file_style.classify;
+            options.view.file_style.classify = classify.for_terminal(is_tty);
+
+            // Hyperlinks are escape sequences like colours are, so they
+            // shouldn’t end up in output that isn’t going to a terminal.
+            if ! is_tty {
+                options.view.file_style.embed_hyperlinks = EmbedHyperlinks::Off;
+            }
+
+            let archives = peek_archives(&options, &input_paths);
+
+            let exa = Exa { options, writer, input_paths, theme, console_width, git, size_total: None, summary: Summary::default(), listed_any: false, empty_branches: HashMap::new(), archives };
 
             match exa.run() {
                 Ok(exit_status) => {
@@ -103,6 +147,10 @@ fn main() {
             print!("{}", version_str);
         }
 
+        OptionsResult::Completions(completions) => {
+            print!("{}", completions);
+        }
+
         OptionsResult::InvalidOptions(error) => {
             eprintln!("exa: {}", error);
 
@@ -123,7 +171,7 @@ pub struct Exa<'args> {
     pub options: Options,
 
     /// The output handle that we write to.
-    pub writer: io::Stdout,
+    pub writer: Output,
 
     /// List of the free command-line arguments that should correspond to file
     /// names (anything that isn’t an option).
@@ -142,6 +190,193 @@ pub struct Exa<'args> {
     /// This has to last the lifetime of the program, because the user might
     /// want to list several directories in the same repository.
     pub git: Option<GitCache>,
+
+    /// The total size of every file being listed, if file sizes are being
+    /// shown as a percentage of it. This has to be worked out before
+    /// anything gets printed.
+    pub size_total: Option<u64>,
+
+    /// The number of files and directories listed so far, and their total
+    /// size, which gets shown at the end when `--summary` is given.
+    pub summary: Summary,
+
+    /// Whether anything has been listed yet, so the listings after the first
+    /// one know they carry on from it.
+    pub listed_any: bool,
+
+    /// Whether each directory that’s been checked for pruning has nothing
+    /// in it to list. Each directory gets checked when its parent gets
+    /// listed, then again for each of its own children, so the answers
+    /// are kept rather than reading the same directories over and over.
+    pub empty_branches: HashMap<PathBuf, bool>,
+
+    /// The archives given as arguments that have been looked inside, so
+    /// they can be listed like directories, or the errors from trying to
+    /// read them.
+    pub archives: Vec<(&'args OsStr, io::Result<Archived>)>,
+}
+
+/// Where exa’s output goes. Normally this is straight to stdout, but when
+/// paging, it has to be held back until it’s known whether it’s too tall to
+/// fit in the terminal.
+pub enum Output {
+
+    /// Write straight to stdout.
+    Stdout(io::Stdout),
+
+    /// Collect the output until it’s more lines than the terminal’s height,
+    /// then start the given pager command and send it everything so far.
+    /// Output that never gets that tall goes to stdout at the end.
+    Held { buffer: Vec<u8>, lines: usize, height: usize, command: String },
+
+    /// Write to the pager that’s been started.
+    Paged(Child),
+
+    /// Collect the output to be looked at afterwards.
+    #[cfg(test)]
+    Buffer(Vec<u8>),
+}
+
+impl Output {
+
+    /// Picks where to write to based on the paging option. Paging only
+    /// happens when stdout is a terminal, which is also the only time its
+    /// height is known.
+    fn for_paging(paging: &Paging) -> Self {
+        let height = terminal_size::terminal_size().map(|(_, h)| usize::from(h.0));
+
+        match (paging, height) {
+            (Paging::Auto(command), Some(height))  => Self::Held { buffer: Vec::new(), lines: 0, height, command: command.clone() },
+            _                                      => Self::Stdout(io::stdout()),
+        }
+    }
+
+    /// Starts the pager, and sends it the output that’s been held back. If
+    /// the pager can’t be run, everything gets written to stdout instead.
+    fn start_pager(&mut self) -> io::Result<()> {
+        let (buffer, child) = match self {
+            Self::Held { buffer, command, .. }  => (std::mem::take(buffer), spawn_pager(command)),
+            _                                   => return Ok(()),
+        };
+
+        *self = match child {
+            Some(child)  => Self::Paged(child),
+            None         => Self::Stdout(io::stdout()),
+        };
+
+        self.write_all(&buffer)
+    }
+
+    /// Sends any held-back output to wherever it should go, and waits for
+    /// the pager to be quit if one was started. This gets called after
+    /// everything has been written, and again when the output is dropped,
+    /// so that nothing gets lost when listing stops early with an error.
+    fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => {
+                stdout.flush()
+            }
+            Self::Held { buffer, .. } => {
+                let buffer = std::mem::take(buffer);
+                io::stdout().write_all(&buffer)
+            }
+            Self::Paged(child) => {
+                // The pager’s stdin gets dropped first, so it sees the end.
+                drop(child.stdin.take());
+                child.wait().map(drop)
+            }
+            #[cfg(test)]
+            Self::Buffer(_) => {
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns everything that’s been written to a test buffer.
+    #[cfg(test)]
+    fn written(&self) -> String {
+        match self {
+            Self::Buffer(buffer)  => String::from_utf8(buffer.clone()).unwrap(),
+            _                     => unreachable!(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Self::Held { buffer, lines, height, .. } = self {
+            buffer.extend_from_slice(buf);
+
+            #[allow(clippy::naive_bytecount)]
+            let count = buf.iter().filter(|&&b| b == b'\n').count();
+            *lines += count;
+
+            // Leave a line at the bottom for the shell prompt.
+            if *lines >= *height {
+                self.start_pager()?;
+            }
+
+            return Ok(buf.len());
+        }
+
+        match self {
+            Self::Stdout(stdout)      => stdout.write(buf),
+            Self::Held { .. }         => unreachable!(),
+            Self::Paged(child)        => match child.stdin {
+                Some(ref mut stdin)   => stdin.write(buf),
+                None                  => Ok(buf.len()),
+            },
+            #[cfg(test)]
+            Self::Buffer(buffer)      => buffer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout)      => stdout.flush(),
+            Self::Paged(child)        => match child.stdin {
+                Some(ref mut stdin)   => stdin.flush(),
+                None                  => Ok(()),
+            },
+            Self::Held { .. }         => Ok(()),
+            #[cfg(test)]
+            Self::Buffer(_)           => Ok(()),
+        }
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        // Only a listing that’s already failed gets here with anything left
+        // to send, so there’s nowhere better to report this.
+        if let Err(e) = self.finish() {
+            warn!("Error finishing output: {:?}", e.kind());
+        }
+    }
+}
+
+/// Runs the pager command, which can include arguments, with its stdin
+/// piped from exa. Returns nothing if the pager can’t be run. Quitting the
+/// pager before it’s read everything gives a broken pipe, which gets
+/// handled the same way as when writing to stdout.
+fn spawn_pager(command: &str) -> Option<Child> {
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+
+    let mut pager = Command::new(program);
+    pager.args(words).stdin(Stdio::piped());
+
+    if env::var_os(vars::LESS).is_none() {
+        pager.env(vars::LESS, "R");
+    }
+
+    match pager.spawn() {
+        Ok(child) => Some(child),
+        Err(e) => {
+            warn!("Couldn't run pager {:?}: {}", command, e);
+            None
+        }
+    }
 }
 
 /// The “real” environment variables type.
@@ -154,11 +389,54 @@ impl Vars for LiveVars {
     }
 }
 
+/// Reads the paths in the file given to `--paths-from`, which are separated
+/// by newlines, or by NUL bytes when the output is. Blank lines get skipped,
+/// and so do the carriage returns of files with Windows line endings.
+fn read_paths_from(path: &Path, zero: bool) -> io::Result<Vec<OsString>> {
+    let contents = std::fs::read(path)?;
+    let separator = if zero { b'\0' } else { b'\n' };
+
+    let paths = contents.split(|&b| b == separator)
+        .map(|line| if zero { line } else { line.strip_suffix(b"\r").unwrap_or(line) })
+        .filter(|line| ! line.is_empty())
+        .map(os_string_from_bytes)
+        .collect();
+
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Reads the indexes of the archives given as arguments, if the options
+/// say to look inside them. Arguments that aren’t archives exa can read
+/// get left out, as do ones that can’t be found.
+fn peek_archives<'args>(options: &Options, args: &[&'args OsStr]) -> Vec<(&'args OsStr, io::Result<Archived>)> {
+    if ! options.peek_archives || matches!(options.dir_action, DirAction::AsFile) {
+        return Vec::new();
+    }
+
+    args.iter().filter_map(|&arg| {
+        let file = File::from_args(PathBuf::from(arg), None, None).ok()?;
+        let format = Format::of(&file).filter(|_| file.is_file())?;
+        Some((arg, Archived::peek(&file.path, format)))
+    }).collect()
+}
+
 /// Create a Git cache populated with the arguments that are going to be
 /// listed before they’re actually listed, if the options demand it.
 fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
     if options.should_scan_for_git() {
-        Some(args.iter().map(PathBuf::from).collect())
+        let git = args.iter().map(PathBuf::from).collect::<GitCache>();
+        Some(git.with_file_limit(options.git_file_limit))
     }
     else {
         None
@@ -166,6 +444,23 @@ fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
 }
 
 impl<'args> Exa<'args> {
+
+    /// Sets exa up with the given command-line arguments and no paths,
+    /// writing to a buffer rather than stdout, for the tests to drive.
+    #[cfg(test)]
+    fn for_test(args: &[&str]) -> Self {
+        match Options::parse(args.iter().map(OsStr::new), &None::<OsString>) {
+            OptionsResult::Ok(options, _) => {
+                let theme = options.theme.to_theme(false);
+                let writer = Output::Buffer(Vec::new());
+                Exa { options, writer, input_paths: Vec::new(), theme, console_width: None, git: None, size_total: None, summary: Summary::default(), listed_any: false, empty_branches: HashMap::new(), archives: Vec::new() }
+            }
+            _ => {
+                panic!("Options didn't parse")
+            }
+        }
+    }
+
     /// # Errors
     ///
     /// Will return `Err` if printing to stderr fails.
@@ -177,14 +472,24 @@ impl<'args> Exa<'args> {
         let mut exit_status = 0;
 
         for file_path in &self.input_paths {
-            match File::from_args(PathBuf::from(file_path), None, None) {
+            let file = match self.archives.iter().find(|(arg, _)| arg == file_path) {
+                Some((_, Ok(archived))) => Ok(File::from_archive(archived.clone(), None, file_path.to_string_lossy().into_owned())),
+                Some((_, Err(e)))       => {
+                    exit_status = 2;
+                    writeln!(io::stderr(), "{:?}: {}", file_path, e)?;
+                    continue;
+                }
+                None                    => File::from_args(PathBuf::from(file_path), None, None),
+            };
+
+            match file {
                 Err(e) => {
                     exit_status = 2;
                     writeln!(io::stderr(), "{:?}: {}", file_path, e)?;
                 }
 
                 Ok(f) => {
-                    if f.points_to_directory() && ! self.options.dir_action.treat_dirs_as_files() {
+                    if self.lists_contents(&f) {
                         match f.to_dir() {
                             Ok(d)   => dirs.push(d),
                             Err(e)  => writeln!(io::stderr(), "{:?}: {}", file_path, e)?,
@@ -205,27 +510,93 @@ impl<'args> Exa<'args> {
         let is_only_dir = dirs.len() == 1 && no_files;
 
         self.options.filter.filter_argument_files(&mut files);
+
+        if self.options.view.size_percent() == Some(SizePercent::Global) {
+            self.size_total = Some(self.listing_size(&files, &dirs));
+        }
+
+        // The delimited view’s header row goes before everything, rather
+        // than before each directory’s worth of files.
+        if let Mode::Delimited(ref opts) = self.options.view.mode {
+            let filter = &self.options.filter;
+            let full_paths = self.options.dir_action.recurse_options().is_some();
+            let r = delimited::Render { files: Vec::new(), theme: &self.theme, opts, filter, git: self.git.as_ref(), full_paths, size_total: None };
+            r.render_header(&mut self.writer)?;
+        }
+
+        // A flat listing has the contents of every directory gathered up
+        // with the files that were passed in, and gets printed all at once.
+        if let Some(recurse_opts) = self.options.dir_action.recurse_options().filter(|r| r.flat) {
+            for dir in &dirs {
+                self.add_flat_files(dir, recurse_opts, &mut files)?;
+            }
+
+            self.print_files(None, files)?;
+            self.print_summary()?;
+            self.writer.finish()?;
+            return Ok(exit_status);
+        }
+
         self.print_files(None, files)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        let exit_status = self.print_dirs(dirs, no_files, is_only_dir, exit_status)?;
+        self.print_summary()?;
+        self.writer.finish()?;
+        Ok(exit_status)
+    }
+
+    /// Whether a file given as an argument should have its contents listed
+    /// as a directory, rather than being listed itself. As with `ls`,
+    /// symlinks to directories get followed unless the long view is being
+    /// used, where they’re only followed with `--dereference-command-line`.
+    fn lists_contents(&self, file: &File<'_>) -> bool {
+        if self.options.dir_action.treat_dirs_as_files() || ! file.points_to_directory() {
+            return false;
+        }
+
+        ! file.is_link()
+            || self.options.dereference_command_line
+            || ! matches!(self.options.view.mode, Mode::Details(_) | Mode::GridDetails(_))
+    }
+
+    /// Prints the number of files and directories that were listed, and
+    /// their total size, across every directory, if it was asked for.
+    fn print_summary(&mut self) -> io::Result<()> {
+        if let Some(size_format) = self.options.view.summary() {
+            writeln!(&mut self.writer, "{}", self.summary.render(&self.theme, size_format).strings())?;
+        }
+
+        Ok(())
     }
 
     fn print_dirs(&mut self, dir_files: Vec<Dir>, mut first: bool, is_only_dir: bool, exit_status: i32) -> io::Result<i32> {
+        // The zero and delimited views have to be usable by other programs,
+        // so they can’t have any gaps or headers that aren’t file names.
+        let is_zero = matches!(self.options.view.mode, Mode::Zero | Mode::Delimited(_));
+
+        // When listing each level at once, the directories inside this lot
+        // get saved up until they’ve all been listed.
+        let mut next_level = Vec::new();
+
         for dir in dir_files {
 
             // Put a gap between directories, or between the list of files and
             // the first directory.
-            if first {
+            if first || is_zero {
                 first = false;
             }
             else {
                 writeln!(&mut self.writer)?;
             }
 
-            if ! is_only_dir {
+            if ! is_only_dir && ! is_zero {
                 let mut bits = Vec::new();
                 escape(dir.path.display().to_string(), &mut bits, Style::default(), Style::default());
-                writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
+
+                match self.git_head(&dir) {
+                    Some(head)  => writeln!(&mut self.writer, "{}: {}", ANSIStrings(&bits), self.theme.ui.git.branch.paint(format!("({})", head)))?,
+                    None        => writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?,
+                }
             }
 
             let mut children = Vec::new();
@@ -237,56 +608,286 @@ impl<'args> Exa<'args> {
                 }
             };
 
-            self.options.filter.filter_child_files(&mut children);
+            // Directories are only removed after the ones to recurse into
+            // have been picked out, so `--only-files` still recurses.
+            self.options.filter.filter_child_entries(&mut children);
             self.options.filter.sort_files(&mut children);
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
+                recurse_opts.filter_child_files(&mut children);
+
                 let depth = dir.path.components().filter(|&c| c != Component::CurDir).count() + 1;
                 if ! recurse_opts.tree && ! recurse_opts.is_too_deep(depth) {
+                    let device = if recurse_opts.one_file_system { dir.device_id() }
+                                                            else { None };
 
+                    // A directory that contains itself, through a symlink
+                    // or a bind mount, only gets listed the once, instead
+                    // of forever.
                     let mut child_dirs = Vec::new();
-                    for child_dir in children.iter().filter(|f| f.is_directory() && ! f.is_all_all) {
-                        match child_dir.to_dir() {
-                            Ok(d)   => child_dirs.push(d),
-                            Err(e)  => writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?,
+                    for child_dir in children.iter().filter(|f| recurse_opts.is_recursable(f) && ! f.is_all_all && ! recurse_opts.is_other_file_system(device, f)) {
+                        match child_dir.to_dir().map(|d| dir.enter(d)) {
+                            Ok(Some(d))  => child_dirs.push(d),
+                            Ok(None)     => {},
+                            Err(e)       => writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?,
                         }
                     }
 
+                    // Empty directories disappear from their parent’s
+                    // listing, as well as not getting listed themselves.
+                    if recurse_opts.prune {
+                        let (empty, kept) = child_dirs.into_iter().partition::<Vec<_>, _>(|d| self.is_empty_branch(d, recurse_opts));
+                        children.retain(|f| ! empty.iter().any(|d| d.path == f.path));
+                        child_dirs = kept;
+                    }
+
+                    self.options.filter.remove_directories(&mut children);
+                    let more = self.options.filter.limit_entries(&mut children);
                     self.print_files(Some(&dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
-                        Ok(_)   => (),
-                        Err(e)  => return Err(e),
+                    self.print_more(&more)?;
+
+                    if recurse_opts.files_first {
+                        next_level.extend(child_dirs);
+                    }
+                    else {
+                        match self.print_dirs(child_dirs, false, false, exit_status) {
+                            Ok(_)   => (),
+                            Err(e)  => return Err(e),
+                        }
                     }
                     continue;
                 }
             }
 
+            self.options.filter.remove_directories(&mut children);
+            let more = self.options.filter.limit_entries(&mut children);
             self.print_files(Some(&dir), children)?;
+            self.print_more(&more)?;
+        }
+
+        if ! next_level.is_empty() {
+            self.print_dirs(next_level, false, false, exit_status)?;
         }
 
         Ok(exit_status)
     }
 
+    /// Prints the line that sums up the entries left out of a directory’s
+    /// listing by `--max-entries`, if there were any. The zero and delimited
+    /// views don’t get one, as it isn’t a file name. The entries still count
+    /// towards the summary, even though they don’t get a row.
+    fn print_more(&mut self, more: &[File<'_>]) -> io::Result<()> {
+        for file in more {
+            self.summary.add(file);
+        }
+
+        let more = more.len();
+        if more == 0 || matches!(self.options.view.mode, Mode::Zero | Mode::Delimited(_)) {
+            return Ok(());
+        }
+
+        writeln!(&mut self.writer, "{}", self.theme.ui.punctuation.paint(format!("… and {} more", more)))
+    }
+
+    /// Adds every file in the given directory that would get listed when
+    /// recursing to the flat listing, followed by the files in each of the
+    /// directories inside it that get descended into.
+    fn add_flat_files(&mut self, dir: &Dir, recurse_opts: RecurseOptions, flat: &mut Vec<File<'_>>) -> io::Result<()> {
+        let mut children = Vec::new();
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        for file in dir.files(self.options.filter.dot_filter, self.git.as_ref(), git_ignore) {
+            match file {
+                Ok(file)        => children.push(file),
+                Err((path, e))  => writeln!(io::stderr(), "[{}: {}]", path.display(), e)?,
+            }
+        };
+
+        self.options.filter.filter_child_entries(&mut children);
+        recurse_opts.filter_child_files(&mut children);
+
+        // The `.` and `..` entries of every directory would all just get
+        // in the way of each other.
+        children.retain(|f| ! f.is_all_all);
+
+        let mut child_dirs = Vec::new();
+        let depth = dir.path.components().filter(|&c| c != Component::CurDir).count() + 1;
+        if ! recurse_opts.is_too_deep(depth) {
+            let device = if recurse_opts.one_file_system { dir.device_id() }
+                                                    else { None };
+
+            for child_dir in children.iter().filter(|f| recurse_opts.is_recursable(f) && ! recurse_opts.is_other_file_system(device, f)) {
+                match child_dir.to_dir().map(|d| dir.enter(d)) {
+                    Ok(Some(d))  => child_dirs.push(d),
+                    Ok(None)     => {},
+                    Err(e)       => writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?,
+                }
+            }
+
+            if recurse_opts.prune {
+                let (empty, kept) = child_dirs.into_iter().partition::<Vec<_>, _>(|d| self.is_empty_branch(d, recurse_opts));
+                children.retain(|f| ! empty.iter().any(|d| d.path == f.path));
+                child_dirs = kept;
+            }
+        }
+
+        self.options.filter.remove_directories(&mut children);
+        flat.extend(children.into_iter().map(File::detach));
+
+        for child_dir in &child_dirs {
+            self.add_flat_files(child_dir, recurse_opts, flat)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the given directory has nothing in it that would get listed
+    /// when recursing, once every filter has been applied, counting what’s
+    /// in the directories inside it too. Directories that don’t get
+    /// descended into, such as ones that are too deep, always count as
+    /// something to list.
+    fn is_empty_branch(&mut self, dir: &Dir, recurse_opts: RecurseOptions) -> bool {
+        if let Some(&empty) = self.empty_branches.get(&dir.path) {
+            return empty;
+        }
+
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        let mut children = dir.files(self.options.filter.dot_filter, self.git.as_ref(), git_ignore)
+                              .filter_map(Result::ok)
+                              .collect::<Vec<_>>();
+
+        self.options.filter.filter_child_entries(&mut children);
+        recurse_opts.filter_child_files(&mut children);
+
+        // This has to match the depths used when actually recursing.
+        let depth = dir.path.components().filter(|&c| c != Component::CurDir).count() + 1;
+        let device = if recurse_opts.one_file_system { dir.device_id() }
+                                                else { None };
+
+        let (descended, mut shown) = children.into_iter().partition::<Vec<_>, _>(|f| {
+            recurse_opts.is_recursable(f) && ! f.is_all_all && ! recurse_opts.is_too_deep(depth) && ! recurse_opts.is_other_file_system(device, f)
+        });
+
+        self.options.filter.remove_directories(&mut shown);
+        let empty = shown.is_empty() && descended.iter().all(|f| {
+            f.to_dir().ok()
+             .and_then(|d| dir.enter(d))
+             .map_or(false, |d| self.is_empty_branch(&d, recurse_opts))
+        });
+
+        self.empty_branches.insert(dir.path.clone(), empty);
+        empty
+    }
+
+    /// The branch that’s checked out in the repository the given directory is
+    /// in, to go in its heading, if Git statuses are being shown.
+    fn git_head(&self, dir: &Dir) -> Option<String> {
+        if ! self.options.shows_git_status() {
+            return None;
+        }
+
+        self.git.as_ref()?.head_name(&dir.path)
+    }
+
+    /// Adds up the sizes of every file that’s going to be listed, descending
+    /// into directories the same way that listing them would.
+    fn listing_size(&self, files: &[File<'_>], dirs: &[Dir]) -> u64 {
+        let mut total = table::total_size(files);
+
+        // In tree mode, directories passed as arguments get listed as
+        // files, with their contents underneath them.
+        if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
+            if recurse_opts.tree && ! recurse_opts.is_too_deep(0) {
+                for dir in files.iter().filter(|f| f.is_directory()).filter_map(|f| f.to_dir().ok()) {
+                    total += self.dir_size(&dir, 0);
+                }
+            }
+        }
+
+        for dir in dirs {
+            total += self.dir_size(dir, 0);
+        }
+
+        total
+    }
+
+    /// Adds up the sizes of the files in the given directory that are going
+    /// to be listed, and those of any directories that will be recursed into.
+    fn dir_size(&self, dir: &Dir, tree_depth: usize) -> u64 {
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        let mut children = dir.files(self.options.filter.dot_filter, self.git.as_ref(), git_ignore)
+                              .filter_map(Result::ok)
+                              .collect::<Vec<_>>();
+
+        self.options.filter.filter_child_entries(&mut children);
+        let mut total = table::total_size(&children);
+
+        if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
+            recurse_opts.filter_child_files(&mut children);
+
+            // This has to match the depths used when actually recursing.
+            let depth = if recurse_opts.tree { tree_depth + 1 }
+                                        else { dir.path.components().filter(|&c| c != Component::CurDir).count() + 1 };
+
+            if ! recurse_opts.is_too_deep(depth) {
+                let device = if recurse_opts.one_file_system { dir.device_id() }
+                                                        else { None };
+
+                for child_dir in children.iter().filter(|f| recurse_opts.is_recursable(f) && ! f.is_all_all && ! recurse_opts.is_other_file_system(device, f)).filter_map(|f| f.to_dir().ok()).filter_map(|d| dir.enter(d)) {
+                    total += self.dir_size(&child_dir, tree_depth + 1);
+                }
+            }
+        }
+
+        total
+    }
+
     /// Prints the list of files using whichever view is selected.
     fn print_files(&mut self, dir: Option<&Dir>, files: Vec<File<'_>>) -> io::Result<()> {
         if files.is_empty() {
             return Ok(());
         }
 
+        let continued = self.listed_any;
+        self.listed_any = true;
+
         let theme = &self.theme;
         let View { ref mode, ref file_style, .. } = self.options.view;
 
         match (mode, self.console_width) {
             (Mode::Grid(ref opts), Some(console_width)) => {
                 let filter = &self.options.filter;
-                let r = grid::Render { files, theme, file_style, opts, console_width, filter };
+                let git = self.git.as_ref();
+                let r = grid::Render { files, theme, file_style, opts, console_width, filter, git };
                 r.render(&mut self.writer)
             }
 
             (Mode::Grid(_), None) |
             (Mode::Lines,   _)    => {
                 let filter = &self.options.filter;
-                let r = lines::Render { files, theme, file_style, filter };
+                let git = self.git.as_ref();
+                let r = lines::Render { files, theme, file_style, filter, git };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Zero, _) => {
+                let filter = &self.options.filter;
+                let full_paths = self.options.dir_action.recurse_options().is_some();
+                let r = zero::Render { files, filter, full_paths };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Delimited(ref opts), _) => {
+                let filter = &self.options.filter;
+                let full_paths = self.options.dir_action.recurse_options().is_some();
+                let git = self.git.as_ref();
+                let size_total = self.size_total;
+                let r = delimited::Render { files, theme, opts, filter, git, full_paths, size_total };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Stat(ref opts), _) => {
+                let filter = &self.options.filter;
+                let r = stat::Render { files, theme, file_style, opts, filter };
                 r.render(&mut self.writer)
             }
 
@@ -296,8 +897,9 @@ impl<'args> Exa<'args> {
 
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
-                let r = details::Render { dir, files, theme, file_style, opts, recurse, filter, git_ignoring, git };
-                r.render(&mut self.writer)
+                let size_total = self.size_total;
+                let r = details::Render { dir, files, theme, file_style, opts, recurse, filter, git_ignoring, git, size_total, continued };
+                r.render(&mut self.writer).map(|summary| self.summary += summary)
             }
 
             (Mode::GridDetails(ref opts), Some(console_width)) => {
@@ -309,8 +911,10 @@ impl<'args> Exa<'args> {
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
 
-                let r = grid_details::Render { dir, files, theme, file_style, grid, details, filter, row_threshold, git_ignoring, git, console_width };
-                r.render(&mut self.writer)
+                let size_total = self.size_total;
+
+                let r = grid_details::Render { dir, files, theme, file_style, grid, details, filter, row_threshold, git_ignoring, git, console_width, size_total, continued };
+                r.render(&mut self.writer).map(|summary| self.summary += summary)
             }
 
             (Mode::GridDetails(ref opts), None) => {
@@ -320,8 +924,9 @@ impl<'args> Exa<'args> {
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
 
                 let git = self.git.as_ref();
-                let r = details::Render { dir, files, theme, file_style, opts, recurse, filter, git_ignoring, git };
-                r.render(&mut self.writer)
+                let size_total = self.size_total;
+                let r = details::Render { dir, files, theme, file_style, opts, recurse, filter, git_ignoring, git, size_total, continued };
+                r.render(&mut self.writer).map(|summary| self.summary += summary)
             }
         }
     }
@@ -339,3 +944,315 @@ mod exits {
     /// Exit code for when the command-line options are invalid.
     pub const OPTIONS_ERROR: i32 = 3;
 }
+
+
+#[cfg(test)]
+mod flat_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    fn flat_paths(name: &str, args: &[&str]) -> Vec<PathBuf> {
+        let root = temp_dir(&[ "flat-", name ].concat());
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("top.txt"), "").unwrap();
+        fs::write(root.join("a").join("middle.txt"), "").unwrap();
+        fs::write(root.join("a").join("b").join("bottom.txt"), "").unwrap();
+        fs::write(root.join("a").join("b").join(".hidden"), "").unwrap();
+
+        let mut exa = Exa::for_test(args);
+        let recurse_opts = exa.options.dir_action.recurse_options().unwrap();
+
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        let mut files = Vec::new();
+        exa.add_flat_files(&dir, recurse_opts, &mut files).unwrap();
+        exa.options.filter.sort_files(&mut files);
+
+        let paths = files.iter().map(|f| f.path.strip_prefix(&root).unwrap().to_path_buf()).collect();
+        fs::remove_dir_all(root).unwrap();
+        paths
+    }
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(flat_paths("nested", &[ "--flat" ]), paths(&[ "a", "a/b", "a/b/bottom.txt", "a/middle.txt", "top.txt" ]));
+    }
+
+    #[test]
+    fn sorted_together() {
+        assert_eq!(flat_paths("reversed", &[ "--flat", "--reverse" ]), paths(&[ "top.txt", "a/middle.txt", "a/b/bottom.txt", "a/b", "a" ]));
+    }
+
+    #[test]
+    fn only_files() {
+        assert_eq!(flat_paths("only-files", &[ "--flat", "--only-files" ]), paths(&[ "a/b/bottom.txt", "a/middle.txt", "top.txt" ]));
+    }
+
+    #[test]
+    fn only_dirs() {
+        assert_eq!(flat_paths("list-dirs", &[ "--flat", "--list-dirs" ]), paths(&[ "a", "a/b" ]));
+    }
+
+    #[test]
+    fn dotfiles_but_not_dots() {
+        assert_eq!(flat_paths("all-all", &[ "--flat", "-aa" ]), paths(&[ "a/b/.hidden", "a", "a/b", "a/b/bottom.txt", "a/middle.txt", "top.txt" ]));
+    }
+}
+
+
+#[cfg(test)]
+mod files_first_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    /// Lists a nested tree of directories with the given arguments, and
+    /// returns the headings of the directories, in the order they were
+    /// listed, relative to the top of the tree.
+    fn headings(name: &str, args: &[&str]) -> Vec<String> {
+        listed_headings(name, args, |root| vec![ Dir::read_dir(root.to_path_buf()).unwrap() ])
+    }
+
+    /// Lists the directories inside the nested tree picked out by the given
+    /// function, as though they’d been given as arguments, and returns the
+    /// headings, as above.
+    fn listed_headings(name: &str, args: &[&str], dirs: impl Fn(&Path) -> Vec<Dir>) -> Vec<String> {
+        let root = temp_dir(&[ "files-first-", name ].concat());
+        fs::create_dir_all(root.join("a").join("x")).unwrap();
+        fs::create_dir_all(root.join("b").join("y")).unwrap();
+        fs::write(root.join("top.txt"), "").unwrap();
+        fs::write(root.join("a").join("one.txt"), "").unwrap();
+        fs::write(root.join("b").join("two.txt"), "").unwrap();
+
+        // A link back up to the top, which only gets followed with
+        // `--follow-symlinks`, and would be followed forever if exa didn’t
+        // keep track of where it had been.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("../..", root.join("a").join("x").join("up")).unwrap();
+
+        let mut exa = Exa::for_test(args);
+
+        exa.print_dirs(dirs(&root), true, false, 0).unwrap();
+
+        fs::remove_dir_all(&root).unwrap();
+        let prefix = root.display().to_string();
+        exa.writer.written().lines()
+              .filter_map(|line| line.strip_suffix(':'))
+              .map(|heading| heading.replacen(&prefix, ".", 1))
+              .collect()
+    }
+
+    #[test]
+    fn depth_first() {
+        assert_eq!(headings("depth", &[ "-1R" ]), vec![ ".", "./a", "./a/x", "./b", "./b/y" ]);
+    }
+
+    #[test]
+    fn each_level_first() {
+        assert_eq!(headings("level", &[ "-1R", "--files-first" ]), vec![ ".", "./a", "./b", "./a/x", "./b/y" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn loop_listed_once() {
+        assert_eq!(headings("loop", &[ "-1R", "--follow-symlinks" ]), vec![ ".", "./a", "./a/x", "./b", "./b/y" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn loop_listed_once_each_level() {
+        assert_eq!(headings("loop-level", &[ "-1R", "--follow-symlinks", "--files-first" ]), vec![ ".", "./a", "./b", "./a/x", "./b/y" ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn loop_listed_once_without_following() {
+        // The top of the tree, reached from inside itself the way it would
+        // be through a bind mount, which gets descended into whether or not
+        // symlinks are being followed.
+        let inside_itself = |root: &Path| {
+            let x = Dir::read_dir(root.join("a").join("x")).unwrap();
+            vec![ x.enter(Dir::read_dir(root.to_path_buf()).unwrap()).unwrap() ]
+        };
+
+        assert_eq!(listed_headings("loop-unfollowed", &[ "-1R" ], inside_itself), vec![ ".", "./a", "./b", "./b/y" ]);
+    }
+
+    #[test]
+    fn nested_argument_listed_in_full() {
+        let top_and_a = |root: &Path| vec![ Dir::read_dir(root.to_path_buf()).unwrap(), Dir::read_dir(root.join("a")).unwrap() ];
+        assert_eq!(listed_headings("nested", &[ "-1R" ], top_and_a), vec![ ".", "./a", "./a/x", "./b", "./b/y", "./a", "./a/x" ]);
+    }
+
+    #[test]
+    fn each_level_reversed() {
+        assert_eq!(headings("reversed", &[ "-1R", "--files-first", "--reverse" ]), vec![ ".", "./b", "./a", "./b/y", "./a/x" ]);
+    }
+}
+
+
+#[cfg(test)]
+mod paths_from_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    fn read_paths(name: &str, contents: &[u8], zero: bool) -> Vec<OsString> {
+        let root = temp_dir(&[ "paths-from-", name ].concat());
+        let path = root.join("paths");
+        fs::write(&path, contents).unwrap();
+        let paths = read_paths_from(&path, zero).unwrap();
+        fs::remove_dir_all(root).unwrap();
+        paths
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(read_paths("lines", b"src\nCargo.toml\n", false), vec![ "src", "Cargo.toml" ]);
+    }
+
+    #[test]
+    fn blank_lines() {
+        assert_eq!(read_paths("blank", b"\nsrc\n\n\nREADME.md", false), vec![ "src", "README.md" ]);
+    }
+
+    #[test]
+    fn windows_lines() {
+        assert_eq!(read_paths("windows", b"src\r\nCargo.toml\r\n", false), vec![ "src", "Cargo.toml" ]);
+    }
+
+    #[test]
+    fn spaces_kept() {
+        assert_eq!(read_paths("spaces", b" leading\ntrailing \n", false), vec![ " leading", "trailing " ]);
+    }
+
+    #[test]
+    fn nul_separated() {
+        assert_eq!(read_paths("nul", b"new\nline\0other\0", true), vec![ "new\nline", "other" ]);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(read_paths("empty", b"", false), Vec::<OsString>::new());
+    }
+
+    #[test]
+    fn missing_file() {
+        let root = temp_dir("paths-from-missing");
+        assert_eq!(read_paths_from(&root.join("paths"), false).unwrap_err().kind(), ErrorKind::NotFound);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn parsed() {
+        assert_eq!(Exa::for_test(&[ "--paths-from", "list.txt" ]).options.paths_from, Some(PathBuf::from("list.txt")));
+    }
+
+    #[test]
+    fn not_parsed() {
+        assert_eq!(Exa::for_test(&[]).options.paths_from, None);
+    }
+}
+
+
+#[cfg(test)]
+mod header_once_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    /// Lists a nested tree recursively with the given options, and returns
+    /// the lines that were written.
+    fn listed_lines(name: &str, args: &[&str]) -> Vec<String> {
+        let root = temp_dir(&[ "header-once-", name ].concat());
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("top.txt"), "").unwrap();
+        fs::write(root.join("a").join("middle.txt"), "").unwrap();
+        fs::write(root.join("a").join("b").join("bottom.txt"), "").unwrap();
+
+        let mut exa = Exa::for_test(args);
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        exa.print_dirs(vec![ dir ], true, true, 0).unwrap();
+        fs::remove_dir_all(root).unwrap();
+
+        exa.writer.written().lines().map(String::from).collect()
+    }
+
+    fn header_count(lines: &[String]) -> usize {
+        lines.iter().filter(|line| line.starts_with("Permissions")).count()
+    }
+
+    #[test]
+    fn header_for_every_directory() {
+        let lines = listed_lines("every", &[ "-lR", "--header", "--no-user", "--no-time" ]);
+        assert_eq!(header_count(&lines), 3);
+    }
+
+    #[test]
+    fn header_once() {
+        let lines = listed_lines("once", &[ "-lR", "--header", "--no-user", "--no-time", "--no-headers-between-dirs" ]);
+        assert_eq!(header_count(&lines), 1);
+        assert!(lines[0].starts_with("Permissions"));
+    }
+
+    #[test]
+    fn no_header_at_all() {
+        let lines = listed_lines("none", &[ "-lR", "--no-user", "--no-time", "--no-headers-between-dirs" ]);
+        assert_eq!(header_count(&lines), 0);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod dereference_command_line_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+    use std::fs;
+
+    /// Makes a directory and a symlink to it, then returns whether each of
+    /// them would have its contents listed when given as an argument.
+    fn lists_contents(name: &str, args: &[&str]) -> (bool, bool) {
+        let root = temp_dir(&[ "deref-args-", name ].concat());
+        fs::create_dir_all(root.join("target")).unwrap();
+        std::os::unix::fs::symlink(root.join("target"), root.join("link")).unwrap();
+
+        let exa = Exa::for_test(args);
+
+        let dir = File::from_args(root.join("target"), None, None).unwrap();
+        let link = File::from_args(root.join("link"), None, None).unwrap();
+        let listed = (exa.lists_contents(&dir), exa.lists_contents(&link));
+
+        fs::remove_dir_all(root).unwrap();
+        listed
+    }
+
+    #[test]
+    fn followed_in_grid() {
+        assert_eq!(lists_contents("grid", &[]), (true, true));
+    }
+
+    #[test]
+    fn not_followed_in_long_view() {
+        assert_eq!(lists_contents("long", &[ "--long" ]), (true, false));
+    }
+
+    #[test]
+    fn followed_in_long_view_with_flag() {
+        assert_eq!(lists_contents("long-flag", &[ "--long", "--dereference-command-line" ]), (true, true));
+    }
+
+    #[test]
+    fn followed_in_long_grid_with_flag() {
+        assert_eq!(lists_contents("long-grid", &[ "--long", "--grid", "--dereference-command-line" ]), (true, true));
+    }
+
+    #[test]
+    fn not_followed_as_files() {
+        assert_eq!(lists_contents("list-dirs", &[ "--list-dirs", "--dereference-command-line" ]), (false, false));
+    }
+}
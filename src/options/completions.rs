@@ -0,0 +1,239 @@
+//! Generating shell completion scripts from the flag definitions.
+//!
+//! The scripts in the `completions` directory are written by hand, and can
+//! drift from the real set of flags. These ones are derived from
+//! `flags::ALL_ARGS` instead, so they always match the binary that printed
+//! them, although they can’t describe what each flag does.
+
+use std::fmt;
+
+use crate::options::{flags, OptionsError};
+use crate::options::parser::{Arg, MatchedFlags, TakesValue};
+
+
+/// The shells that completion scripts can be generated for.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// A completion script for one shell, which gets printed out instead of
+/// listing any files.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct CompletionsString(Shell);
+
+impl CompletionsString {
+
+    /// Determines which shell to generate completions for, if any. Like
+    /// `--help` and `--version`, this is checked before anything else.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let word = match matches.get(&flags::GENERATE_COMPLETIONS)? {
+            Some(w)  => w,
+            None     => return Ok(None),
+        };
+
+        if word == "bash" {
+            Ok(Some(Self(Shell::Bash)))
+        }
+        else if word == "zsh" {
+            Ok(Some(Self(Shell::Zsh)))
+        }
+        else if word == "fish" {
+            Ok(Some(Self(Shell::Fish)))
+        }
+        else {
+            Err(OptionsError::BadArgument(&flags::GENERATE_COMPLETIONS, word.into()))
+        }
+    }
+}
+
+/// Every argument that should be completed, which is all of them apart from
+/// the one that generates completions.
+fn visible_args() -> impl Iterator<Item = &'static Arg> {
+    flags::ALL_ARGS.0.iter()
+        .copied()
+        .filter(|arg| arg.long != flags::GENERATE_COMPLETIONS.long)
+}
+
+impl fmt::Display for CompletionsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Shell::Bash  => write_bash(f),
+            Shell::Zsh   => write_zsh(f),
+            Shell::Fish  => write_fish(f),
+        }
+    }
+}
+
+fn write_bash(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "_exa()")?;
+    writeln!(f, "{{")?;
+    writeln!(f, "    cur=${{COMP_WORDS[COMP_CWORD]}}")?;
+    writeln!(f, "    prev=${{COMP_WORDS[COMP_CWORD-1]}}")?;
+    writeln!(f)?;
+    writeln!(f, "    case \"$prev\" in")?;
+
+    for arg in visible_args() {
+        if let TakesValue::Necessary(values) = arg.takes_value {
+            match arg.short {
+                Some(short)  => writeln!(f, "        -{}|--{})", short as char, arg.long)?,
+                None         => writeln!(f, "        --{})", arg.long)?,
+            }
+
+            match values {
+                Some(values)  => writeln!(f, "            COMPREPLY=( $( compgen -W '{}' -- \"$cur\" ) )", values.join(" "))?,
+                None          => writeln!(f, "            _filedir")?,
+            }
+
+            writeln!(f, "            return")?;
+            writeln!(f, "            ;;")?;
+            writeln!(f)?;
+        }
+    }
+
+    writeln!(f, "    esac")?;
+    writeln!(f)?;
+
+    let mut words = Vec::new();
+    for arg in visible_args() {
+        words.push(format!("--{}", arg.long));
+
+        if let TakesValue::Optional(Some(values)) = arg.takes_value {
+            words.extend(values.iter().map(|v| format!("--{}={}", arg.long, v)));
+        }
+
+        if let Some(short) = arg.short {
+            words.push(format!("-{}", short as char));
+        }
+    }
+
+    writeln!(f, "    case \"$cur\" in")?;
+    writeln!(f, "        -*)")?;
+    writeln!(f, "            COMPREPLY=( $( compgen -W '{}' -- \"$cur\" ) )", words.join(" "))?;
+    writeln!(f, "            ;;")?;
+    writeln!(f)?;
+    writeln!(f, "        *)")?;
+    writeln!(f, "            _filedir")?;
+    writeln!(f, "            ;;")?;
+    writeln!(f, "    esac")?;
+    writeln!(f, "}} &&")?;
+    writeln!(f, "complete -o filenames -o bashdefault -F _exa exa")
+}
+
+fn write_zsh(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "#compdef exa")?;
+    writeln!(f)?;
+    writeln!(f, "__exa() {{")?;
+    writeln!(f, "    _arguments -s -S \\")?;
+
+    for arg in visible_args() {
+        let (short_suffix, long_suffix, action) = match arg.takes_value {
+            TakesValue::Forbidden                 => ("", "", String::new()),
+            TakesValue::Necessary(Some(values))   => ("+", "=", format!(":value:({})", values.join(" "))),
+            TakesValue::Necessary(None)           => ("+", "=", String::from(":value:_files")),
+            TakesValue::Optional(Some(values))    => ("", "=-", format!("::value:({})", values.join(" "))),
+            TakesValue::Optional(None)            => ("", "=-", String::from("::value: ")),
+        };
+
+        match arg.short {
+            Some(short) if short.is_ascii_alphanumeric()  => write!(f, "        {{-{}{},--{}{}}}", short as char, short_suffix, arg.long, long_suffix)?,
+            Some(short)  => write!(f, "        {{-'\\{}'{},--{}{}}}", short as char, short_suffix, arg.long, long_suffix)?,
+            None         => write!(f, "        --{}{}", arg.long, long_suffix)?,
+        }
+
+        if action.is_empty() {
+            writeln!(f, " \\")?;
+        }
+        else {
+            writeln!(f, "'{}' \\", action)?;
+        }
+    }
+
+    writeln!(f, "        '*:filename:_files'")?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+    writeln!(f, "__exa")
+}
+
+fn write_fish(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for arg in visible_args() {
+        write!(f, "complete -c exa")?;
+
+        if let Some(short) = arg.short {
+            write!(f, " -s '{}'", short as char)?;
+        }
+
+        write!(f, " -l '{}'", arg.long)?;
+
+        match arg.takes_value {
+            TakesValue::Forbidden                => {},
+            TakesValue::Necessary(Some(values))  => write!(f, " -x -a '{}'", values.join(" "))?,
+            TakesValue::Necessary(None)          => write!(f, " -r")?,
+            TakesValue::Optional(Some(values))   => write!(f, " -f -a '{}'", values.join(" "))?,
+            TakesValue::Optional(None)           => write!(f, " -f")?,
+        }
+
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::{Options, OptionsResult};
+    use std::ffi::{OsStr, OsString};
+
+    fn generate(shell: &str) -> String {
+        let arg = format!("--generate-completions={}", shell);
+        match Options::parse(vec![ OsStr::new(&arg) ], &None) {
+            OptionsResult::Completions(completions)  => completions.to_string(),
+            other                                    => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bad_shell() {
+        let opts = Options::parse(vec![ OsStr::new("--generate-completions=csh") ], &None);
+        assert!(matches!(opts, OptionsResult::InvalidOptions(OptionsError::BadArgument(_, ref word)) if word == &OsString::from("csh")));
+    }
+
+    #[test]
+    fn every_flag_is_completed() {
+        for shell in &[ "bash", "zsh", "fish" ] {
+            let script = generate(shell);
+            for arg in visible_args() {
+                assert!(script.contains(arg.long), "{} completions are missing --{}", shell, arg.long);
+            }
+            assert!(! script.contains("generate-completions"), "{} completions include themselves", shell);
+        }
+    }
+
+    #[test]
+    fn bash() {
+        let script = generate("bash");
+        assert!(script.contains("        -s|--sort)\n            COMPREPLY=( $( compgen -W 'name Name "));
+        assert!(script.contains("--size-percent=dir --size-percent=global"));
+    }
+
+    #[test]
+    fn zsh() {
+        let script = generate("zsh");
+        assert!(script.contains("        {-a,--all} \\\n"));
+        assert!(script.contains("        {-'\\?',--help} \\\n"));
+        assert!(script.contains("        {-t+,--time=}':value:(modified changed accessed created)' \\\n"));
+        assert!(script.contains("        --size-percent=-'::value:(dir global)' \\\n"));
+    }
+
+    #[test]
+    fn fish() {
+        let script = generate("fish");
+        assert!(script.contains("complete -c exa -s 'a' -l 'all'\n"));
+        assert!(script.contains("complete -c exa -l 'theme-file' -r\n"));
+        assert!(script.contains("complete -c exa -l 'size-percent' -f -a 'dir global'\n"));
+    }
+}
@@ -11,32 +11,82 @@ impl DirAction {
     /// Determine which action to perform when trying to list a directory.
     /// There are three possible actions, and they overlap somewhat: the
     /// `--tree` flag is another form of recursion, so those two are allowed
-    /// to both be present, but the `--list-dirs` flag is used separately.
+    /// to both be present. The `--list-dirs` flag is used separately, unless
+    /// it’s combined with one of those two, in which case only directories
+    /// get recursed into and listed.
+    ///
+    /// Giving a maximum depth with `--level` or `--depth` on its own implies
+    /// recursion, as there’d be no point to it otherwise. The exception is
+    /// alongside `--list-dirs`, where it gets ignored. The `--flat` flag
+    /// always implies recursion, but can’t be used with a tree.
     pub fn deduce(matches: &MatchedFlags<'_>, can_tree: bool) -> Result<Self, OptionsError> {
-        let recurse = matches.has(&flags::RECURSE)?;
         let as_file = matches.has(&flags::LIST_DIRS)?;
         let tree    = matches.has(&flags::TREE)?;
+        let flat    = matches.has(&flags::FLAT)?;
+        let level   = matches.count(&flags::LEVEL) + matches.count(&flags::DEPTH) > 0;
+        let recurse = matches.has(&flags::RECURSE)? || flat || (level && ! tree && ! as_file);
+
+        if tree && flat {
+            return Err(OptionsError::Conflict(&flags::FLAT, &flags::TREE));
+        }
 
         if matches.is_strict() {
             // Early check for --level when it wouldn’t do anything
-            if ! recurse && ! tree && matches.count(&flags::LEVEL) > 0 {
-                return Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE));
+            if ! recurse && ! tree && level {
+                return Err(OptionsError::Useless(&flags::LEVEL, true, &flags::LIST_DIRS));
+            }
+
+            if ! recurse && ! tree && matches.count(&flags::ONE_FILE_SYSTEM) > 0 {
+                return Err(OptionsError::Useless2(&flags::ONE_FILE_SYSTEM, &flags::RECURSE, &flags::TREE));
+            }
+
+            if ! recurse && ! tree && matches.count(&flags::FOLLOW_SYMLINKS) > 0 {
+                return Err(OptionsError::Useless2(&flags::FOLLOW_SYMLINKS, &flags::RECURSE, &flags::TREE));
+            }
+
+            if ! recurse && ! tree && matches.count(&flags::PRUNE) > 0 {
+                return Err(OptionsError::Useless2(&flags::PRUNE, &flags::RECURSE, &flags::TREE));
+            }
+
+            // Folding files together only happens in a tree
+            if ! tree && matches.count(&flags::COLLAPSE_EXTS) > 0 {
+                return Err(OptionsError::Useless(&flags::COLLAPSE_EXTS, false, &flags::TREE));
             }
-            else if recurse && as_file {
-                return Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS));
+
+            if ! tree && matches.count(&flags::COLLAPSE_SINGLE_CHILD) > 0 {
+                return Err(OptionsError::Useless(&flags::COLLAPSE_SINGLE_CHILD, false, &flags::TREE));
+            }
+
+            if ! tree && matches.count(&flags::REVERSE_TREE) > 0 {
+                return Err(OptionsError::Useless(&flags::REVERSE_TREE, false, &flags::TREE));
+            }
+
+            if ! tree && matches.count(&flags::TREE_STYLE) > 0 {
+                return Err(OptionsError::Useless(&flags::TREE_STYLE, false, &flags::TREE));
             }
-            else if tree && as_file {
-                return Err(OptionsError::Conflict(&flags::TREE, &flags::LIST_DIRS));
+
+            // Listing each level at once only happens when recursing
+            // without a tree, which has its own order
+            if matches.count(&flags::FILES_FIRST) > 0 {
+                if tree {
+                    return Err(OptionsError::Useless(&flags::FILES_FIRST, true, &flags::TREE));
+                }
+                else if flat {
+                    return Err(OptionsError::Useless(&flags::FILES_FIRST, true, &flags::FLAT));
+                }
+                else if ! recurse {
+                    return Err(OptionsError::Useless(&flags::FILES_FIRST, false, &flags::RECURSE));
+                }
             }
         }
 
         if tree && can_tree {
             // Tree is only appropriate in details mode, so this has to
             // examine the View, which should have already been deduced by now
-            Ok(Self::Recurse(RecurseOptions::deduce(matches, true)?))
+            Ok(Self::Recurse(RecurseOptions::deduce(matches, true, as_file)?))
         }
         else if recurse {
-            Ok(Self::Recurse(RecurseOptions::deduce(matches, false)?))
+            Ok(Self::Recurse(RecurseOptions::deduce(matches, false, as_file)?))
         }
         else if as_file {
             Ok(Self::AsFile)
@@ -50,16 +100,27 @@ impl DirAction {
 
 impl RecurseOptions {
 
-    /// Determine which files should be recursed into, based on the `--level`
-    /// flag’s value, and whether the `--tree` flag was passed, which was
-    /// determined earlier. The maximum level should be a number, and this
-    /// will fail with an `Err` if it isn’t.
-    pub fn deduce(matches: &MatchedFlags<'_>, tree: bool) -> Result<Self, OptionsError> {
-        if let Some(level) = matches.get(&flags::LEVEL)? {
+    /// Determine which files should be recursed into, based on the value of
+    /// the `--level` or `--depth` flag, whether the `--one-file-system`, `--follow-symlinks`,
+    /// `--prune`, `--flat`, `--files-first`, `--reverse-tree`, and `--collapse-single-child` flags were passed, and whether the `--tree` and `--list-dirs` flags
+    /// were passed, which was determined earlier. The maximum level should be
+    /// a number, and this will fail with an `Err` if it isn’t.
+    pub fn deduce(matches: &MatchedFlags<'_>, tree: bool, dirs_only: bool) -> Result<Self, OptionsError> {
+        let one_file_system = matches.has(&flags::ONE_FILE_SYSTEM)?;
+        let follow_symlinks = matches.has(&flags::FOLLOW_SYMLINKS)?;
+        let prune = matches.has(&flags::PRUNE)?;
+        let flat = matches.has(&flags::FLAT)?;
+        let files_first = ! tree && ! flat && matches.has(&flags::FILES_FIRST)?;
+        let reverse_tree = tree && matches.has(&flags::REVERSE_TREE)?;
+        let collapse_single_child = tree && matches.has(&flags::COLLAPSE_SINGLE_CHILD)?;
+        let collapse_exts = if tree { Self::deduce_collapse(matches)? }
+                                else { None };
+
+        if let Some(level) = matches.get_where(|f| f.matches(&flags::LEVEL) || f.matches(&flags::DEPTH))? {
             let arg_str = level.to_string_lossy();
             match arg_str.parse() {
                 Ok(l) => {
-                    Ok(Self { tree, max_depth: Some(l) })
+                    Ok(Self { tree, max_depth: Some(l), dirs_only, collapse_exts, one_file_system, follow_symlinks, prune, flat, reverse_tree, collapse_single_child, files_first })
                 }
                 Err(e) => {
                     let source = NumberSource::Arg(&flags::LEVEL);
@@ -68,7 +129,33 @@ impl RecurseOptions {
             }
         }
         else {
-            Ok(Self { tree, max_depth: None })
+            Ok(Self { tree, max_depth: None, dirs_only, collapse_exts, one_file_system, follow_symlinks, prune, flat, reverse_tree, collapse_single_child, files_first })
+        }
+    }
+
+    /// Determine how many files have to share an extension before they get
+    /// folded together, based on the `--collapse-exts` flag. Passing it
+    /// without a value uses the default of eight; the value should otherwise
+    /// be a number, and this will fail with an `Err` if it isn’t.
+    fn deduce_collapse(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+        match matches.get_optional(&flags::COLLAPSE_EXTS)? {
+            None             => Ok(None),
+            Some(None)       => Ok(Some(8)),
+            Some(Some(arg))  => {
+                let arg_str = arg.to_string_lossy();
+                match arg_str.parse() {
+                    Ok(0) => {
+                        Err(OptionsError::BadArgument(&flags::COLLAPSE_EXTS, arg.into()))
+                    }
+                    Ok(n) => {
+                        Ok(Some(n))
+                    }
+                    Err(e) => {
+                        let source = NumberSource::Arg(&flags::COLLAPSE_EXTS);
+                        Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                    }
+                }
+            }
         }
     }
 }
@@ -77,6 +164,7 @@ impl RecurseOptions {
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::ffi::OsString;
     use crate::options::flags;
     use crate::options::parser::Flag;
 
@@ -88,7 +176,8 @@ mod test {
                 use crate::options::test::parse_for_test;
                 use crate::options::test::Strictnesses::*;
 
-                static TEST_ARGS: &[&Arg] = &[&flags::RECURSE, &flags::LIST_DIRS, &flags::TREE, &flags::LEVEL ];
+                static TEST_ARGS: &[&Arg] = &[&flags::RECURSE, &flags::LIST_DIRS, &flags::TREE, &flags::LEVEL, &flags::DEPTH,
+                                              &flags::COLLAPSE_EXTS, &flags::ONE_FILE_SYSTEM, &flags::FOLLOW_SYMLINKS, &flags::PRUNE, &flags::FLAT, &flags::FILES_FIRST, &flags::REVERSE_TREE, &flags::TREE_STYLE, &flags::COLLAPSE_SINGLE_CHILD ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf, true)) {
                     assert_eq!(result, $result);
                 }
@@ -106,27 +195,101 @@ mod test {
 
     // Recursing
     use self::DirAction::Recurse;
-    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4) })));
-    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5) })));
-    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666) })));
-    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118) })));
-    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-
-    // Overriding --list-dirs, --recurse, and --tree
-    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(just_level:      DirAction <- ["--level=4"];                    Last => Ok(DirAction::List));
-
-    test!(dirs_recurse_2:  DirAction <- ["--list-dirs", "--recurse"]; Complain => Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS)));
-    test!(dirs_tree_2:     DirAction <- ["--list-dirs", "--tree"];    Complain => Err(OptionsError::Conflict(&flags::TREE,    &flags::LIST_DIRS)));
-    test!(just_level_2:    DirAction <- ["--level=4"];                Complain => Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE)));
+    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, .. RecurseOptions::default() })));
+    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, .. RecurseOptions::default() })));
+    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4), .. RecurseOptions::default() })));
+    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5), .. RecurseOptions::default() })));
+    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666), .. RecurseOptions::default() })));
+    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118), .. RecurseOptions::default() })));
+    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, .. RecurseOptions::default() })));
+    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, .. RecurseOptions::default() })));
+    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, .. RecurseOptions::default() })));
+
+    // Combining --list-dirs with --recurse or --tree
+    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, dirs_only: true, .. RecurseOptions::default() })));
+    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, dirs_only: true, .. RecurseOptions::default() })));
+    test!(dirs_rec_short:  DirAction <- ["-dR"];                          Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, dirs_only: true, .. RecurseOptions::default() })));
+    test!(dirs_tree_lim:   DirAction <- ["-dTL2"];                        Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: Some(2), dirs_only: true, .. RecurseOptions::default() })));
+    test!(dirs_rec_tree:   DirAction <- ["-dRT"];                         Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, dirs_only: true, .. RecurseOptions::default() })));
+
+    // Giving a level without recursing implies it
+    test!(just_level:      DirAction <- ["--level=4"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4), .. RecurseOptions::default() })));
+    test!(just_level_short: DirAction <- ["-L2"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(2), .. RecurseOptions::default() })));
+    test!(just_depth:      DirAction <- ["--depth", "3"];                 Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(3), .. RecurseOptions::default() })));
+    test!(depth_tree:      DirAction <- ["--tree", "--depth=1"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: Some(1), .. RecurseOptions::default() })));
+    test!(level_one_fs:    DirAction <- ["-L1", "--one-file-system"];     Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(1), one_file_system: true, .. RecurseOptions::default() })));
+    test!(level_depth:     DirAction <- ["--level=4", "--depth=5"];       Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5), .. RecurseOptions::default() })));
+    test!(level_depth_2:   DirAction <- ["--level=4", "--depth=5"];   Complain => Err(OptionsError::Duplicate(Flag::Long("level"), Flag::Long("depth"))));
+    test!(depth_nan:       DirAction <- ["--depth=deep"];                 Both => Err(OptionsError::FailedParse(String::from("deep"), NumberSource::Arg(&flags::LEVEL), "deep".parse::<usize>().unwrap_err())));
+
+    // Except when listing directories as files
+    test!(dirs_level:      DirAction <- ["--list-dirs", "--level=4"];     Last => Ok(DirAction::AsFile));
+    test!(dirs_level_2:    DirAction <- ["--list-dirs", "--level=4"]; Complain => Err(OptionsError::Useless(&flags::LEVEL, true, &flags::LIST_DIRS)));
 
 
     // Overriding levels
-    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7) })));
+    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7), .. RecurseOptions::default() })));
     test!(overriding_2:    DirAction <- ["-RL=6", "-L=7"];            Complain => Err(OptionsError::Duplicate(Flag::Short(b'L'), Flag::Short(b'L'))));
+
+
+    // Folding files by extension
+    test!(collapse:        DirAction <- ["--tree", "--collapse-exts"];    Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, collapse_exts: Some(8), .. RecurseOptions::default() })));
+    test!(collapse_num:    DirAction <- ["-T", "--collapse-exts=15"];     Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, collapse_exts: Some(15), .. RecurseOptions::default() })));
+    test!(collapse_zero:   DirAction <- ["-T", "--collapse-exts=0"];      Both => Err(OptionsError::BadArgument(&flags::COLLAPSE_EXTS, OsString::from("0"))));
+    test!(collapse_nan:    DirAction <- ["-T", "--collapse-exts=lots"];   Both => Err(OptionsError::FailedParse(String::from("lots"), NumberSource::Arg(&flags::COLLAPSE_EXTS), "lots".parse::<usize>().unwrap_err())));
+    test!(collapse_rec:    DirAction <- ["-R", "--collapse-exts"];        Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, .. RecurseOptions::default() })));
+    test!(collapse_rec_2:  DirAction <- ["-R", "--collapse-exts"];    Complain => Err(OptionsError::Useless(&flags::COLLAPSE_EXTS, false, &flags::TREE)));
+    test!(collapse_twice:  DirAction <- ["-T", "--collapse-exts", "--collapse-exts=3"];  Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, collapse_exts: Some(3), .. RecurseOptions::default() })));
+
+
+    // Staying on one file system
+    test!(one_fs_rec:      DirAction <- ["-R", "--one-file-system"];      Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, one_file_system: true, .. RecurseOptions::default() })));
+    test!(one_fs_tree:     DirAction <- ["--one-file-system", "-T"];      Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, one_file_system: true, .. RecurseOptions::default() })));
+    test!(one_fs_alone:    DirAction <- ["--one-file-system"];            Last => Ok(DirAction::List));
+    test!(one_fs_alone_2:  DirAction <- ["--one-file-system"];        Complain => Err(OptionsError::Useless2(&flags::ONE_FILE_SYSTEM, &flags::RECURSE, &flags::TREE)));
+
+    // Following symlinks
+    test!(follow_rec:      DirAction <- ["-R", "--follow-symlinks"];      Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, follow_symlinks: true, .. RecurseOptions::default() })));
+    test!(follow_tree:     DirAction <- ["-T", "--follow-symlinks"];      Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, follow_symlinks: true, .. RecurseOptions::default() })));
+    test!(follow_alone:    DirAction <- ["--follow-symlinks"];            Last => Ok(DirAction::List));
+    test!(follow_alone_2:  DirAction <- ["--follow-symlinks"];        Complain => Err(OptionsError::Useless2(&flags::FOLLOW_SYMLINKS, &flags::RECURSE, &flags::TREE)));
+
+    // Pruning empty directories
+    test!(prune_rec:       DirAction <- ["-R", "--prune"];                Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, prune: true, .. RecurseOptions::default() })));
+    test!(prune_tree:      DirAction <- ["--tree", "--prune"];            Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, prune: true, .. RecurseOptions::default() })));
+    test!(prune_alone:     DirAction <- ["--prune"];                      Last => Ok(DirAction::List));
+    test!(prune_alone_2:   DirAction <- ["--prune"];                  Complain => Err(OptionsError::Useless2(&flags::PRUNE, &flags::RECURSE, &flags::TREE)));
+
+    // Flat listings
+    test!(flat_alone:      DirAction <- ["--flat"];                       Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: true, .. RecurseOptions::default() })));
+    test!(flat_rec:        DirAction <- ["-R", "--flat"];                 Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: true, .. RecurseOptions::default() })));
+    test!(flat_level:      DirAction <- ["--flat", "-L2"];                Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(2), flat: true, .. RecurseOptions::default() })));
+    test!(flat_dirs:       DirAction <- ["--flat", "--list-dirs"];        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, dirs_only: true, flat: true, .. RecurseOptions::default() })));
+    test!(flat_tree:       DirAction <- ["--flat", "--tree"];             Both => Err(OptionsError::Conflict(&flags::FLAT, &flags::TREE)));
+
+    // Listing each level before the next
+    test!(files_first:     DirAction <- ["-R", "--files-first"];          Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, files_first: true, .. RecurseOptions::default() })));
+    test!(files_first_lvl: DirAction <- ["-RL2", "--files-first"];        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(2), files_first: true, .. RecurseOptions::default() })));
+    test!(files_first_tr:  DirAction <- ["--tree", "--files-first"];      Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, .. RecurseOptions::default() })));
+    test!(files_first_tr2: DirAction <- ["--tree", "--files-first"];  Complain => Err(OptionsError::Useless(&flags::FILES_FIRST, true, &flags::TREE)));
+    test!(files_first_fl:  DirAction <- ["--flat", "--files-first"];  Complain => Err(OptionsError::Useless(&flags::FILES_FIRST, true, &flags::FLAT)));
+    test!(files_first_no:  DirAction <- ["--files-first"];                Last => Ok(DirAction::List));
+    test!(files_first_no2: DirAction <- ["--files-first"];            Complain => Err(OptionsError::Useless(&flags::FILES_FIRST, false, &flags::RECURSE)));
+
+    // Reversing siblings in a tree
+    test!(rev_tree:        DirAction <- ["--tree", "--reverse-tree"];     Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, reverse_tree: true, .. RecurseOptions::default() })));
+    test!(rev_tree_rec:    DirAction <- ["-R", "--reverse-tree"];         Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, .. RecurseOptions::default() })));
+    test!(rev_tree_rec_2:  DirAction <- ["-R", "--reverse-tree"];     Complain => Err(OptionsError::Useless(&flags::REVERSE_TREE, false, &flags::TREE)));
+    test!(rev_tree_alone:  DirAction <- ["--reverse-tree"];           Complain => Err(OptionsError::Useless(&flags::REVERSE_TREE, false, &flags::TREE)));
+
+    // Drawing trees with other characters
+    test!(style_tree:      DirAction <- ["--tree", "--tree-style=ascii"]; Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, .. RecurseOptions::default() })));
+    test!(style_alone:     DirAction <- ["--tree-style=ascii"];           Last => Ok(DirAction::List));
+    test!(style_alone_2:   DirAction <- ["--tree-style=ascii"];       Complain => Err(OptionsError::Useless(&flags::TREE_STYLE, false, &flags::TREE)));
+
+    // Collapsing chains of single directories
+    test!(single_tree:     DirAction <- ["--tree", "--collapse-single-child"]; Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, collapse_single_child: true, .. RecurseOptions::default() })));
+    test!(single_rec:      DirAction <- ["-R", "--collapse-single-child"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, .. RecurseOptions::default() })));
+    test!(single_rec_2:    DirAction <- ["-R", "--collapse-single-child"];     Complain => Err(OptionsError::Useless(&flags::COLLAPSE_SINGLE_CHILD, false, &flags::TREE)));
+    test!(single_alone:    DirAction <- ["--collapse-single-child"];           Complain => Err(OptionsError::Useless(&flags::COLLAPSE_SINGLE_CHILD, false, &flags::TREE)));
 }
@@ -41,6 +41,10 @@ pub enum OptionsError {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A size option was given that failed to be parsed as a number of
+    /// bytes with an optional unit.
+    FailedSizeParse(String, NumberSource),
 }
 
 /// The source of a string that failed to be parsed as a number.
@@ -75,7 +79,7 @@ impl fmt::Display for OptionsError {
 
         match self {
             Self::BadArgument(arg, attempt) => {
-                if let TakesValue::Necessary(Some(values)) = arg.takes_value {
+                if let TakesValue::Necessary(Some(values)) | TakesValue::Optional(Some(values)) = arg.takes_value {
                     write!(f, "Option {} has no {:?} setting ({})", arg, attempt, Choices(values))
                 }
                 else {
@@ -93,6 +97,7 @@ impl fmt::Display for OptionsError {
             Self::TreeAllAll                 => write!(f, "Option --tree is useless given --all --all"),
             Self::FailedParse(s, n, e)       => write!(f, "Value {:?} not valid for {}: {}", s, n, e),
             Self::FailedGlobPattern(ref e)   => write!(f, "Failed to parse glob pattern: {}", e),
+            Self::FailedSizeParse(s, n)      => write!(f, "Value {:?} not valid for {}: expected a size such as 500, 10K, or 1.5G", s, n),
         }
     }
 }
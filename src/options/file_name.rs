@@ -1,46 +1,349 @@
+use std::ffi::OsString;
+
 use crate::options::{flags, OptionsError, NumberSource};
 use crate::options::parser::MatchedFlags;
 use crate::options::vars::{self, Vars};
 
-use crate::output::file_name::{Options, Classify, ShowIcons};
+use crate::output::file_name::{Options, Classify, ShowIcons, EmbedHyperlinks, QuotingStyle, Absolute, LinkTargets};
+use crate::output::icons::{IconOverrides, IconSet};
 
 
 impl Options {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let classify = Classify::deduce(matches)?;
         let show_icons = ShowIcons::deduce(matches, vars)?;
+        let icon_overrides = IconOverrides::deduce(vars);
+        let icon_set = IconSet::deduce(matches, vars)?;
+        let embed_hyperlinks = EmbedHyperlinks::deduce(matches)?;
+        let quoting = QuotingStyle::deduce(matches, vars)?;
+        let absolute = Absolute::deduce(matches)?;
+        let git_highlight = matches.has(&flags::GIT_HIGHLIGHT)?;
+        let link_targets = LinkTargets::deduce(matches)?;
 
-        Ok(Self { classify, show_icons })
+        Ok(Self { classify, show_icons, icon_overrides, icon_set, embed_hyperlinks, quoting, absolute, git_highlight, link_targets })
     }
 }
 
 impl Classify {
+
+    /// Determines whether to add file indicators. Giving `--classify`
+    /// without a value always adds them.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get_optional(&flags::CLASSIFY)? {
+            None                                                       => Ok(Self::JustFilenames),
+            Some(None)                                                 => Ok(Self::AddFileIndicators),
+            Some(Some(word)) if word == "always"                       => Ok(Self::AddFileIndicators),
+            Some(Some(word)) if word == "auto" || word == "automatic"  => Ok(Self::Automatic),
+            Some(Some(word)) if word == "never"                        => Ok(Self::JustFilenames),
+            Some(Some(word))                                           => Err(OptionsError::BadArgument(&flags::CLASSIFY, word.into())),
+        }
+    }
+}
+
+impl EmbedHyperlinks {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let flagged = matches.has(&flags::CLASSIFY)?;
+        let flagged = matches.has(&flags::HYPERLINK)?;
 
-        if flagged { Ok(Self::AddFileIndicators) }
-              else { Ok(Self::JustFilenames) }
+        if flagged { Ok(Self::On) }
+              else { Ok(Self::Off) }
+    }
+}
+
+impl QuotingStyle {
+
+    /// Determines how to quote file names, using the `QUOTING_STYLE`
+    /// environment variable if `--quoting-style` isn’t given.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if let Some(word) = matches.get(&flags::QUOTING_STYLE)? {
+            return Self::from_word(word.to_os_string());
+        }
+
+        match vars.get(vars::QUOTING_STYLE) {
+            Some(word) if ! word.is_empty()  => Self::from_word(word),
+            _                                => Ok(Self::default()),
+        }
+    }
+
+    /// Determine which quoting style a single style name refers to.
+    fn from_word(word: OsString) -> Result<Self, OptionsError> {
+        if word == "literal" {
+            Ok(Self::Literal)
+        }
+        else if word == "shell" {
+            Ok(Self::Shell)
+        }
+        else if word == "shell-always" {
+            Ok(Self::ShellAlways)
+        }
+        else if word == "c" {
+            Ok(Self::C)
+        }
+        else if word == "escape" {
+            Ok(Self::Escape)
+        }
+        else {
+            Err(OptionsError::BadArgument(&flags::QUOTING_STYLE, word))
+        }
+    }
+}
+
+impl LinkTargets {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let hide = matches.has(&flags::NO_SYMLINKS)?;
+        let show = matches.has(&flags::SHOW_SYMLINK_TARGETS)?;
+
+        match (hide, show) {
+            (true,  true)   => Err(OptionsError::Conflict(&flags::NO_SYMLINKS, &flags::SHOW_SYMLINK_TARGETS)),
+            (true,  false)  => Ok(Self::Hide),
+            (false, true)   => Ok(Self::Show),
+            (false, false)  => Ok(Self::Automatic),
+        }
+    }
+}
+
+impl Absolute {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let word = match matches.get_optional(&flags::ABSOLUTE)? {
+            Some(Some(w))  => w,
+            Some(None)                                                   => return Ok(Self::On),
+            None                                                         => return Ok(Self::Off),
+        };
+
+        if word == "on" {
+            Ok(Self::On)
+        }
+        else if word == "off" {
+            Ok(Self::Off)
+        }
+        else if word == "follow" {
+            Ok(Self::Follow)
+        }
+        else {
+            Err(OptionsError::BadArgument(&flags::ABSOLUTE, word.into()))
+        }
     }
 }
 
 impl ShowIcons {
+
+    /// Determines whether to show icons. Giving `--icons` without a value
+    /// always shows them, and `--no-icons` overrides it whatever its value.
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        if matches.has(&flags::NO_ICONS)? || !matches.has(&flags::ICONS)? {
-            Ok(Self::Off)
+        if matches.has(&flags::NO_ICONS)? {
+            return Ok(Self::Off);
         }
-        else if let Some(columns) = vars.get(vars::EXA_ICON_SPACING).and_then(|s| s.into_string().ok()) {
+
+        let automatic = match matches.get_optional(&flags::ICONS)? {
+            None                                                       => return Ok(Self::Off),
+            Some(None)                                                 => false,
+            Some(Some(word)) if word == "always" || word == "minimal"  => false,
+            Some(Some(word)) if word == "auto" || word == "automatic"  => true,
+            Some(Some(word)) if word == "never"                        => return Ok(Self::Off),
+            Some(Some(word))                                           => return Err(OptionsError::BadArgument(&flags::ICONS, word.into())),
+        };
+
+        let spaces = if let Some(columns) = vars.get(vars::EXA_ICON_SPACING).and_then(|s| s.into_string().ok()) {
             match columns.parse() {
                 Ok(width) => {
-                    Ok(Self::On(width))
+                    width
                 }
                 Err(e) => {
                     let source = NumberSource::Env(vars::EXA_ICON_SPACING);
-                    Err(OptionsError::FailedParse(columns, source, e))
+                    return Err(OptionsError::FailedParse(columns, source, e));
                 }
             }
         }
         else {
-            Ok(Self::On(1))
+            1
+        };
+
+        if automatic { Ok(Self::Automatic(spaces)) }
+                else { Ok(Self::On(spaces)) }
+    }
+}
+
+impl IconSet {
+
+    /// Determines which icons to show. Giving `--icons=minimal` picks the
+    /// ASCII ones, as does setting the `EXA_ICONS_ASCII` variable, which
+    /// means that whichever way icons get turned on, they stay readable in
+    /// a terminal without a Nerd Font.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if let Some(Some(word)) = matches.get_optional(&flags::ICONS)? {
+            if word == "minimal" {
+                return Ok(Self::Ascii);
+            }
+        }
+
+        match vars.get(vars::EXA_ICONS_ASCII) {
+            Some(var) if ! var.is_empty()  => Ok(Self::Ascii),
+            _                              => Ok(Self::NerdFont),
+        }
+    }
+}
+
+impl IconOverrides {
+    fn deduce<V: Vars>(vars: &V) -> Self {
+        match vars.get(vars::EXA_ICONS) {
+            Some(var)  => Self::parse(&var.to_string_lossy()),
+            None       => Self::default(),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsString;
+    use crate::options::flags;
+    use crate::options::parser::{Arg, Flag};
+
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::*;
+
+    static TEST_ARGS: &[&Arg] = &[ &flags::QUOTING_STYLE, &flags::ABSOLUTE, &flags::ICONS, &flags::NO_ICONS,
+                                   &flags::NO_SYMLINKS, &flags::SHOW_SYMLINK_TARGETS, &flags::CLASSIFY, &flags::LONG ];
+
+    macro_rules! test {
+        ($name:ident: $type:ident <- $inputs:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf)) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+
+        ($name:ident: $type:ident <- $inputs:expr, $vars:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf, &$vars)) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+    }
+
+    struct MockVars {
+        spacing: Option<&'static str>,
+        ascii: Option<&'static str>,
+    }
+
+    impl Vars for MockVars {
+        fn get(&self, name: &'static str) -> Option<OsString> {
+            if name == vars::EXA_ICON_SPACING {
+                self.spacing.map(OsString::from)
+            }
+            else if name == vars::EXA_ICONS_ASCII {
+                self.ascii.map(OsString::from)
+            }
+            else {
+                None
+            }
         }
     }
+
+    const NO_SPACING: MockVars = MockVars { spacing: None, ascii: None };
+    const TWO_SPACES: MockVars = MockVars { spacing: Some("2"), ascii: None };
+    const ASCII:      MockVars = MockVars { spacing: None, ascii: Some("1") };
+    const NOT_ASCII:  MockVars = MockVars { spacing: None, ascii: Some("") };
+
+    mod quoting_styles {
+        use super::*;
+
+        test!(empty:         QuotingStyle <- [], None;                                    Both => Ok(QuotingStyle::Literal));
+        test!(literal:       QuotingStyle <- ["--quoting-style=literal"], None;           Both => Ok(QuotingStyle::Literal));
+        test!(shell:         QuotingStyle <- ["--quoting-style=shell"], None;             Both => Ok(QuotingStyle::Shell));
+        test!(shell_always:  QuotingStyle <- ["--quoting-style", "shell-always"], None;   Both => Ok(QuotingStyle::ShellAlways));
+        test!(c:             QuotingStyle <- ["--quoting-style=c"], None;                 Both => Ok(QuotingStyle::C));
+        test!(escape:        QuotingStyle <- ["--quoting-style=escape"], None;            Both => Ok(QuotingStyle::Escape));
+        test!(last:          QuotingStyle <- ["--quoting-style=shell", "--quoting-style=literal"], None;  Last => Ok(QuotingStyle::Literal));
+        test!(twice:         QuotingStyle <- ["--quoting-style=shell", "--quoting-style=literal"], None;  Complain => Err(OptionsError::Duplicate(Flag::Long("quoting-style"), Flag::Long("quoting-style"))));
+        test!(unknown:       QuotingStyle <- ["--quoting-style=locale"], None;            Both => Err(OptionsError::BadArgument(&flags::QUOTING_STYLE, OsString::from("locale"))));
+
+        // Environment variable
+        test!(env_literal:   QuotingStyle <- [], Some("literal".into());                  Both => Ok(QuotingStyle::Literal));
+        test!(env_shell:     QuotingStyle <- [], Some("shell".into());                    Both => Ok(QuotingStyle::Shell));
+        test!(env_always:    QuotingStyle <- [], Some("shell-always".into());             Both => Ok(QuotingStyle::ShellAlways));
+        test!(env_c:         QuotingStyle <- [], Some("c".into());                        Both => Ok(QuotingStyle::C));
+        test!(env_escape:    QuotingStyle <- [], Some("escape".into());                   Both => Ok(QuotingStyle::Escape));
+        test!(env_empty:     QuotingStyle <- [], Some("".into());                         Both => Ok(QuotingStyle::Literal));
+        test!(env_unknown:   QuotingStyle <- [], Some("locale".into());                   Both => Err(OptionsError::BadArgument(&flags::QUOTING_STYLE, OsString::from("locale"))));
+
+        // Arguments override the environment variable
+        test!(override_c:    QuotingStyle <- ["--quoting-style=c"], Some("shell".into());         Both => Ok(QuotingStyle::C));
+        test!(override_lit:  QuotingStyle <- ["--quoting-style=literal"], Some("escape".into());  Both => Ok(QuotingStyle::Literal));
+        test!(override_bad:  QuotingStyle <- ["--quoting-style=shell"], Some("locale".into());    Both => Ok(QuotingStyle::Shell));
+    }
+
+    mod classifies {
+        use super::*;
+
+        test!(empty:     Classify <- [];                                     Both => Ok(Classify::JustFilenames));
+        test!(bare:      Classify <- ["--classify"];                         Both => Ok(Classify::AddFileIndicators));
+        test!(short:     Classify <- ["-F"];                                 Both => Ok(Classify::AddFileIndicators));
+        test!(bunched:   Classify <- ["-Fl"];                                Both => Ok(Classify::AddFileIndicators));
+        test!(always:    Classify <- ["--classify=always"];                  Both => Ok(Classify::AddFileIndicators));
+        test!(auto:      Classify <- ["--classify=auto"];                    Both => Ok(Classify::Automatic));
+        test!(automatic: Classify <- ["--classify=automatic"];               Both => Ok(Classify::Automatic));
+        test!(never:     Classify <- ["--classify=never"];                   Both => Ok(Classify::JustFilenames));
+        test!(short_eq:  Classify <- ["-F=auto"];                            Both => Ok(Classify::Automatic));
+        test!(last:      Classify <- ["--classify=never", "-F"];             Last => Ok(Classify::AddFileIndicators));
+        test!(twice:     Classify <- ["--classify=never", "-F"];             Complain => Err(OptionsError::Duplicate(Flag::Long("classify"), Flag::Short(b'F'))));
+        test!(unknown:   Classify <- ["--classify=sometimes"];               Both => Err(OptionsError::BadArgument(&flags::CLASSIFY, OsString::from("sometimes"))));
+    }
+
+    mod absolutes {
+        use super::*;
+
+        test!(empty:     Absolute <- [];                                     Both => Ok(Absolute::Off));
+        test!(bare:      Absolute <- ["--absolute"];                         Both => Ok(Absolute::On));
+        test!(on:        Absolute <- ["--absolute=on"];                      Both => Ok(Absolute::On));
+        test!(off:       Absolute <- ["--absolute=off"];                     Both => Ok(Absolute::Off));
+        test!(follow:    Absolute <- ["--absolute=follow"];                  Both => Ok(Absolute::Follow));
+        test!(last:      Absolute <- ["--absolute=follow", "--absolute"];    Last => Ok(Absolute::On));
+        test!(twice:     Absolute <- ["--absolute=follow", "--absolute"];    Complain => Err(OptionsError::Duplicate(Flag::Long("absolute"), Flag::Long("absolute"))));
+        test!(unknown:   Absolute <- ["--absolute=yes"];                     Both => Err(OptionsError::BadArgument(&flags::ABSOLUTE, OsString::from("yes"))));
+    }
+
+    mod link_targets {
+        use super::*;
+
+        test!(empty:     LinkTargets <- [];                                              Both => Ok(LinkTargets::Automatic));
+        test!(hide:      LinkTargets <- ["--no-symlinks"];                               Both => Ok(LinkTargets::Hide));
+        test!(show:      LinkTargets <- ["--show-symlink-targets"];                      Both => Ok(LinkTargets::Show));
+        test!(both:      LinkTargets <- ["--no-symlinks", "--show-symlink-targets"];     Both => Err(OptionsError::Conflict(&flags::NO_SYMLINKS, &flags::SHOW_SYMLINK_TARGETS)));
+        test!(twice:     LinkTargets <- ["--no-symlinks", "--no-symlinks"];              Complain => Err(OptionsError::Duplicate(Flag::Long("no-symlinks"), Flag::Long("no-symlinks"))));
+    }
+
+    mod icons {
+        use super::*;
+
+        test!(empty:     ShowIcons <- [], NO_SPACING;                         Both => Ok(ShowIcons::Off));
+        test!(bare:      ShowIcons <- ["--icons"], NO_SPACING;                Both => Ok(ShowIcons::On(1)));
+        test!(always:    ShowIcons <- ["--icons=always"], NO_SPACING;         Both => Ok(ShowIcons::On(1)));
+        test!(auto:      ShowIcons <- ["--icons=auto"], NO_SPACING;           Both => Ok(ShowIcons::Automatic(1)));
+        test!(automatic: ShowIcons <- ["--icons=automatic"], NO_SPACING;      Both => Ok(ShowIcons::Automatic(1)));
+        test!(never:     ShowIcons <- ["--icons=never"], NO_SPACING;          Both => Ok(ShowIcons::Off));
+        test!(spacing:   ShowIcons <- ["--icons=auto"], TWO_SPACES;           Both => Ok(ShowIcons::Automatic(2)));
+        test!(no_icons:  ShowIcons <- ["--icons=always", "--no-icons"], NO_SPACING;  Both => Ok(ShowIcons::Off));
+        test!(last:      ShowIcons <- ["--icons=never", "--icons"], NO_SPACING;      Last => Ok(ShowIcons::On(1)));
+        test!(twice:     ShowIcons <- ["--icons=never", "--icons"], NO_SPACING;      Complain => Err(OptionsError::Duplicate(Flag::Long("icons"), Flag::Long("icons"))));
+        test!(unknown:   ShowIcons <- ["--icons=sometimes"], NO_SPACING;      Both => Err(OptionsError::BadArgument(&flags::ICONS, OsString::from("sometimes"))));
+        test!(minimal:   ShowIcons <- ["--icons=minimal"], NO_SPACING;        Both => Ok(ShowIcons::On(1)));
+    }
+
+    mod icon_sets {
+        use super::*;
+
+        test!(empty:     IconSet <- [], NO_SPACING;                           Both => Ok(IconSet::NerdFont));
+        test!(bare:      IconSet <- ["--icons"], NO_SPACING;                  Both => Ok(IconSet::NerdFont));
+        test!(auto:      IconSet <- ["--icons=auto"], NO_SPACING;             Both => Ok(IconSet::NerdFont));
+        test!(minimal:   IconSet <- ["--icons=minimal"], NO_SPACING;          Both => Ok(IconSet::Ascii));
+        test!(env:       IconSet <- ["--icons=auto"], ASCII;                  Both => Ok(IconSet::Ascii));
+        test!(env_bare:  IconSet <- ["--icons"], ASCII;                       Both => Ok(IconSet::Ascii));
+        test!(env_empty: IconSet <- ["--icons"], NOT_ASCII;                   Both => Ok(IconSet::NerdFont));
+        test!(last:      IconSet <- ["--icons=minimal", "--icons"], NO_SPACING;  Last => Ok(IconSet::NerdFont));
+    }
 }
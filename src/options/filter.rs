@@ -1,46 +1,213 @@
 //! Parsing the options for `FileFilter`.
 
+use std::ffi::OsStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use crate::fs::DotFilter;
-use crate::fs::filter::{FileFilter, SortField, SortCase, IgnorePatterns, GitIgnore};
+use crate::fs::filter::{FileFilter, SortField, SortCase, IgnorePatterns, ExtensionFilter, GitIgnore, IgnoreFiles};
 
-use crate::options::{flags, OptionsError};
-use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, NumberSource, Vars};
+use crate::options::parser::{Arg, MatchedFlags};
+use crate::output::time::parse_duration;
 
 
 impl FileFilter {
 
     /// Determines which of all the file filter options to use.
-    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        let now = SystemTime::now();
+
+        if matches.has(&flags::ONLY_FILES)? && matches.has(&flags::ONLY_DIRS)? {
+            return Err(OptionsError::Conflict(&flags::ONLY_FILES, &flags::ONLY_DIRS));
+        }
+
+        if matches.has(&flags::DIRS_FIRST)? && matches.has(&flags::DIRS_LAST)? {
+            return Err(OptionsError::Conflict(&flags::DIRS_FIRST, &flags::DIRS_LAST));
+        }
+
         Ok(Self {
             list_dirs_first:  matches.has(&flags::DIRS_FIRST)?,
+            list_dirs_last:   matches.has(&flags::DIRS_LAST)?,
             reverse:          matches.has(&flags::REVERSE)?,
             only_dirs:        matches.has(&flags::ONLY_DIRS)?,
+            only_files:       matches.has(&flags::ONLY_FILES)?,
             sort_field:       SortField::deduce(matches)?,
+            secondary_sorts:  SortField::deduce_secondaries(matches)?,
+            random_seed:      deduce_random_seed(vars)?,
             dot_filter:       DotFilter::deduce(matches)?,
+            extensions:       ExtensionFilter::deduce(matches)?,
             ignore_patterns:  IgnorePatterns::deduce(matches)?,
+            newer_than:       deduce_time_bound(matches, &flags::NEWER_THAN, now)?,
+            older_than:       deduce_time_bound(matches, &flags::OLDER_THAN, now)?,
+            bigger_than:      deduce_size_bound(matches, &flags::BIGGER_THAN)?,
+            smaller_than:     deduce_size_bound(matches, &flags::SMALLER_THAN)?,
             git_ignore:       GitIgnore::deduce(matches)?,
+            ignore_files:     IgnoreFiles::deduce(matches)?,
+            max_entries:      deduce_max_entries(matches)?,
         })
     }
 }
 
+
+/// Determines the most entries to list from each directory, if there’s a
+/// limit. A limit of zero would leave nothing to list, so it isn’t allowed.
+fn deduce_max_entries(matches: &MatchedFlags<'_>) -> Result<Option<usize>, OptionsError> {
+    if let Some(input) = matches.get(&flags::MAX_ENTRIES)? {
+        let arg_str = input.to_string_lossy();
+        match arg_str.parse() {
+            Ok(0)      => Err(OptionsError::BadArgument(&flags::MAX_ENTRIES, input.into())),
+            Ok(count)  => Ok(Some(count)),
+            Err(e)     => Err(OptionsError::FailedParse(arg_str.to_string(), NumberSource::Arg(&flags::MAX_ENTRIES), e)),
+        }
+    }
+    else {
+        Ok(None)
+    }
+}
+
+
+/// Determines the seed to use when sorting files randomly, if one was given
+/// in the `EXA_SEED` environment variable to make the order reproducible.
+fn deduce_random_seed<V: Vars>(vars: &V) -> Result<Option<u64>, OptionsError> {
+    if let Some(seed) = vars.get(vars::EXA_SEED).and_then(|s| s.into_string().ok()) {
+        match seed.parse() {
+            Ok(number)  => Ok(Some(number)),
+            Err(e)      => Err(OptionsError::FailedParse(seed, NumberSource::Env(vars::EXA_SEED), e)),
+        }
+    }
+    else {
+        Ok(None)
+    }
+}
+
+
+/// Determines the size given to either the `--bigger-than` or
+/// `--smaller-than` argument, if it was given at all.
+fn deduce_size_bound(matches: &MatchedFlags<'_>, arg: &'static Arg) -> Result<Option<u64>, OptionsError> {
+    let input = match matches.get(arg)? {
+        Some(i)  => i.to_string_lossy(),
+        None     => return Ok(None),
+    };
+
+    match parse_size(&input) {
+        Some(size)  => Ok(Some(size)),
+        None        => Err(OptionsError::FailedSizeParse(input.to_string(), NumberSource::Arg(arg))),
+    }
+}
+
+/// Parses a human-readable size into a number of bytes. The number can
+/// have a decimal part, and can be followed by one of the units `K`, `M`,
+/// `G`, or `T`, which use decimal multiples like the size column does, or
+/// by `Ki`, `Mi`, `Gi`, or `Ti` to use binary ones instead.
+pub(super) fn parse_size(input: &str) -> Option<u64> {
+    let split = input.find(|c: char| ! (c.is_ascii_digit() || c == '.')).unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let multiplier: u64 = match unit {
+        ""             => 1,
+        "k" | "K"      => 1000,
+        "M"            => 1000_u64.pow(2),
+        "G"            => 1000_u64.pow(3),
+        "T"            => 1000_u64.pow(4),
+        "ki" | "Ki"    => 1024,
+        "Mi"           => 1024_u64.pow(2),
+        "Gi"           => 1024_u64.pow(3),
+        "Ti"           => 1024_u64.pow(4),
+        _              => return None,
+    };
+
+    if let Ok(whole) = number.parse::<u64>() {
+        return whole.checked_mul(multiplier);
+    }
+
+    let fraction: f64 = number.parse().ok()?;
+    let bytes = (fraction * multiplier as f64).round();
+    if bytes.is_finite() && bytes < u64::MAX as f64 { Some(bytes as u64) } else { None }
+}
+
+/// Determines the time given to either the `--newer-than` or `--older-than`
+/// argument, if it was given at all.
+fn deduce_time_bound(matches: &MatchedFlags<'_>, arg: &'static Arg, now: SystemTime) -> Result<Option<SystemTime>, OptionsError> {
+    match matches.get(arg)? {
+        Some(input)  => parse_time_bound(arg, input, now).map(Some),
+        None         => Ok(None),
+    }
+}
+
+/// Parses the value of a time filter argument, which can either be an ISO
+/// date such as `2024-01-01`, taken as midnight UTC, or a duration before
+/// the current time such as `7d`, `2h`, or `30m`.
+fn parse_time_bound(arg: &'static Arg, input: &OsStr, now: SystemTime) -> Result<SystemTime, OptionsError> {
+    let bad_argument = || OptionsError::BadArgument(arg, input.into());
+    let word = input.to_str().ok_or_else(bad_argument)?;
+
+    if let Some(duration) = parse_duration(word) {
+        return now.checked_sub(duration).ok_or_else(bad_argument);
+    }
+
+    let seconds = parse_iso_date(word).ok_or_else(bad_argument)?;
+    if seconds >= 0 {
+        Ok(UNIX_EPOCH + Duration::from_secs(seconds as u64))
+    }
+    else {
+        Ok(UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs()))
+    }
+}
+
+/// Parses a date in `YYYY-MM-DD` form, returning the number of seconds
+/// since the Unix epoch at the start of that day.
+fn parse_iso_date(word: &str) -> Option<i64> {
+    use datetime::{LocalDate, LocalDateTime, LocalTime, Month};
+
+    let mut bits = word.splitn(3, '-');
+    let year = bits.next()?.parse().ok()?;
+    let month = Month::from_one(bits.next()?.parse().ok()?).ok()?;
+    let day = bits.next()?.parse().ok()?;
+
+    let date = LocalDate::ymd(year, month, day).ok()?;
+    Some(LocalDateTime::new(date, LocalTime::midnight()).to_instant().seconds())
+}
+
 impl SortField {
 
     /// Determines which sort field to use based on the `--sort` argument.
-    /// This argument’s value can be one of several flags, listed above.
+    /// This argument’s value can be one of several flags, listed above, or
+    /// a comma-separated list of them, in which case this is the first.
     /// Returns the default sort field if none is given, or `Err` if the
     /// value doesn’t correspond to a sort field we know about.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let word = match matches.get(&flags::SORT)? {
-            Some(w)  => w,
-            None     => return Ok(Self::default()),
-        };
+        let fields = Self::deduce_list(matches, &flags::SORT)?;
+        Ok(fields.first().copied().unwrap_or_default())
+    }
 
-        // Get String because we can’t match an OsStr
-        let word = match word.to_str() {
-            Some(w)  => w,
-            None     => return Err(OptionsError::BadArgument(&flags::SORT, word.into()))
-        };
+    /// Determines which fields to sort by when files tie on the main one:
+    /// the rest of the comma-separated `--sort` argument, followed by the
+    /// fields in the `--then` argument. Every field in it has to be one
+    /// that `--sort` accepts.
+    fn deduce_secondaries(matches: &MatchedFlags<'_>) -> Result<Vec<Self>, OptionsError> {
+        let mut fields = Self::deduce_list(matches, &flags::SORT)?;
+        if ! fields.is_empty() {
+            fields.remove(0);
+        }
+
+        fields.extend(Self::deduce_list(matches, &flags::THEN)?);
+        Ok(fields)
+    }
 
+    /// Parses the comma-separated list of sort fields given to the flag,
+    /// returning an empty list if it wasn’t given at all.
+    fn deduce_list(matches: &MatchedFlags<'_>, flag: &'static Arg) -> Result<Vec<Self>, OptionsError> {
+        match matches.get(flag)? {
+            None         => Ok(Vec::new()),
+            Some(words)  => words.to_str()
+                                 .and_then(|w| w.split(',').map(Self::from_word).collect())
+                                 .ok_or_else(|| OptionsError::BadArgument(flag, words.into())),
+        }
+    }
+
+    /// Returns the sort field with the given name, or one of its aliases,
+    /// if there is one.
+    fn from_word(word: &str) -> Option<Self> {
         let field = match word {
             "name" | "filename" => {
                 Self::Name(SortCase::AaBbCc)
@@ -92,18 +259,30 @@ impl SortField {
             "inode" => {
                 Self::FileInode
             }
-            "type" => {
-                Self::FileType
+            "type" | "kind" => {
+                Self::FileKind
+            }
+            "dirsize" => {
+                Self::DirEntryCount
+            }
+            "dir" | "dirs" => {
+                Self::DirsFirst
+            }
+            "name-len" | "length" => {
+                Self::NameLength
             }
             "none" => {
                 Self::Unsorted
             }
+            "random" | "rand" => {
+                Self::Random
+            }
             _ => {
-                return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
+                return None;
             }
         };
 
-        Ok(field)
+        Some(field)
     }
 }
 
@@ -204,6 +383,28 @@ impl IgnorePatterns {
 }
 
 
+impl ExtensionFilter {
+
+    /// Determines the list of extensions to keep based on the `--ext`
+    /// argument’s value, which is a list of extensions separated by commas.
+    /// Giving the argument with no extensions in it is an error, rather than
+    /// a way to hide every file.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let input = match matches.get(&flags::EXTENSIONS)? {
+            Some(i)  => i,
+            None     => return Ok(Self::default()),
+        };
+
+        let extensions = input.to_string_lossy().split(',').collect::<Self>();
+        if extensions.is_empty() {
+            return Err(OptionsError::BadArgument(&flags::EXTENSIONS, input.into()));
+        }
+
+        Ok(extensions)
+    }
+}
+
+
 impl GitIgnore {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         if matches.has(&flags::GIT_IGNORE)? {
@@ -216,6 +417,18 @@ impl GitIgnore {
 }
 
 
+impl IgnoreFiles {
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if matches.has(&flags::IGNORE_FILES)? {
+            Ok(Self::CheckAndIgnore)
+        }
+        else {
+            Ok(Self::Off)
+        }
+    }
+}
+
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -231,12 +444,26 @@ mod test {
                 use crate::options::test::parse_for_test;
                 use crate::options::test::Strictnesses::*;
 
-                static TEST_ARGS: &[&Arg] = &[ &flags::SORT, &flags::ALL, &flags::TREE, &flags::IGNORE_GLOB, &flags::GIT_IGNORE ];
+                static TEST_ARGS: &[&Arg] = &[ &flags::SORT, &flags::ALL, &flags::TREE, &flags::IGNORE_GLOB, &flags::GIT_IGNORE, &flags::IGNORE_FILES, &flags::EXTENSIONS, &flags::ONLY_DIRS, &flags::ONLY_FILES, &flags::DIRS_FIRST, &flags::DIRS_LAST ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf)) {
                     assert_eq!(result, $result);
                 }
             }
         };
+
+        ($name:ident: $type:ident <- $inputs:expr, $vars:expr; $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                use crate::options::parser::Arg;
+                use crate::options::test::parse_for_test;
+                use crate::options::test::Strictnesses::*;
+
+                static TEST_ARGS: &[&Arg] = &[ &flags::ONLY_DIRS, &flags::ONLY_FILES, &flags::DIRS_FIRST, &flags::DIRS_LAST ];
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf, &$vars)) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
     }
 
     mod sort_fields {
@@ -256,6 +483,21 @@ mod test {
         test!(new:           SortField <- ["--sort", "old"];   Both => Ok(SortField::ModifiedAge));
         test!(newest:        SortField <- ["--sort=oldest"];   Both => Ok(SortField::ModifiedAge));
         test!(age:           SortField <- ["-sage"];           Both => Ok(SortField::ModifiedAge));
+        test!(dirsize:       SortField <- ["--sort=dirsize"];  Both => Ok(SortField::DirEntryCount));
+        test!(kind:          SortField <- ["--sort", "kind"];  Both => Ok(SortField::FileKind));
+        test!(kind_type:     SortField <- ["--sort=type"];     Both => Ok(SortField::FileKind));
+        test!(name_len:      SortField <- ["--sort=name-len"]; Both => Ok(SortField::NameLength));
+        test!(length:        SortField <- ["--sort", "length"]; Both => Ok(SortField::NameLength));
+        test!(random:        SortField <- ["--sort=random"];   Both => Ok(SortField::Random));
+        test!(dir:           SortField <- ["--sort=dir"];      Both => Ok(SortField::DirsFirst));
+
+        // Lists of sort fields
+        test!(dir_name:      SortField <- ["--sort=dir,name"]; Both => Ok(SortField::DirsFirst));
+        test!(dir_size:      SortField <- ["--sort=dir,size"]; Both => Ok(SortField::DirsFirst));
+        test!(size_dir:      SortField <- ["--sort=size,dir"]; Both => Ok(SortField::Size));
+        test!(list_error:    SortField <- ["--sort=dir,colour"]; Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("dir,colour"))));
+        test!(list_blank:    SortField <- ["--sort=dir,"];     Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("dir,"))));
+        test!(rand:          SortField <- ["--sort", "rand"];  Both => Ok(SortField::Random));
 
         test!(mix_hidden_lowercase:     SortField <- ["--sort", ".name"];  Both => Ok(SortField::NameMixHidden(SortCase::AaBbCc)));
         test!(mix_hidden_uppercase:     SortField <- ["--sort", ".Name"];  Both => Ok(SortField::NameMixHidden(SortCase::ABCabc)));
@@ -271,6 +513,121 @@ mod test {
     }
 
 
+    mod max_entries {
+        use super::*;
+        use crate::options::parser::Arg;
+        use crate::options::test::parse_for_test;
+        use crate::options::test::Strictnesses::{self, *};
+
+        static TEST_ARGS: &[&Arg] = &[ &flags::MAX_ENTRIES ];
+
+        fn check(inputs: &[&str], stricts: Strictnesses, expected: Result<Option<usize>, OptionsError>) {
+            for result in parse_for_test(inputs, TEST_ARGS, stricts, deduce_max_entries) {
+                assert_eq!(result, expected);
+            }
+        }
+
+        #[test]
+        fn empty() {
+            check(&[], Both, Ok(None));
+        }
+
+        #[test]
+        fn count() {
+            check(&["--max-entries=20"], Both, Ok(Some(20)));
+        }
+
+        #[test]
+        fn last() {
+            check(&["--max-entries=20", "--max-entries", "5"], Last, Ok(Some(5)));
+        }
+
+        #[test]
+        fn twice() {
+            check(&["--max-entries=20", "--max-entries", "5"], Complain, Err(OptionsError::Duplicate(Flag::Long("max-entries"), Flag::Long("max-entries"))));
+        }
+
+        #[test]
+        fn zero() {
+            check(&["--max-entries=0"], Both, Err(OptionsError::BadArgument(&flags::MAX_ENTRIES, OsString::from("0"))));
+        }
+
+        #[test]
+        fn not_a_number() {
+            for result in parse_for_test(&["--max-entries=lots"], TEST_ARGS, Both, deduce_max_entries) {
+                assert!(matches!(result, Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _))));
+            }
+        }
+    }
+
+
+    mod secondary_sorts {
+        use super::*;
+        use crate::options::parser::Arg;
+        use crate::options::test::parse_for_test;
+        use crate::options::test::Strictnesses::{self, *};
+
+        static TEST_ARGS: &[&Arg] = &[ &flags::SORT, &flags::THEN ];
+
+        fn check(inputs: &[&str], stricts: Strictnesses, expected: Result<Vec<SortField>, OptionsError>) {
+            for result in parse_for_test(inputs, TEST_ARGS, stricts, SortField::deduce_secondaries) {
+                assert_eq!(result, expected);
+            }
+        }
+
+        #[test]
+        fn empty() {
+            check(&[], Both, Ok(Vec::new()));
+        }
+
+        #[test]
+        fn one() {
+            check(&["--sort=ext", "--then=size"], Both, Ok(vec![ SortField::Size ]));
+        }
+
+        #[test]
+        fn several() {
+            check(&["--then", "size,Name,age"], Both, Ok(vec![ SortField::Size, SortField::Name(SortCase::ABCabc), SortField::ModifiedAge ]));
+        }
+
+        #[test]
+        fn unknown() {
+            check(&["--then=size,colour"], Both, Err(OptionsError::BadArgument(&flags::THEN, OsString::from("size,colour"))));
+        }
+
+        #[test]
+        fn blank() {
+            check(&["--then=size,"], Both, Err(OptionsError::BadArgument(&flags::THEN, OsString::from("size,"))));
+        }
+
+        #[test]
+        fn single_sort() {
+            check(&["--sort=size"], Both, Ok(Vec::new()));
+        }
+
+        #[test]
+        fn dir_name() {
+            check(&["--sort=dir,name"], Both, Ok(vec![ SortField::Name(SortCase::AaBbCc) ]));
+        }
+
+        #[test]
+        fn dir_size() {
+            check(&["--sort=dir,size"], Both, Ok(vec![ SortField::Size ]));
+        }
+
+        #[test]
+        fn sort_list_then() {
+            check(&["--sort=dir,size,Ext", "--then=name"], Both, Ok(vec![ SortField::Size, SortField::Extension(SortCase::ABCabc), SortField::Name(SortCase::AaBbCc) ]));
+        }
+
+        #[test]
+        fn overridden() {
+            check(&["--then=size", "--then=mod"], Last, Ok(vec![ SortField::ModifiedDate ]));
+            check(&["--then=size", "--then=mod"], Complain, Err(OptionsError::Duplicate(Flag::Long("then"), Flag::Long("then"))));
+        }
+    }
+
+
     mod dot_filters {
         use super::*;
 
@@ -292,6 +649,68 @@ mod test {
     }
 
 
+    mod only_files {
+        use super::*;
+
+        test!(conflict:   FileFilter <- ["--only-files", "--only-dirs"], None;  Both => Err(OptionsError::Conflict(&flags::ONLY_FILES, &flags::ONLY_DIRS)));
+        test!(conflict_2: FileFilter <- ["-fD"], None;                          Both => Err(OptionsError::Conflict(&flags::ONLY_FILES, &flags::ONLY_DIRS)));
+    }
+
+
+    mod random_seeds {
+        use super::*;
+        use std::num::ParseIntError;
+
+        #[test]
+        fn no_seed() {
+            assert_eq!(deduce_random_seed(&None), Ok(None));
+        }
+
+        #[test]
+        fn seed() {
+            assert_eq!(deduce_random_seed(&Some(OsString::from("42"))), Ok(Some(42)));
+        }
+
+        #[test]
+        fn bad_seed() {
+            let e: ParseIntError = "lucky".parse::<u64>().unwrap_err();
+            assert_eq!(deduce_random_seed(&Some(OsString::from("lucky"))),
+                       Err(OptionsError::FailedParse(String::from("lucky"), NumberSource::Env(vars::EXA_SEED), e)));
+        }
+    }
+
+
+    mod dirs_grouping {
+        use super::*;
+
+        test!(conflict:   FileFilter <- ["--group-directories-first", "--group-directories-last"], None;  Both => Err(OptionsError::Conflict(&flags::DIRS_FIRST, &flags::DIRS_LAST)));
+    }
+
+
+    mod extensions {
+        use super::*;
+        use std::iter::FromIterator;
+
+        fn exts(list: &[&'static str]) -> ExtensionFilter {
+            ExtensionFilter::from_iter(list.iter().copied())
+        }
+
+        test!(none:      ExtensionFilter <- [];                      Both => Ok(ExtensionFilter::default()));
+        test!(one:       ExtensionFilter <- ["--ext", "rs"];         Both => Ok(exts(&[ "rs" ])));
+        test!(two:       ExtensionFilter <- ["--ext=rs,toml"];       Both => Ok(exts(&[ "rs", "toml" ])));
+        test!(cased:     ExtensionFilter <- ["--ext=RS,.Toml"];      Both => Ok(exts(&[ "rs", "toml" ])));
+        test!(spaced:    ExtensionFilter <- ["--ext=rs, toml,"];     Both => Ok(exts(&[ "rs", "toml" ])));
+
+        // Errors
+        test!(blank:     ExtensionFilter <- ["--ext", ""];           Both => Err(OptionsError::BadArgument(&flags::EXTENSIONS, OsString::from(""))));
+        test!(commas:    ExtensionFilter <- ["--ext=,,"];            Both => Err(OptionsError::BadArgument(&flags::EXTENSIONS, OsString::from(",,"))));
+
+        // Overriding
+        test!(overridden:   ExtensionFilter <- ["--ext=rs", "--ext", "md"];  Last => Ok(exts(&[ "md" ])));
+        test!(overridden_2: ExtensionFilter <- ["--ext=rs", "--ext", "md"];  Complain => Err(OptionsError::Duplicate(Flag::Long("ext"), Flag::Long("ext"))));
+    }
+
+
     mod ignore_patterns {
         use super::*;
         use std::iter::FromIterator;
@@ -314,10 +733,157 @@ mod test {
     }
 
 
+    mod time_bounds {
+        use super::*;
+
+        fn at(seconds: u64) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(seconds)
+        }
+
+        fn parse(input: &str) -> Result<SystemTime, OptionsError> {
+            parse_time_bound(&flags::NEWER_THAN, OsStr::new(input), at(10_000_000))
+        }
+
+        #[test]
+        fn relative_seconds() {
+            assert_eq!(parse("90s"), Ok(at(10_000_000 - 90)));
+        }
+
+        #[test]
+        fn relative_minutes() {
+            assert_eq!(parse("30m"), Ok(at(10_000_000 - 30 * 60)));
+        }
+
+        #[test]
+        fn relative_hours() {
+            assert_eq!(parse("2h"), Ok(at(10_000_000 - 2 * 60 * 60)));
+        }
+
+        #[test]
+        fn relative_days() {
+            assert_eq!(parse("7d"), Ok(at(10_000_000 - 7 * 24 * 60 * 60)));
+        }
+
+        #[test]
+        fn relative_weeks() {
+            assert_eq!(parse("1w"), Ok(at(10_000_000 - 7 * 24 * 60 * 60)));
+        }
+
+        #[test]
+        fn absolute_date() {
+            assert_eq!(parse("2024-01-01"), Ok(at(1_704_067_200)));
+        }
+
+        #[test]
+        fn absolute_leap_day() {
+            assert_eq!(parse("2020-02-29"), Ok(at(1_582_934_400)));
+        }
+
+        #[test]
+        fn bad_unit() {
+            assert_eq!(parse("7y"), Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("7y"))));
+        }
+
+        #[test]
+        fn bad_word() {
+            assert_eq!(parse("yesterday"), Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("yesterday"))));
+        }
+
+        #[test]
+        fn bad_date() {
+            assert_eq!(parse("2024-13-01"), Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("2024-13-01"))));
+        }
+
+        #[test]
+        fn bad_day() {
+            assert_eq!(parse("2023-02-29"), Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("2023-02-29"))));
+        }
+
+        #[test]
+        fn too_long_ago() {
+            assert_eq!(parse("40000000000000000w"), Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("40000000000000000w"))));
+        }
+    }
+
+
+    mod size_bounds {
+        use super::*;
+
+        macro_rules! test {
+            ($name:ident: $input:expr => $result:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(parse_size($input), $result);
+                }
+            };
+        }
+
+        // Plain bytes
+        test!(bytes:      "500"     => Some(500));
+        test!(zero:       "0"       => Some(0));
+
+        // Units
+        test!(kilo:       "10K"     => Some(10_000));
+        test!(kilo_lower: "10k"     => Some(10_000));
+        test!(mega:       "10M"     => Some(10_000_000));
+        test!(giga:       "2G"      => Some(2_000_000_000));
+        test!(tera:       "1T"      => Some(1_000_000_000_000));
+        test!(kibi:       "1Ki"     => Some(1024));
+        test!(mebi:       "3Mi"     => Some(3 * 1024 * 1024));
+        test!(gibi:       "1Gi"     => Some(1024 * 1024 * 1024));
+
+        // Decimals
+        test!(dec_giga:   "1.5G"    => Some(1_500_000_000));
+        test!(dec_kibi:   "0.5Ki"   => Some(512));
+        test!(dec_bytes:  "2.4"     => Some(2));
+
+        // Errors
+        test!(empty:      ""        => None);
+        test!(no_number:  "M"       => None);
+        test!(bad_unit:   "10X"     => None);
+        test!(long_unit:  "10MB"    => None);
+        test!(two_dots:   "1.2.3K"  => None);
+        test!(negative:   "-10K"    => None);
+        test!(overflow:   "99999999999T" => None);
+
+        #[test]
+        fn error_names_flag() {
+            use crate::options::parser::Arg;
+            use crate::options::test::parse_for_test;
+            use crate::options::test::Strictnesses::*;
+
+            static TEST_ARGS: &[&Arg] = &[ &flags::BIGGER_THAN, &flags::SMALLER_THAN ];
+            for result in parse_for_test(&["--bigger-than=10X"], TEST_ARGS, Both, |mf| deduce_size_bound(mf, &flags::BIGGER_THAN)) {
+                assert_eq!(result, Err(OptionsError::FailedSizeParse(String::from("10X"), NumberSource::Arg(&flags::BIGGER_THAN))));
+            }
+        }
+
+        #[test]
+        fn absent() {
+            use crate::options::parser::Arg;
+            use crate::options::test::parse_for_test;
+            use crate::options::test::Strictnesses::*;
+
+            static TEST_ARGS: &[&Arg] = &[ &flags::BIGGER_THAN, &flags::SMALLER_THAN ];
+            for result in parse_for_test(&["--smaller-than", "1M"], TEST_ARGS, Both, |mf| deduce_size_bound(mf, &flags::BIGGER_THAN)) {
+                assert_eq!(result, Ok(None));
+            }
+        }
+    }
+
+
     mod git_ignores {
         use super::*;
 
         test!(off:  GitIgnore <- [];                Both => Ok(GitIgnore::Off));
         test!(on:   GitIgnore <- ["--git-ignore"];  Both => Ok(GitIgnore::CheckAndIgnore));
     }
+
+
+    mod ignore_files {
+        use super::*;
+
+        test!(off:  IgnoreFiles <- [];                       Both => Ok(IgnoreFiles::Off));
+        test!(on:   IgnoreFiles <- ["--ignore-glob-files"];  Both => Ok(IgnoreFiles::CheckAndIgnore));
+    }
 }
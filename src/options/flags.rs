@@ -4,55 +4,122 @@ use crate::options::parser::{Arg, Args, TakesValue, Values};
 // exa options
 pub static VERSION: Arg = Arg { short: Some(b'v'), long: "version",  takes_value: TakesValue::Forbidden };
 pub static HELP:    Arg = Arg { short: Some(b'?'), long: "help",     takes_value: TakesValue::Forbidden };
+pub static GENERATE_COMPLETIONS: Arg = Arg { short: None, long: "generate-completions", takes_value: TakesValue::Necessary(Some(SHELLS)) };
+const SHELLS: Values = &["bash", "zsh", "fish"];
 
 // display options
 pub static ONE_LINE: Arg = Arg { short: Some(b'1'), long: "oneline",  takes_value: TakesValue::Forbidden };
+pub static ZERO:     Arg = Arg { short: Some(b'0'), long: "zero",     takes_value: TakesValue::Forbidden };
+pub static CSV:      Arg = Arg { short: None,       long: "csv",      takes_value: TakesValue::Forbidden };
+pub static TSV:      Arg = Arg { short: None,       long: "tsv",      takes_value: TakesValue::Forbidden };
+pub static STAT:     Arg = Arg { short: None,       long: "stat",     takes_value: TakesValue::Forbidden };
+pub static PAGING:   Arg = Arg { short: None,       long: "paging",   takes_value: TakesValue::Optional(Some(PAGINGS)) };
+const PAGINGS: Values = &["auto", "never"];
 pub static LONG:     Arg = Arg { short: Some(b'l'), long: "long",     takes_value: TakesValue::Forbidden };
 pub static GRID:     Arg = Arg { short: Some(b'G'), long: "grid",     takes_value: TakesValue::Forbidden };
 pub static ACROSS:   Arg = Arg { short: Some(b'x'), long: "across",   takes_value: TakesValue::Forbidden };
+pub static WIDTH:    Arg = Arg { short: Some(b'w'), long: "width",    takes_value: TakesValue::Necessary(None) };
+pub static MIN_COLUMNS: Arg = Arg { short: None, long: "min-columns", takes_value: TakesValue::Necessary(None) };
+pub static MAX_COLUMNS: Arg = Arg { short: None, long: "max-columns", takes_value: TakesValue::Necessary(None) };
 pub static RECURSE:  Arg = Arg { short: Some(b'R'), long: "recurse",  takes_value: TakesValue::Forbidden };
 pub static TREE:     Arg = Arg { short: Some(b'T'), long: "tree",     takes_value: TakesValue::Forbidden };
-pub static CLASSIFY: Arg = Arg { short: Some(b'F'), long: "classify", takes_value: TakesValue::Forbidden };
+pub static CLASSIFY: Arg = Arg { short: Some(b'F'), long: "classify", takes_value: TakesValue::Optional(Some(CLASSIFY_MODES)) };
+const CLASSIFY_MODES: Values = &["always", "auto", "never"];
 
 pub static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Necessary(Some(COLOURS)) };
 pub static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Necessary(Some(COLOURS)) };
 const COLOURS: &[&str] = &["always", "auto", "never"];
 
-pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_value: TakesValue::Forbidden };
-pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::Forbidden };
+pub static THEME:      Arg = Arg { short: None, long: "theme",      takes_value: TakesValue::Necessary(Some(THEMES)) };
+const THEMES: Values = &["dark", "light"];
+pub static THEME_FILE: Arg = Arg { short: None, long: "theme-file", takes_value: TakesValue::Necessary(None) };
+pub static DIM_HIDDEN: Arg = Arg { short: None, long: "dim-hidden", takes_value: TakesValue::Forbidden };
+
+pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_value: TakesValue::Optional(Some(COLOUR_SCALES)) };
+pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::Optional(Some(COLOUR_SCALES)) };
+const COLOUR_SCALES: Values = &["size", "age", "all"];
+pub static AGE_COLORS:  Arg = Arg { short: None, long: "age-colors",  takes_value: TakesValue::Forbidden };
+pub static AGE_COLOURS: Arg = Arg { short: None, long: "age-colours", takes_value: TakesValue::Forbidden };
 
 // filtering and sorting options
 pub static ALL:         Arg = Arg { short: Some(b'a'), long: "all",         takes_value: TakesValue::Forbidden };
 pub static LIST_DIRS:   Arg = Arg { short: Some(b'd'), long: "list-dirs",   takes_value: TakesValue::Forbidden };
 pub static LEVEL:       Arg = Arg { short: Some(b'L'), long: "level",       takes_value: TakesValue::Necessary(None) };
+pub static DEPTH:       Arg = Arg { short: None,       long: "depth",       takes_value: TakesValue::Necessary(None) };
+pub static COLLAPSE_EXTS: Arg = Arg { short: None, long: "collapse-exts",   takes_value: TakesValue::Optional(None) };
+pub static COLLAPSE_SINGLE_CHILD: Arg = Arg { short: None, long: "collapse-single-child", takes_value: TakesValue::Forbidden };
+pub static ONE_FILE_SYSTEM: Arg = Arg { short: None, long: "one-file-system", takes_value: TakesValue::Forbidden };
+pub static FOLLOW_SYMLINKS: Arg = Arg { short: None, long: "follow-symlinks", takes_value: TakesValue::Forbidden };
+pub static DEREFERENCE_COMMAND_LINE: Arg = Arg { short: None, long: "dereference-command-line", takes_value: TakesValue::Forbidden };
+pub static PEEK_ARCHIVES: Arg = Arg { short: None, long: "peek-archives",   takes_value: TakesValue::Forbidden };
+pub static PATHS_FROM:  Arg = Arg { short: None,       long: "paths-from",  takes_value: TakesValue::Necessary(None) };
+pub static PRUNE:       Arg = Arg { short: None,       long: "prune",       takes_value: TakesValue::Forbidden };
+pub static FLAT:        Arg = Arg { short: None,       long: "flat",        takes_value: TakesValue::Forbidden };
+pub static FILES_FIRST: Arg = Arg { short: None,       long: "files-first", takes_value: TakesValue::Forbidden };
+pub static REVERSE_TREE: Arg = Arg { short: None,    long: "reverse-tree", takes_value: TakesValue::Forbidden };
+pub static TREE_STYLE:  Arg = Arg { short: None,       long: "tree-style",  takes_value: TakesValue::Necessary(Some(TREE_STYLES)) };
+const TREE_STYLES: Values = &["unicode", "ascii"];
 pub static REVERSE:     Arg = Arg { short: Some(b'r'), long: "reverse",     takes_value: TakesValue::Forbidden };
 pub static SORT:        Arg = Arg { short: Some(b's'), long: "sort",        takes_value: TakesValue::Necessary(Some(SORTS)) };
+pub static THEN:        Arg = Arg { short: None,       long: "then",        takes_value: TakesValue::Necessary(Some(SORTS)) };
 pub static IGNORE_GLOB: Arg = Arg { short: Some(b'I'), long: "ignore-glob", takes_value: TakesValue::Necessary(None) };
+pub static EXTENSIONS:  Arg = Arg { short: None,       long: "ext",         takes_value: TakesValue::Necessary(None) };
 pub static GIT_IGNORE:  Arg = Arg { short: None, long: "git-ignore",           takes_value: TakesValue::Forbidden };
+pub static IGNORE_FILES: Arg = Arg { short: None, long: "ignore-glob-files",   takes_value: TakesValue::Forbidden };
 pub static DIRS_FIRST:  Arg = Arg { short: None, long: "group-directories-first",  takes_value: TakesValue::Forbidden };
+pub static DIRS_LAST:   Arg = Arg { short: None, long: "group-directories-last",   takes_value: TakesValue::Forbidden };
 pub static ONLY_DIRS:   Arg = Arg { short: Some(b'D'), long: "only-dirs", takes_value: TakesValue::Forbidden };
+pub static ONLY_FILES:  Arg = Arg { short: Some(b'f'), long: "only-files", takes_value: TakesValue::Forbidden };
+pub static NEWER_THAN:  Arg = Arg { short: None, long: "newer-than",  takes_value: TakesValue::Necessary(None) };
+pub static OLDER_THAN:  Arg = Arg { short: None, long: "older-than",  takes_value: TakesValue::Necessary(None) };
+pub static BIGGER_THAN:  Arg = Arg { short: None, long: "bigger-than",  takes_value: TakesValue::Necessary(None) };
+pub static SMALLER_THAN: Arg = Arg { short: None, long: "smaller-than", takes_value: TakesValue::Necessary(None) };
+pub static MAX_ENTRIES:  Arg = Arg { short: None, long: "max-entries",  takes_value: TakesValue::Necessary(None) };
 const SORTS: Values = &[ "name", "Name", "size", "extension",
                          "Extension", "modified", "changed", "accessed",
-                         "created", "inode", "type", "none" ];
+                         "created", "inode", "type", "kind", "dirsize", "dir", "name-len",
+                         "random", "none" ];
 
 // display options
 pub static BINARY:     Arg = Arg { short: Some(b'b'), long: "binary",     takes_value: TakesValue::Forbidden };
 pub static BYTES:      Arg = Arg { short: Some(b'B'), long: "bytes",      takes_value: TakesValue::Forbidden };
+pub static SI:         Arg = Arg { short: None,       long: "si",         takes_value: TakesValue::Forbidden };
+pub static IEC:        Arg = Arg { short: None,       long: "iec",        takes_value: TakesValue::Forbidden };
+pub static COMMA:      Arg = Arg { short: None,       long: "comma",      takes_value: TakesValue::Forbidden };
+pub static SIZE_PERCENT: Arg = Arg { short: None,     long: "size-percent", takes_value: TakesValue::Optional(Some(SIZE_PERCENTS)) };
+const SIZE_PERCENTS: Values = &["dir", "global"];
 pub static GROUP:      Arg = Arg { short: Some(b'g'), long: "group",      takes_value: TakesValue::Forbidden };
 pub static NUMERIC:    Arg = Arg { short: Some(b'n'), long: "numeric",    takes_value: TakesValue::Forbidden };
 pub static HEADER:     Arg = Arg { short: Some(b'h'), long: "header",     takes_value: TakesValue::Forbidden };
-pub static ICONS:      Arg = Arg { short: None,       long: "icons",      takes_value: TakesValue::Forbidden };
+pub static REPEAT_HEADER: Arg = Arg { short: None,   long: "repeat-header", takes_value: TakesValue::Optional(None) };
+pub static NO_HEADERS_BETWEEN_DIRS: Arg = Arg { short: None, long: "no-headers-between-dirs", takes_value: TakesValue::Forbidden };
+pub static SUMMARY:    Arg = Arg { short: None,       long: "summary",    takes_value: TakesValue::Forbidden };
+pub static ICONS:      Arg = Arg { short: None,       long: "icons",      takes_value: TakesValue::Optional(Some(ICON_MODES)) };
+const ICON_MODES: Values = &["always", "auto", "never", "minimal"];
+pub static HYPERLINK:  Arg = Arg { short: None,       long: "hyperlink",  takes_value: TakesValue::Forbidden };
+pub static QUOTING_STYLE: Arg = Arg { short: None,    long: "quoting-style", takes_value: TakesValue::Necessary(Some(QUOTING_STYLES)) };
+const QUOTING_STYLES: Values = &["literal", "shell", "shell-always", "c", "escape"];
+pub static ABSOLUTE:   Arg = Arg { short: None,       long: "absolute",   takes_value: TakesValue::Optional(Some(ABSOLUTE_MODES)) };
+const ABSOLUTE_MODES: Values = &["on", "off", "follow"];
+pub static NO_SYMLINKS: Arg = Arg { short: None,     long: "no-symlinks", takes_value: TakesValue::Forbidden };
+pub static SHOW_SYMLINK_TARGETS: Arg = Arg { short: None, long: "show-symlink-targets", takes_value: TakesValue::Forbidden };
 pub static INODE:      Arg = Arg { short: Some(b'i'), long: "inode",      takes_value: TakesValue::Forbidden };
 pub static LINKS:      Arg = Arg { short: Some(b'H'), long: "links",      takes_value: TakesValue::Forbidden };
+pub static HARD_LINK_GROUPS: Arg = Arg { short: None, long: "hard-link-groups", takes_value: TakesValue::Forbidden };
 pub static MODIFIED:   Arg = Arg { short: Some(b'm'), long: "modified",   takes_value: TakesValue::Forbidden };
 pub static CHANGED:    Arg = Arg { short: None,       long: "changed",    takes_value: TakesValue::Forbidden };
+pub static TOTAL_SIZE: Arg = Arg { short: None,       long: "total-size", takes_value: TakesValue::Forbidden };
+pub static DIR_COUNTS: Arg = Arg { short: None,       long: "dir-counts", takes_value: TakesValue::Forbidden };
 pub static BLOCKS:     Arg = Arg { short: Some(b'S'), long: "blocks",     takes_value: TakesValue::Forbidden };
+pub static BLOCK_SIZE: Arg = Arg { short: None,       long: "block-size", takes_value: TakesValue::Necessary(None) };
+pub static BLOCKSIZE_HUMAN: Arg = Arg { short: None,  long: "blocksize-human", takes_value: TakesValue::Forbidden };
 pub static TIME:       Arg = Arg { short: Some(b't'), long: "time",       takes_value: TakesValue::Necessary(Some(TIMES)) };
 pub static ACCESSED:   Arg = Arg { short: Some(b'u'), long: "accessed",   takes_value: TakesValue::Forbidden };
 pub static CREATED:    Arg = Arg { short: Some(b'U'), long: "created",    takes_value: TakesValue::Forbidden };
 pub static TIME_STYLE: Arg = Arg { short: None,       long: "time-style", takes_value: TakesValue::Necessary(Some(TIME_STYLES)) };
+pub static DEREFERENCE: Arg = Arg { short: None,      long: "dereference", takes_value: TakesValue::Forbidden };
 const TIMES: Values = &["modified", "changed", "accessed", "created"];
-const TIME_STYLES: Values = &["default", "long-iso", "full-iso", "iso"];
+const TIME_STYLES: Values = &["default", "long-iso", "full-iso", "iso", "precise", "relative"];
 
 // suppressing columns
 pub static NO_PERMISSIONS: Arg = Arg { short: None, long: "no-permissions", takes_value: TakesValue::Forbidden };
@@ -61,24 +128,37 @@ pub static NO_USER: Arg = Arg { short: None, long: "no-user", takes_value: Takes
 pub static NO_TIME: Arg = Arg { short: None, long: "no-time", takes_value: TakesValue::Forbidden };
 pub static NO_ICONS: Arg = Arg { short: None, long: "no-icons", takes_value: TakesValue::Forbidden };
 
+// picking columns
+pub static COLUMNS: Arg = Arg { short: None, long: "columns", takes_value: TakesValue::Necessary(Some(COLUMN_NAMES)) };
+pub static ONLY: Arg = Arg { short: None, long: "only", takes_value: TakesValue::Necessary(Some(COLUMN_NAMES)) };
+const COLUMN_NAMES: Values = &["perms", "octal", "links", "inode", "blocks", "user", "group", "size", "git", "date", "name"];
+
 // optional feature options
-pub static GIT:       Arg = Arg { short: None,       long: "git",               takes_value: TakesValue::Forbidden };
+pub static GIT:       Arg = Arg { short: None,       long: "git",               takes_value: TakesValue::Optional(Some(GIT_FORMATS)) };
+const GIT_FORMATS: Values = &["short", "long"];
+pub static GIT_HIGHLIGHT: Arg = Arg { short: None,   long: "git-highlight",     takes_value: TakesValue::Forbidden };
+pub static GIT_REPOS: Arg = Arg { short: None,       long: "git-repos",         takes_value: TakesValue::Forbidden };
+pub static NO_GIT_REPOS_RECURSE: Arg = Arg { short: None, long: "no-git-repos-recurse", takes_value: TakesValue::Forbidden };
 pub static EXTENDED:  Arg = Arg { short: Some(b'@'), long: "extended",          takes_value: TakesValue::Forbidden };
+pub static XATTR_NAMES: Arg = Arg { short: None,     long: "xattr-names",       takes_value: TakesValue::Forbidden };
 pub static OCTAL:     Arg = Arg { short: None,       long: "octal-permissions", takes_value: TakesValue::Forbidden };
+pub static MOUNTS:    Arg = Arg { short: None,       long: "mounts",            takes_value: TakesValue::Forbidden };
+pub static MIME:      Arg = Arg { short: None,       long: "mime",              takes_value: TakesValue::Forbidden };
 
 
 pub static ALL_ARGS: Args = Args(&[
-    &VERSION, &HELP,
+    &VERSION, &HELP, &GENERATE_COMPLETIONS,
 
-    &ONE_LINE, &LONG, &GRID, &ACROSS, &RECURSE, &TREE, &CLASSIFY,
-    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE,
+    &ONE_LINE, &ZERO, &CSV, &TSV, &STAT, &PAGING, &LONG, &GRID, &ACROSS, &WIDTH, &MIN_COLUMNS, &MAX_COLUMNS, &RECURSE, &TREE, &CLASSIFY,
+    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &AGE_COLORS, &AGE_COLOURS, &THEME, &THEME_FILE, &DIM_HIDDEN,
 
-    &ALL, &LIST_DIRS, &LEVEL, &REVERSE, &SORT, &DIRS_FIRST,
-    &IGNORE_GLOB, &GIT_IGNORE, &ONLY_DIRS,
+    &ALL, &LIST_DIRS, &LEVEL, &DEPTH, &COLLAPSE_EXTS, &COLLAPSE_SINGLE_CHILD, &ONE_FILE_SYSTEM, &FOLLOW_SYMLINKS, &DEREFERENCE_COMMAND_LINE, &PEEK_ARCHIVES, &PATHS_FROM, &PRUNE, &FLAT, &FILES_FIRST, &REVERSE_TREE, &TREE_STYLE, &REVERSE, &SORT, &THEN, &DIRS_FIRST, &DIRS_LAST,
+    &IGNORE_GLOB, &EXTENSIONS, &GIT_IGNORE, &IGNORE_FILES, &ONLY_DIRS, &ONLY_FILES, &NEWER_THAN, &OLDER_THAN,
+    &BIGGER_THAN, &SMALLER_THAN, &MAX_ENTRIES,
 
-    &BINARY, &BYTES, &GROUP, &NUMERIC, &HEADER, &ICONS, &INODE, &LINKS, &MODIFIED, &CHANGED,
-    &BLOCKS, &TIME, &ACCESSED, &CREATED, &TIME_STYLE,
-    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &NO_ICONS,
+    &BINARY, &BYTES, &SI, &IEC, &COMMA, &SIZE_PERCENT, &GROUP, &NUMERIC, &HEADER, &REPEAT_HEADER, &NO_HEADERS_BETWEEN_DIRS, &SUMMARY, &ICONS, &HYPERLINK, &QUOTING_STYLE, &ABSOLUTE, &NO_SYMLINKS, &SHOW_SYMLINK_TARGETS, &INODE, &LINKS, &HARD_LINK_GROUPS, &MODIFIED, &CHANGED,
+    &TOTAL_SIZE, &DIR_COUNTS, &BLOCKS, &BLOCK_SIZE, &BLOCKSIZE_HUMAN, &TIME, &ACCESSED, &CREATED, &TIME_STYLE, &DEREFERENCE,
+    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &NO_ICONS, &COLUMNS, &ONLY,
 
-    &GIT, &EXTENDED, &OCTAL
+    &GIT, &GIT_HIGHLIGHT, &GIT_REPOS, &NO_GIT_REPOS_RECURSE, &EXTENDED, &XATTR_NAMES, &OCTAL, &MOUNTS, &MIME
 ]);
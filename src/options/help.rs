@@ -14,56 +14,120 @@ META OPTIONS
 
 DISPLAY OPTIONS
   -1, --oneline      display one entry per line
+  -0, --zero         display entries separated by NUL bytes, for xargs -0
+  --csv, --tsv       display the long view's columns as comma- or tab-separated values
+  --stat             display every detail of each file as a block of labelled lines
+  --paging[=WHEN]    send output taller than the terminal to $PAGER (auto, never)
   -l, --long         display extended file metadata as a table
   -G, --grid         display entries as a grid (default)
   -x, --across       sort the grid across, rather than downwards
+  -w, --width COLS   set the width of the terminal, overriding $COLUMNS
+  --min-columns N    lay the grid out in at least N columns, if they fit
+  --max-columns N    lay the grid out in at most N columns
   -R, --recurse      recurse into directories
   -T, --tree         recurse into directories as a tree
-  -F, --classify     display type indicator by file names
+  -F, --classify     when to display type indicators (always, auto, never)
   --colo[u]r=WHEN    when to use terminal colours (always, auto, never)
-  --colo[u]r-scale   highlight levels of file sizes distinctly
-  --icons            display icons
+  --colo[u]r-scale   highlight levels of file sizes or ages (size,age or all)
+  --age-colo[u]rs    colour timestamps by how long ago they were
+  --theme=THEME      which built-in colours to use (dark, light)
+  --theme-file FILE  load the colours of the interface from a file
+  --dim-hidden       dim the names of hidden files
+  --icons[=WHEN]     when to display icons (always, auto, never, minimal)
   --no-icons         don't display icons (always overrides --icons)
+  --hyperlink        display entries as hyperlinks
+  --quoting-style=STYLE  how to quote file names (literal, shell, shell-always, c, escape)
+  --absolute[=WHEN]  display absolute paths instead of names (on, off, follow)
+  --no-symlinks      don't display the targets of symlinks after their names
+  --show-symlink-targets  display the targets of symlinks in every view
 
 FILTERING AND SORTING OPTIONS
   -a, --all                  show hidden and 'dot' files
   -d, --list-dirs            list directories as files; don't list their contents
-  -L, --level DEPTH          limit the depth of recursion
+  -L, --level DEPTH          limit the depth of recursion, recursing if not already
+  --depth DEPTH              same as --level
+  --collapse-exts[=COUNT]    fold files sharing an extension in a tree (default 8)
+  --collapse-single-child    list chains of directories with one directory in each on one line
+  --one-file-system          don't recurse into directories on other file systems
+  --follow-symlinks          recurse into symlinks that point to directories
+  --dereference-command-line list the directories that symlinks given as arguments point to
+  --peek-archives            list the contents of zip and tar files like directories
+  --paths-from FILE          also list the paths in a file, one per line
+  --prune                    when recursing, hide directories with nothing in them to list
+  --flat                     recurse into directories, listing every file by its path at once
+  --files-first              when recursing, list each level before descending any further
+  --reverse-tree             list the entries at each level of a tree the other way round
+  --tree-style STYLE         which characters to draw the tree with (unicode, ascii)
   -r, --reverse              reverse the sort order
-  -s, --sort SORT_FIELD      which field to sort by
+  -s, --sort SORT_FIELDS     which fields to sort by, separated by commas
+  --then SORT_FIELDS         which fields to sort by when files tie, separated by commas
   --group-directories-first  list directories before other files
+  --group-directories-last   list directories after other files
   -D, --only-dirs            list only directories
-  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore";
+  -f, --only-files           list only files
+  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore
+  --ignore-glob-files        ignore files mentioned in '.ignore' and '.fdignore'
+  --ext EXTS                 only list files with these extensions (comma-separated)
+  --newer-than WHEN          only list files modified after a date or duration ago
+  --older-than WHEN          only list files modified before a date or duration ago
+  --bigger-than SIZE         only list files bigger than a size, such as 10M
+  --smaller-than SIZE        only list files smaller than a size, such as 1.5G
+  --max-entries COUNT        list at most this many entries from each directory";
 
   static USAGE_PART2: &str = "  \
   Valid sort fields:         name, Name, extension, Extension, size, type,
-                             modified, accessed, created, inode, and none.
+                             modified, accessed, created, inode, kind,
+                             dirsize, dir, name-len, random, and none.
                              date, time, old, and new all refer to modified.
 
 LONG VIEW OPTIONS
   -b, --binary         list file sizes with binary prefixes
   -B, --bytes          list file sizes in bytes, without any prefixes
+  --si                 list file sizes with SI units (kB, MB, GB)
+  --iec                list file sizes with IEC units (KiB, MiB, GiB)
+  --comma              put commas between thousands in byte sizes
+  --size-percent[=WHERE]  list file sizes as a percentage of the total (dir, global)
   -g, --group          list each file's group
   -h, --header         add a header row to each column
+  --repeat-header[=N]  repeat the header row every N rows (default: screen height)
+  --no-headers-between-dirs  only show the header row above the first directory
+  --summary            end with the number of files and directories and their total size
   -H, --links          list each file's number of hard links
+  --hard-link-groups   tag the files that are hard links to each other
   -i, --inode          list each file's inode number
   -m, --modified       use the modified timestamp field
   -n, --numeric        list numeric user and group IDs
   -S, --blocks         show number of file system blocks
+  --block-size SIZE    count blocks in units of SIZE bytes (default 512)
+  --blocksize-human    show the space blocks take up on disk as a size instead
+  --total-size         show the recursive total size of directories
+  --dir-counts         show the number of entries in directories as their size
   -t, --time FIELD     which timestamp field to list (modified, accessed, created)
   -u, --accessed       use the accessed timestamp field
   -U, --created        use the created timestamp field
   --changed            use the changed timestamp field
-  --time-style         how to format timestamps (default, iso, long-iso, full-iso)
+  --time-style         how to format timestamps (default, iso, long-iso, full-iso, precise,
+                       relative, relative:AGE, +FORMAT); repeat it to style each time column
+  --dereference        list the details of the files symlinks point to
   --no-permissions     suppress the permissions field
-  --octal-permissions  list each file's permission in octal format
+  --octal-permissions  list each file's permissions in octal as well
+  --mounts             list the type of file system each file is on
+  --mime               list each file's MIME type, such as image/png
   --no-filesize        suppress the filesize field
   --no-user            suppress the user field
-  --no-time            suppress the time field";
+  --no-time            suppress the time field
+  --columns COLUMNS    list only these columns, in this order (perms, octal, links,
+                       inode, blocks, user, group, size, git, date, name)
+  --only COLUMNS       list only these columns, in their usual order";
 
 static GIT_FILTER_HELP: &str = "  --git-ignore               ignore files mentioned in '.gitignore'";
-static GIT_VIEW_HELP:   &str = "  --git                list each file's Git status, if tracked or ignored";
-static EXTENDED_HELP:   &str = "  -@, --extended       list each file's extended attributes and sizes";
+static GIT_VIEW_HELP:   &str = "  --git[=FORMAT]       list each file's Git status, if tracked or ignored
+                       (short, or long for separate index and worktree columns)
+  --git-repos          list each Git repository's branch and status
+  --git-highlight      colour file names by their Git status, in any view
+  --no-git-repos-recurse  skip the Git statuses of repositories with over 50,000 files";
+static EXTENDED_HELP:   &str = "  -@, --extended       list each file's extended attributes and sizes
+  --xattr-names        list the names of each file's extended attributes in a column";
 
 
 /// All the information needed to display the help text, which depends
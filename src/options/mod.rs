@@ -67,14 +67,26 @@
 //! Creating an alias `exal="exa --long --inode --header"` then invoking `exal
 //! --grid --long` shouldn’t complain about `--long` being given twice when
 //! it’s clear what the user wants.
+//!
+//!
+//! ## Config files
+//!
+//! The same heuristic makes a config file of default options simple: its
+//! lines get put in front of the command-line arguments, exactly as an alias
+//! would put them there, so anything given on the command-line overrides
+//! them. The few arguments that can be given more than once, with each one
+//! counting, are the exception, so the parser keeps track of which flags
+//! came from the file, and drops them when the same argument is given on the
+//! command-line. See `Options::config_args`.
 
 
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
 
 use crate::fs::dir_action::DirAction;
 use crate::fs::filter::{FileFilter, GitIgnore};
-use crate::output::{View, Mode, details, grid_details};
-use crate::theme::Options as ThemeOptions;
+use crate::output::{View, Mode, delimited, details, grid_details};
+use crate::theme::{Options as ThemeOptions, UseColours};
 
 mod dir_action;
 mod file_name;
@@ -83,6 +95,9 @@ mod flags;
 mod theme;
 mod view;
 
+mod completions;
+use self::completions::CompletionsString;
+
 mod error;
 pub use self::error::{OptionsError, NumberSource};
 
@@ -119,17 +134,50 @@ pub struct Options {
 
     /// The options to make up the styles of the UI and file names.
     pub theme: ThemeOptions,
+
+    /// Whether symlinks to directories given as arguments should always
+    /// have their targets’ contents listed, even in the long view, which
+    /// otherwise lists the links themselves.
+    pub dereference_command_line: bool,
+
+    /// Whether archives given as arguments should have their contents
+    /// listed, as though they were directories.
+    pub peek_archives: bool,
+
+    /// A file containing more paths to list, one per line, or separated
+    /// by NUL bytes when the output is too.
+    pub paths_from: Option<PathBuf>,
+
+    /// The number of files a Git repository can have in its index before
+    /// its statuses stop being looked up, if there’s a limit at all.
+    pub git_file_limit: Option<usize>,
 }
 
+/// The number of files above which `--no-git-repos-recurse` skips a Git
+/// repository. Getting the statuses means checking every file in the
+/// working tree, which takes seconds for repositories this big.
+pub const GIT_FILE_LIMIT: usize = 50_000;
+
 impl Options {
 
     /// Parse the given iterator of command-line strings into an Options
     /// struct and a list of free filenames, using the environment variables
     /// for extra options.
-    #[allow(unused_results)]
     pub fn parse<'args, I, V>(args: I, vars: &V) -> OptionsResult<'args>
     where I: IntoIterator<Item = &'args OsStr>,
           V: Vars,
+    {
+        Self::parse_with_defaults(std::iter::empty(), args, vars)
+    }
+
+    /// Parse the given command-line strings, as `parse` does, after the
+    /// given default ones, such as the ones from the config file, which the
+    /// command-line ones override.
+    #[allow(unused_results)]
+    pub fn parse_with_defaults<'args, D, I, V>(defaults: D, args: I, vars: &V) -> OptionsResult<'args>
+    where D: IntoIterator<Item = &'args OsStr>,
+          I: IntoIterator<Item = &'args OsStr>,
+          V: Vars,
     {
         use crate::options::parser::{Matches, Strictness};
 
@@ -139,11 +187,17 @@ impl Options {
             Some(_)                      => Strictness::ComplainAboutRedundantArguments,
         };
 
-        let Matches { flags, frees } = match flags::ALL_ARGS.parse(args, strictness) {
+        let Matches { flags, frees } = match flags::ALL_ARGS.parse_with_defaults(defaults, args, strictness) {
             Ok(m)    => m,
             Err(pe)  => return OptionsResult::InvalidOptions(OptionsError::Parse(pe)),
         };
 
+        match CompletionsString::deduce(&flags) {
+            Ok(Some(completions))  => return OptionsResult::Completions(completions),
+            Ok(None)               => {},
+            Err(oe)                => return OptionsResult::InvalidOptions(oe),
+        }
+
         if let Some(help) = HelpString::deduce(&flags) {
             return OptionsResult::Help(help);
         }
@@ -158,41 +212,121 @@ impl Options {
         }
     }
 
+    /// Reads the arguments in the user’s config file, which should be
+    /// placed before the command-line arguments so they can be overridden.
+    /// A missing file means there are no arguments, but one that can’t be
+    /// read gets warned about.
+    pub fn config_args<V: Vars>(vars: &V) -> Vec<OsString> {
+        use log::*;
+
+        let path = match Self::config_path(vars) {
+            Some(p)  => p,
+            None     => return Vec::new(),
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                debug!("Reading options from {:?}", path);
+                parse_config_args(&contents)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Vec::new()
+            }
+            Err(e) => {
+                warn!("Couldn't read options file {:?}: {}", path, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// The path of the config file: the one in `EXA_OPTIONS_FILE` if it’s
+    /// set, or `exa/config` in the user’s configuration directory.
+    fn config_path<V: Vars>(vars: &V) -> Option<PathBuf> {
+        let non_empty = |name| vars.get(name).filter(|v| ! v.is_empty());
+
+        if let Some(path) = non_empty(vars::EXA_OPTIONS_FILE) {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_dir = match non_empty(vars::XDG_CONFIG_HOME) {
+            Some(dir)  => PathBuf::from(dir),
+            None       => PathBuf::from(non_empty(vars::HOME)?).join(".config"),
+        };
+
+        Some(config_dir.join("exa").join("config"))
+    }
+
     /// Whether the View specified in this set of options includes a Git
-    /// status column. It’s only worth trying to discover a repository if the
-    /// results will end up being displayed.
+    /// status column, or highlights file names by their status. It’s only
+    /// worth trying to discover a repository if the results will end up
+    /// being displayed.
     pub fn should_scan_for_git(&self) -> bool {
-        if self.filter.git_ignore == GitIgnore::CheckAndIgnore {
-            return true;
-        }
+        self.filter.git_ignore == GitIgnore::CheckAndIgnore || self.shows_git_status() || self.view.file_style.git_highlight
+    }
 
+    /// Whether the View specified in this set of options includes a Git
+    /// status column, which also means the branch of each directory being
+    /// listed gets shown next to its name.
+    pub fn shows_git_status(&self) -> bool {
         match self.view.mode {
             Mode::Details(details::Options { table: Some(ref table), .. }) |
-            Mode::GridDetails(grid_details::Options { details: details::Options { table: Some(ref table), .. }, .. }) => table.columns.git,
+            Mode::GridDetails(grid_details::Options { details: details::Options { table: Some(ref table), .. }, .. }) |
+            Mode::Delimited(delimited::Options { ref table, .. }) => table.columns.git,
             _ => false,
         }
     }
 
+    /// Whether exa should behave as though it’s writing to a terminal, given
+    /// whether its output actually is one. Forcing colours on or off with
+    /// `--colour` forces this as well, so every feature with an automatic
+    /// mode makes the same decision, instead of each one checking the
+    /// terminal separately.
+    pub fn is_tty(&self, stdout_isatty: bool) -> bool {
+        match self.theme.use_colours {
+            UseColours::Always     => true,
+            UseColours::Never      => false,
+            UseColours::Automatic  => stdout_isatty,
+        }
+    }
+
     /// Determines the complete set of options based on the given command-line
     /// arguments, after they’ve been parsed.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         if cfg!(not(feature = "git")) &&
-                matches.has_where_any(|f| f.matches(&flags::GIT) || f.matches(&flags::GIT_IGNORE)).is_some() {
+                (matches.get_optional(&flags::GIT)?.is_some() ||
+                 matches.has_where_any(|f| f.matches(&flags::GIT_IGNORE) || f.matches(&flags::GIT_HIGHLIGHT) || f.matches(&flags::NO_GIT_REPOS_RECURSE)).is_some()) {
             return Err(OptionsError::Unsupported(String::from(
-                "Options --git and --git-ignore can't be used because `git` feature was disabled in this build of exa"
+                "Options --git, --git-ignore, --git-highlight, and --no-git-repos-recurse can't be used because `git` feature was disabled in this build of exa"
             )));
         }
 
         let view = View::deduce(matches, vars)?;
-        let dir_action = DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)))?;
-        let filter = FileFilter::deduce(matches)?;
+        let dir_action = if let Mode::Stat(_) = view.mode { DirAction::AsFile }
+                                                      else { DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)))? };
+        let filter = FileFilter::deduce(matches, vars)?;
         let theme = ThemeOptions::deduce(matches, vars)?;
+        let dereference_command_line = matches.has(&flags::DEREFERENCE_COMMAND_LINE)?;
+        let peek_archives = matches.has(&flags::PEEK_ARCHIVES)?;
+        let paths_from = matches.get(&flags::PATHS_FROM)?.map(PathBuf::from);
+        let git_file_limit = if matches.has(&flags::NO_GIT_REPOS_RECURSE)? { Some(GIT_FILE_LIMIT) } else { None };
 
-        Ok(Self { dir_action, filter, view, theme })
+        Ok(Self { dir_action, filter, view, theme, dereference_command_line, peek_archives, paths_from, git_file_limit })
     }
 }
 
 
+/// Splits the contents of a config file into arguments. Each line is one
+/// argument, such as `--sort=size`, with surrounding whitespace removed,
+/// and blank lines and lines starting with `#` are skipped.
+fn parse_config_args(contents: &str) -> Vec<OsString> {
+    contents.lines()
+            .map(str::trim)
+            .filter(|line| ! line.is_empty() && ! line.starts_with('#'))
+            .map(OsString::from)
+            .collect()
+}
+
+
 /// The result of the `Options::getopts` function.
 #[derive(Debug)]
 pub enum OptionsResult<'args> {
@@ -208,6 +342,10 @@ pub enum OptionsResult<'args> {
 
     /// One of the arguments was `--version`, so display the version number.
     Version(VersionString),
+
+    /// One of the arguments was `--generate-completions`, so print out a
+    /// completion script for the given shell.
+    Completions(CompletionsString),
 }
 
 
@@ -251,3 +389,139 @@ pub mod test {
         result
     }
 }
+
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+    use crate::fs::filter::{SortField, SortCase};
+    use crate::output::time::TimeFormat;
+
+    struct MockVars(&'static [(&'static str, &'static str)]);
+
+    impl Vars for MockVars {
+        fn get(&self, name: &'static str) -> Option<OsString> {
+            self.0.iter().find(|(n, _)| *n == name).map(|(_, v)| OsString::from(v))
+        }
+    }
+
+    /// Parses the given config file contents followed by the given
+    /// command-line arguments, returning the sort field and the number of
+    /// free arguments if they parsed.
+    fn parse(config: &str, args: &[&'static str]) -> Option<(SortField, usize)> {
+        let config = parse_config_args(config);
+        let defaults = config.iter().map(OsString::as_os_str);
+
+        match Options::parse_with_defaults(defaults, args.iter().map(|a| OsStr::new(*a)), &MockVars(&[])) {
+            OptionsResult::Ok(options, frees)  => Some((options.filter.sort_field, frees.len())),
+            _                                  => None,
+        }
+    }
+
+    /// Parses the given config file contents followed by the given
+    /// command-line arguments, returning the time formats of the long view.
+    fn time_formats(config: &str, args: &[&'static str]) -> Vec<TimeFormat> {
+        let config = parse_config_args(config);
+        let defaults = config.iter().map(OsString::as_os_str);
+
+        match Options::parse_with_defaults(defaults, args.iter().map(|a| OsStr::new(*a)), &MockVars(&[])) {
+            OptionsResult::Ok(Options { view: View { mode: Mode::Details(details::Options { table: Some(table), .. }), .. }, .. }, _) => table.time_formats,
+            _  => panic!("Options didn't parse to a long view"),
+        }
+    }
+
+    #[test]
+    fn comments_and_blanks() {
+        let args = parse_config_args("# defaults\n--long\n\n  --header  \n#--inode\n--time-style=+%Y %m\n");
+        assert_eq!(args, vec![ OsString::from("--long"), OsString::from("--header"), OsString::from("--time-style=+%Y %m") ]);
+    }
+
+    #[test]
+    fn command_line_wins() {
+        assert_eq!(parse("--sort=size\n", &[ "--sort=name", "src" ]), Some((SortField::Name(SortCase::AaBbCc), 1)));
+    }
+
+    #[test]
+    fn config_is_used() {
+        assert_eq!(parse("--sort=size\n", &[]), Some((SortField::Size, 0)));
+    }
+
+    #[test]
+    fn command_line_time_style_wins() {
+        assert_eq!(time_formats("--long\n--time-style=iso\n", &[ "--time-style=relative" ]), vec![ TimeFormat::Relative ]);
+    }
+
+    #[test]
+    fn config_time_styles_are_used() {
+        assert_eq!(time_formats("--long\n--time-style=iso\n--time-style=relative\n", &[ "--sort=size" ]), vec![ TimeFormat::ISOFormat, TimeFormat::Relative ]);
+    }
+
+    #[test]
+    fn command_line_time_styles_replace_config() {
+        assert_eq!(time_formats("--time-style=iso\n--time-style=iso\n", &[ "-l", "--time-style=relative", "--time-style=full-iso" ]), vec![ TimeFormat::Relative, TimeFormat::FullISO ]);
+    }
+
+    #[test]
+    fn useless_defaults_are_fine() {
+        assert!(parse("--inode\n--long\n", &[ "--grid" ]).is_some());
+    }
+
+    #[test]
+    fn explicit_path() {
+        let vars = MockVars(&[ ("EXA_OPTIONS_FILE", "/etc/exa"), ("HOME", "/home/me") ]);
+        assert_eq!(Options::config_path(&vars), Some(PathBuf::from("/etc/exa")));
+    }
+
+    #[test]
+    fn xdg_path() {
+        let vars = MockVars(&[ ("XDG_CONFIG_HOME", "/home/me/.conf"), ("HOME", "/home/me") ]);
+        assert_eq!(Options::config_path(&vars), Some(PathBuf::from("/home/me/.conf/exa/config")));
+    }
+
+    #[test]
+    fn home_path() {
+        let vars = MockVars(&[ ("EXA_OPTIONS_FILE", ""), ("HOME", "/home/me") ]);
+        assert_eq!(Options::config_path(&vars), Some(PathBuf::from("/home/me/.config/exa/config")));
+    }
+
+    #[test]
+    fn no_path() {
+        assert_eq!(Options::config_path(&MockVars(&[])), None);
+    }
+
+    fn scans_for_git(args: &[&'static str]) -> bool {
+        match Options::parse(args.iter().map(|a| OsStr::new(*a)), &MockVars(&[])) {
+            OptionsResult::Ok(options, _)  => options.should_scan_for_git(),
+            _                              => panic!("Options didn't parse"),
+        }
+    }
+
+    #[test]
+    fn no_git_scan() {
+        assert!(! scans_for_git(&[ "--long" ]));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn git_highlight_scan() {
+        assert!(scans_for_git(&[ "--git-highlight" ]));
+    }
+
+    fn git_file_limit(args: &[&'static str]) -> Option<usize> {
+        match Options::parse(args.iter().map(|a| OsStr::new(*a)), &MockVars(&[])) {
+            OptionsResult::Ok(options, _)  => options.git_file_limit,
+            _                              => panic!("Options didn't parse"),
+        }
+    }
+
+    #[test]
+    fn no_git_file_limit() {
+        assert_eq!(git_file_limit(&[ "--long" ]), None);
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn git_file_limit_given() {
+        assert_eq!(git_file_limit(&[ "--long", "--git", "--no-git-repos-recurse" ]), Some(GIT_FILE_LIMIT));
+    }
+}
@@ -102,7 +102,9 @@ pub enum TakesValue {
     /// This flag will throw an error if there’s a value after it.
     Forbidden,
 
-    /// This flag may be followed by a value to override its defaults
+    /// This flag may be followed by a value to override its defaults.
+    /// Long flags only take a value when it’s given after an equals sign,
+    /// so the next argument (usually a file name) isn’t mistaken for one.
     Optional(Option<Values>),
 }
 
@@ -141,6 +143,25 @@ pub struct Args(pub &'static [&'static Arg]);
 
 impl Args {
 
+    /// Parses a list of default arguments, such as the ones from a config
+    /// file, followed by the command-line arguments, keeping track of which
+    /// flags were defaults so that repeatable arguments given on the
+    /// command-line can replace them rather than add to them.
+    pub fn parse_with_defaults<'args, D, I>(&self, defaults: D, inputs: I, strictness: Strictness) -> Result<Matches<'args>, ParseError>
+    where D: IntoIterator<Item = &'args OsStr>,
+          I: IntoIterator<Item = &'args OsStr>,
+    {
+        let Matches { flags: default_flags, frees: mut default_frees } = self.parse(defaults, strictness)?;
+        let Matches { flags, frees } = self.parse(inputs, strictness)?;
+
+        let defaults = default_flags.flags.len();
+        let mut all_flags = default_flags.flags;
+        all_flags.extend(flags.flags);
+        default_frees.extend(frees);
+
+        Ok(Matches { frees: default_frees, flags: MatchedFlags { flags: all_flags, defaults, strictness } })
+    }
+
     /// Iterates over the given list of command-line arguments and parses
     /// them into a list of matched flags and free strings.
     pub fn parse<'args, I>(&self, inputs: I, strictness: Strictness) -> Result<Matches<'args>, ParseError>
@@ -205,12 +226,7 @@ impl Args {
                             }
                         }
                         TakesValue::Optional(_) => {
-                            if let Some(next_arg) = inputs.next() {
-                                result_flags.push((flag, Some(next_arg)));
-                            }
-                            else {
-                                result_flags.push((flag, None));
-                            }
+                            result_flags.push((flag, None));
                         }
                     }
                 }
@@ -269,24 +285,27 @@ impl Args {
                 // its own short argument. However, if any of the arguments
                 // takes a value, then the *rest* of the string is used as
                 // its value, and if there’s no rest of the string, then it
-                // uses the next one in the iterator.
+                // uses the next one in the iterator. Arguments whose value
+                // is optional only get one with an equals, just like long
+                // ones, so they can still be bunched up with others.
                 //
                 //   -a        => ‘a’
                 //   -abc      => ‘a’, ‘b’, ‘c’
                 //   -abxdef   => ‘a’, ‘b’, ‘x=def’
                 //   -abx def  => ‘a’, ‘b’, ‘x=def’
                 //   -abx      =>  error
+                //   -aob      => ‘a’, ‘o’, ‘b’
                 //
                 else {
                     for (index, byte) in bytes.iter().enumerate().skip(1) {
                         let arg = self.lookup_short(*byte)?;
                         let flag = Flag::Short(*byte);
                         match arg.takes_value {
-                            TakesValue::Forbidden => {
+                            TakesValue::Forbidden |
+                            TakesValue::Optional(_) => {
                                 result_flags.push((flag, None))
                             }
-                            TakesValue::Necessary(values) |
-                            TakesValue::Optional(values) => {
+                            TakesValue::Necessary(values) => {
                                 if index < bytes.len() - 1 {
                                     let remnants = &bytes[index+1 ..];
                                     result_flags.push((flag, Some(bytes_to_os_str(remnants))));
@@ -296,17 +315,7 @@ impl Args {
                                     result_flags.push((flag, Some(next_arg)));
                                 }
                                 else {
-                                    match arg.takes_value {
-                                        TakesValue::Forbidden => {
-                                            unreachable!()
-                                        }
-                                        TakesValue::Necessary(_) => {
-                                            return Err(ParseError::NeedsValue { flag, values });
-                                        }
-                                        TakesValue::Optional(_) => {
-                                            result_flags.push((flag, None));
-                                        }
-                                    }
+                                    return Err(ParseError::NeedsValue { flag, values });
                                 }
                             }
                         }
@@ -320,7 +329,7 @@ impl Args {
             }
         }
 
-        Ok(Matches { frees, flags: MatchedFlags { flags: result_flags, strictness } })
+        Ok(Matches { frees, flags: MatchedFlags { flags: result_flags, defaults: 0, strictness } })
     }
 
     fn lookup_short(&self, short: ShortArg) -> Result<&Arg, ParseError> {
@@ -330,10 +339,30 @@ impl Args {
         }
     }
 
+    /// Looks up a long argument by name. If there’s no flag with exactly
+    /// that name, any unambiguous prefix of one is accepted instead, so
+    /// `--rev` works for `--reverse` but `--co` is an error.
     fn lookup_long<'b>(&self, long: &'b OsStr) -> Result<&Arg, ParseError> {
-        match self.0.iter().find(|arg| arg.long == long) {
-            Some(arg)  => Ok(arg),
-            None       => Err(ParseError::UnknownArgument { attempt: long.to_os_string() })
+        if let Some(arg) = self.0.iter().find(|arg| arg.long == long) {
+            return Ok(arg);
+        }
+
+        let prefix = os_str_to_bytes(long);
+        if prefix.is_empty() {
+            return Err(ParseError::UnknownArgument { attempt: long.to_os_string() });
+        }
+
+        let candidates = self.0.iter()
+                             .filter(|arg| arg.long.as_bytes().starts_with(prefix))
+                             .collect::<Vec<_>>();
+
+        match candidates[..] {
+            [arg]  => Ok(arg),
+            []     => Err(ParseError::UnknownArgument { attempt: long.to_os_string() }),
+            _      => Err(ParseError::AmbiguousArgument {
+                          attempt: long.to_os_string(),
+                          matches: candidates.iter().map(|arg| arg.long).collect(),
+                      }),
         }
     }
 }
@@ -362,6 +391,10 @@ pub struct MatchedFlags<'args> {
     /// we need to know where they are in relation to one another.
     flags: Vec<(Flag, Option<&'args OsStr>)>,
 
+    /// The number of flags at the start of `flags` that came from default
+    /// arguments, rather than from the command-line.
+    defaults: usize,
+
     /// Whether to check for duplicate or redundant arguments.
     strictness: Strictness,
 }
@@ -411,6 +444,35 @@ impl<'a> MatchedFlags<'a> {
     // Both ‘has’ and ‘get’ immediately begin with a conditional, which makes
     // me think the functionality could be moved to inside Strictness.
 
+    /// Returns whether an argument that takes an optional value was
+    /// specified, and if so, the value it was given, if any. Unlike `has`
+    /// and `get`, this counts the argument both with and without a value,
+    /// so in strict mode, giving it twice in either form is an error.
+    pub fn get_optional(&self, arg: &'static Arg) -> Result<Option<Option<&OsStr>>, OptionsError> {
+        self.get_optional_where(|flag| flag.matches(arg))
+    }
+
+    /// Returns whether an argument that takes an optional value and that
+    /// matches the predicate was specified, and if so, the value it was
+    /// given, if any, with the same strictness rules as `get_optional`.
+    pub fn get_optional_where<P>(&self, predicate: P) -> Result<Option<Option<&OsStr>>, OptionsError>
+    where P: Fn(&Flag) -> bool {
+        if self.is_strict() {
+            let those = self.flags.iter()
+                            .filter(|tuple| predicate(&tuple.0))
+                            .collect::<Vec<_>>();
+
+            if those.len() < 2 { Ok(those.first().map(|t| t.1)) }
+                          else { Err(OptionsError::Duplicate(those[0].0, those[1].0)) }
+        }
+        else {
+            let found = self.flags.iter().rev()
+                            .find(|tuple| predicate(&tuple.0))
+                            .map(|tuple| tuple.1);
+            Ok(found)
+        }
+    }
+
     /// Returns the value of the given argument if it was specified, nothing
     /// if it wasn’t, and an error in strict mode if it was specified more
     /// than once.
@@ -441,6 +503,22 @@ impl<'a> MatchedFlags<'a> {
         }
     }
 
+    /// Returns the values of every occurrence of the given argument, in the
+    /// order they were given, for arguments where each occurrence means
+    /// something on its own. If any of them were given on the command-line,
+    /// the default ones get left out, so they’re replaced rather than added
+    /// to. Strict mode has no effect.
+    pub fn get_all(&self, arg: &'static Arg) -> Vec<&OsStr> {
+        let given = &self.flags[self.defaults ..];
+        let flags = if given.iter().any(|tuple| tuple.0.matches(arg)) { given }
+                                                                 else { &self.flags[..] };
+
+        flags.iter()
+            .filter(|tuple| tuple.0.matches(arg))
+            .filter_map(|tuple| tuple.1)
+            .collect()
+    }
+
     // It’s annoying that ‘has’ and ‘get’ won’t work when accidentally given
     // flags that do/don’t take values, but this should be caught by tests.
 
@@ -479,6 +557,10 @@ pub enum ParseError {
     /// We don’t have a known &str version of the flag, so
     /// this may not be valid UTF-8.
     UnknownArgument { attempt: OsString },
+
+    /// A long argument was an abbreviation of more than one flag, so
+    /// there’s no way to tell which was meant.
+    AmbiguousArgument { attempt: OsString, matches: Vec<&'static str> },
 }
 
 impl fmt::Display for ParseError {
@@ -489,6 +571,7 @@ impl fmt::Display for ParseError {
             Self::ForbiddenValue { flag }                => write!(f, "Flag {} cannot take a value", flag),
             Self::UnknownShortArgument { attempt }       => write!(f, "Unknown argument -{}", *attempt as char),
             Self::UnknownArgument { attempt }            => write!(f, "Unknown argument --{}", attempt.to_string_lossy()),
+            Self::AmbiguousArgument { attempt, matches } => write!(f, "Ambiguous argument --{} (could be --{})", attempt.to_string_lossy(), matches.join(", --")),
         }
     }
 }
@@ -591,7 +674,7 @@ mod parse_test {
 
                 let strictness = Strictness::UseLastArguments;  // this isn’t even used
                 let got = Args(TEST_ARGS).parse(inputs, strictness);
-                let flags = MatchedFlags { flags, strictness };
+                let flags = MatchedFlags { flags, defaults: 0, strictness };
 
                 let expected = Ok(Matches { frees, flags });
                 assert_eq!(got, expected);
@@ -618,7 +701,10 @@ mod parse_test {
         &Arg { short: Some(b'l'), long: "long",     takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'v'), long: "verbose",  takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'c'), long: "count",    takes_value: TakesValue::Necessary(None) },
-        &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(SUGGESTIONS)) }
+        &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(SUGGESTIONS)) },
+        &Arg { short: Some(b'p'), long: "percent",  takes_value: TakesValue::Optional(Some(SUGGESTIONS)) },
+        &Arg { short: None,       long: "time",     takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "time-style", takes_value: TakesValue::Forbidden }
     ];
 
 
@@ -650,6 +736,11 @@ mod parse_test {
     test!(arg_equals_s:  ["--type=exa"]     => frees: [],  flags: [ (Flag::Long("type"), Some(OsStr::new("exa"))) ]);
     test!(arg_then_s:    ["--type", "exa"]  => frees: [],  flags: [ (Flag::Long("type"), Some(OsStr::new("exa"))) ]);
 
+    // Long args with optional values
+    test!(opt_none:      ["--percent"]         => frees: [],         flags: [ (Flag::Long("percent"), None) ]);
+    test!(opt_equals:    ["--percent=exa"]     => frees: [],         flags: [ (Flag::Long("percent"), Some(OsStr::new("exa"))) ]);
+    test!(opt_then:      ["--percent", "exa"]  => frees: [ "exa" ],  flags: [ (Flag::Long("percent"), None) ]);
+
 
     // Short args
     test!(short:       ["-l"]            => frees: [],       flags: [ (Flag::Short(b'l'), None) ]);
@@ -672,6 +763,12 @@ mod parse_test {
     test!(short_two_equals_s:   ["-t=exa"]     => frees: [],  flags: [(Flag::Short(b't'), Some(OsStr::new("exa"))) ]);
     test!(short_two_next_s:     ["-t", "exa"]  => frees: [],  flags: [(Flag::Short(b't'), Some(OsStr::new("exa"))) ]);
 
+    // Short args with optional values
+    test!(short_opt:        ["-p"]         => frees: [],         flags: [(Flag::Short(b'p'), None) ]);
+    test!(short_opt_eq:     ["-p=exa"]     => frees: [],         flags: [(Flag::Short(b'p'), Some(OsStr::new("exa"))) ]);
+    test!(short_opt_then:   ["-p", "exa"]  => frees: [ "exa" ],  flags: [(Flag::Short(b'p'), None) ]);
+    test!(short_opt_two:    ["-pl"]        => frees: [],         flags: [(Flag::Short(b'p'), None), (Flag::Short(b'l'), None) ]);
+
 
     // Unknown args
     test!(unknown_long:          ["--quiet"]      => error UnknownArgument      { attempt: OsString::from("quiet") });
@@ -680,6 +777,19 @@ mod parse_test {
     test!(unknown_short_2nd:     ["-lq"]          => error UnknownShortArgument { attempt: b'q' });
     test!(unknown_short_eq:      ["-q=shhh"]      => error UnknownShortArgument { attempt: b'q' });
     test!(unknown_short_2nd_eq:  ["-lq=shhh"]     => error UnknownShortArgument { attempt: b'q' });
+
+    // Abbreviated long args
+    test!(prefix:              ["--verb"]        => frees: [],  flags: [ (Flag::Long("verbose"), None) ]);
+    test!(prefix_one:          ["--l"]           => frees: [],  flags: [ (Flag::Long("long"), None) ]);
+    test!(prefix_equals:       ["--cou=4"]       => frees: [],  flags: [ (Flag::Long("count"), Some(OsStr::new("4"))) ]);
+    test!(prefix_then:         ["--ty", "exa"]   => frees: [],  flags: [ (Flag::Long("type"), Some(OsStr::new("exa"))) ]);
+    test!(prefix_forbidden:    ["--lo=equals"]   => error ForbiddenValue    { flag: Flag::Long("long") });
+    test!(prefix_too_long:     ["--longer"]      => error UnknownArgument   { attempt: OsString::from("longer") });
+    test!(prefix_ambiguous:    ["--t"]           => error AmbiguousArgument { attempt: OsString::from("t"), matches: vec![ "type", "time", "time-style" ] });
+    test!(prefix_ambiguous_2:  ["--tim"]         => error AmbiguousArgument { attempt: OsString::from("tim"), matches: vec![ "time", "time-style" ] });
+    test!(exact_over_prefix:   ["--time"]        => frees: [],  flags: [ (Flag::Long("time"), None) ]);
+    test!(exact_over_prefix_2: ["--time-style"]  => frees: [],  flags: [ (Flag::Long("time-style"), None) ]);
+    test!(prefix_after_exact:  ["--time-"]       => frees: [],  flags: [ (Flag::Long("time-style"), None) ]);
 }
 
 
@@ -693,6 +803,7 @@ mod matches_test {
             fn $name() {
                 let flags = MatchedFlags {
                     flags: $input.to_vec(),
+                    defaults: 0,
                     strictness: Strictness::UseLastArguments,
                 };
 
@@ -719,6 +830,7 @@ mod matches_test {
 
         let flags = MatchedFlags {
             flags: vec![ (Flag::Short(b'c'), Some(&*everything)) ],
+            defaults: 0,
             strictness: Strictness::UseLastArguments,
         };
 
@@ -733,6 +845,7 @@ mod matches_test {
         let flags = MatchedFlags {
             flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
                          (Flag::Short(b'c'), Some(&*nothing)) ],
+            defaults: 0,
             strictness: Strictness::UseLastArguments,
         };
 
@@ -741,7 +854,7 @@ mod matches_test {
 
     #[test]
     fn no_count() {
-        let flags = MatchedFlags { flags: Vec::new(), strictness: Strictness::UseLastArguments };
+        let flags = MatchedFlags { flags: Vec::new(), defaults: 0, strictness: Strictness::UseLastArguments };
 
         assert!(!flags.has(&COUNT).unwrap());
     }
@@ -1,30 +1,51 @@
+use std::path::PathBuf;
+
 use crate::options::{flags, vars, Vars, OptionsError};
 use crate::options::parser::MatchedFlags;
-use crate::theme::{Options, UseColours, ColourScale, Definitions};
+use crate::theme::{Options, UseColours, ColourScale, ThemePreset, Definitions};
 
 
 impl Options {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let use_colours = UseColours::deduce(matches, vars)?;
         let colour_scale = ColourScale::deduce(matches)?;
+        let preset = ThemePreset::deduce(matches)?;
+        let dim_hidden = matches.has(&flags::DIM_HIDDEN)?;
+        let age_colours = matches.has_where(|f| f.matches(&flags::AGE_COLORS) || f.matches(&flags::AGE_COLOURS))?.is_some();
 
         let definitions = if use_colours == UseColours::Never {
                 Definitions::default()
             }
             else {
-                Definitions::deduce(vars)
+                Definitions::deduce(matches, vars)?
             };
 
-        Ok(Self { use_colours, colour_scale, definitions })
+        Ok(Self { use_colours, colour_scale, preset, definitions, dim_hidden, age_colours })
     }
 }
 
 
 impl UseColours {
+    /// Determines when to use colours. The `--colour` flag always wins;
+    /// without it, the environment is checked in this order:
+    ///
+    /// 1. `CLICOLOR_FORCE`, if non-empty, means always use colours;
+    /// 2. `NO_COLOR`, if set to anything, means never use them;
+    /// 3. `CLICOLOR=0` means never use them either;
+    ///
+    /// and otherwise colours get used when the output is a terminal.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let default_value = match vars.get(vars::NO_COLOR) {
-            Some(_) => Self::Never,
-            None => Self::Automatic,
+        let default_value = if vars.get(vars::CLICOLOR_FORCE).map_or(false, |v| ! v.is_empty()) {
+            Self::Always
+        }
+        else if vars.get(vars::NO_COLOR).is_some() {
+            Self::Never
+        }
+        else if vars.get(vars::CLICOLOR).map_or(false, |v| v == "0") {
+            Self::Never
+        }
+        else {
+            Self::Automatic
         };
 
         let word = match matches.get_where(|f| f.matches(&flags::COLOR) || f.matches(&flags::COLOUR))? {
@@ -49,22 +70,62 @@ impl UseColours {
 
 
 impl ColourScale {
+    /// Determines which columns to colour on a scale, from a list of
+    /// scales separated by commas. Giving the argument without a value
+    /// colours file sizes, as it did before the other scales existed.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        if matches.has_where(|f| f.matches(&flags::COLOR_SCALE) || f.matches(&flags::COLOUR_SCALE))?.is_some() {
-            Ok(Self::Gradient)
+        let input = match matches.get_optional_where(|f| f.matches(&flags::COLOR_SCALE) || f.matches(&flags::COLOUR_SCALE))? {
+            Some(Some(w))  => w,
+            Some(None)     => return Ok(Self { size: true, age: false }),
+            None           => return Ok(Self::default()),
+        };
+
+        let mut scale = Self::default();
+        for word in input.to_string_lossy().split(',') {
+            match word {
+                "size"  => scale.size = true,
+                "age"   => scale.age = true,
+                "all"   => { scale.size = true; scale.age = true; }
+                _       => return Err(OptionsError::BadArgument(&flags::COLOR_SCALE, input.into())),
+            }
+        }
+
+        Ok(scale)
+    }
+}
+
+
+impl ThemePreset {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let word = match matches.get(&flags::THEME)? {
+            Some(w)  => w,
+            None     => return Ok(Self::Dark),
+        };
+
+        if word == "dark" {
+            Ok(Self::Dark)
+        }
+        else if word == "light" {
+            Ok(Self::Light)
         }
         else {
-            Ok(Self::Fixed)
+            Err(OptionsError::BadArgument(&flags::THEME, word.into()))
         }
     }
 }
 
 
 impl Definitions {
-    fn deduce<V: Vars>(vars: &V) -> Self {
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let ls =  vars.get(vars::LS_COLORS) .map(|e| e.to_string_lossy().to_string());
         let exa = vars.get(vars::EXA_COLORS).map(|e| e.to_string_lossy().to_string());
-        Self { ls, exa }
+
+        let theme_file = match matches.get(&flags::THEME_FILE)? {
+            Some(path)  => Some(PathBuf::from(path)),
+            None        => vars.get(vars::EXA_CONFIG).filter(|p| ! p.is_empty()).map(PathBuf::from),
+        };
+
+        Ok(Self { ls, exa, theme_file })
     }
 }
 
@@ -80,7 +141,8 @@ mod terminal_test {
     use crate::options::test::Strictnesses::*;
 
     static TEST_ARGS: &[&Arg] = &[ &flags::COLOR,       &flags::COLOUR,
-                                   &flags::COLOR_SCALE, &flags::COLOUR_SCALE, ];
+                                   &flags::COLOR_SCALE, &flags::COLOUR_SCALE,
+                                   &flags::THEME, &flags::THEME_FILE, ];
 
     macro_rules! test {
         ($name:ident:  $type:ident <- $inputs:expr;  $stricts:expr => $result:expr) => {
@@ -125,7 +187,10 @@ mod terminal_test {
     struct MockVars {
         ls: &'static str,
         exa: &'static str,
-        no_color: &'static str,
+        no_color: Option<&'static str>,
+        clicolor: Option<&'static str>,
+        clicolor_force: Option<&'static str>,
+        config: &'static str,
     }
 
     impl MockVars {
@@ -133,14 +198,50 @@ mod terminal_test {
             MockVars {
                 ls: "",
                 exa: "",
-                no_color: "",
+                no_color: None,
+                clicolor: None,
+                clicolor_force: None,
+                config: "",
             }
         }
         fn with_no_color() -> MockVars {
             MockVars {
                 ls: "",
                 exa: "",
-                no_color: "true",
+                no_color: Some("true"),
+                clicolor: None,
+                clicolor_force: None,
+                config: "",
+            }
+        }
+        fn with_config() -> MockVars {
+            MockVars {
+                ls: "",
+                exa: "",
+                no_color: None,
+                clicolor: None,
+                clicolor_force: None,
+                config: "/etc/exa.toml",
+            }
+        }
+        fn with_empty_no_color() -> MockVars {
+            MockVars {
+                ls: "",
+                exa: "",
+                no_color: Some(""),
+                clicolor: None,
+                clicolor_force: None,
+                config: "",
+            }
+        }
+        fn with_clicolor(clicolor: Option<&'static str>, clicolor_force: Option<&'static str>, no_color: Option<&'static str>) -> MockVars {
+            MockVars {
+                ls: "",
+                exa: "",
+                no_color,
+                clicolor,
+                clicolor_force,
+                config: "",
             }
         }
     }
@@ -154,8 +255,17 @@ mod terminal_test {
             else if name == vars::EXA_COLORS && ! self.exa.is_empty() {
                 Some(OsString::from(self.exa.clone()))
             }
-            else if name == vars::NO_COLOR && ! self.no_color.is_empty() {
-                Some(OsString::from(self.no_color.clone()))
+            else if name == vars::NO_COLOR {
+                self.no_color.map(OsString::from)
+            }
+            else if name == vars::CLICOLOR {
+                self.clicolor.map(OsString::from)
+            }
+            else if name == vars::CLICOLOR_FORCE {
+                self.clicolor_force.map(OsString::from)
+            }
+            else if name == vars::EXA_CONFIG && ! self.config.is_empty() {
+                Some(OsString::from(self.config))
             }
             else {
                 None
@@ -169,6 +279,15 @@ mod terminal_test {
     test!(empty:         UseColours <- [], MockVars::empty();                     Both => Ok(UseColours::Automatic));
     test!(empty_with_no_color: UseColours <- [], MockVars::with_no_color();             Both => Ok(UseColours::Never));
 
+    // NO_COLOR changes the default, whatever its value, but never overrides the flag
+    test!(nc_empty:         UseColours <- [], MockVars::with_empty_no_color();                       Both => Ok(UseColours::Never));
+    test!(nc_always:        UseColours <- ["--colour=always"], MockVars::with_no_color();            Both => Ok(UseColours::Always));
+    test!(nc_auto:          UseColours <- ["--colour=auto"], MockVars::with_no_color();              Both => Ok(UseColours::Automatic));
+    test!(nc_never:         UseColours <- ["--colour=never"], MockVars::with_no_color();             Both => Ok(UseColours::Never));
+    test!(nc_empty_always:  UseColours <- ["--color=always"], MockVars::with_empty_no_color();       Both => Ok(UseColours::Always));
+    test!(nc_empty_auto:    UseColours <- ["--color=auto"], MockVars::with_empty_no_color();         Both => Ok(UseColours::Automatic));
+    test!(nc_empty_never:   UseColours <- ["--color=never"], MockVars::with_empty_no_color();        Both => Ok(UseColours::Never));
+
     // --colour
     test!(u_always:      UseColours <- ["--colour=always"], MockVars::empty();    Both => Ok(UseColours::Always));
     test!(u_auto:        UseColours <- ["--colour", "auto"], MockVars::empty();   Both => Ok(UseColours::Automatic));
@@ -183,6 +302,21 @@ mod terminal_test {
     test!(no_u_error:    UseColours <- ["--color=upstream"], MockVars::empty();   Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("upstream"))); // the error is for --color
     test!(u_error:       UseColours <- ["--colour=lovers"], MockVars::empty();    Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("lovers"))); // and so is this one!
 
+    // CLICOLOR=0 changes the default, but other values don’t
+    test!(cc_zero:          UseColours <- [], MockVars::with_clicolor(Some("0"), None, None);                    Both => Ok(UseColours::Never));
+    test!(cc_one:           UseColours <- [], MockVars::with_clicolor(Some("1"), None, None);                    Both => Ok(UseColours::Automatic));
+    test!(cc_empty:         UseColours <- [], MockVars::with_clicolor(Some(""), None, None);                     Both => Ok(UseColours::Automatic));
+    test!(cc_zero_always:   UseColours <- ["--color=always"], MockVars::with_clicolor(Some("0"), None, None);    Both => Ok(UseColours::Always));
+    test!(cc_one_nc:        UseColours <- [], MockVars::with_clicolor(Some("1"), None, Some("1"));               Both => Ok(UseColours::Never));
+
+    // CLICOLOR_FORCE beats NO_COLOR and CLICOLOR, but never the flag
+    test!(ccf_set:          UseColours <- [], MockVars::with_clicolor(None, Some("1"), None);                    Both => Ok(UseColours::Always));
+    test!(ccf_empty:        UseColours <- [], MockVars::with_clicolor(None, Some(""), None);                     Both => Ok(UseColours::Automatic));
+    test!(ccf_nc:           UseColours <- [], MockVars::with_clicolor(None, Some("1"), Some("1"));               Both => Ok(UseColours::Always));
+    test!(ccf_cc_zero:      UseColours <- [], MockVars::with_clicolor(Some("0"), Some("1"), None);               Both => Ok(UseColours::Always));
+    test!(ccf_never:        UseColours <- ["--color=never"], MockVars::with_clicolor(None, Some("1"), None);     Both => Ok(UseColours::Never));
+    test!(ccf_auto:         UseColours <- ["--color=auto"], MockVars::with_clicolor(None, Some("1"), None);      Both => Ok(UseColours::Automatic));
+
     // Overriding
     test!(overridden_1:  UseColours <- ["--colour=auto", "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
     test!(overridden_2:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
@@ -194,13 +328,47 @@ mod terminal_test {
     test!(overridden_7:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("color")));
     test!(overridden_8:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("color")));
 
-    test!(scale_1:  ColourScale <- ["--color-scale", "--colour-scale"];   Last => Ok(ColourScale::Gradient));
-    test!(scale_2:  ColourScale <- ["--color-scale",                 ];   Last => Ok(ColourScale::Gradient));
-    test!(scale_3:  ColourScale <- [                 "--colour-scale"];   Last => Ok(ColourScale::Gradient));
-    test!(scale_4:  ColourScale <- [                                 ];   Last => Ok(ColourScale::Fixed));
+    const FIXED: ColourScale = ColourScale { size: false, age: false };
+    const SIZE:  ColourScale = ColourScale { size: true,  age: false };
+    const AGE:   ColourScale = ColourScale { size: false, age: true  };
+    const ALL:   ColourScale = ColourScale { size: true,  age: true  };
+
+    test!(scale_1:  ColourScale <- ["--color-scale", "--colour-scale"];   Last => Ok(SIZE));
+    test!(scale_2:  ColourScale <- ["--color-scale",                 ];   Last => Ok(SIZE));
+    test!(scale_3:  ColourScale <- [                 "--colour-scale"];   Last => Ok(SIZE));
+    test!(scale_4:  ColourScale <- [                                 ];   Last => Ok(FIXED));
 
     test!(scale_5:  ColourScale <- ["--color-scale", "--colour-scale"];   Complain => err OptionsError::Duplicate(Flag::Long("color-scale"),  Flag::Long("colour-scale")));
-    test!(scale_6:  ColourScale <- ["--color-scale",                 ];   Complain => Ok(ColourScale::Gradient));
-    test!(scale_7:  ColourScale <- [                 "--colour-scale"];   Complain => Ok(ColourScale::Gradient));
-    test!(scale_8:  ColourScale <- [                                 ];   Complain => Ok(ColourScale::Fixed));
+    test!(scale_6:  ColourScale <- ["--color-scale",                 ];   Complain => Ok(SIZE));
+    test!(scale_7:  ColourScale <- [                 "--colour-scale"];   Complain => Ok(SIZE));
+    test!(scale_8:  ColourScale <- [                                 ];   Complain => Ok(FIXED));
+
+    test!(scale_size:   ColourScale <- ["--color-scale=size"];                      Both => Ok(SIZE));
+    test!(scale_age:    ColourScale <- ["--colour-scale=age"];                      Both => Ok(AGE));
+    test!(scale_all:    ColourScale <- ["--color-scale=all"];                       Both => Ok(ALL));
+    test!(scale_last:   ColourScale <- ["--color-scale=age", "--colour-scale=all"]; Last => Ok(ALL));
+    test!(scale_dupe:   ColourScale <- ["--color-scale=age", "--colour-scale"];     Complain => err OptionsError::Duplicate(Flag::Long("color-scale"), Flag::Long("colour-scale")));
+    test!(scale_error:  ColourScale <- ["--colour-scale=date"];                     Both => err OptionsError::BadArgument(&flags::COLOR_SCALE, OsString::from("date")));
+
+    test!(scale_both:     ColourScale <- ["--color-scale=size,age"];                Both => Ok(ALL));
+    test!(scale_reversed: ColourScale <- ["--color-scale=age,size"];                Both => Ok(ALL));
+    test!(scale_repeated: ColourScale <- ["--colour-scale=age,age"];                Both => Ok(AGE));
+    test!(scale_blank:    ColourScale <- ["--color-scale=size,"];                   Both => err OptionsError::BadArgument(&flags::COLOR_SCALE, OsString::from("size,")));
+    test!(scale_unknown:  ColourScale <- ["--color-scale=age,date"];                Both => err OptionsError::BadArgument(&flags::COLOR_SCALE, OsString::from("age,date")));
+
+    test!(preset_none:  ThemePreset <- [];                   Both => Ok(ThemePreset::Dark));
+    test!(preset_dark:  ThemePreset <- ["--theme=dark"];     Both => Ok(ThemePreset::Dark));
+    test!(preset_light: ThemePreset <- ["--theme", "light"]; Both => Ok(ThemePreset::Light));
+    test!(preset_both:  ThemePreset <- ["--theme=light", "--theme=dark"];  Last => Ok(ThemePreset::Dark));
+    test!(preset_dupe:  ThemePreset <- ["--theme=light", "--theme=dark"];  Complain => err OptionsError::Duplicate(Flag::Long("theme"), Flag::Long("theme")));
+    test!(preset_bad:   ThemePreset <- ["--theme=solarized"];              Both => err OptionsError::BadArgument(&flags::THEME, OsString::from("solarized")));
+
+    fn with_theme_file(path: &str) -> Definitions {
+        Definitions { ls: None, exa: None, theme_file: Some(path.into()) }
+    }
+
+    test!(no_theme:     Definitions <- [], MockVars::empty();                                       Both => Ok(Definitions::default()));
+    test!(theme_flag:   Definitions <- ["--theme-file", "dark.toml"], MockVars::empty();            Both => Ok(with_theme_file("dark.toml")));
+    test!(theme_env:    Definitions <- [], MockVars::with_config();                                 Both => Ok(with_theme_file("/etc/exa.toml")));
+    test!(theme_both:   Definitions <- ["--theme-file=dark.toml"], MockVars::with_config();         Both => Ok(with_theme_file("dark.toml")));
 }
@@ -15,10 +15,35 @@ pub static COLUMNS: &str = "COLUMNS";
 /// Environment variable used to datetime format.
 pub static TIME_STYLE: &str = "TIME_STYLE";
 
+/// Environment variable used to pick how file names get quoted, the same
+/// way as with GNU `ls`. It gets overridden by `--quoting-style`.
+pub static QUOTING_STYLE: &str = "QUOTING_STYLE";
+
+/// Environment variables used to find the user’s configuration directory,
+/// which is `$XDG_CONFIG_HOME` if it’s set, and `$HOME/.config` otherwise.
+pub static XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+pub static HOME: &str = "HOME";
+
 /// Environment variable used to disable colors.
 /// See: <https://no-color.org/>
 pub static NO_COLOR: &str = "NO_COLOR";
 
+/// Environment variable used by BSD tools to disable colors, when it’s set
+/// to `0`. See: <https://bixense.com/clicolors/>
+pub static CLICOLOR: &str = "CLICOLOR";
+
+/// Environment variable used by BSD tools to force colors, even when the
+/// output isn’t a terminal. Any non-empty value will turn colors on.
+pub static CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
+
+/// Environment variable used to choose the pager that long output gets
+/// sent through with `--paging`, which can include its own arguments.
+pub static PAGER: &str = "PAGER";
+
+/// Environment variable used to give `less` its default options. If it’s
+/// not set, exa sets it so that colours get through the pager.
+pub static LESS: &str = "LESS";
+
 // exa-specific variables
 
 /// Environment variable used to colour exa’s interface when colours are
@@ -26,6 +51,17 @@ pub static NO_COLOR: &str = "NO_COLOR";
 /// overriding them if necessary. It can also contain exa-specific codes.
 pub static EXA_COLORS: &str = "EXA_COLORS";
 
+/// Environment variable used to give the path of a theme file, which sets
+/// the styles of exa’s interface by name. It gets overridden by the
+/// `--theme-file` option, and its styles by `LS_COLORS` and `EXA_COLORS`.
+pub static EXA_CONFIG: &str = "EXA_CONFIG";
+
+/// Environment variable used to give the path of a file of default
+/// options, with one argument on each line, which get used before the
+/// command-line ones. Without it, `exa/config` in the user’s configuration
+/// directory is read, if it exists.
+pub static EXA_OPTIONS_FILE: &str = "EXA_OPTIONS_FILE";
+
 /// Environment variable used to switch on strict argument checking, such as
 /// complaining if an argument was specified twice, or if two conflict.
 /// This is meant to be so you don’t accidentally introduce the wrong
@@ -48,6 +84,18 @@ pub static EXA_GRID_ROWS: &str = "EXA_GRID_ROWS";
 /// far apart, so this may be necessary depending on how they are shown.
 pub static EXA_ICON_SPACING: &str = "EXA_ICON_SPACING";
 
+/// Environment variable used to seed the shuffle when sorting files with
+/// `--sort=random`, so the same order comes out each time.
+pub static EXA_SEED: &str = "EXA_SEED";
+
+/// Environment variable used to pick the icons for files matching certain
+/// globs, overriding the built-in ones, in the same format as `EXA_COLORS`.
+pub static EXA_ICONS: &str = "EXA_ICONS";
+
+/// Environment variable used to show ASCII characters as icons instead of
+/// Nerd Font glyphs, for terminals whose fonts don’t have them.
+pub static EXA_ICONS_ASCII: &str = "EXA_ICONS_ASCII";
+
 
 /// Mockable wrapper for `std::env::var_os`.
 pub trait Vars {
@@ -1,19 +1,26 @@
+use std::ffi::OsString;
+
 use crate::fs::feature::xattr;
 use crate::options::{flags, OptionsError, NumberSource, Vars};
-use crate::options::parser::MatchedFlags;
-use crate::output::{View, Mode, TerminalWidth, grid, details};
+use crate::options::filter::parse_size;
+use crate::options::parser::{Arg, MatchedFlags};
+use crate::output::{View, Mode, Paging, TerminalWidth, grid, details};
+use crate::output::delimited::{self, Delimiter};
 use crate::output::grid_details::{self, RowThreshold};
+use crate::output::stat;
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{TimeTypes, SizeFormat, UserFormat, Columns, Options as TableOptions};
-use crate::output::time::TimeFormat;
+use crate::output::table::{TimeTypes, SizeFormat, SizePercent, BlockSize, UserFormat, Columns, ColumnName, Options as TableOptions};
+use crate::output::time::{TimeFormat, parse_duration};
+use crate::output::tree::TreeStyle;
 
 
 impl View {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let mode = Mode::deduce(matches, vars)?;
-        let width = TerminalWidth::deduce(vars)?;
+        let width = TerminalWidth::deduce(matches, vars)?;
         let file_style = FileStyle::deduce(matches, vars)?;
-        Ok(Self { mode, width, file_style })
+        let paging = Paging::deduce(matches, vars)?;
+        Ok(Self { mode, width, file_style, paging })
     }
 }
 
@@ -28,7 +35,54 @@ impl Mode {
     ///
     /// This is complicated a little by the fact that `--grid` and `--tree`
     /// can also combine with `--long`, so care has to be taken to use the
+    ///
+    /// The `--zero`, `--csv`, and `--tsv` flags override all of these, as
+    /// their output is meant for other programs rather than people, and
+    /// `--stat` overrides everything, as it describes each file on its own.
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if matches.has(&flags::STAT)? {
+            if matches.is_strict() {
+                for option in &[ &flags::LONG, &flags::ONE_LINE, &flags::GRID, &flags::TREE, &flags::ZERO, &flags::CSV, &flags::TSV ] {
+                    if matches.has(option)? {
+                        return Err(OptionsError::Conflict(&flags::STAT, option));
+                    }
+                }
+            }
+
+            // Every timestamp gets shown, in full, unless the user has
+            // picked a format for them.
+            let mut table = TableOptions::deduce(matches, vars)?;
+            table.columns.time_types = TimeTypes { modified: true, changed: true, accessed: true, created: true };
+            table.human_blocks = true;
+            if table.time_formats == [ TimeFormat::DefaultFormat ] {
+                table.time_formats = vec![ TimeFormat::Precise ];
+            }
+
+            return Ok(Self::Stat(stat::Options { table }));
+        }
+
+        if matches.has(&flags::ZERO)? {
+            if matches.is_strict() {
+                for option in &[ &flags::LONG, &flags::CSV, &flags::TSV ] {
+                    if matches.has(option)? {
+                        return Err(OptionsError::Conflict(&flags::ZERO, option));
+                    }
+                }
+            }
+
+            Self::strict_check_long_flags(matches)?;
+            return Ok(Self::Zero);
+        }
+
+        // The delimited view has the same columns as the details view, so
+        // it takes all the same options, and doesn’t need `--long` for them.
+        if let Some(flag) = matches.has_where(|f| f.matches(&flags::CSV) || f.matches(&flags::TSV))? {
+            let delimiter = if flag.matches(&flags::CSV) { Delimiter::Comma }
+                                                    else { Delimiter::Tab };
+            let table = TableOptions::deduce(matches, vars)?;
+            return Ok(Self::Delimited(delimited::Options { delimiter, table }));
+        }
+
         let flag = matches.has_where_any(|f| f.matches(&flags::LONG) || f.matches(&flags::ONE_LINE)
                                           || f.matches(&flags::GRID) || f.matches(&flags::TREE));
 
@@ -80,18 +134,36 @@ impl Mode {
         // If --long hasn’t been passed, then check if we need to warn the
         // user about flags that won’t have any effect.
         if matches.is_strict() {
-            for option in &[ &flags::BINARY, &flags::BYTES, &flags::INODE, &flags::LINKS,
-                             &flags::HEADER, &flags::BLOCKS, &flags::TIME, &flags::GROUP, &flags::NUMERIC ] {
+            for option in &[ &flags::BINARY, &flags::BYTES, &flags::COMMA, &flags::INODE, &flags::LINKS,
+                             &flags::HEADER, &flags::BLOCKS, &flags::BLOCKSIZE_HUMAN, &flags::TOTAL_SIZE, &flags::DIR_COUNTS, &flags::TIME, &flags::GROUP,
+                             &flags::HARD_LINK_GROUPS, &flags::NUMERIC, &flags::GIT_REPOS, &flags::MOUNTS, &flags::MIME, &flags::XATTR_NAMES, &flags::DEREFERENCE ] {
                 if matches.has(option)? {
                     return Err(OptionsError::Useless(*option, false, &flags::LONG));
                 }
             }
 
-            if matches.has(&flags::GIT)? {
+            if matches.get_optional(&flags::GIT)?.is_some() {
                 return Err(OptionsError::Useless(&flags::GIT, false, &flags::LONG));
             }
-            else if matches.has(&flags::LEVEL)? && ! matches.has(&flags::RECURSE)? && ! matches.has(&flags::TREE)? {
-                return Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE));
+
+            if matches.get(&flags::COLUMNS)?.is_some() {
+                return Err(OptionsError::Useless(&flags::COLUMNS, false, &flags::LONG));
+            }
+
+            if matches.has(&flags::SUMMARY)? {
+                return Err(OptionsError::Useless(&flags::SUMMARY, false, &flags::LONG));
+            }
+
+            if matches.has(&flags::NO_HEADERS_BETWEEN_DIRS)? {
+                return Err(OptionsError::Useless(&flags::NO_HEADERS_BETWEEN_DIRS, false, &flags::LONG));
+            }
+
+            if matches.get_optional(&flags::REPEAT_HEADER)?.is_some() {
+                return Err(OptionsError::Useless(&flags::REPEAT_HEADER, false, &flags::LONG));
+            }
+
+            if matches.get(&flags::BLOCK_SIZE)?.is_some() {
+                return Err(OptionsError::Useless(&flags::BLOCK_SIZE, false, &flags::LONG));
             }
         }
 
@@ -102,12 +174,42 @@ impl Mode {
 
 impl grid::Options {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let min_columns = Self::deduce_columns(matches, &flags::MIN_COLUMNS)?;
+        let max_columns = Self::deduce_columns(matches, &flags::MAX_COLUMNS)?;
+
+        if let (Some(min), Some(max)) = (min_columns, max_columns) {
+            if min > max {
+                return Err(OptionsError::Conflict(&flags::MIN_COLUMNS, &flags::MAX_COLUMNS));
+            }
+        }
+
         let grid = grid::Options {
             across: matches.has(&flags::ACROSS)?,
+            min_columns,
+            max_columns,
         };
 
         Ok(grid)
     }
+
+    /// Parses the number of columns given to `--min-columns` or
+    /// `--max-columns`, neither of which makes sense as zero.
+    fn deduce_columns(matches: &MatchedFlags<'_>, flag: &'static Arg) -> Result<Option<usize>, OptionsError> {
+        match matches.get(flag)? {
+            None         => Ok(None),
+            Some(input)  => {
+                let arg_str = input.to_string_lossy();
+                match arg_str.parse::<usize>() {
+                    Ok(0)        => Err(OptionsError::BadArgument(flag, input.into())),
+                    Ok(columns)  => Ok(Some(columns)),
+                    Err(e)       => {
+                        let source = NumberSource::Arg(flag);
+                        Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                    }
+                }
+            }
+        }
+    }
 }
 
 
@@ -116,7 +218,11 @@ impl details::Options {
         let details = details::Options {
             table: None,
             header: false,
+            repeat_header: None,
+            header_once: false,
+            summary: false,
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
+            tree_style: TreeStyle::deduce(matches)?,
         };
 
         Ok(details)
@@ -127,24 +233,125 @@ impl details::Options {
             if matches.has(&flags::ACROSS)? && ! matches.has(&flags::GRID)? {
                 return Err(OptionsError::Useless(&flags::ACROSS, true, &flags::LONG));
             }
+            else if matches.get(&flags::MIN_COLUMNS)?.is_some() && ! matches.has(&flags::GRID)? {
+                return Err(OptionsError::Useless(&flags::MIN_COLUMNS, true, &flags::LONG));
+            }
+            else if matches.get(&flags::MAX_COLUMNS)?.is_some() && ! matches.has(&flags::GRID)? {
+                return Err(OptionsError::Useless(&flags::MAX_COLUMNS, true, &flags::LONG));
+            }
             else if matches.has(&flags::ONE_LINE)? {
                 return Err(OptionsError::Useless(&flags::ONE_LINE, true, &flags::LONG));
             }
         }
 
+        let repeat_header = details::RepeatHeader::deduce(matches)?;
+        let header = matches.has(&flags::HEADER)? || repeat_header.is_some();
+        let header_once = matches.has(&flags::NO_HEADERS_BETWEEN_DIRS)?;
+
+        if header_once && ! header && matches.is_strict() {
+            return Err(OptionsError::Useless(&flags::NO_HEADERS_BETWEEN_DIRS, false, &flags::HEADER));
+        }
+
         Ok(details::Options {
             table: Some(TableOptions::deduce(matches, vars)?),
-            header: matches.has(&flags::HEADER)?,
+            header,
+            repeat_header,
+            header_once,
+            summary: matches.has(&flags::SUMMARY)?,
             xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
+            tree_style: TreeStyle::deduce(matches)?,
         })
     }
 }
 
 
+impl TreeStyle {
+
+    /// Determines which characters to draw the tree with, based on the
+    /// `--tree-style` argument, which defaults to the Unicode ones.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::TREE_STYLE)? {
+            None                               => Ok(Self::default()),
+            Some(word) if word == "unicode"    => Ok(Self::Unicode),
+            Some(word) if word == "ascii"      => Ok(Self::Ascii),
+            Some(word)                         => Err(OptionsError::BadArgument(&flags::TREE_STYLE, word.into())),
+        }
+    }
+}
+
+
+impl details::RepeatHeader {
+
+    /// Determine how often to repeat the header line. Giving the argument
+    /// without a value repeats it once per screenful.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        match matches.get_optional(&flags::REPEAT_HEADER)? {
+            None              => Ok(None),
+            Some(None)        => Ok(Some(Self::TerminalHeight)),
+            Some(Some(input)) => {
+                let arg_str = input.to_string_lossy();
+                match arg_str.parse::<usize>() {
+                    Ok(0)     => Err(OptionsError::BadArgument(&flags::REPEAT_HEADER, input.into())),
+                    Ok(rows)  => Ok(Some(Self::Every(rows))),
+                    Err(e)    => {
+                        let source = NumberSource::Arg(&flags::REPEAT_HEADER);
+                        Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+impl Paging {
+
+    /// Determine whether to use a pager, and which one. Passing `--paging`
+    /// on its own is the same as `--paging=auto`. The pager comes from the
+    /// `PAGER` environment variable, falling back to `less -R`, with the
+    /// `-R` so that colours get through.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        use crate::options::vars;
+
+        match matches.get_optional(&flags::PAGING)? {
+            None                                 => return Ok(Self::Never),
+            Some(Some(word)) if word == "never"  => return Ok(Self::Never),
+            Some(None)                           => {},
+            Some(Some(word)) if word == "auto"   => {},
+            Some(Some(word))                     => return Err(OptionsError::BadArgument(&flags::PAGING, word.into())),
+        }
+
+        let command = vars.get(vars::PAGER)
+                          .and_then(|s| s.into_string().ok())
+                          .filter(|s| ! s.trim().is_empty())
+                          .unwrap_or_else(|| String::from("less -R"));
+
+        Ok(Self::Auto(command))
+    }
+}
+
+
 impl TerminalWidth {
-    fn deduce<V: Vars>(vars: &V) -> Result<Self, OptionsError> {
+
+    /// Determines the width of the terminal to use. The `--width` argument
+    /// takes precedence over the `COLUMNS` environment variable, and without
+    /// either, the width gets looked up once exa is running.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         use crate::options::vars;
 
+        if let Some(width) = matches.get(&flags::WIDTH)? {
+            let arg_str = width.to_string_lossy();
+            return match arg_str.parse() {
+                Ok(w) => {
+                    Ok(Self::Set(w))
+                }
+                Err(e) => {
+                    let source = NumberSource::Arg(&flags::WIDTH);
+                    Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                }
+            };
+        }
+
         if let Some(columns) = vars.get(vars::COLUMNS).and_then(|s| s.into_string().ok()) {
             match columns.parse() {
                 Ok(width) => {
@@ -187,11 +394,22 @@ impl RowThreshold {
 
 impl TableOptions {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let time_format = TimeFormat::deduce(matches, vars)?;
+        let time_formats = TimeFormat::deduce(matches, vars)?;
         let size_format = SizeFormat::deduce(matches)?;
+        let size_percent = SizePercent::deduce(matches)?;
+        let block_size = BlockSize::deduce(matches)?;
+        let human_blocks = matches.has(&flags::BLOCKSIZE_HUMAN)?;
         let user_format = UserFormat::deduce(matches)?;
         let columns = Columns::deduce(matches)?;
-        Ok(Self { size_format, time_format, user_format, columns })
+        let dereference = matches.has(&flags::DEREFERENCE)?;
+
+        // Sizes on disk are always worked out from 512-byte blocks, so
+        // there’s nothing for a block size to change.
+        if human_blocks && matches.is_strict() && matches.get(&flags::BLOCK_SIZE)?.is_some() {
+            return Err(OptionsError::Conflict(&flags::BLOCK_SIZE, &flags::BLOCKSIZE_HUMAN));
+        }
+
+        Ok(Self { size_format, size_percent, block_size, human_blocks, time_formats, user_format, columns, dereference })
     }
 }
 
@@ -199,19 +417,153 @@ impl TableOptions {
 impl Columns {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let time_types = TimeTypes::deduce(matches)?;
-        let git = matches.has(&flags::GIT)?;
+        let dir_counts = matches.has(&flags::DIR_COUNTS)?;
 
-        let blocks = matches.has(&flags::BLOCKS)?;
+        let (git, git_split) = Self::deduce_git(matches)?;
+
+        if let Some(order) = Self::deduce_order(matches)? {
+            if matches.is_strict() && matches.get(&flags::ONLY)?.is_some() {
+                return Err(OptionsError::Conflict(&flags::COLUMNS, &flags::ONLY));
+            }
+
+            return Ok(Self::from_order(time_types, order, dir_counts, git_split));
+        }
+
+        if let Some(only) = Self::deduce_only(matches)? {
+            return Ok(Self::from_only(time_types, &only, dir_counts, git_split));
+        }
+
+        let git_repos = matches.has(&flags::GIT_REPOS)?;
+        let mounts = matches.has(&flags::MOUNTS)?;
+        let mime = matches.has(&flags::MIME)?;
+        let xattr_names = xattr::ENABLED && matches.has(&flags::XATTR_NAMES)?;
+
+        let blocks = matches.has(&flags::BLOCKS)? || matches.has(&flags::BLOCKSIZE_HUMAN)?;
         let group  = matches.has(&flags::GROUP)?;
         let inode  = matches.has(&flags::INODE)?;
         let links  = matches.has(&flags::LINKS)?;
+        let link_groups = matches.has(&flags::HARD_LINK_GROUPS)?;
         let octal  = matches.has(&flags::OCTAL)?;
+        let total_size = matches.has(&flags::TOTAL_SIZE)?;
 
         let permissions = ! matches.has(&flags::NO_PERMISSIONS)?;
         let filesize =    ! matches.has(&flags::NO_FILESIZE)?;
         let user =        ! matches.has(&flags::NO_USER)?;
 
-        Ok(Self { time_types, inode, links, blocks, group, git, octal, permissions, filesize, user })
+        Ok(Self { time_types, inode, links, link_groups, blocks, group, git, git_split, git_repos, mounts, mime, xattr_names, octal, total_size, dir_counts, permissions, filesize, user, order: None })
+    }
+
+    /// Determines whether the Git column is shown, and whether it’s split
+    /// into separate index and working tree columns. Giving `--git` without
+    /// a value shows the short, combined column.
+    fn deduce_git(matches: &MatchedFlags<'_>) -> Result<(bool, bool), OptionsError> {
+        match matches.get_optional(&flags::GIT)? {
+            None                                => Ok((false, false)),
+            Some(None)                          => Ok((true, false)),
+            Some(Some(word)) if word == "short" => Ok((true, false)),
+            Some(Some(word)) if word == "long"  => Ok((true, true)),
+            Some(Some(word))                    => Err(OptionsError::BadArgument(&flags::GIT, word.into())),
+        }
+    }
+
+    /// Determines the list of columns picked with the `--columns` argument,
+    /// which is a list of column names separated by commas. The file name
+    /// is always displayed last, so naming it has no effect, and naming a
+    /// column twice only displays it once.
+    fn deduce_order(matches: &MatchedFlags<'_>) -> Result<Option<Vec<ColumnName>>, OptionsError> {
+        match matches.get(&flags::COLUMNS)? {
+            Some(input)  => Self::column_names(&flags::COLUMNS, &input.to_string_lossy()).map(Some),
+            None         => Ok(None),
+        }
+    }
+
+    /// Determines the list of columns picked with the `--only` argument,
+    /// which uses the same names as `--columns`, but leaves the columns in
+    /// their usual order. Every `--no-*` flag would be redundant alongside
+    /// it, so they conflict with it in strict mode.
+    fn deduce_only(matches: &MatchedFlags<'_>) -> Result<Option<Vec<ColumnName>>, OptionsError> {
+        let input = match matches.get(&flags::ONLY)? {
+            Some(i)  => i.to_string_lossy(),
+            None     => return Ok(None),
+        };
+
+        if matches.is_strict() {
+            for flag in &[ &flags::NO_PERMISSIONS, &flags::NO_FILESIZE, &flags::NO_USER, &flags::NO_TIME ] {
+                if matches.has(flag)? {
+                    return Err(OptionsError::Conflict(&flags::ONLY, flag));
+                }
+            }
+        }
+
+        Self::column_names(&flags::ONLY, &input).map(Some)
+    }
+
+    /// Parses a list of column names separated by commas, complaining
+    /// about the given flag if one of them isn’t a column.
+    fn column_names(flag: &'static Arg, input: &str) -> Result<Vec<ColumnName>, OptionsError> {
+        let mut order = Vec::new();
+        for word in input.split(',') {
+            let name = match word {
+                "perms"   => ColumnName::Permissions,
+                "octal"   => ColumnName::Octal,
+                "links"   => ColumnName::HardLinks,
+                "inode"   => ColumnName::Inode,
+                "blocks"  => ColumnName::Blocks,
+                "user"    => ColumnName::User,
+                "group"   => ColumnName::Group,
+                "size"    => ColumnName::FileSize,
+                "git"     => ColumnName::GitStatus,
+                "date"    => ColumnName::Timestamps,
+                "name"    => continue,
+                _         => return Err(OptionsError::BadArgument(flag, word.into())),
+            };
+
+            if ! order.contains(&name) {
+                order.push(name);
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// The set of columns to display when they’ve been picked by name,
+    /// which replaces any columns turned on or off by the other flags.
+    /// Directory counts still apply, as they go in the size column.
+    fn from_order(time_types: TimeTypes, order: Vec<ColumnName>, dir_counts: bool, git_split: bool) -> Self {
+        let has = |name| order.contains(&name);
+
+        Self {
+            time_types,
+            inode:       has(ColumnName::Inode),
+            links:       has(ColumnName::HardLinks),
+            link_groups: false,
+            blocks:      has(ColumnName::Blocks),
+            group:       has(ColumnName::Group),
+            git:         has(ColumnName::GitStatus),
+            git_split,
+            git_repos:   false,
+            mounts:      false,
+            mime:        false,
+            xattr_names: false,
+            octal:       has(ColumnName::Octal),
+            total_size:  false,
+            dir_counts,
+            permissions: has(ColumnName::Permissions),
+            filesize:    has(ColumnName::FileSize),
+            user:        has(ColumnName::User),
+            order:       Some(order),
+        }
+    }
+
+    /// The set of columns to display when they’ve been picked with
+    /// `--only`, which turns every other column off, but keeps them in
+    /// their usual order. The timestamps picked with `--time` and friends
+    /// are only shown if the dates are picked.
+    fn from_only(time_types: TimeTypes, only: &[ColumnName], dir_counts: bool, git_split: bool) -> Self {
+        let time_types = if only.contains(&ColumnName::Timestamps) { time_types }
+                         else { TimeTypes { modified: false, changed: false, accessed: false, created: false } };
+
+        Self { order: None, ..Self::from_order(time_types, only.to_vec(), dir_counts, git_split) }
     }
 }
 
@@ -225,35 +577,94 @@ impl SizeFormat {
     /// most commonly-understood, and don’t involve trying to parse large
     /// strings of digits in your head. Changing the format to anything else
     /// involves the `--binary` or `--bytes` flags, and these conflict with
-    /// each other.
+    /// each other. Those strings of digits can be split up with `--comma`.
+    ///
+    /// The `--si` and `--iec` flags pick the decimal and binary prefixes
+    /// too, but write the units out in full, the way their standards do.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let flag = matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES))?;
+        let flag = matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES) ||
+                                         f.matches(&flags::SI)     || f.matches(&flags::IEC))?;
+        let comma = matches.has(&flags::COMMA)?;
 
-        Ok(match flag {
+        let format = match flag {
             Some(f) if f.matches(&flags::BINARY)  => Self::BinaryBytes,
-            Some(f) if f.matches(&flags::BYTES)   => Self::JustBytes,
+            Some(f) if f.matches(&flags::SI)      => Self::SIBytes,
+            Some(f) if f.matches(&flags::IEC)     => Self::IECBytes,
+            Some(f) if f.matches(&flags::BYTES)   => if comma { Self::GroupedBytes } else { Self::JustBytes },
             _                                     => Self::DecimalBytes,
-        })
+        };
+
+        if comma && format != Self::GroupedBytes && matches.is_strict() {
+            return Err(OptionsError::Useless(&flags::COMMA, false, &flags::BYTES));
+        }
+
+        Ok(format)
     }
 }
 
 
-impl TimeFormat {
+impl SizePercent {
 
-    /// Determine how time should be formatted in timestamp columns.
-    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let word =
-            if let Some(w) = matches.get(&flags::TIME_STYLE)? {
-                w.to_os_string()
+    /// Determine whether file sizes should be shown as percentages, and of
+    /// what. Passing `--size-percent` on its own uses the total of each
+    /// directory, while `--size-percent=global` uses the total of the whole
+    /// listing.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        match matches.get_optional(&flags::SIZE_PERCENT)? {
+            None                                  => Ok(None),
+            Some(None)                            => Ok(Some(Self::PerDirectory)),
+            Some(Some(word)) if word == "dir"     => Ok(Some(Self::PerDirectory)),
+            Some(Some(word)) if word == "global"  => Ok(Some(Self::Global)),
+            Some(Some(word))                      => Err(OptionsError::BadArgument(&flags::SIZE_PERCENT, word.into())),
+        }
+    }
+}
+
+
+impl BlockSize {
+
+    /// Determine how many bytes each block in the blocks column should
+    /// stand for. The size is written the same way as for `--bigger-than`,
+    /// and can’t be zero.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if let Some(input) = matches.get(&flags::BLOCK_SIZE)? {
+            match parse_size(&input.to_string_lossy()) {
+                Some(0)     => Err(OptionsError::BadArgument(&flags::BLOCK_SIZE, input.into())),
+                Some(size)  => Ok(Self(size)),
+                None        => Err(OptionsError::FailedSizeParse(input.to_string_lossy().into_owned(), NumberSource::Arg(&flags::BLOCK_SIZE))),
             }
-            else {
-                use crate::options::vars;
-                match vars.get(vars::TIME_STYLE) {
-                    Some(ref t) if ! t.is_empty()  => t.clone(),
-                    _                              => return Ok(Self::DefaultFormat)
-                }
-            };
+        }
+        else {
+            Ok(Self::default())
+        }
+    }
+}
+
+
+impl TimeFormat {
+
+    /// Determine how time should be formatted in timestamp columns. The
+    /// `--time-style` argument can be given more than once, with each one
+    /// applying to the next timestamp column, so this returns a list of
+    /// formats that always has at least one in it.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Vec<Self>, OptionsError> {
+        use crate::options::vars;
+
+        let words = matches.get_all(&flags::TIME_STYLE);
+        if ! words.is_empty() {
+            return words.into_iter()
+                        .map(|word| Self::from_word(word.to_os_string()))
+                        .collect();
+        }
+
+        match vars.get(vars::TIME_STYLE) {
+            Some(t) if ! t.is_empty()  => Ok(vec![ Self::from_word(t)? ]),
+            _                          => Ok(vec![ Self::DefaultFormat ]),
+        }
+    }
 
+    /// Determine which time format a single style name refers to.
+    fn from_word(word: OsString) -> Result<Self, OptionsError> {
         if &word == "default" {
             Ok(Self::DefaultFormat)
         }
@@ -266,6 +677,21 @@ impl TimeFormat {
         else if &word == "full-iso" {
             Ok(Self::FullISO)
         }
+        else if &word == "precise" {
+            Ok(Self::Precise)
+        }
+        else if &word == "relative" {
+            Ok(Self::Relative)
+        }
+        else if let Some(cutoff) = word.to_str().and_then(|w| w.strip_prefix("relative:")) {
+            parse_duration(cutoff)
+                .map(Self::RelativeWithin)
+                .ok_or_else(|| OptionsError::BadArgument(&flags::TIME_STYLE, word.clone()))
+        }
+        else if let Some(format) = word.to_str().and_then(|w| w.strip_prefix('+')) {
+            Self::custom(format)
+                .ok_or_else(|| OptionsError::BadArgument(&flags::TIME_STYLE, word.clone()))
+        }
         else {
             Err(OptionsError::BadArgument(&flags::TIME_STYLE, word))
         }
@@ -340,6 +766,14 @@ impl TimeTypes {
             Self::default()
         };
 
+        // Only Unix keeps track of when a file’s metadata changed, and the
+        // modified time would otherwise be shown in its place.
+        if cfg!(not(unix)) && time_types.changed {
+            return Err(OptionsError::Unsupported(String::from(
+                "Options --changed and --time=changed can't be used because this platform doesn't record when files were changed"
+            )));
+        }
+
         Ok(time_types)
     }
 }
@@ -349,19 +783,25 @@ impl TimeTypes {
 mod test {
     use super::*;
     use std::ffi::OsString;
+    use std::time::Duration;
     use crate::options::flags;
     use crate::options::parser::{Flag, Arg};
 
     use crate::options::test::parse_for_test;
     use crate::options::test::Strictnesses::*;
 
-    static TEST_ARGS: &[&Arg] = &[ &flags::BINARY, &flags::BYTES,    &flags::TIME_STYLE,
+    static TEST_ARGS: &[&Arg] = &[ &flags::BINARY, &flags::BYTES, &flags::SI, &flags::IEC, &flags::COMMA, &flags::TIME_STYLE,
+                                   &flags::SIZE_PERCENT, &flags::BLOCK_SIZE, &flags::BLOCKSIZE_HUMAN,
                                    &flags::TIME,   &flags::MODIFIED, &flags::CHANGED,
                                    &flags::CREATED, &flags::ACCESSED,
                                    &flags::HEADER, &flags::GROUP,  &flags::INODE, &flags::GIT,
                                    &flags::LINKS,  &flags::BLOCKS, &flags::LONG,  &flags::LEVEL,
                                    &flags::GRID,   &flags::ACROSS, &flags::ONE_LINE, &flags::TREE,
-                                   &flags::NUMERIC ];
+                                   &flags::NUMERIC, &flags::ZERO, &flags::TOTAL_SIZE, &flags::DIR_COUNTS, &flags::GIT_REPOS, &flags::MOUNTS,
+                                   &flags::MIME, &flags::XATTR_NAMES, &flags::COLUMNS, &flags::HARD_LINK_GROUPS, &flags::DEREFERENCE, &flags::CSV, &flags::TSV, &flags::STAT, &flags::PAGING,
+                                   &flags::WIDTH, &flags::REPEAT_HEADER, &flags::SUMMARY, &flags::NO_HEADERS_BETWEEN_DIRS,
+                                   &flags::MIN_COLUMNS, &flags::MAX_COLUMNS, &flags::OCTAL, &flags::NO_PERMISSIONS,
+                                   &flags::NO_FILESIZE, &flags::NO_USER, &flags::NO_TIME, &flags::ONLY, &flags::TREE_STYLE ];
 
     macro_rules! test {
 
@@ -426,6 +866,16 @@ mod test {
                 }
             }
         };
+
+        ($name:ident: $type:ident <- $inputs:expr, $vars:expr; $stricts:expr => $result:expr) => {
+            /// Like the first one, but with $vars.
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| $type::deduce(mf, &$vars)) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
     }
 
 
@@ -438,6 +888,11 @@ mod test {
         // Individual flags
         test!(binary:  SizeFormat <- ["--binary"];             Both => Ok(SizeFormat::BinaryBytes));
         test!(bytes:   SizeFormat <- ["--bytes"];              Both => Ok(SizeFormat::JustBytes));
+        test!(comma:   SizeFormat <- ["--bytes", "--comma"];   Both => Ok(SizeFormat::GroupedBytes));
+        test!(comma_2: SizeFormat <- ["--comma", "-B"];        Both => Ok(SizeFormat::GroupedBytes));
+        test!(comma_3: SizeFormat <- ["--comma"];              Last => Ok(SizeFormat::DecimalBytes));
+        test!(comma_4: SizeFormat <- ["--comma"];              Complain => err OptionsError::Useless(&flags::COMMA, false, &flags::BYTES));
+        test!(comma_5: SizeFormat <- ["--binary", "--comma"];  Complain => err OptionsError::Useless(&flags::COMMA, false, &flags::BYTES));
 
         // Overriding
         test!(both_1:  SizeFormat <- ["--binary", "--binary"];  Last => Ok(SizeFormat::BinaryBytes));
@@ -449,40 +904,158 @@ mod test {
         test!(both_6:  SizeFormat <- ["--bytes",  "--binary"];  Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("binary")));
         test!(both_7:  SizeFormat <- ["--binary", "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("binary"), Flag::Long("bytes")));
         test!(both_8:  SizeFormat <- ["--bytes",  "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("bytes")));
+
+        // Standards’ names
+        test!(si:      SizeFormat <- ["--si"];                 Both => Ok(SizeFormat::SIBytes));
+        test!(iec:     SizeFormat <- ["--iec"];                Both => Ok(SizeFormat::IECBytes));
+        test!(si_iec:  SizeFormat <- ["--si", "--iec"];        Last => Ok(SizeFormat::IECBytes));
+        test!(iec_si:  SizeFormat <- ["--iec", "--si"];        Last => Ok(SizeFormat::SIBytes));
+        test!(bytes_si: SizeFormat <- ["--bytes", "--si"];     Last => Ok(SizeFormat::SIBytes));
+        test!(si_iec_2: SizeFormat <- ["--si", "--iec"];       Complain => err OptionsError::Duplicate(Flag::Long("si"), Flag::Long("iec")));
+        test!(si_comma: SizeFormat <- ["--si", "--comma"];     Complain => err OptionsError::Useless(&flags::COMMA, false, &flags::BYTES));
     }
 
 
-    mod time_formats {
+    mod terminal_widths {
+        use super::*;
+
+        test!(empty:      TerminalWidth <- [], None;                                Both => Ok(TerminalWidth::Automatic));
+        test!(flag:       TerminalWidth <- ["--width=100"], None;                   Both => Ok(TerminalWidth::Set(100)));
+        test!(short:      TerminalWidth <- ["-w", "40"], None;                      Both => Ok(TerminalWidth::Set(40)));
+        test!(zero:       TerminalWidth <- ["--width=0"], None;                     Both => Ok(TerminalWidth::Set(0)));
+        test!(env:        TerminalWidth <- [], Some("80".into());                   Both => Ok(TerminalWidth::Set(80)));
+        test!(both:       TerminalWidth <- ["--width=100"], Some("80".into());      Both => Ok(TerminalWidth::Set(100)));
+        test!(last:       TerminalWidth <- ["-w", "40", "--width=100"], None;       Last => Ok(TerminalWidth::Set(100)));
+        test!(twice:      TerminalWidth <- ["-w", "40", "--width=100"], None;       Complain => err OptionsError::Duplicate(Flag::Short(b'w'), Flag::Long("width")));
+        test!(bad_env:    TerminalWidth <- [], Some("wide".into());                 Both => like Err(OptionsError::FailedParse(_, NumberSource::Env(_), _)));
+        test!(bad_flag:   TerminalWidth <- ["--width=wide"], Some("80".into());     Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+    }
+
+
+    mod pagings {
+        use super::*;
+
+        test!(empty:      Paging <- [], None;                            Both => Ok(Paging::Never));
+        test!(bare:       Paging <- ["--paging"], None;                  Both => Ok(Paging::Auto(String::from("less -R"))));
+        test!(auto:       Paging <- ["--paging=auto"], None;             Both => Ok(Paging::Auto(String::from("less -R"))));
+        test!(never:      Paging <- ["--paging=never"], None;            Both => Ok(Paging::Never));
+        test!(pager:      Paging <- ["--paging"], Some("most".into());   Both => Ok(Paging::Auto(String::from("most"))));
+        test!(pager_args: Paging <- ["--paging"], Some("less -FR".into());  Both => Ok(Paging::Auto(String::from("less -FR"))));
+        test!(pager_blank: Paging <- ["--paging"], Some(" ".into());     Both => Ok(Paging::Auto(String::from("less -R"))));
+        test!(never_last: Paging <- ["--paging", "--paging=never"], None;  Last => Ok(Paging::Never));
+        test!(never_twice: Paging <- ["--paging", "--paging=never"], None;  Complain => err OptionsError::Duplicate(Flag::Long("paging"), Flag::Long("paging")));
+        test!(unknown:    Paging <- ["--paging=always"], None;           Both => err OptionsError::BadArgument(&flags::PAGING, OsString::from("always")));
+    }
+
+
+    mod size_percents {
+        use super::*;
+
+        // Default behaviour
+        test!(empty:    SizePercent <- [];                                        Both => Ok(None));
+
+        // Individual settings
+        test!(bare:     SizePercent <- ["--size-percent"];                        Both => Ok(Some(SizePercent::PerDirectory)));
+        test!(dir:      SizePercent <- ["--size-percent=dir"];                    Both => Ok(Some(SizePercent::PerDirectory)));
+        test!(global:   SizePercent <- ["--size-percent=global"];                 Both => Ok(Some(SizePercent::Global)));
+
+        // Overriding
+        test!(both_1:   SizePercent <- ["--size-percent=global", "--size-percent=dir"];  Last => Ok(Some(SizePercent::PerDirectory)));
+        test!(both_2:   SizePercent <- ["--size-percent=global", "--size-percent=dir"];  Complain => err OptionsError::Duplicate(Flag::Long("size-percent"), Flag::Long("size-percent")));
+        test!(both_3:   SizePercent <- ["--size-percent=global", "--size-percent"];      Last => Ok(Some(SizePercent::PerDirectory)));
+        test!(both_4:   SizePercent <- ["--size-percent", "--size-percent=global"];      Complain => err OptionsError::Duplicate(Flag::Long("size-percent"), Flag::Long("size-percent")));
+
+        // Errors
+        test!(error:    SizePercent <- ["--size-percent=local"];                  Both => err OptionsError::BadArgument(&flags::SIZE_PERCENT, OsString::from("local")));
+    }
+
+
+    mod block_sizes {
         use super::*;
 
-        // These tests use pattern matching because TimeFormat doesn’t
-        // implement PartialEq.
+        // Default behaviour
+        test!(empty:    BlockSize <- [];                            Both => Ok(BlockSize(512)));
+
+        // Individual settings
+        test!(bytes:    BlockSize <- ["--block-size=1024"];         Both => Ok(BlockSize(1024)));
+        test!(binary:   BlockSize <- ["--block-size", "4Ki"];       Both => Ok(BlockSize(4096)));
+        test!(decimal:  BlockSize <- ["--block-size=1M"];           Both => Ok(BlockSize(1_000_000)));
+
+        // Overriding
+        test!(both_1:   BlockSize <- ["--block-size=1K", "--block-size=512"];  Last => Ok(BlockSize(512)));
+        test!(both_2:   BlockSize <- ["--block-size=1K", "--block-size=512"];  Complain => err OptionsError::Duplicate(Flag::Long("block-size"), Flag::Long("block-size")));
+
+        // Errors
+        test!(zero:     BlockSize <- ["--block-size=0"];            Both => err OptionsError::BadArgument(&flags::BLOCK_SIZE, OsString::from("0")));
+        test!(error:    BlockSize <- ["--block-size=big"];          Both => err OptionsError::FailedSizeParse(String::from("big"), NumberSource::Arg(&flags::BLOCK_SIZE)));
+    }
+
+
+    mod repeat_headers {
+        use super::*;
+        use crate::output::details::RepeatHeader;
 
         // Default behaviour
-        test!(empty:     TimeFormat <- [], None;                            Both => like Ok(TimeFormat::DefaultFormat));
+        test!(empty:    RepeatHeader <- [];                           Both => Ok(None));
 
         // Individual settings
-        test!(default:   TimeFormat <- ["--time-style=default"], None;      Both => like Ok(TimeFormat::DefaultFormat));
-        test!(iso:       TimeFormat <- ["--time-style", "iso"], None;       Both => like Ok(TimeFormat::ISOFormat));
-        test!(long_iso:  TimeFormat <- ["--time-style=long-iso"], None;     Both => like Ok(TimeFormat::LongISO));
-        test!(full_iso:  TimeFormat <- ["--time-style", "full-iso"], None;  Both => like Ok(TimeFormat::FullISO));
+        test!(screen:   RepeatHeader <- ["--repeat-header"];          Both => Ok(Some(RepeatHeader::TerminalHeight)));
+        test!(rows:     RepeatHeader <- ["--repeat-header=40"];       Both => Ok(Some(RepeatHeader::Every(40))));
 
         // Overriding
-        test!(actually:  TimeFormat <- ["--time-style=default", "--time-style", "iso"], None;  Last => like Ok(TimeFormat::ISOFormat));
-        test!(actual_2:  TimeFormat <- ["--time-style=default", "--time-style", "iso"], None;  Complain => err OptionsError::Duplicate(Flag::Long("time-style"), Flag::Long("time-style")));
+        test!(both_1:   RepeatHeader <- ["--repeat-header=40", "--repeat-header"];  Last => Ok(Some(RepeatHeader::TerminalHeight)));
+        test!(both_2:   RepeatHeader <- ["--repeat-header=40", "--repeat-header"];  Complain => err OptionsError::Duplicate(Flag::Long("repeat-header"), Flag::Long("repeat-header")));
+
+        // Errors
+        test!(zero:     RepeatHeader <- ["--repeat-header=0"];        Both => err OptionsError::BadArgument(&flags::REPEAT_HEADER, OsString::from("0")));
+        test!(error:    RepeatHeader <- ["--repeat-header=lots"];     Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+    }
+
 
-        test!(nevermind: TimeFormat <- ["--time-style", "long-iso", "--time-style=full-iso"], None;  Last => like Ok(TimeFormat::FullISO));
-        test!(nevermore: TimeFormat <- ["--time-style", "long-iso", "--time-style=full-iso"], None;  Complain => err OptionsError::Duplicate(Flag::Long("time-style"), Flag::Long("time-style")));
+    mod time_formats {
+        use super::*;
+
+
+        // Default behaviour
+        test!(empty:     TimeFormat <- [], None;                            Both => Ok(vec![ TimeFormat::DefaultFormat ]));
+
+        // Individual settings
+        test!(default:   TimeFormat <- ["--time-style=default"], None;      Both => Ok(vec![ TimeFormat::DefaultFormat ]));
+        test!(iso:       TimeFormat <- ["--time-style", "iso"], None;       Both => Ok(vec![ TimeFormat::ISOFormat ]));
+        test!(long_iso:  TimeFormat <- ["--time-style=long-iso"], None;     Both => Ok(vec![ TimeFormat::LongISO ]));
+        test!(full_iso:  TimeFormat <- ["--time-style", "full-iso"], None;  Both => Ok(vec![ TimeFormat::FullISO ]));
+        test!(precise:   TimeFormat <- ["--time-style=precise"], None;      Both => Ok(vec![ TimeFormat::Precise ]));
+        test!(relative:  TimeFormat <- ["--time-style=relative"], None;     Both => Ok(vec![ TimeFormat::Relative ]));
+
+        // One for each column
+        test!(two:       TimeFormat <- ["--time-style=default", "--time-style", "iso"], None;  Both => Ok(vec![ TimeFormat::DefaultFormat, TimeFormat::ISOFormat ]));
+        test!(three:     TimeFormat <- ["--time-style", "relative", "--time-style=full-iso", "--time-style=+%H:%M"], None;  Both => Ok(vec![ TimeFormat::Relative, TimeFormat::FullISO, TimeFormat::Custom("%H:%M".into()) ]));
+        test!(repeated:  TimeFormat <- ["--time-style", "long-iso", "--time-style=long-iso"], None;  Both => Ok(vec![ TimeFormat::LongISO, TimeFormat::LongISO ]));
+        test!(two_env:   TimeFormat <- ["--time-style=iso", "--time-style=relative"], Some("long-iso".into());  Both => Ok(vec![ TimeFormat::ISOFormat, TimeFormat::Relative ]));
 
         // Errors
         test!(daily:     TimeFormat <- ["--time-style=24-hour"], None;  Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("24-hour")));
+        test!(bad_later: TimeFormat <- ["--time-style=iso", "--time-style=24-hour"], None;  Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("24-hour")));
+        test!(bad_spec:  TimeFormat <- ["--time-style=+%Y-%Q"], None;   Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("+%Y-%Q")));
+
+        // Relative up to a cutoff
+        test!(rel_days:  TimeFormat <- ["--time-style=relative:30d"], None;  Both => Ok(vec![ TimeFormat::RelativeWithin(Duration::from_secs(60 * 60 * 24 * 30)) ]));
+        test!(rel_hours: TimeFormat <- ["--time-style", "relative:12h"], None;  Both => Ok(vec![ TimeFormat::RelativeWithin(Duration::from_secs(60 * 60 * 12)) ]));
+        test!(rel_env:   TimeFormat <- [], Some("relative:2w".into());    Both => Ok(vec![ TimeFormat::RelativeWithin(Duration::from_secs(60 * 60 * 24 * 14)) ]));
+        test!(rel_empty: TimeFormat <- ["--time-style=relative:"], None;  Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("relative:")));
+        test!(rel_unit:  TimeFormat <- ["--time-style=relative:3y"], None;  Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("relative:3y")));
+
+        // Custom formats
+        test!(custom:    TimeFormat <- ["--time-style=+%Y-%m-%d"], None;   Both => Ok(vec![ TimeFormat::Custom("%Y-%m-%d".into()) ]));
+        test!(cust_sep:  TimeFormat <- ["--time-style", "+%H:%M"], None;   Both => Ok(vec![ TimeFormat::Custom("%H:%M".into()) ]));
+        test!(cust_env:  TimeFormat <- [], Some("+%d/%m".into());          Both => Ok(vec![ TimeFormat::Custom("%d/%m".into()) ]));
 
         // `TIME_STYLE` environment variable is defined.
         // If the time-style argument is not given, `TIME_STYLE` is used.
-        test!(use_env:     TimeFormat <- [], Some("long-iso".into());  Both => like Ok(TimeFormat::LongISO));
+        test!(use_env:     TimeFormat <- [], Some("long-iso".into());  Both => Ok(vec![ TimeFormat::LongISO ]));
 
         // If the time-style argument is given, `TIME_STYLE` is overriding.
-        test!(override_env:     TimeFormat <- ["--time-style=full-iso"], Some("long-iso".into());  Both => like Ok(TimeFormat::FullISO));
+        test!(override_env:     TimeFormat <- ["--time-style=full-iso"], Some("long-iso".into());  Both => Ok(vec![ TimeFormat::FullISO ]));
     }
 
 
@@ -505,6 +1078,14 @@ mod test {
         test!(time_ch:   TimeTypes <- ["--time=changed"];      Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false }));
         #[cfg(target_family = "unix")]
         test!(t_ch:    TimeTypes <- ["-t", "ch"];              Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false }));
+        #[cfg(not(target_family = "unix"))]
+        test!(changed:   TimeTypes <- ["--changed"];           Both => like Err(OptionsError::Unsupported(_)));
+        #[cfg(not(target_family = "unix"))]
+        test!(time_ch:   TimeTypes <- ["--time=changed"];      Both => like Err(OptionsError::Unsupported(_)));
+        #[cfg(not(target_family = "unix"))]
+        test!(t_ch:    TimeTypes <- ["-t", "ch"];              Both => like Err(OptionsError::Unsupported(_)));
+        #[cfg(not(target_family = "unix"))]
+        test!(ch_and_mod: TimeTypes <- ["--changed", "-m"];    Both => like Err(OptionsError::Unsupported(_)));
 
         // Accessed
         test!(acc:       TimeTypes <- ["--accessed"];          Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false }));
@@ -532,6 +1113,81 @@ mod test {
     }
 
 
+    mod human_blocks {
+        use super::*;
+
+        test!(raw:        TableOptions <- ["--blocks"], None;                               Both => like Ok(TableOptions { human_blocks: false, columns: Columns { blocks: true, .. }, .. }));
+        test!(human:      TableOptions <- ["--blocksize-human"], None;                      Both => like Ok(TableOptions { human_blocks: true, columns: Columns { blocks: true, .. }, .. }));
+        test!(both:       TableOptions <- ["--blocks", "--blocksize-human"], None;          Both => like Ok(TableOptions { human_blocks: true, columns: Columns { blocks: true, .. }, .. }));
+        test!(picked:     TableOptions <- ["--blocksize-human", "--columns=blocks"], None;  Both => like Ok(TableOptions { human_blocks: true, columns: Columns { blocks: true, filesize: false, .. }, .. }));
+        test!(sized:      TableOptions <- ["--blocksize-human", "--block-size=1K"], None;   Last => like Ok(TableOptions { human_blocks: true, .. }));
+        test!(sized_2:    TableOptions <- ["--blocksize-human", "--block-size=1K"], None;   Complain => err OptionsError::Conflict(&flags::BLOCK_SIZE, &flags::BLOCKSIZE_HUMAN));
+    }
+
+
+    mod columns {
+        use super::*;
+
+        fn order(inputs: &[&str]) -> Option<Vec<ColumnName>> {
+            parse_for_test(inputs, TEST_ARGS, Last, Columns::deduce).remove(0).unwrap().order
+        }
+
+        // Default behaviour
+        test!(empty:     Columns <- [];                              Both => like Ok(Columns { order: None, permissions: true, filesize: true, user: true, .. }));
+        test!(default:   Columns <- [];                              Both => Ok(Columns::default()));
+
+        // Picking columns
+        test!(picked:    Columns <- ["--columns=size,perms"];        Both => like Ok(Columns { permissions: true, filesize: true, user: false, inode: false, git: false, .. }));
+        test!(git:       Columns <- ["--columns", "git"];            Both => like Ok(Columns { git: true, permissions: false, .. }));
+        test!(replaces:  Columns <- ["--inode", "--columns=links"];  Both => like Ok(Columns { inode: false, links: true, .. }));
+        test!(counts:    Columns <- ["--dir-counts", "--columns=size"];  Both => like Ok(Columns { dir_counts: true, filesize: true, .. }));
+
+        // Octal permissions
+        test!(octal:      Columns <- ["--octal-permissions"];                      Both => like Ok(Columns { octal: true, permissions: true, .. }));
+        test!(only_octal: Columns <- ["--octal-permissions", "--no-permissions"];  Both => like Ok(Columns { octal: true, permissions: false, .. }));
+
+        // Extended attribute names
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        test!(xattr_names: Columns <- ["--xattr-names"];                           Both => like Ok(Columns { xattr_names: true, permissions: true, .. }));
+        test!(no_xattrs:  Columns <- [];                                           Both => like Ok(Columns { xattr_names: false, .. }));
+        test!(xattr_cols: Columns <- ["--xattr-names", "--columns=size"];          Both => like Ok(Columns { xattr_names: false, filesize: true, .. }));
+
+        // Showing only some columns
+        test!(only:       Columns <- ["--only=size,date"];                         Both => like Ok(Columns { order: None, filesize: true, permissions: false, user: false, time_types: TimeTypes { modified: true, .. }, .. }));
+        test!(only_perms: Columns <- ["--only", "perms"];                          Both => like Ok(Columns { order: None, permissions: true, filesize: false, user: false, time_types: TimeTypes { modified: false, changed: false, accessed: false, created: false }, .. }));
+        test!(only_flags: Columns <- ["--only=user", "--inode", "--git"];          Both => like Ok(Columns { user: true, inode: false, git: false, .. }));
+        test!(only_time:  Columns <- ["--only=date", "--accessed"];                Both => like Ok(Columns { filesize: false, time_types: TimeTypes { modified: false, accessed: true, .. }, .. }));
+        test!(only_name:  Columns <- ["--only=name"];                              Both => like Ok(Columns { permissions: false, filesize: false, user: false, time_types: TimeTypes { modified: false, .. }, .. }));
+        test!(only_bad:   Columns <- ["--only=size,colour"];                       Both => err OptionsError::BadArgument(&flags::ONLY, OsString::from("colour")));
+        test!(only_no:    Columns <- ["--only=size", "--no-user"];                 Last => like Ok(Columns { filesize: true, user: false, .. }));
+        test!(only_no_2:  Columns <- ["--only=size", "--no-user"];             Complain => err OptionsError::Conflict(&flags::ONLY, &flags::NO_USER));
+        test!(only_no_3:  Columns <- ["--only=perms", "--no-time"];            Complain => err OptionsError::Conflict(&flags::ONLY, &flags::NO_TIME));
+        test!(only_cols:  Columns <- ["--only=size", "--columns=user"];            Last => like Ok(Columns { filesize: false, user: true, .. }));
+        test!(only_cols_2: Columns <- ["--only=size", "--columns=user"];       Complain => err OptionsError::Conflict(&flags::COLUMNS, &flags::ONLY));
+
+        // Git formats
+        test!(git_short: Columns <- ["--git"];                       Both => like Ok(Columns { git: true, git_split: false, .. }));
+        test!(git_named: Columns <- ["--git=short"];                 Both => like Ok(Columns { git: true, git_split: false, .. }));
+        test!(git_long:  Columns <- ["--git=long"];                  Both => like Ok(Columns { git: true, git_split: true, .. }));
+        test!(git_order: Columns <- ["--git=long", "--columns=git"]; Both => like Ok(Columns { git: true, git_split: true, permissions: false, .. }));
+        test!(git_bad:   Columns <- ["--git=medium"];                Both => err OptionsError::BadArgument(&flags::GIT, OsString::from("medium")));
+
+        // Errors
+        test!(unknown:   Columns <- ["--columns=perms,colour"];      Both => err OptionsError::BadArgument(&flags::COLUMNS, OsString::from("colour")));
+        test!(blank:     Columns <- ["--columns", ""];               Both => err OptionsError::BadArgument(&flags::COLUMNS, OsString::from("")));
+
+        #[test]
+        fn in_order() {
+            assert_eq!(order(&["--columns=size,date,perms"]), Some(vec![ ColumnName::FileSize, ColumnName::Timestamps, ColumnName::Permissions ]));
+        }
+
+        #[test]
+        fn name_and_repeats() {
+            assert_eq!(order(&["--columns=user,name,user,inode"]), Some(vec![ ColumnName::User, ColumnName::Inode ]));
+        }
+    }
+
+
     mod views {
         use super::*;
 
@@ -547,17 +1203,77 @@ mod test {
         test!(across:        Mode <- ["--across"], None;  Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
         test!(gracross:      Mode <- ["-xG"], None;       Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
 
+        // Grid column bounds
+        test!(min_cols:      Mode <- ["--min-columns=2"], None;                     Both => like Ok(Mode::Grid(GridOptions { min_columns: Some(2), max_columns: None, .. })));
+        test!(max_cols:      Mode <- ["--max-columns=4"], None;                     Both => like Ok(Mode::Grid(GridOptions { min_columns: None, max_columns: Some(4), .. })));
+        test!(both_cols:     Mode <- ["--min-columns=3", "--max-columns=3"], None;  Both => like Ok(Mode::Grid(GridOptions { min_columns: Some(3), max_columns: Some(3), .. })));
+        test!(min_over_max:  Mode <- ["--min-columns=5", "--max-columns=2"], None;  Both => err OptionsError::Conflict(&flags::MIN_COLUMNS, &flags::MAX_COLUMNS));
+        test!(zero_cols:     Mode <- ["--max-columns=0"], None;                     Both => err OptionsError::BadArgument(&flags::MAX_COLUMNS, OsString::from("0")));
+        test!(long_cols:     Mode <- ["--long", "--max-columns=2"], None;           Complain => err OptionsError::Useless(&flags::MAX_COLUMNS, true, &flags::LONG));
+        test!(word_cols:     Mode <- ["--min-columns=lots"], None;                  Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
+
         // Lines views
         test!(lines:         Mode <- ["--oneline"], None;     Both => like Ok(Mode::Lines));
         test!(prima:         Mode <- ["-1"], None;            Both => like Ok(Mode::Lines));
 
+        // Zero views
+        test!(zero:          Mode <- ["--zero"], None;        Both => like Ok(Mode::Zero));
+        test!(zero_short:    Mode <- ["-0"], None;            Both => like Ok(Mode::Zero));
+        test!(zero_lines:    Mode <- ["-1", "--zero"], None;  Both => like Ok(Mode::Zero));
+        test!(zero_grid:     Mode <- ["--zero", "-G"], None;  Both => like Ok(Mode::Zero));
+        test!(zero_long:     Mode <- ["--long", "--zero"], None;  Last => like Ok(Mode::Zero));
+        test!(zero_long_2:   Mode <- ["--long", "--zero"], None;  Complain => err OptionsError::Conflict(&flags::ZERO, &flags::LONG));
+        test!(zero_csv:      Mode <- ["--csv", "--zero"], None;   Last => like Ok(Mode::Zero));
+        test!(zero_csv_2:    Mode <- ["--csv", "--zero"], None;   Complain => err OptionsError::Conflict(&flags::ZERO, &flags::CSV));
+
+        // Delimited views
+        test!(csv:           Mode <- ["--csv"], None;             Both => like Ok(Mode::Delimited(delimited::Options { delimiter: Delimiter::Comma, .. })));
+        test!(tsv:           Mode <- ["--tsv"], None;             Both => like Ok(Mode::Delimited(delimited::Options { delimiter: Delimiter::Tab, .. })));
+        test!(csv_tsv:       Mode <- ["--csv", "--tsv"], None;    Last => like Ok(Mode::Delimited(delimited::Options { delimiter: Delimiter::Tab, .. })));
+        test!(csv_tsv_2:     Mode <- ["--csv", "--tsv"], None;    Complain => err OptionsError::Duplicate(Flag::Long("csv"), Flag::Long("tsv")));
+        test!(csv_long:      Mode <- ["--long", "--csv"], None;   Both => like Ok(Mode::Delimited(_)));
+        test!(csv_grid:      Mode <- ["--csv", "--grid"], None;   Both => like Ok(Mode::Delimited(_)));
+        test!(csv_columns:   Mode <- ["--csv", "--inode", "--links"], None;  Both => like Ok(Mode::Delimited(delimited::Options { table: TableOptions { columns: Columns { inode: true, links: true, .. }, .. }, .. })));
+
+        // Stat views
+        test!(stat:          Mode <- ["--stat"], None;            Both => like Ok(Mode::Stat(stat::Options { table: TableOptions { columns: Columns { time_types: TimeTypes { modified: true, changed: true, accessed: true, created: true }, .. }, human_blocks: true, .. } })));
+        test!(stat_long:     Mode <- ["--long", "--stat"], None;  Last => like Ok(Mode::Stat(_)));
+        test!(stat_long_2:   Mode <- ["--long", "--stat"], None;  Complain => err OptionsError::Conflict(&flags::STAT, &flags::LONG));
+        test!(stat_zero:     Mode <- ["--stat", "--zero"], None;  Last => like Ok(Mode::Stat(_)));
+
+        fn stat_time_formats(inputs: &[&str]) -> Vec<TimeFormat> {
+            match parse_for_test(inputs, TEST_ARGS, Last, |mf| Mode::deduce(mf, &None::<OsString>)).remove(0) {
+                Ok(Mode::Stat(opts))  => opts.table.time_formats,
+                _                     => panic!("Not a stat view"),
+            }
+        }
+
+        #[test]
+        fn stat_precise_by_default() {
+            assert_eq!(stat_time_formats(&["--stat"]), vec![ TimeFormat::Precise ]);
+        }
+
+        #[test]
+        fn stat_time_style_kept() {
+            assert_eq!(stat_time_formats(&["--stat", "--time-style=iso"]), vec![ TimeFormat::ISOFormat ]);
+        }
+
         // Details views
         test!(long:          Mode <- ["--long"], None;    Both => like Ok(Mode::Details(_)));
         test!(ell:           Mode <- ["-l"], None;        Both => like Ok(Mode::Details(_)));
+        test!(long_repeat:   Mode <- ["--long", "--repeat-header=5"], None;  Both => like Ok(Mode::Details(details::Options { header: true, repeat_header: Some(details::RepeatHeader::Every(5)), .. })));
+        test!(long_summary:  Mode <- ["--long", "--summary"], None;  Both => like Ok(Mode::Details(details::Options { summary: true, .. })));
+        test!(long_no_sum:   Mode <- ["--long"], None;               Both => like Ok(Mode::Details(details::Options { summary: false, .. })));
+        test!(long_once:     Mode <- ["--long", "--header", "--no-headers-between-dirs"], None;  Both => like Ok(Mode::Details(details::Options { header: true, header_once: true, .. })));
+        test!(long_once_rep: Mode <- ["--long", "--repeat-header", "--no-headers-between-dirs"], None;  Both => like Ok(Mode::Details(details::Options { header: true, header_once: true, .. })));
+        test!(long_not_once: Mode <- ["--long", "--header"], None;  Both => like Ok(Mode::Details(details::Options { header_once: false, .. })));
+        test!(long_once_2:   Mode <- ["--long", "--no-headers-between-dirs"], None;  Last => like Ok(Mode::Details(details::Options { header: false, header_once: true, .. })));
+        test!(long_once_3:   Mode <- ["--long", "--no-headers-between-dirs"], None;  Complain => err OptionsError::Useless(&flags::NO_HEADERS_BETWEEN_DIRS, false, &flags::HEADER));
 
         // Grid-details views
         test!(lid:           Mode <- ["--long", "--grid"], None;  Both => like Ok(Mode::GridDetails(_)));
         test!(leg:           Mode <- ["-lG"], None;               Both => like Ok(Mode::GridDetails(_)));
+        test!(lid_summary:   Mode <- ["-lG", "--summary"], None;  Both => like Ok(Mode::GridDetails(grid_details::Options { details: details::Options { summary: true, .. }, .. })));
 
         // Options that do nothing with --long
         test!(long_across:   Mode <- ["--long", "--across"],   None;  Last => like Ok(Mode::Details(_)));
@@ -568,6 +1284,16 @@ mod test {
         test!(just_inode:    Mode <- ["--inode"],    None;  Last => like Ok(Mode::Grid(_)));
         test!(just_links:    Mode <- ["--links"],    None;  Last => like Ok(Mode::Grid(_)));
         test!(just_blocks:   Mode <- ["--blocks"],   None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_blkhuman: Mode <- ["--blocksize-human"], None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_total:    Mode <- ["--total-size"], None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_counts:   Mode <- ["--dir-counts"], None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_repos:    Mode <- ["--git-repos"], None;   Last => like Ok(Mode::Grid(_)));
+        test!(just_mounts:   Mode <- ["--mounts"],   None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_mime:     Mode <- ["--mime"],     None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_xattrs:   Mode <- ["--xattr-names"], None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_hlgroups: Mode <- ["--hard-link-groups"], None; Last => like Ok(Mode::Grid(_)));
+        test!(just_columns:  Mode <- ["--columns=size"], None; Last => like Ok(Mode::Grid(_)));
+        test!(just_deref:    Mode <- ["--dereference"], None; Last => like Ok(Mode::Grid(_)));
         test!(just_binary:   Mode <- ["--binary"],   None;  Last => like Ok(Mode::Grid(_)));
         test!(just_bytes:    Mode <- ["--bytes"],    None;  Last => like Ok(Mode::Grid(_)));
         test!(just_numeric:  Mode <- ["--numeric"],  None;  Last => like Ok(Mode::Grid(_)));
@@ -580,12 +1306,27 @@ mod test {
         test!(just_inode_2:  Mode <- ["--inode"],    None;  Complain => err OptionsError::Useless(&flags::INODE,   false, &flags::LONG));
         test!(just_links_2:  Mode <- ["--links"],    None;  Complain => err OptionsError::Useless(&flags::LINKS,   false, &flags::LONG));
         test!(just_blocks_2: Mode <- ["--blocks"],   None;  Complain => err OptionsError::Useless(&flags::BLOCKS,  false, &flags::LONG));
+        test!(just_blksize_2: Mode <- ["--block-size=1K"], None;  Complain => err OptionsError::Useless(&flags::BLOCK_SIZE, false, &flags::LONG));
+        test!(just_blkhuman_2: Mode <- ["--blocksize-human"], None;  Complain => err OptionsError::Useless(&flags::BLOCKSIZE_HUMAN, false, &flags::LONG));
+        test!(just_total_2:  Mode <- ["--total-size"], None;  Complain => err OptionsError::Useless(&flags::TOTAL_SIZE, false, &flags::LONG));
+        test!(just_counts_2: Mode <- ["--dir-counts"], None;  Complain => err OptionsError::Useless(&flags::DIR_COUNTS, false, &flags::LONG));
+        test!(just_repos_2:  Mode <- ["--git-repos"], None;   Complain => err OptionsError::Useless(&flags::GIT_REPOS, false, &flags::LONG));
+        test!(just_mounts_2: Mode <- ["--mounts"],   None;  Complain => err OptionsError::Useless(&flags::MOUNTS,  false, &flags::LONG));
+        test!(just_mime_2:   Mode <- ["--mime"],     None;  Complain => err OptionsError::Useless(&flags::MIME,    false, &flags::LONG));
+        test!(just_xattrs_2: Mode <- ["--xattr-names"], None;  Complain => err OptionsError::Useless(&flags::XATTR_NAMES, false, &flags::LONG));
+        test!(just_hlgroups_2: Mode <- ["--hard-link-groups"], None; Complain => err OptionsError::Useless(&flags::HARD_LINK_GROUPS, false, &flags::LONG));
+        test!(just_columns_2: Mode <- ["--columns=size"], None; Complain => err OptionsError::Useless(&flags::COLUMNS, false, &flags::LONG));
+        test!(just_deref_2:  Mode <- ["--dereference"], None; Complain => err OptionsError::Useless(&flags::DEREFERENCE, false, &flags::LONG));
         test!(just_binary_2: Mode <- ["--binary"],   None;  Complain => err OptionsError::Useless(&flags::BINARY,  false, &flags::LONG));
         test!(just_bytes_2:  Mode <- ["--bytes"],    None;  Complain => err OptionsError::Useless(&flags::BYTES,   false, &flags::LONG));
         test!(just_numeric2: Mode <- ["--numeric"],  None;  Complain => err OptionsError::Useless(&flags::NUMERIC, false, &flags::LONG));
 
         #[cfg(feature = "git")]
         test!(just_git_2:    Mode <- ["--git"],    None;  Complain => err OptionsError::Useless(&flags::GIT,    false, &flags::LONG));
+        test!(just_repeat_2: Mode <- ["--repeat-header=9"], None;  Complain => err OptionsError::Useless(&flags::REPEAT_HEADER, false, &flags::LONG));
+        test!(just_summary_2: Mode <- ["--summary"], None;  Complain => err OptionsError::Useless(&flags::SUMMARY, false, &flags::LONG));
+        test!(just_once_2:   Mode <- ["--no-headers-between-dirs"], None;  Complain => err OptionsError::Useless(&flags::NO_HEADERS_BETWEEN_DIRS, false, &flags::LONG));
+        test!(just_git_3:    Mode <- ["--git=long"], None;  Complain => err OptionsError::Useless(&flags::GIT,  false, &flags::LONG));
 
         // Contradictions and combinations
         test!(lgo:           Mode <- ["--long", "--grid", "--oneline"], None;  Both => like Ok(Mode::Lines));
@@ -595,5 +1336,11 @@ mod test {
         test!(ot:            Mode <- ["--oneline", "--tree"],           None;  Both => like Ok(Mode::Details(_)));
         test!(og:            Mode <- ["--oneline", "--grid"],           None;  Both => like Ok(Mode::Grid(_)));
         test!(tg:            Mode <- ["--tree", "--grid"],              None;  Both => like Ok(Mode::Grid(_)));
+
+        // Tree styles
+        test!(tree_unicode:  Mode <- ["--tree"],                         None;  Both => like Ok(Mode::Details(details::Options { tree_style: TreeStyle::Unicode, .. })));
+        test!(tree_ascii:    Mode <- ["--tree", "--tree-style=ascii"],   None;  Both => like Ok(Mode::Details(details::Options { tree_style: TreeStyle::Ascii, .. })));
+        test!(long_ascii:    Mode <- ["-lT", "--tree-style", "ascii"],   None;  Both => like Ok(Mode::Details(details::Options { tree_style: TreeStyle::Ascii, .. })));
+        test!(tree_bad:      Mode <- ["--tree", "--tree-style=boxes"],   None;  Both => err OptionsError::BadArgument(&flags::TREE_STYLE, OsString::from("boxes")));
     }
 }
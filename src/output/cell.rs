@@ -161,8 +161,11 @@ impl TextCellContents {
 
     /// Calculates the width that a cell with these contents would take up, by
     /// counting the number of characters in each unformatted ANSI string.
+    /// Strings that are operating system commands, such as hyperlinks, take
+    /// up no space on the screen, so they aren’t counted.
     pub fn width(&self) -> DisplayWidth {
         self.0.iter()
+            .filter(|anstr| ! anstr.starts_with("\x1B]"))
             .map(|anstr| DisplayWidth::from(&**anstr))
             .sum()
     }
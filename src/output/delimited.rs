@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::fs::File;
+use crate::fs::feature::git::GitCache;
+use crate::fs::feature::xattr::FileAttributes;
+use crate::fs::filter::FileFilter;
+use crate::output::table::{Options as TableOptions, Row, Table};
+use crate::theme::Theme;
+
+
+/// The delimited view writes each file as a row of comma- or tab-separated
+/// values, for importing into spreadsheets or other programs. It has the
+/// same columns as the details view would, followed by the file’s name, and
+/// its path when recursing, as otherwise the rows would be ambiguous.
+///
+/// The values are written as plain text, without any colours or icons,
+/// and any that contain the delimiter, a quote, or a line break get quoted
+/// as described in RFC 4180.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Options {
+    pub delimiter: Delimiter,
+    pub table: TableOptions,
+}
+
+/// The character that separates the values in each row.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Delimiter {
+
+    /// Comma-separated values, from `--csv`.
+    Comma,
+
+    /// Tab-separated values, from `--tsv`.
+    Tab,
+}
+
+impl Delimiter {
+    fn as_char(self) -> char {
+        match self {
+            Self::Comma  => ',',
+            Self::Tab    => '\t',
+        }
+    }
+}
+
+
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub theme: &'a Theme,
+    pub opts: &'a Options,
+    pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
+
+    /// Whether to add a column with each file’s full path, which is needed
+    /// when recursing for the rows to be usable.
+    pub full_paths: bool,
+
+    /// The total size of the whole listing, for when file sizes are shown
+    /// as a percentage of it.
+    pub size_total: Option<u64>,
+}
+
+impl<'a> Render<'a> {
+
+    /// Writes the row of column names. This only gets written once, before
+    /// any files, rather than once per directory.
+    pub fn render_header<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let table = Table::new(&self.opts.table, self.git, self.theme);
+
+        let mut fields = plain_cells(&table.header_row());
+        fields.push("Name".into());
+        if self.full_paths {
+            fields.push("Path".into());
+        }
+
+        self.write_row(w, &fields)
+    }
+
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files);
+
+        let mut table = Table::new(&self.opts.table, self.git, self.theme);
+        table.set_dot_filter(self.filter.dot_filter);
+
+        if let Some(total) = self.opts.table.size_total(&self.files, self.size_total) {
+            table.set_size_total(total);
+        }
+
+        table.set_link_groups(&self.files);
+
+        for file in &self.files {
+            let xattrs = file.path.attributes().map_or(false, |attrs| ! attrs.is_empty());
+
+            let mut fields = plain_cells(&table.row_for_file(file, xattrs));
+            fields.push(file.name.clone());
+            if self.full_paths {
+                fields.push(file.path.display().to_string());
+            }
+
+            self.write_row(w, &fields)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_row<W: Write>(&self, w: &mut W, fields: &[String]) -> io::Result<()> {
+        let delimiter = self.opts.delimiter.as_char();
+
+        for (index, field) in fields.iter().enumerate() {
+            if index > 0 {
+                write!(w, "{}", delimiter)?;
+            }
+
+            write!(w, "{}", quote(field, delimiter))?;
+        }
+
+        writeln!(w)
+    }
+}
+
+
+/// The text of each of the row’s cells, with their styles thrown away.
+fn plain_cells(row: &Row) -> Vec<String> {
+    row.cells().iter()
+       .map(|cell| cell.contents.iter().map(|s| &**s).collect())
+       .collect()
+}
+
+/// Quotes a value if it contains the delimiter, a double quote, or a line
+/// break, by surrounding it with double quotes and doubling any double
+/// quotes inside it. Other values are left as they are.
+fn quote(field: &str, delimiter: char) -> Cow<'_, str> {
+    if field.contains(&[ delimiter, '"', '\n', '\r' ][..]) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    }
+    else {
+        Cow::Borrowed(field)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::quote;
+
+    #[test]
+    fn plain() {
+        assert_eq!(quote("file.txt", ','), "file.txt");
+    }
+
+    #[test]
+    fn space() {
+        assert_eq!(quote("my file.txt", ','), "my file.txt");
+    }
+
+    #[test]
+    fn comma() {
+        assert_eq!(quote("one,two.txt", ','), "\"one,two.txt\"");
+    }
+
+    #[test]
+    fn comma_in_tsv() {
+        assert_eq!(quote("one,two.txt", '\t'), "one,two.txt");
+    }
+
+    #[test]
+    fn tab_in_tsv() {
+        assert_eq!(quote("one\ttwo.txt", '\t'), "\"one\ttwo.txt\"");
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(quote("say \"hi\".txt", ','), "\"say \"\"hi\"\".txt\"");
+    }
+
+    #[test]
+    fn comma_and_quotes() {
+        assert_eq!(quote("\"a\",b", ','), "\"\"\"a\"\",b\"");
+    }
+
+    #[test]
+    fn newline() {
+        assert_eq!(quote("line\nbreak", ','), "\"line\nbreak\"");
+    }
+}
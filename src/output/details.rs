@@ -66,17 +66,22 @@ use std::path::PathBuf;
 use std::vec::IntoIter as VecIntoIter;
 
 use ansi_term::Style;
+use number_prefix::Prefix;
 use scoped_threadpool::Pool;
 
 use crate::fs::{Dir, File};
+use crate::fs::fields as f;
 use crate::fs::dir_action::RecurseOptions;
 use crate::fs::feature::git::GitCache;
 use crate::fs::feature::xattr::{Attribute, FileAttributes};
 use crate::fs::filter::FileFilter;
-use crate::output::cell::TextCell;
+use crate::output::cell::{TextCell, TextCellContents};
+use crate::output::escape;
+use crate::output::file_name::Classify;
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Table, Options as TableOptions, Row as TableRow};
-use crate::output::tree::{TreeTrunk, TreeParams, TreeDepth};
+use crate::output::render::SizeColours;
+use crate::output::table::{Table, Options as TableOptions, Row as TableRow, SizeFormat};
+use crate::output::tree::{TreeTrunk, TreeParams, TreeDepth, TreeStyle, folded_extensions};
 use crate::theme::Theme;
 
 
@@ -91,7 +96,8 @@ use crate::theme::Theme;
 ///
 /// Almost all the heavy lifting is done in a Table object, which handles the
 /// columns for each row.
-#[derive(PartialEq, Eq, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(PartialEq, Eq, Debug, Default)]
 pub struct Options {
 
     /// Options specific to drawing a table.
@@ -103,8 +109,120 @@ pub struct Options {
     /// Whether to show a header line or not.
     pub header: bool,
 
+    /// Whether to show the header line again partway through the listing,
+    /// and if so, how often.
+    pub repeat_header: Option<RepeatHeader>,
+
+    /// Whether to only show the header line above the first listing, rather
+    /// than above the listing of every directory.
+    pub header_once: bool,
+
+    /// Whether to show a line after the listing with the number of files
+    /// and directories in it and their total size.
+    pub summary: bool,
+
     /// Whether to show each file’s extended attributes.
     pub xattr: bool,
+
+    /// The characters to draw the tree with, if there is one.
+    pub tree_style: TreeStyle,
+}
+
+/// How often the header line gets shown again in a long listing, so there’s
+/// still one in view after the first has scrolled off the screen.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum RepeatHeader {
+
+    /// Show it again after this many rows.
+    Every(usize),
+
+    /// Show it again after a screenful of rows, working out the height of
+    /// the terminal at runtime. Nothing gets repeated if the output isn’t
+    /// going to a terminal.
+    TerminalHeight,
+}
+
+impl RepeatHeader {
+
+    /// The number of rows to show between each header line, if any.
+    pub fn rows(self) -> Option<usize> {
+        match self {
+            Self::Every(rows)     => Some(rows),
+            Self::TerminalHeight  => terminal_size::terminal_size().map(|(_, h)| usize::from(h.0).saturating_sub(1).max(1)),
+        }
+    }
+}
+
+
+/// The number of files and directories that have been listed, and the total
+/// size of the files, which can be shown as a line at the end of a listing.
+/// Directories’ own sizes aren’t counted.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Summary {
+    pub files: usize,
+    pub dirs: usize,
+    pub bytes: u64,
+}
+
+impl Summary {
+
+    /// Counts the given file, adding its size to the total if it has one.
+    pub fn add(&mut self, file: &File<'_>) {
+        if file.is_directory() {
+            self.dirs += 1;
+        }
+        else {
+            self.files += 1;
+
+            if let f::Size::Some(bytes) = file.size() {
+                self.bytes += bytes;
+            }
+        }
+    }
+
+    /// Renders the summary line, such as “42 files, 3 directories, 1.2G
+    /// total”, with the size in the given format.
+    pub fn render(&self, theme: &Theme, size_format: SizeFormat) -> TextCell {
+        let numeric = locale::Numeric::load_user_locale()
+                             .unwrap_or_else(|_| locale::Numeric::english());
+
+        let files = match self.files {
+            1  => String::from("1 file"),
+            n  => numeric.format_int(n) + " files",
+        };
+
+        let dirs = match self.dirs {
+            1  => String::from("1 directory"),
+            n  => numeric.format_int(n) + " directories",
+        };
+
+        let style = theme.ui.header;
+        let mut cell = TextCell::paint(style, files + ", " + &dirs + ", ");
+        cell.append(f::Size::Some(self.bytes).render(&SummaryColours(style), size_format, &numeric));
+        cell.append(TextCell::paint_str(style, " total"));
+        cell
+    }
+}
+
+impl std::ops::AddAssign for Summary {
+    fn add_assign(&mut self, other: Self) {
+        self.files += other.files;
+        self.dirs += other.dirs;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Paints every part of the total size in the same style, so that it
+/// matches the rest of the summary line.
+struct SummaryColours(Style);
+
+impl SizeColours for SummaryColours {
+    fn size(&self, _prefix: Option<Prefix>) -> Style { self.0 }
+    fn unit(&self, _prefix: Option<Prefix>) -> Style { self.0 }
+    fn no_size(&self) -> Style { self.0 }
+    fn major(&self) -> Style { self.0 }
+    fn comma(&self) -> Style { self.0 }
+    fn minor(&self) -> Style { self.0 }
 }
 
 
@@ -127,6 +245,14 @@ pub struct Render<'a> {
     pub git_ignoring: bool,
 
     pub git: Option<&'a GitCache>,
+
+    /// The total size of every file in the listing, which only gets worked
+    /// out when sizes are being shown as a percentage of it.
+    pub size_total: Option<u64>,
+
+    /// Whether this listing carries on from an earlier one, as happens when
+    /// each directory gets listed in turn, so it might not need a header.
+    pub continued: bool,
 }
 
 
@@ -146,13 +272,16 @@ impl<'a> AsRef<File<'a>> for Egg<'a> {
 
 
 impl<'a> Render<'a> {
-    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+    /// Writes the listing, returning how many files and directories were
+    /// in it, and their total size.
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<Summary> {
         let n_cpus = match num_cpus::get() as u32 {
             0 => 1,
             n => n,
         };
         let mut pool = Pool::new(n_cpus);
         let mut rows = Vec::new();
+        let mut summary = Summary::default();
 
         if let Some(ref table) = self.opts.table {
             match (self.git, self.dir) {
@@ -162,47 +291,125 @@ impl<'a> Render<'a> {
             }
 
             let mut table = Table::new(table, self.git, self.theme);
+            table.set_dot_filter(self.filter.dot_filter);
 
-            if self.opts.header {
+            if self.shows_header() {
                 let header = table.header_row();
-                table.add_widths(&header);
                 rows.push(self.render_header(header));
             }
 
             // This is weird, but I can’t find a way around it:
             // https://internals.rust-lang.org/t/should-option-mut-t-implement-copy/3715/6
             let mut table = Some(table);
-            self.add_files_to_table(&mut pool, &mut table, &mut rows, &self.files, TreeDepth::root());
+            self.add_files_to_table(&mut pool, &mut table, &mut rows, &mut summary, self.dir, &self.files, TreeDepth::root());
+
+            // The widths only get added once every row is known, as rows
+            // for directories that get pruned are made and then thrown away.
+            let mut table = table.unwrap();
+            for cells in rows.iter().filter_map(|row| row.cells.as_ref()) {
+                table.add_widths(cells);
+            }
+
+            // The header line is rendered up front so it can be written out
+            // again between rows, lined up with the columns around it.
+            let repeat = if self.shows_header() { self.opts.repeat_header.and_then(RepeatHeader::rows) }
+                                           else { None };
+            let header = repeat.map(|_| {
+                let mut cell = table.render(table.header_row());
+                cell.append(TextCell::paint_str(self.theme.ui.header, "Name"));
+                cell
+            });
+
+            for (index, row) in self.iterate_with_table(table, rows).enumerate() {
+                if let (Some(every), Some(header)) = (repeat, &header) {
+                    if index > 1 && (index - 1) % every == 0 {
+                        writeln!(w, "{}", header.strings())?;
+                    }
+                }
 
-            for row in self.iterate_with_table(table.unwrap(), rows) {
                 writeln!(w, "{}", row.strings())?
             }
         }
         else {
-            self.add_files_to_table(&mut pool, &mut None, &mut rows, &self.files, TreeDepth::root());
+            self.add_files_to_table(&mut pool, &mut None, &mut rows, &mut summary, self.dir, &self.files, TreeDepth::root());
 
             for row in self.iterate(rows) {
                 writeln!(w, "{}", row.strings())?
             }
         }
 
-        Ok(())
+        Ok(summary)
+    }
+
+    /// Whether this listing gets a header line, which it doesn’t if it
+    /// carries on from an earlier listing that already had one, and the
+    /// header’s only meant to be shown once.
+    pub fn shows_header(&self) -> bool {
+        self.opts.header && ! (self.opts.header_once && self.continued)
     }
 
     /// Adds files to the table, possibly recursively. This is easily
-    /// parallelisable, and uses a pool of threads.
-    fn add_files_to_table<'dir>(&self, pool: &mut Pool, table: &mut Option<Table<'a>>, rows: &mut Vec<Row>, src: &[File<'dir>], depth: TreeDepth) {
+    /// parallelisable, and uses a pool of threads. The parent directory is
+    /// the one the files are in, if they aren’t the files given as arguments.
+    /// Every file that gets a row is counted in the summary, apart from the
+    /// directories at the root of a tree, whose contents are listed instead,
+    /// and so is every file that gets folded or left out of the listing.
+    fn add_files_to_table<'dir>(&self, pool: &mut Pool, table: &mut Option<Table<'a>>, rows: &mut Vec<Row>, summary: &mut Summary, parent: Option<&Dir>, src: &[File<'dir>], depth: TreeDepth) {
         use std::sync::{Arc, Mutex};
         use log::*;
         use crate::fs::feature::xattr;
 
+        if let (Some(t), Some(options)) = (table.as_mut(), self.opts.table.as_ref()) {
+            if let Some(total) = options.size_total(src, self.size_total) {
+                t.set_size_total(total);
+            }
+
+            t.set_age_ranges(src);
+            t.set_link_groups(src);
+        }
+
+        // Files that share an extension with enough others get folded into
+        // summary rows, rather than each getting a row of their own.
+        let folded = match self.recurse {
+            Some(RecurseOptions { tree: true, collapse_exts: Some(threshold), .. }) => {
+                let exts = src.iter().filter(|f| ! f.is_directory()).filter_map(|f| f.ext.as_deref());
+                folded_extensions(exts, threshold)
+            }
+            _ => Vec::new(),
+        };
+
+        let (mut src, folded_files) = src.iter()
+                                         .partition::<Vec<_>, _>(|f| f.is_directory() || ! folded.iter().any(|(ext, _)| f.ext.as_ref() == Some(ext)));
+
+        // Only the first few entries of each directory in a tree get listed
+        // when there’s a limit, with another summary row for the rest. The
+        // directories being listed on their own have already been cut down.
+        let more = match (self.recurse, parent) {
+            (Some(RecurseOptions { tree: true, .. }), Some(_)) => {
+                self.filter.sort_files(&mut src);
+                self.filter.limit_entries(&mut src)
+            }
+            _ => Vec::new(),
+        };
+
+        for file in folded_files.iter().chain(&more) {
+            summary.add(file);
+        }
+
+        let more = more.len();
+
+        let parent_device = match (self.recurse, parent) {
+            (Some(r), Some(dir)) if r.one_file_system  => dir.device_id(),
+            _                                          => None,
+        };
+
         let mut file_eggs = (0..src.len()).map(|_| MaybeUninit::uninit()).collect::<Vec<_>>();
 
         pool.scoped(|scoped| {
             let file_eggs = Arc::new(Mutex::new(&mut file_eggs));
             let table = table.as_ref();
 
-            for (idx, file) in src.iter().enumerate() {
+            for (idx, file) in src.into_iter().enumerate() {
                 let file_eggs = Arc::clone(&file_eggs);
 
                 scoped.execute(move || {
@@ -231,7 +438,7 @@ impl<'a> Render<'a> {
                     // printed unless the user passes --extended to signify
                     // that they want to see them.
 
-                    if xattr::ENABLED {
+                    if xattr::ENABLED && file.archived.is_none() {
                         match file.path.attributes() {
                             Ok(xs) => {
                                 xattrs.extend(xs);
@@ -256,10 +463,16 @@ impl<'a> Render<'a> {
 
                     let mut dir = None;
                     if let Some(r) = self.recurse {
-                        if file.is_directory() && r.tree && ! r.is_too_deep(depth.0) {
+                        if r.is_recursable(file) && r.tree && ! r.is_too_deep(depth.0) && ! r.is_other_file_system(parent_device, file) {
+                            // A directory that contains itself, through a
+                            // symlink or a bind mount, gets listed without
+                            // its contents inside itself.
                             match file.to_dir() {
                                 Ok(d) => {
-                                    dir = Some(d);
+                                    dir = match parent {
+                                        Some(p)  => p.enter(d),
+                                        None     => Some(d),
+                                    };
                                 }
                                 Err(e) => {
                                     errors.push((e, None));
@@ -278,55 +491,111 @@ impl<'a> Render<'a> {
         let mut file_eggs = unsafe { std::mem::transmute::<_, Vec<Egg<'_>>>(file_eggs) };
         self.filter.sort_files(&mut file_eggs);
 
-        for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
-            let mut files = Vec::new();
-            let mut errors = egg.errors;
+        // Flipping the siblings only changes the order they get emitted in,
+        // so the tree glyphs still get worked out from whichever row ends
+        // up last, and any summary rows stay at the end. Directories that
+        // are grouped before or after the files stay there.
+        if self.recurse.map_or(false, |r| r.tree && r.reverse_tree) {
+            file_eggs.reverse();
+            self.filter.group_directories(&mut file_eggs);
+        }
 
-            if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
-                t.add_widths(row);
-            }
+        // Each directory’s contents get turned into rows before the
+        // directory’s own row gets added, so that directories with nothing
+        // left in them can be pruned before it’s known which row is last.
+        let prune = parent.is_some() && self.recurse.map_or(false, |r| r.prune);
+        let mut branches = Vec::with_capacity(file_eggs.len());
 
-            let file_name = self.file_style.for_file(egg.file, self.theme)
-                                .with_link_paths()
-                                .paint()
-                                .promote();
+        for mut egg in file_eggs {
+            let mut files = Vec::new();
 
-            let row = Row {
-                tree:   tree_params,
-                cells:  egg.table_row,
-                name:   file_name,
-            };
+            let dir = egg.dir.as_ref();
 
-            rows.push(row);
+            // A chain of directories with only a directory in each gets
+            // listed as one row, with the contents of the last one under it.
+            let collapsed = dir.and_then(|d| self.collapse_single_children(d, depth));
+            let dir = collapsed.as_ref().map(|c| &c.0).or(dir);
 
-            if let Some(ref dir) = egg.dir {
+            let mut child_rows = None;
+            if let Some(dir) = dir {
                 for file_to_add in dir.files(self.filter.dot_filter, self.git, self.git_ignoring) {
                     match file_to_add {
                         Ok(f) => {
                             files.push(f);
                         }
                         Err((path, e)) => {
-                            errors.push((e, Some(path)));
+                            egg.errors.push((e, Some(path)));
                         }
                     }
                 }
 
                 self.filter.filter_child_files(&mut files);
 
-                if ! files.is_empty() {
-                    for xattr in egg.xattrs {
-                        rows.push(self.render_xattr(&xattr, TreeParams::new(depth.deeper(), false)));
-                    }
+                if let Some(r) = self.recurse {
+                    r.filter_child_files(&mut files);
+                }
 
-                    for (error, path) in errors {
-                        rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), false), path));
-                    }
+                let mut dir_rows = Vec::new();
+                if ! files.is_empty() {
+                    self.add_files_to_table(pool, table, &mut dir_rows, summary, Some(dir), &files, depth.deeper());
+                }
 
-                    self.add_files_to_table(pool, table, rows, &files, depth.deeper());
+                if dir_rows.is_empty() && egg.errors.is_empty() && prune {
                     continue;
                 }
+
+                if ! dir_rows.is_empty() {
+                    child_rows = Some(dir_rows);
+                }
             }
 
+            let collapsed = collapsed.map(|(_, label, count)| (label, count));
+            branches.push((egg, child_rows, collapsed));
+        }
+
+        for (tree_params, (egg, child_rows, collapsed)) in depth.iterate_over(branches.into_iter()) {
+            let mut file_name = self.file_style.for_file(egg.file, self.theme)
+                                    .with_link_paths()
+                                    .with_git_status(self.git)
+                                    .paint()
+                                    .promote();
+
+            if let Some((label, count)) = collapsed {
+                file_name.append(label);
+                summary.dirs += count;
+            }
+
+            // The summary rows come after every file, so none of the files
+            // can be the last row when there are any.
+            let tree_params = if folded.is_empty() && more == 0 { tree_params }
+                                                           else { TreeParams::new(depth, false) };
+
+            if parent.is_some() || egg.dir.is_none() {
+                summary.add(egg.file);
+            }
+
+            let row = Row {
+                tree:   tree_params,
+                cells:  egg.table_row,
+                name:   file_name,
+            };
+
+            rows.push(row);
+
+            if let Some(child_rows) = child_rows {
+                for xattr in egg.xattrs {
+                    rows.push(self.render_xattr(&xattr, TreeParams::new(depth.deeper(), false)));
+                }
+
+                for (error, path) in egg.errors {
+                    rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), false), path));
+                }
+
+                rows.extend(child_rows);
+                continue;
+            }
+
+            let errors = egg.errors;
             let count = egg.xattrs.len();
             for (index, xattr) in egg.xattrs.into_iter().enumerate() {
                 let params = TreeParams::new(depth.deeper(), errors.is_empty() && index == count - 1);
@@ -341,6 +610,68 @@ impl<'a> Render<'a> {
                 rows.push(r);
             }
         }
+
+        let count = folded.len();
+        for (index, (ext, files)) in folded.into_iter().enumerate() {
+            let params = TreeParams::new(depth, index == count - 1 && more == 0);
+            rows.push(self.render_folded(&ext, files, params));
+        }
+
+        if more > 0 {
+            rows.push(self.render_more(more, TreeParams::new(depth, true)));
+        }
+    }
+
+    /// Follows the chain of directories under the given one for as long as
+    /// each has only one entry to list, which is a directory that would be
+    /// descended into, when single children are being collapsed. Returns
+    /// the last directory in the chain, along with the rest of the label
+    /// for the row and the number of directories that got folded into it.
+    fn collapse_single_children(&self, dir: &Dir, depth: TreeDepth) -> Option<(Dir, TextCell, usize)> {
+        let r = self.recurse.filter(|r| r.tree && r.collapse_single_child)?;
+        let mut chain: Option<Dir> = None;
+        let mut label = TextCell::default();
+        let mut count = 0;
+
+        // Reading a directory in the chain going wrong ends it there, so the
+        // error gets shown under that directory like it would be otherwise.
+        while let Ok(mut files) = chain.as_ref().unwrap_or(dir).files(self.filter.dot_filter, self.git, self.git_ignoring).collect::<Result<Vec<_>, _>>() {
+            let current = chain.as_ref().unwrap_or(dir);
+            self.filter.filter_child_files(&mut files);
+            r.filter_child_files(&mut files);
+
+            let parent_device = if r.one_file_system { current.device_id() } else { None };
+            let next = match files.as_slice() {
+                [ child ] if r.is_recursable(child) && ! r.is_too_deep(depth.0 + count + 1) && ! r.is_other_file_system(parent_device, child) => {
+                    match child.to_dir().map(|next| current.enter(next)) {
+                        Ok(Some(next)) => next,
+                        _ => break,
+                    }
+                }
+                _ => break,
+            };
+
+            // The directory indicator already separates the names when
+            // files are being classified.
+            let mut bits = Vec::new();
+            if self.file_style.classify != Classify::AddFileIndicators {
+                bits.push(self.theme.ui.punctuation.paint("/"));
+            }
+
+            escape(files[0].name.clone(), &mut bits, self.theme.ui.filekinds.directory, self.theme.ui.control_char);
+
+            if self.file_style.classify == Classify::AddFileIndicators {
+                bits.push(self.theme.ui.punctuation.paint("/"));
+            }
+
+            let contents = TextCellContents::from(bits);
+            label.append(TextCell { width: contents.width(), contents });
+
+            chain = Some(next);
+            count += 1;
+        }
+
+        chain.map(|dir| (dir, label, count))
     }
 
     pub fn render_header(&self, header: TableRow) -> Row {
@@ -366,6 +697,16 @@ impl<'a> Render<'a> {
         Row { cells: None, name, tree }
     }
 
+    fn render_folded(&self, ext: &str, count: usize, tree: TreeParams) -> Row {
+        let name = TextCell::paint(self.theme.ui.punctuation, format!("{} × .{}", count, ext));
+        Row { cells: None, name, tree }
+    }
+
+    fn render_more(&self, count: usize, tree: TreeParams) -> Row {
+        let name = TextCell::paint(self.theme.ui.punctuation, format!("… and {} more", count));
+        Row { cells: None, name, tree }
+    }
+
     fn render_xattr(&self, xattr: &Attribute, tree: TreeParams) -> Row {
         let name = TextCell::paint(self.theme.ui.perms.attribute, format!("{} (len {})", xattr.name, xattr.size));
         Row { cells: None, name, tree }
@@ -382,6 +723,7 @@ impl<'a> Render<'a> {
             table,
             inner: rows.into_iter(),
             tree_style: self.theme.ui.punctuation,
+            tree_glyphs: self.opts.tree_style,
         }
     }
 
@@ -390,6 +732,7 @@ impl<'a> Render<'a> {
             tree_trunk: TreeTrunk::default(),
             inner: rows.into_iter(),
             tree_style: self.theme.ui.punctuation,
+            tree_glyphs: self.opts.tree_style,
         }
     }
 }
@@ -420,6 +763,7 @@ pub struct TableIter<'a> {
 
     total_width: usize,
     tree_style:  Style,
+    tree_glyphs: TreeStyle,
     tree_trunk:  TreeTrunk,
 }
 
@@ -439,7 +783,7 @@ impl<'a> Iterator for TableIter<'a> {
                 };
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+                cell.push(self.tree_style.paint(tree_part.ascii_art(self.tree_glyphs)), 4);
             }
 
             // If any tree characters have been printed, then add an extra
@@ -458,6 +802,7 @@ impl<'a> Iterator for TableIter<'a> {
 pub struct Iter {
     tree_trunk: TreeTrunk,
     tree_style: Style,
+    tree_glyphs: TreeStyle,
     inner: VecIntoIter<Row>,
 }
 
@@ -469,7 +814,7 @@ impl Iterator for Iter {
             let mut cell = TextCell::default();
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+                cell.push(self.tree_style.paint(tree_part.ascii_art(self.tree_glyphs)), 4);
             }
 
             // If any tree characters have been printed, then add an extra
@@ -483,3 +828,439 @@ impl Iterator for Iter {
         })
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use crate::fs::filter::ExtensionFilter;
+    use crate::fs::test::temp_dir;
+    use crate::output::table::{Columns, SizeFormat, TimeTypes};
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    /// Creates a directory containing the given files, along with any
+    /// directories they’re in.
+    fn make_tree(name: &str, paths: &[&str]) -> PathBuf {
+        let root = temp_dir(&[ "tree-", name ].concat());
+
+        for path in paths {
+            let path = root.join(path);
+            if path.to_string_lossy().ends_with('/') {
+                fs::create_dir_all(path).unwrap();
+            }
+            else {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, "").unwrap();
+            }
+        }
+
+        root
+    }
+
+    /// A theme without any colours.
+    fn theme() -> Theme {
+        ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false)
+    }
+
+    /// Renders the given files with the given options, and returns the
+    /// lines of output.
+    fn render_lines(files: Vec<File<'_>>, opts: &Options, recurse: Option<RecurseOptions>, filter: &FileFilter) -> Vec<String> {
+        render_with_summary(files, opts, recurse, filter).0
+    }
+
+    /// Renders the given files with the given options, and returns the
+    /// lines of output along with the summary of what got listed.
+    fn render_with_summary(files: Vec<File<'_>>, opts: &Options, recurse: Option<RecurseOptions>, filter: &FileFilter) -> (Vec<String>, Summary) {
+        let theme = theme();
+        let file_style = FileStyle::default();
+
+        let render = Render { dir: None, files, theme: &theme, file_style: &file_style, opts, recurse, filter, git_ignoring: false, git: None, size_total: None, continued: false };
+        let mut output = Vec::new();
+        let summary = render.render(&mut output).unwrap();
+
+        (String::from_utf8(output).unwrap().lines().map(String::from).collect(), summary)
+    }
+
+    /// The options for listing a directory as a tree, which each test
+    /// changes the fields it’s checking of.
+    fn tree() -> RecurseOptions {
+        RecurseOptions { tree: true, .. RecurseOptions::default() }
+    }
+
+    /// Renders the given directory as a tree, and returns its lines without
+    /// the root along with the summary of what got listed.
+    fn render_tree(root: &Path, recurse: RecurseOptions, filter: &FileFilter) -> (Vec<String>, Summary) {
+        let files = vec![ File::from_args(root.to_path_buf(), None, None).unwrap() ];
+        let (lines, summary) = render_with_summary(files, &Options::default(), Some(recurse), filter);
+        (lines.into_iter().skip(1).collect(), summary)
+    }
+
+    /// Renders the given directory as a tree, only listing the files with
+    /// the given extension, and returns its lines without the root.
+    fn tree_lines(root: &Path, ext: &str, prune: bool) -> Vec<String> {
+        let filter = FileFilter { extensions: ExtensionFilter::from_iter(vec![ ext ]), .. FileFilter::default() };
+        render_tree(root, RecurseOptions { prune, .. tree() }, &filter).0
+    }
+
+    /// Renders the given directory as a tree, listing at most the given
+    /// number of entries from each directory, and returns its lines
+    /// without the root.
+    fn capped_tree_lines(root: &Path, max_entries: usize, reverse: bool) -> Vec<String> {
+        let filter = FileFilter { max_entries: Some(max_entries), reverse, .. FileFilter::default() };
+        render_tree(root, tree(), &filter).0
+    }
+
+    /// Renders the given directory as a tree with the siblings at each
+    /// level listed the other way round, and returns its lines without
+    /// the root.
+    fn reversed_tree_lines(root: &Path) -> Vec<String> {
+        render_tree(root, RecurseOptions { reverse_tree: true, .. tree() }, &FileFilter::default()).0
+    }
+
+    /// Renders the given directory as a tree with the directories at each
+    /// level grouped before or after the files, with or without the
+    /// siblings listed the other way round, and returns its lines without
+    /// the root.
+    fn grouped_tree_lines(root: &Path, dirs_first: bool, reverse_tree: bool) -> Vec<String> {
+        let filter = FileFilter { list_dirs_first: dirs_first, list_dirs_last: ! dirs_first, .. FileFilter::default() };
+        render_tree(root, RecurseOptions { reverse_tree, .. tree() }, &filter).0
+    }
+
+    /// Renders the given directory as a tree, with or without chains of
+    /// single directories collapsed, and returns its lines without the root
+    /// along with the summary of what got listed.
+    fn collapsed_tree_lines(root: &Path, collapse_single_child: bool) -> (Vec<String>, Summary) {
+        render_tree(root, RecurseOptions { collapse_single_child, .. tree() }, &FileFilter::default())
+    }
+
+    /// Renders the given files in the directory as a table with just a
+    /// size column and a header line, which gets repeated as often as
+    /// given, and returns its lines with the directory left out.
+    fn table_lines(root: &Path, names: &[&str], repeat_header: Option<RepeatHeader>) -> Vec<String> {
+        let files = names.iter()
+                         .map(|name| File::from_args(root.join(name), None, None).unwrap())
+                         .collect();
+
+        let time_types = TimeTypes { modified: false, .. TimeTypes::default() };
+        let columns = Columns { permissions: false, user: false, time_types, .. Columns::default() };
+        let table = TableOptions { size_format: SizeFormat::JustBytes, columns, .. TableOptions::default() };
+
+        let opts = Options { table: Some(table), header: true, repeat_header, .. Options::default() };
+        let prefix = format!("{}/", root.display());
+        render_lines(files, &opts, None, &FileFilter::default()).into_iter()
+            .map(|line| line.replace(&prefix, ""))
+            .collect()
+    }
+
+    #[test]
+    fn empty_branches_shown() {
+        let root = make_tree("shown", &[ "src/main.rs", "docs/guide.md", "empty/" ]);
+
+        assert_eq!(tree_lines(&root, "rs", false), vec![
+            "├── docs",
+            "├── empty",
+            "└── src",
+            "   └── main.rs",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn empty_branches_pruned() {
+        let root = make_tree("pruned", &[ "src/main.rs", "docs/guide.md", "empty/" ]);
+
+        assert_eq!(tree_lines(&root, "rs", true), vec![
+            "└── src",
+            "   └── main.rs",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn nested_empty_branches_pruned() {
+        let root = make_tree("nested", &[ "a/b/c/notes.txt", "a/b/d/lib.rs", "a/e/f/", "g/h/readme.md", "top.rs" ]);
+
+        assert_eq!(tree_lines(&root, "rs", true), vec![
+            "├── a",
+            "│  └── b",
+            "│     └── d",
+            "│        └── lib.rs",
+            "└── top.rs",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn everything_pruned() {
+        let root = make_tree("everything", &[ "a/b/notes.txt", "c/" ]);
+        assert_eq!(tree_lines(&root, "rs", true), Vec::<String>::new());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn header_not_repeated() {
+        let root = make_tree("header-once", &[ "a", "b", "c" ]);
+
+        assert_eq!(table_lines(&root, &[ "a", "b", "c" ], None), vec![
+            "Size Name",
+            "   0 a",
+            "   0 b",
+            "   0 c",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn header_repeated() {
+        let root = make_tree("header-every", &[ "a", "b", "c", "d", "e" ]);
+
+        assert_eq!(table_lines(&root, &[ "a", "b", "c", "d", "e" ], Some(RepeatHeader::Every(2))), vec![
+            "Size Name",
+            "   0 a",
+            "   0 b",
+            "Size Name",
+            "   0 c",
+            "   0 d",
+            "Size Name",
+            "   0 e",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn header_not_repeated_at_end() {
+        let root = make_tree("header-end", &[ "a", "b", "c", "d" ]);
+
+        assert_eq!(table_lines(&root, &[ "a", "b", "c", "d" ], Some(RepeatHeader::Every(2))), vec![
+            "Size Name",
+            "   0 a",
+            "   0 b",
+            "Size Name",
+            "   0 c",
+            "   0 d",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn entries_capped() {
+        let root = make_tree("capped", &[ "a/1", "a/2", "a/3", "b", "c", "d" ]);
+
+        assert_eq!(capped_tree_lines(&root, 2, false), vec![
+            "├── a",
+            "│  ├── 1",
+            "│  ├── 2",
+            "│  └── … and 1 more",
+            "├── b",
+            "└── … and 2 more",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn entries_capped_after_reversing() {
+        let root = make_tree("capped-reverse", &[ "a/1", "a/2", "a/3", "b", "c", "d" ]);
+
+        assert_eq!(capped_tree_lines(&root, 2, true), vec![
+            "├── d",
+            "├── c",
+            "└── … and 2 more",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn entries_under_cap() {
+        let root = make_tree("uncapped", &[ "a/1", "b" ]);
+
+        assert_eq!(capped_tree_lines(&root, 2, false), vec![
+            "├── a",
+            "│  └── 1",
+            "└── b",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn siblings_reversed() {
+        let root = make_tree("reverse-tree", &[ "a/1", "a/2", "a/b/3", "c" ]);
+
+        assert_eq!(reversed_tree_lines(&root), vec![
+            "├── c",
+            "└── a",
+            "   ├── b",
+            "   │  └── 3",
+            "   ├── 2",
+            "   └── 1",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dirs_first() {
+        let root = make_tree("dirs-first", &[ "a", "b/1", "b/c/", "d", "e/" ]);
+
+        assert_eq!(grouped_tree_lines(&root, true, false), vec![
+            "├── b",
+            "│  ├── c",
+            "│  └── 1",
+            "├── e",
+            "├── a",
+            "└── d",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dirs_first_reversed() {
+        let root = make_tree("dirs-first-reversed", &[ "a", "b/1", "b/c/", "d", "e/" ]);
+
+        assert_eq!(grouped_tree_lines(&root, true, true), vec![
+            "├── e",
+            "├── b",
+            "│  ├── c",
+            "│  └── 1",
+            "├── d",
+            "└── a",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dirs_last_reversed() {
+        let root = make_tree("dirs-last-reversed", &[ "a", "b/1", "b/c/", "d", "e/" ]);
+
+        assert_eq!(grouped_tree_lines(&root, false, true), vec![
+            "├── d",
+            "├── a",
+            "├── e",
+            "└── b",
+            "   ├── 1",
+            "   └── c",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    /// Renders the given directory as a tree, with files written with the
+    /// given contents, and returns the summary of what got listed, folding
+    /// extensions and capping entries at the given thresholds.
+    fn tree_summary(name: &str, files: &[(&str, &str)], dirs: &[&str], collapse_exts: Option<usize>, max_entries: Option<usize>) -> Summary {
+        let root = make_tree(name, dirs);
+        for (path, contents) in files {
+            fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+            fs::write(root.join(path), contents).unwrap();
+        }
+
+        let filter = FileFilter { max_entries, .. FileFilter::default() };
+        let (_, summary) = render_tree(&root, RecurseOptions { collapse_exts, .. tree() }, &filter);
+        fs::remove_dir_all(root).unwrap();
+        summary
+    }
+
+    #[test]
+    fn summary_counts() {
+        let summary = tree_summary("summary-counts", &[ ("a", "123"), ("b/c", "12345"), ("b/d/e", "") ], &[ "f/" ], None, None);
+        assert_eq!(summary, Summary { files: 3, dirs: 3, bytes: 8 });
+    }
+
+    #[test]
+    fn summary_empty() {
+        let summary = tree_summary("summary-empty", &[], &[ "a/" ], None, None);
+        assert_eq!(summary, Summary { files: 0, dirs: 1, bytes: 0 });
+    }
+
+    #[test]
+    fn summary_counts_folded() {
+        let summary = tree_summary("summary-folded", &[ ("a.txt", "1"), ("b.txt", "22"), ("c.txt", "333"), ("d.rs", "4444") ], &[], Some(3), None);
+        assert_eq!(summary, Summary { files: 4, dirs: 0, bytes: 10 });
+    }
+
+    #[test]
+    fn summary_counts_capped() {
+        let summary = tree_summary("summary-capped", &[ ("sub/a", "1"), ("sub/b", "22"), ("sub/c", "333") ], &[ "sub/d/" ], None, Some(1));
+        assert_eq!(summary, Summary { files: 3, dirs: 2, bytes: 6 });
+    }
+
+    #[test]
+    fn summary_line() {
+        let summary = Summary { files: 42, dirs: 3, bytes: 1_500_000 };
+        assert_eq!(summary.render(&theme(), SizeFormat::DecimalBytes).strings().to_string(), "42 files, 3 directories, 1.5M total");
+    }
+
+    #[test]
+    fn summary_line_singular() {
+        let summary = Summary { files: 1, dirs: 1, bytes: 2048 };
+        assert_eq!(summary.render(&theme(), SizeFormat::BinaryBytes).strings().to_string(), "1 file, 1 directory, 2.0Ki total");
+    }
+
+    const CHAINS: &[&str] = &[ "docs/api/v1/index.md", "docs/readme.md", "empty/a/b/", "src/main/java/App.java",
+                               "src/main/java/Util.java", "src/test/java/AppTest.java", "top.txt" ];
+
+    #[test]
+    fn single_children_collapsed() {
+        let root = make_tree("collapsed", CHAINS);
+
+        assert_eq!(collapsed_tree_lines(&root, true).0, vec![
+            "├── docs",
+            "│  ├── api/v1",
+            "│  │  └── index.md",
+            "│  └── readme.md",
+            "├── empty/a/b",
+            "├── src",
+            "│  ├── main/java",
+            "│  │  ├── App.java",
+            "│  │  └── Util.java",
+            "│  └── test/java",
+            "│     └── AppTest.java",
+            "└── top.txt",
+        ]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn single_children_not_collapsed() {
+        let root = make_tree("not-collapsed", CHAINS);
+
+        let lines = collapsed_tree_lines(&root, false).0;
+        assert_eq!(lines.len(), 17);
+        assert_eq!(lines[5], "├── empty");
+        assert_eq!(lines[6], "│  └── a");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn collapsed_directories_counted() {
+        let root = make_tree("collapsed-summary", CHAINS);
+
+        let collapsed = collapsed_tree_lines(&root, true).1;
+        let expanded = collapsed_tree_lines(&root, false).1;
+        assert_eq!((collapsed.files, collapsed.dirs), (expanded.files, expanded.dirs));
+        assert_eq!((collapsed.files, collapsed.dirs), (6, 11));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
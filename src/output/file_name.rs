@@ -1,17 +1,21 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use ansi_term::{ANSIString, Style};
+use lazy_static::lazy_static;
 
 use crate::fs::{File, FileTarget};
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
 use crate::output::cell::TextCellContents;
 use crate::output::escape;
-use crate::output::icons::{icon_for_file, iconify_style};
+use crate::output::icons::{icon_for_file, iconify_style, IconOverrides, IconSet};
 use crate::output::render::FiletypeColours;
 
 
 /// Basically a file name factory.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Options {
 
     /// Whether to append file class characters to file names.
@@ -19,20 +23,43 @@ pub struct Options {
 
     /// Whether to prepend icon characters before file names.
     pub show_icons: ShowIcons,
+
+    /// The icons to use instead of the built-in ones for certain files.
+    pub icon_overrides: IconOverrides,
+
+    /// Which set of built-in icons to pick from.
+    pub icon_set: IconSet,
+
+    /// Whether to make file names into clickable terminal hyperlinks.
+    pub embed_hyperlinks: EmbedHyperlinks,
+
+    /// Whether to put quotes around file names.
+    pub quoting: QuotingStyle,
+
+    /// Whether to display each file’s absolute path instead of its name.
+    pub absolute: Absolute,
+
+    /// Whether to tint file names with the colour of their Git status.
+    pub git_highlight: bool,
+
+    /// Whether to follow symlinks’ names with an arrow and their targets.
+    pub link_targets: LinkTargets,
 }
 
 impl Options {
 
     /// Create a new `FileName` that prints the given file’s name, painting it
     /// with the remaining arguments.
-    pub fn for_file<'a, 'dir, C>(self, file: &'a File<'dir>, colours: &'a C) -> FileName<'a, 'dir, C> {
+    pub fn for_file<'a, 'dir, C>(&'a self, file: &'a File<'dir>, colours: &'a C) -> FileName<'a, 'dir, C> {
         FileName {
             file,
             colours,
-            link_style: LinkStyle::JustFilenames,
+            link_style: if self.link_targets == LinkTargets::Show { LinkStyle::FullLinkPaths }
+                                                             else { LinkStyle::JustFilenames },
             options:    self,
             target:     if file.is_link() { Some(file.link_target()) }
-                                     else { None }
+                                     else { None },
+            git_status: None,
         }
     }
 }
@@ -53,6 +80,23 @@ enum LinkStyle {
 }
 
 
+/// Whether to display the targets of symlinks after their names.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum LinkTargets {
+
+    /// Display them in the views that have room for them, which are the
+    /// long view and the lines view. This is the default.
+    #[default]
+    Automatic,
+
+    /// Never display them, even in those views.
+    Hide,
+
+    /// Display them in every view, including the grid view.
+    Show,
+}
+
+
 /// Whether to append file class characters to the file names.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Classify {
@@ -63,6 +107,24 @@ pub enum Classify {
     /// Add a character after the file name depending on what class of file
     /// it is.
     AddFileIndicators,
+
+    /// Add the characters, as above, but only when the output is a
+    /// terminal. This gets resolved to one of the others before anything
+    /// is displayed.
+    Automatic,
+}
+
+impl Classify {
+
+    /// Resolves automatic classification into either adding the file
+    /// indicators or not, depending on whether exa is writing to a terminal.
+    pub fn for_terminal(self, is_tty: bool) -> Self {
+        match self {
+            Self::Automatic if is_tty  => Self::AddFileIndicators,
+            Self::Automatic            => Self::JustFilenames,
+            other                      => other,
+        }
+    }
 }
 
 impl Default for Classify {
@@ -73,15 +135,172 @@ impl Default for Classify {
 
 
 /// Whether and how to show icons.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
 pub enum ShowIcons {
 
     /// Don’t show icons at all.
+    #[default]
     Off,
 
     /// Show icons next to file names, with the given number of spaces between
     /// the icon and the file name.
     On(u32),
+
+    /// Show icons, as above, but only when the output is a terminal. This
+    /// gets resolved to one of the others before anything is displayed.
+    Automatic(u32),
+}
+
+impl ShowIcons {
+
+    /// Resolves automatic icons into either showing them or not, depending
+    /// on whether exa is writing to a terminal.
+    pub fn for_terminal(self, is_tty: bool) -> Self {
+        match self {
+            Self::Automatic(spaces) if is_tty  => Self::On(spaces),
+            Self::Automatic(_)                 => Self::Off,
+            other                              => other,
+        }
+    }
+}
+
+
+/// Whether to quote file names, so that names with spaces or other special
+/// characters can be copied and pasted into a shell.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum QuotingStyle {
+
+    /// Display file names exactly as they are.
+    Literal,
+
+    /// Surround file names with single quotes if the shell would treat any
+    /// of their characters specially.
+    Shell,
+
+    /// Surround every file name with single quotes.
+    ShellAlways,
+
+    /// Surround every file name with double quotes, escaping any double
+    /// quotes or backslashes inside it, like a string in C.
+    C,
+
+    /// Escape spaces and backslashes with a backslash, without quoting.
+    Escape,
+}
+
+impl Default for QuotingStyle {
+    fn default() -> Self {
+        Self::Literal
+    }
+}
+
+/// The characters that the shell treats specially, which would make a file
+/// name get split up or expanded if it was pasted in without quotes.
+const SHELL_SPECIAL_CHARS: &[char] = &[
+    ' ', '\t', '!', '"', '#', '$', '&', '\'', '(', ')', '*', ';', '<', '>',
+    '?', '[', '\\', ']', '^', '`', '{', '|', '}', '~',
+];
+
+impl QuotingStyle {
+
+    /// Quotes the given name or path in this style. Single quotes inside
+    /// the name can’t be escaped within single quotes, so they have to be
+    /// ended, escaped, and started again: `it's` becomes `'it'\''s'`.
+    ///
+    /// The C and escape styles put a backslash before the characters they
+    /// treat specially instead.
+    ///
+    /// Control characters are left alone, as they get escaped afterwards
+    /// along with the rest of the name.
+    pub fn quote(self, name: &str) -> Cow<'_, str> {
+        match self {
+            Self::Literal                                        => Cow::Borrowed(name),
+            Self::Shell if ! name.contains(SHELL_SPECIAL_CHARS)  => Cow::Borrowed(name),
+            Self::Shell | Self::ShellAlways                      => Cow::Owned(format!("'{}'", name.replace('\'', "'\\''"))),
+            Self::C                                              => Cow::Owned(format!("\"{}\"", backslash_escape(name, &['"', '\\']))),
+            Self::Escape                                         => backslash_escape(name, &[' ', '\\']),
+        }
+    }
+}
+
+/// Puts a backslash before every one of the given characters in the name,
+/// only allocating if there are any.
+fn backslash_escape<'a>(name: &'a str, specials: &[char]) -> Cow<'a, str> {
+    if ! name.contains(specials) {
+        return Cow::Borrowed(name);
+    }
+
+    let mut escaped = String::with_capacity(name.len() + 1);
+    for c in name.chars() {
+        if specials.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    Cow::Owned(escaped)
+}
+
+
+/// Whether to display the absolute paths of files, rather than just their
+/// names.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Absolute {
+
+    /// Display file names as usual.
+    Off,
+
+    /// Display the absolute path of each file, with any `.` or `..`
+    /// components and symlinked directories in it resolved.
+    On,
+
+    /// Display the absolute path of each file, resolving the file itself
+    /// if it’s a symlink, so links are displayed as their targets’ paths.
+    Follow,
+}
+
+impl Default for Absolute {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl Absolute {
+
+    /// Returns the absolute path of the file at the given path, or `None`
+    /// if absolute paths are turned off. If the path can’t be resolved,
+    /// such as when the file is a broken link being followed, it gets
+    /// joined onto the current directory instead.
+    pub fn path(self, path: &Path) -> Option<PathBuf> {
+        let resolved = match (self, path.parent(), path.file_name()) {
+            (Self::Off, _, _) => {
+                return None;
+            }
+            (Self::On, Some(parent), Some(name)) => {
+                let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+                parent.canonicalize().map(|p| p.join(name))
+            }
+            _ => {
+                path.canonicalize()
+            }
+        };
+
+        resolved.or_else(|_| std::env::current_dir().map(|dir| dir.join(path))).ok()
+    }
+}
+
+
+/// Whether to wrap file names in hyperlinks to the files themselves.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum EmbedHyperlinks {
+
+    /// Just display the file names.
+    #[default]
+    Off,
+
+    /// Surround each file name with the OSC 8 escape sequences that turn it
+    /// into a `file://` link, for terminals that support them.
+    On,
 }
 
 
@@ -101,15 +320,39 @@ pub struct FileName<'a, 'dir, C> {
     /// How to handle displaying links.
     link_style: LinkStyle,
 
-    options: Options,
+    options: &'a Options,
+
+    /// The Git status to tint the name with, if it has one and names are
+    /// being highlighted by their status.
+    git_status: Option<f::GitStatus>,
 }
 
 impl<'a, 'dir, C> FileName<'a, 'dir, C> {
 
     /// Sets the flag on this file name to display link targets with an
-    /// arrow followed by their path.
+    /// arrow followed by their path, unless they’ve been turned off.
     pub fn with_link_paths(mut self) -> Self {
-        self.link_style = LinkStyle::FullLinkPaths;
+        if self.options.link_targets != LinkTargets::Hide {
+            self.link_style = LinkStyle::FullLinkPaths;
+        }
+
+        self
+    }
+
+    /// Looks up this file’s status in the Git cache, if there is one and
+    /// names are being highlighted by their status. Changes that haven’t
+    /// been staged yet take priority over ones that have.
+    pub fn with_git_status(mut self, git: Option<&GitCache>) -> Self {
+        if ! self.options.git_highlight {
+            return self;
+        }
+
+        if let Some(git) = git {
+            let status = git.get(&self.file.path, self.file.is_directory());
+            self.git_status = if status.unstaged == f::GitStatus::NotModified { Some(status.staged) }
+                                                                           else { Some(status.unstaged) };
+        }
+
         self
     }
 }
@@ -127,7 +370,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
 
         if let ShowIcons::On(spaces_count) = self.options.show_icons {
             let style = iconify_style(self.style());
-            let file_icon = icon_for_file(self.file).to_string();
+            let file_icon = icon_for_file(self.file, &self.options.icon_overrides, self.options.icon_set).to_string();
 
             bits.push(style.paint(file_icon));
 
@@ -138,22 +381,43 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             }
         }
 
-        if self.file.parent_dir.is_none() {
+        let absolute_path = self.options.absolute.path(&self.file.path);
+
+        if let Some(path) = &absolute_path {
+            if let Some(parent) = path.parent() {
+                self.add_parent_bits(&mut bits, parent);
+            }
+        }
+        else if self.file.parent_dir.is_none() {
             if let Some(parent) = self.file.path.parent() {
                 self.add_parent_bits(&mut bits, parent);
             }
         }
 
-        if ! self.file.name.is_empty() {
+        let name = match absolute_path.as_ref().and_then(|path| path.file_name()) {
+            Some(name)  => name.to_string_lossy(),
+            None        => Cow::Borrowed(&*self.file.name),
+        };
+
+        if ! name.is_empty() {
         	// The “missing file” colour seems like it should be used here,
         	// but it’s not! In a grid view, where there’s no space to display
         	// link targets, the filename has to have a different style to
         	// indicate this fact. But when showing targets, we can just
         	// colour the path instead (see below), and leave the broken
         	// link’s filename as the link colour.
-            for bit in self.coloured_file_name() {
+            let hyperlink = self.options.embed_hyperlinks == EmbedHyperlinks::On;
+            if hyperlink {
+                bits.push(Style::default().paint(hyperlink_start(&self.file.path)));
+            }
+
+            for bit in self.coloured_file_name(&name) {
                 bits.push(bit);
             }
+
+            if hyperlink {
+                bits.push(Style::default().paint(HYPERLINK_END));
+            }
         }
 
         if let (LinkStyle::FullLinkPaths, Some(target)) = (self.link_style, self.target.as_ref()) {
@@ -163,6 +427,13 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                     bits.push(self.colours.normal_arrow().paint("->"));
                     bits.push(Style::default().paint(" "));
 
+                    // The whole of the target’s path links to the target,
+                    // but its indicator, which isn’t part of it, doesn’t.
+                    let hyperlink = self.options.embed_hyperlinks == EmbedHyperlinks::On;
+                    if hyperlink {
+                        bits.push(Style::default().paint(hyperlink_start(&self.file.reorient_target_path(&target.path))));
+                    }
+
                     if let Some(parent) = target.path.parent() {
                         self.add_parent_bits(&mut bits, parent);
                     }
@@ -171,6 +442,13 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                         let target_options = Options {
                             classify: Classify::JustFilenames,
                             show_icons: ShowIcons::Off,
+                            icon_overrides: IconOverrides::default(),
+                            icon_set: IconSet::default(),
+                            embed_hyperlinks: EmbedHyperlinks::Off,
+                            quoting: self.options.quoting,
+                            absolute: Absolute::Off,
+                            git_highlight: false,
+                            link_targets: LinkTargets::Automatic,
                         };
 
                         let target_name = FileName {
@@ -178,13 +456,20 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             colours: self.colours,
                             target: None,
                             link_style: LinkStyle::FullLinkPaths,
-                            options: target_options,
+                            options: &target_options,
+                            git_status: None,
                         };
 
-                        for bit in target_name.coloured_file_name() {
+                        for bit in target_name.coloured_file_name(&target.name) {
                             bits.push(bit);
                         }
+                    }
 
+                    if hyperlink {
+                        bits.push(Style::default().paint(HYPERLINK_END));
+                    }
+
+                    if ! target.name.is_empty() {
                         if let Classify::AddFileIndicators = self.options.classify {
                             if let Some(class) = self.classify_char(target) {
                                 bits.push(Style::default().paint(class));
@@ -199,7 +484,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                     bits.push(Style::default().paint(" "));
 
                     escape(
-                        broken_path.display().to_string(),
+                        self.options.quoting.quote(&broken_path.display().to_string()).into_owned(),
                         &mut bits,
                         self.colours.broken_filename(),
                         self.colours.broken_control_char(),
@@ -215,6 +500,14 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             if let Some(class) = self.classify_char(self.file) {
                 bits.push(Style::default().paint(class));
             }
+
+            // Without the link’s target shown, its indicator goes after the
+            // link’s own, so a link to a directory gets `@/`
+            if let Some(FileTarget::Ok(target)) = &self.target {
+                if let Some(class) = self.classify_char(target) {
+                    bits.push(Style::default().paint(class));
+                }
+            }
         }
 
         bits.into()
@@ -230,7 +523,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         }
         else if coconut >= 1 {
             escape(
-                parent.to_string_lossy().to_string(),
+                self.options.quoting.quote(&parent.to_string_lossy()).into_owned(),
                 bits,
                 self.colours.symlink_path(),
                 self.colours.control_char(),
@@ -276,8 +569,8 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         }
     }
 
-    /// Returns at least one ANSI-highlighted string representing this file’s
-    /// name using the given set of colours.
+    /// Returns at least one ANSI-highlighted string representing the given
+    /// name, which is usually this file’s, using the given set of colours.
     ///
     /// Ordinarily, this will be just one string: the file’s complete name,
     /// coloured according to its file type. If the name contains control
@@ -286,12 +579,12 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
     ///
     /// So in that situation, those characters will be escaped and highlighted in
     /// a different colour.
-    fn coloured_file_name<'unused>(&self) -> Vec<ANSIString<'unused>> {
+    fn coloured_file_name<'unused>(&self, name: &str) -> Vec<ANSIString<'unused>> {
         let file_style = self.style();
         let mut bits = Vec::new();
 
         escape(
-            self.file.name.clone(),
+            self.options.quoting.quote(name).into_owned(),
             &mut bits,
             file_style,
             self.colours.control_char(),
@@ -305,6 +598,23 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
     /// class on the filesystem or from its name. (Or the broken link colour,
     /// if there’s nowhere else for that fact to be shown.)
     pub fn style(&self) -> Style {
+        let mut style = self.kind_style();
+
+        if let Some(status) = self.git_status {
+            style = self.colours.git_status(style, status);
+        }
+
+        if self.file.name.starts_with('.') {
+            self.colours.hidden_file(style)
+        }
+        else {
+            style
+        }
+    }
+
+    /// The style of the file without taking into account whether it’s
+    /// hidden.
+    fn kind_style(&self) -> Style {
         if let LinkStyle::JustFilenames = self.link_style {
             if let Some(ref target) = self.target {
                 if target.is_broken() {
@@ -362,6 +672,14 @@ pub trait Colours: FiletypeColours {
     fn executable_file(&self) -> Style;
 
     fn colour_file(&self, file: &File<'_>) -> Style;
+
+    /// The style to paint a hidden file, given the style it would have had
+    /// if it weren’t hidden.
+    fn hidden_file(&self, style: Style) -> Style;
+
+    /// The style to paint a file with the given Git status, given the style
+    /// it would have had otherwise.
+    fn git_status(&self, style: Style, status: f::GitStatus) -> Style;
 }
 
 
@@ -369,3 +687,352 @@ pub trait Colours: FiletypeColours {
 fn spaces(width: u32) -> String {
     (0 .. width).into_iter().map(|_| ' ').collect()
 }
+
+
+/// The escape sequence that ends a hyperlink started by `hyperlink_start`.
+const HYPERLINK_END: &str = "\x1B]8;;\x1B\\";
+
+/// Returns the escape sequence that starts a hyperlink to the file at the
+/// given path, using its absolute path if it can be worked out.
+fn hyperlink_start(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    format!("\x1B]8;;file://{}{}\x1B\\", *HOSTNAME, url_encode(&path.to_string_lossy()))
+}
+
+/// Percent-encodes the characters in a path that can’t appear in a URL as
+/// they are, leaving the path separators alone.
+fn url_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+
+    for byte in path.bytes() {
+        match byte {
+            b'A' ..= b'Z' | b'a' ..= b'z' | b'0' ..= b'9' |
+            b'-' | b'_' | b'.' | b'~' | b':' | b'/'  => encoded.push(byte as char),
+            #[cfg(windows)]
+            b'\\'                                    => encoded.push('/'),
+            _                                        => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    #[cfg(windows)]
+    encoded.insert(0, '/');
+
+    encoded
+}
+
+lazy_static! {
+    static ref HOSTNAME: String = hostname();
+}
+
+/// The name of this machine, which terminals use to tell whether a
+/// hyperlink points to a local file. An empty host also means “this
+/// machine”, so that’s used if the name can’t be found.
+#[cfg(unix)]
+fn hostname() -> String {
+    let mut buf = [0_u8; 256];
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if result != 0 {
+        return String::new();
+    }
+
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[.. len]).into_owned()
+}
+
+#[cfg(windows)]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_path() {
+        assert_eq!(url_encode("/home/ben/file.txt"), "/home/ben/file.txt");
+    }
+
+    #[test]
+    fn spaces_and_symbols() {
+        assert_eq!(url_encode("/tmp/a b#c%d"), "/tmp/a%20b%23c%25d");
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(url_encode("/tmp/é"), "/tmp/%C3%A9");
+    }
+
+    #[test]
+    fn icons_for_terminal() {
+        assert_eq!(ShowIcons::Automatic(2).for_terminal(true), ShowIcons::On(2));
+        assert_eq!(ShowIcons::On(2).for_terminal(true), ShowIcons::On(2));
+        assert_eq!(ShowIcons::Off.for_terminal(true), ShowIcons::Off);
+    }
+
+    #[test]
+    fn icons_not_for_terminal() {
+        assert_eq!(ShowIcons::Automatic(2).for_terminal(false), ShowIcons::Off);
+        assert_eq!(ShowIcons::On(2).for_terminal(false), ShowIcons::On(2));
+        assert_eq!(ShowIcons::Off.for_terminal(false), ShowIcons::Off);
+    }
+
+    #[test]
+    fn classify_for_terminal() {
+        assert_eq!(Classify::Automatic.for_terminal(true), Classify::AddFileIndicators);
+        assert_eq!(Classify::AddFileIndicators.for_terminal(true), Classify::AddFileIndicators);
+        assert_eq!(Classify::JustFilenames.for_terminal(true), Classify::JustFilenames);
+    }
+
+    #[test]
+    fn classify_not_for_terminal() {
+        assert_eq!(Classify::Automatic.for_terminal(false), Classify::JustFilenames);
+        assert_eq!(Classify::AddFileIndicators.for_terminal(false), Classify::AddFileIndicators);
+        assert_eq!(Classify::JustFilenames.for_terminal(false), Classify::JustFilenames);
+    }
+}
+
+
+#[cfg(test)]
+mod quoting_test {
+    use super::QuotingStyle::*;
+
+    #[test]
+    fn literal() {
+        assert_eq!(Literal.quote("my file's [1].txt"), "my file's [1].txt");
+    }
+
+    #[test]
+    fn shell_plain() {
+        assert_eq!(Shell.quote("file-name_1.txt"), "file-name_1.txt");
+    }
+
+    #[test]
+    fn shell_space() {
+        assert_eq!(Shell.quote("my file.txt"), "'my file.txt'");
+    }
+
+    #[test]
+    fn shell_single_quote() {
+        assert_eq!(Shell.quote("it's.txt"), "'it'\\''s.txt'");
+    }
+
+    #[test]
+    fn shell_double_quote() {
+        assert_eq!(Shell.quote("say \"hi\".txt"), "'say \"hi\".txt'");
+    }
+
+    #[test]
+    fn shell_globs() {
+        assert_eq!(Shell.quote("*.txt"), "'*.txt'");
+        assert_eq!(Shell.quote("file?.txt"), "'file?.txt'");
+        assert_eq!(Shell.quote("[abc].txt"), "'[abc].txt'");
+    }
+
+    #[test]
+    fn shell_dollar() {
+        assert_eq!(Shell.quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn shell_control_chars() {
+        assert_eq!(Shell.quote("new\nline"), "new\nline");
+    }
+
+    #[test]
+    fn shell_always() {
+        assert_eq!(ShellAlways.quote("file.txt"), "'file.txt'");
+        assert_eq!(ShellAlways.quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn c_plain() {
+        assert_eq!(C.quote("file.txt"), "\"file.txt\"");
+        assert_eq!(C.quote("my file's.txt"), "\"my file's.txt\"");
+    }
+
+    #[test]
+    fn c_escapes() {
+        assert_eq!(C.quote("say \"hi\".txt"), "\"say \\\"hi\\\".txt\"");
+        assert_eq!(C.quote("back\\slash"), "\"back\\\\slash\"");
+    }
+
+    #[test]
+    fn c_control_chars() {
+        assert_eq!(C.quote("new\nline"), "\"new\nline\"");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(Escape.quote("file.txt"), "file.txt");
+        assert_eq!(Escape.quote("my file.txt"), "my\\ file.txt");
+        assert_eq!(Escape.quote("back\\slash"), "back\\\\slash");
+        assert_eq!(Escape.quote("it's"), "it's");
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod classify_test {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+    use crate::fs::Dir;
+    use crate::fs::test::temp_dir;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    fn classified(name: &str, target: &str) -> String {
+        painted(name, target, Classify::AddFileIndicators, LinkTargets::Automatic, false)
+    }
+
+    /// Paints the name of a link to the given target, in a view that does
+    /// or doesn’t display link targets.
+    fn painted(name: &str, target: &str, classify: Classify, link_targets: LinkTargets, link_paths: bool) -> String {
+        let root = temp_dir(&[ "classify-", name ].concat());
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("exe"), "").unwrap();
+        fs::set_permissions(root.join("exe"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(root.join("file"), "").unwrap();
+        symlink(target, root.join("link")).unwrap();
+
+        let theme = ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false);
+
+        let options = Options { classify, link_targets, .. Options::default() };
+
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        let file = File::from_args(root.join("link"), &dir, None).unwrap();
+        let name = if link_paths { options.for_file(&file, &theme).with_link_paths().paint() }
+                            else { options.for_file(&file, &theme).paint() };
+        let name = name.strings().to_string();
+
+        fs::remove_dir_all(root).unwrap();
+        name
+    }
+
+    #[test]
+    fn link_to_dir() {
+        assert_eq!(classified("dir", "dir"), "link@/");
+    }
+
+    #[test]
+    fn link_to_exe() {
+        assert_eq!(classified("exe", "exe"), "link@*");
+    }
+
+    #[test]
+    fn link_to_file() {
+        assert_eq!(classified("file", "file"), "link@");
+    }
+
+    #[test]
+    fn broken_link() {
+        assert_eq!(classified("broken", "nowhere"), "link@");
+    }
+
+    #[test]
+    fn shown_in_long_view() {
+        assert_eq!(painted("long", "file", Classify::JustFilenames, LinkTargets::Automatic, true), "link -> file");
+    }
+
+    #[test]
+    fn hidden_in_long_view() {
+        assert_eq!(painted("hide", "file", Classify::JustFilenames, LinkTargets::Hide, true), "link");
+    }
+
+    #[test]
+    fn hidden_broken_link() {
+        assert_eq!(painted("hide-broken", "nowhere", Classify::JustFilenames, LinkTargets::Hide, true), "link");
+    }
+
+    #[test]
+    fn hidden_still_classified() {
+        assert_eq!(painted("hide-dir", "dir", Classify::AddFileIndicators, LinkTargets::Hide, true), "link@/");
+    }
+
+    #[test]
+    fn shown_in_grid_view() {
+        assert_eq!(painted("show", "dir", Classify::JustFilenames, LinkTargets::Show, false), "link -> dir");
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod hyperlink_test {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use crate::fs::Dir;
+    use crate::fs::test::temp_dir;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    /// Paints the name of a link to the given target in the long view,
+    /// returning it along with the start of a hyperlink to the target.
+    fn painted(name: &str, target: &str, classify: Classify, embed_hyperlinks: EmbedHyperlinks) -> (String, String) {
+        let root = temp_dir(&[ "hyperlink-", name ].concat());
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("file"), "").unwrap();
+        symlink(target, root.join("link")).unwrap();
+
+        let theme = ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false);
+
+        let options = Options { classify, embed_hyperlinks, .. Options::default() };
+
+        let dir = Dir::read_dir(root.clone()).unwrap();
+        let file = File::from_args(root.join("link"), &dir, None).unwrap();
+        let name = options.for_file(&file, &theme).with_link_paths().paint().strings().to_string();
+
+        let target_path = root.canonicalize().unwrap().join(target);
+        let start = format!("\x1B]8;;file://{}{}\x1B\\", *HOSTNAME, url_encode(&target_path.to_string_lossy()));
+
+        fs::remove_dir_all(root).unwrap();
+        (name, start)
+    }
+
+    #[test]
+    fn target_linked() {
+        let (name, start) = painted("file", "file", Classify::JustFilenames, EmbedHyperlinks::On);
+        assert!(name.ends_with(&[ " -> ", &start, "file", HYPERLINK_END ].concat()));
+    }
+
+    #[test]
+    fn name_still_linked() {
+        let (name, start) = painted("name", "file", Classify::JustFilenames, EmbedHyperlinks::On);
+        assert_eq!(name, [ &start, "link", HYPERLINK_END, " -> ", &start, "file", HYPERLINK_END ].concat());
+    }
+
+    #[test]
+    fn indicator_outside_link() {
+        let (name, start) = painted("classify", "dir", Classify::AddFileIndicators, EmbedHyperlinks::On);
+        assert!(name.ends_with(&[ " -> ", &start, "dir", HYPERLINK_END, "/" ].concat()));
+    }
+
+    #[test]
+    fn broken_target_not_linked() {
+        let (name, _) = painted("broken", "nowhere", Classify::JustFilenames, EmbedHyperlinks::On);
+        assert!(name.ends_with(" -> nowhere"));
+        assert_eq!(name.matches(HYPERLINK_END).count(), 1);
+    }
+
+    #[test]
+    fn off_by_default() {
+        let (name, _) = painted("off", "file", Classify::JustFilenames, EmbedHyperlinks::Off);
+        assert_eq!(name, "link -> file");
+    }
+}
@@ -1,8 +1,10 @@
 use std::io::{self, Write};
 
+use log::*;
 use term_grid as tg;
 
 use crate::fs::File;
+use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::output::file_name::Options as FileStyle;
 use crate::theme::Theme;
@@ -11,6 +13,13 @@ use crate::theme::Theme;
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct Options {
     pub across: bool,
+
+    /// The fewest columns to lay the grid out in, so long as the file
+    /// names are narrow enough for that many to fit.
+    pub min_columns: Option<usize>,
+
+    /// The most columns to lay the grid out in, even if more would fit.
+    pub max_columns: Option<usize>,
 }
 
 impl Options {
@@ -28,6 +37,7 @@ pub struct Render<'a> {
     pub opts: &'a Options,
     pub console_width: usize,
     pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
 }
 
 impl<'a> Render<'a> {
@@ -38,10 +48,12 @@ impl<'a> Render<'a> {
         });
 
         grid.reserve(self.files.len());
+        let mut widths = Vec::with_capacity(self.files.len());
 
         self.filter.sort_files(&mut self.files);
         for file in &self.files {
-            let filename = self.file_style.for_file(file, self.theme).paint();
+            let filename = self.file_style.for_file(file, self.theme).with_git_status(self.git).paint();
+            widths.push(*filename.width());
 
             grid.add(tg::Cell {
                 contents:  filename.strings().to_string(),
@@ -50,7 +62,14 @@ impl<'a> Render<'a> {
             });
         }
 
-        if let Some(display) = grid.fit_into_width(self.console_width) {
+        let display = if self.opts.min_columns.is_none() && self.opts.max_columns.is_none() {
+            grid.fit_into_width(self.console_width)
+        }
+        else {
+            fitting_columns(&widths, *self.opts, self.console_width).map(|columns| grid.fit_into_columns(columns))
+        };
+
+        if let Some(display) = display {
             write!(w, "{}", display)
         }
         else {
@@ -58,7 +77,7 @@ impl<'a> Render<'a> {
             // This isn’t *quite* the same as the lines view, which also
             // displays full link paths.
             for file in &self.files {
-                let name_cell = self.file_style.for_file(file, self.theme).paint();
+                let name_cell = self.file_style.for_file(file, self.theme).with_git_status(self.git).paint();
                 writeln!(w, "{}", name_cell.strings())?;
             }
 
@@ -66,3 +85,94 @@ impl<'a> Render<'a> {
         }
     }
 }
+
+
+/// Works out how many columns to lay the grid out in when there’s a
+/// minimum or a maximum: as many as fit into the console, up to the
+/// maximum. If that falls short of the minimum, the names are too wide, so
+/// it settles for fewer. Returns `None` if not even one column fits.
+fn fitting_columns(widths: &[usize], opts: Options, console_width: usize) -> Option<usize> {
+    let narrowest = widths.iter().copied().min()?;
+    let most = ((console_width + 2) / (narrowest + 2)).min(widths.len());
+    let most = opts.max_columns.map_or(most, |max| most.min(max));
+
+    let columns = (1 ..= most).rev()
+                              .find(|&columns| grid_width(widths, columns, opts.across) <= console_width)?;
+
+    if let Some(min) = opts.min_columns.filter(|&min| min > columns) {
+        debug!("Can’t fit {} files into {} columns of width {}, so using {}", widths.len(), min, console_width, columns);
+    }
+
+    Some(columns)
+}
+
+/// The width a grid of cells with these widths takes up when split into
+/// this many columns: the widest cell in each column, plus two spaces
+/// between each of them, the same way `term_grid` lays them out.
+fn grid_width(widths: &[usize], columns: usize, across: bool) -> usize {
+    let rows = (widths.len() + columns - 1) / columns;
+    let mut column_widths = vec![0; columns];
+
+    for (index, width) in widths.iter().enumerate() {
+        let column = if across { index % columns } else { index / rows };
+        column_widths[column] = column_widths[column].max(*width);
+    }
+
+    column_widths.iter().sum::<usize>() + 2 * (columns - 1)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn opts(min_columns: Option<usize>, max_columns: Option<usize>) -> Options {
+        Options { across: false, min_columns, max_columns }
+    }
+
+    #[test]
+    fn as_many_as_fit() {
+        // Five columns of four characters, with two spaces between them
+        let widths = [4; 10];
+        assert_eq!(fitting_columns(&widths, opts(Some(1), None), 28), Some(5));
+    }
+
+    #[test]
+    fn respects_maximum() {
+        let widths = [4; 10];
+        assert_eq!(fitting_columns(&widths, opts(None, Some(3)), 80), Some(3));
+    }
+
+    #[test]
+    fn respects_minimum() {
+        let widths = [4; 10];
+        assert_eq!(fitting_columns(&widths, opts(Some(2), Some(2)), 80), Some(2));
+    }
+
+    #[test]
+    fn minimum_too_wide() {
+        let widths = [20, 4, 4, 4, 4];
+        assert_eq!(fitting_columns(&widths, opts(Some(4), None), 30), Some(2));
+    }
+
+    #[test]
+    fn nothing_fits() {
+        let widths = [40, 4];
+        assert_eq!(fitting_columns(&widths, opts(Some(2), None), 30), None);
+    }
+
+    #[test]
+    fn fewer_files_than_maximum() {
+        let widths = [4; 3];
+        assert_eq!(fitting_columns(&widths, opts(None, Some(8)), 80), Some(3));
+    }
+
+    #[test]
+    fn across_uneven_widths() {
+        // Across, the wide names line up in the first column
+        let widths = [20, 4, 20, 4];
+        let across = Options { across: true, ..opts(None, Some(4)) };
+        assert_eq!(fitting_columns(&widths, across, 30), Some(2));
+        assert_eq!(fitting_columns(&widths, opts(None, Some(4)), 30), Some(1));
+    }
+}
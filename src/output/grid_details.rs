@@ -10,7 +10,7 @@ use crate::fs::feature::git::GitCache;
 use crate::fs::feature::xattr::FileAttributes;
 use crate::fs::filter::FileFilter;
 use crate::output::cell::TextCell;
-use crate::output::details::{Options as DetailsOptions, Row as DetailsRow, Render as DetailsRender};
+use crate::output::details::{Options as DetailsOptions, Row as DetailsRow, Render as DetailsRender, Summary};
 use crate::output::file_name::Options as FileStyle;
 use crate::output::grid::Options as GridOptions;
 use crate::output::table::{Table, Row as TableRow, Options as TableOptions};
@@ -88,6 +88,14 @@ pub struct Render<'a> {
     pub git: Option<&'a GitCache>,
 
     pub console_width: usize,
+
+    /// The total size of every file in the listing, which only gets worked
+    /// out when sizes are being shown as a percentage of it.
+    pub size_total: Option<u64>,
+
+    /// Whether this listing carries on from an earlier one, so it might not
+    /// need a header.
+    pub continued: bool,
 }
 
 impl<'a> Render<'a> {
@@ -109,6 +117,8 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            size_total:    self.size_total,
+            continued:     self.continued,
         }
     }
 
@@ -127,15 +137,26 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            size_total:    self.size_total,
+            continued:     self.continued,
         }
     }
 
     // This doesn’t take an IgnoreCache even though the details one does
     // because grid-details has no tree view.
 
-    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+    /// Writes the listing, returning how many files and directories were
+    /// in it, and their total size.
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<Summary> {
         if let Some((grid, width)) = self.find_fitting_grid() {
-            write!(w, "{}", grid.fit_into_columns(width))
+            write!(w, "{}", grid.fit_into_columns(width))?;
+
+            let mut summary = Summary::default();
+            for file in &self.files {
+                summary.add(file);
+            }
+
+            Ok(summary)
         }
         else {
             self.give_up().render(w)
@@ -154,12 +175,12 @@ impl<'a> Render<'a> {
                        .collect::<Vec<_>>();
 
         let file_names = self.files.iter()
-                             .map(|file| self.file_style.for_file(file, self.theme).paint().promote())
+                             .map(|file| self.file_style.for_file(file, self.theme).with_git_status(self.git).paint().promote())
                              .collect::<Vec<_>>();
 
         let mut last_working_grid = self.make_grid(1, options, &file_names, rows.clone(), &drender);
 
-        if file_names.len() == 1 {
+        if file_names.len() == 1 || self.grid.max_columns == Some(1) {
             return Some((last_working_grid, 1));
         }
 
@@ -177,7 +198,9 @@ impl<'a> Render<'a> {
                 last_working_grid = grid;
             }
 
-            if !the_grid_fits || column_count == file_names.len() {
+            let at_most = column_count == file_names.len() || Some(column_count) == self.grid.max_columns;
+
+            if !the_grid_fits || at_most {
                 let last_column_count = if the_grid_fits { column_count } else { column_count - 1 };
                 // If we’ve figured out how many columns can fit in the user’s terminal,
                 // and it turns out there aren’t enough rows to make it worthwhile
@@ -203,9 +226,16 @@ impl<'a> Render<'a> {
         }
 
         let mut table = Table::new(options, self.git, self.theme);
+        table.set_dot_filter(self.filter.dot_filter);
+        if let Some(total) = options.size_total(&self.files, self.size_total) {
+            table.set_size_total(total);
+        }
+        table.set_age_ranges(&self.files);
+        table.set_link_groups(&self.files);
+
         let mut rows = Vec::new();
 
-        if self.details.header {
+        if drender.shows_header() {
             let row = table.header_row();
             table.add_widths(&row);
             rows.push(drender.render_header(row));
@@ -221,7 +251,7 @@ impl<'a> Render<'a> {
         }
 
         let mut num_cells = rows.len();
-        if self.details.header {
+        if drender.shows_header() {
             num_cells += column_count;
         }
 
@@ -1,7 +1,9 @@
 use ansi_term::Style;
+use log::*;
 
 use crate::fs::File;
-use crate::info::filetype::FileExtensions;
+use crate::info::filetype::{FileExtensions, FileType};
+use crate::theme::LSColors;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 
@@ -29,6 +31,85 @@ impl Icons {
 }
 
 
+/// Which set of icons to show next to file names.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum IconSet {
+
+    /// Glyphs from the Private Use Area that only show up properly in
+    /// fonts patched with the Nerd Font icons.
+    #[default]
+    NerdFont,
+
+    /// A single ASCII character for each kind of file, which every font can
+    /// display, for terminals that would otherwise show boxes.
+    Ascii,
+}
+
+
+/// Icons that the user has picked for files matching certain globs, using
+/// the `EXA_ICONS` environment variable. These get checked before any of
+/// the built-in icons.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct IconOverrides {
+    mappings: Vec<(glob::Pattern, char)>,
+}
+
+impl IconOverrides {
+
+    /// Parses the value of the `EXA_ICONS` variable, which has the same
+    /// format as `EXA_COLORS`, but with icons instead of colour codes:
+    /// `*.rs=\ue7a8:Makefile=\uf728`. Each icon is either a Unicode escape
+    /// or the character itself. Entries that can’t be parsed get skipped.
+    pub fn parse(var: &str) -> Self {
+        let mut overrides = Self::default();
+
+        LSColors(var).each_pair(|pair| {
+            let pattern = match glob::Pattern::new(pair.key) {
+                Ok(pat) => pat,
+                Err(e) => {
+                    warn!("Couldn't parse glob pattern {:?}: {}", pair.key, e);
+                    return;
+                }
+            };
+
+            match parse_icon(pair.value) {
+                Some(icon) => overrides.mappings.push((pattern, icon)),
+                None       => warn!("Couldn't parse icon {:?} for {:?}", pair.value, pair.key),
+            }
+        });
+
+        overrides
+    }
+}
+
+// Loop through backwards so that icons specified later in the list override
+// icons specified earlier, as with the colours in `EXA_COLORS`
+
+impl FileIcon for IconOverrides {
+    fn icon_file(&self, file: &File<'_>) -> Option<char> {
+        self.mappings.iter().rev()
+            .find(|t| t.0.matches(&file.name))
+            .map (|t| t.1)
+    }
+}
+
+/// Parses an icon written as `\uXXXX`, `\u{XXXX}`, or just as the character
+/// itself, returning nothing if it isn’t a valid Unicode codepoint.
+fn parse_icon(input: &str) -> Option<char> {
+    if let Some(hex) = input.strip_prefix("\\u") {
+        let hex = hex.strip_prefix('{').and_then(|h| h.strip_suffix('}')).unwrap_or(hex);
+        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+    }
+    else {
+        let mut chars = input.chars();
+        match (chars.next(), chars.next()) {
+            (Some(icon), None)  => Some(icon),
+            _                   => None,
+        }
+    }
+}
+
+
 /// Converts the style used to paint a file name into the style that should be
 /// used to paint an icon.
 ///
@@ -93,10 +174,12 @@ lazy_static! {
     };
 }
 
-pub fn icon_for_file(file: &File<'_>) -> char {
+pub fn icon_for_file(file: &File<'_>, overrides: &IconOverrides, icon_set: IconSet) -> char {
     let extensions = Box::new(FileExtensions);
 
-    if let Some(icon) = MAP_BY_NAME.get(file.name.as_str()) { *icon }
+    if let Some(icon) = overrides.icon_file(file) { icon }
+    else if icon_set == IconSet::Ascii { ascii_icon_for_file(file) }
+    else if let Some(icon) = MAP_BY_NAME.get(file.name.as_str()) { *icon }
     else if file.points_to_directory() {
         match file.name.as_str() {
             "bin"           => '\u{e5fc}', // 
@@ -372,3 +455,111 @@ pub fn icon_for_file(file: &File<'_>) -> char {
         '\u{f016}'
     }
 }
+
+/// Picks the ASCII character for a file from the broad kind of file it is,
+/// rather than from its exact name or extension, as there are far fewer
+/// characters to go round.
+fn ascii_icon_for_file(file: &File<'_>) -> char {
+    if file.is_link() {
+        'l'
+    }
+    else if file.is_directory() {
+        'd'
+    }
+    else if file.is_executable_file() {
+        'x'
+    }
+    else {
+        match FileExtensions.file_type(file) {
+            Some(FileType::Image)                       => '~',
+            Some(FileType::Video)                       => '>',
+            Some(FileType::Music | FileType::Lossless)  => '#',
+            Some(FileType::Compressed)                  => 'z',
+            _                                           => '-',
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn overrides(list: &[(&str, char)]) -> IconOverrides {
+        let mappings = list.iter().map(|t| (glob::Pattern::new(t.0).unwrap(), t.1)).collect();
+        IconOverrides { mappings }
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(parse_icon("\\ue7a8"), Some('\u{e7a8}'));
+        assert_eq!(parse_icon("\\u{f0001}"), Some('\u{f0001}'));
+    }
+
+    #[test]
+    fn literal() {
+        assert_eq!(parse_icon("λ"), Some('λ'));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse_icon("\\ud800"), None);
+        assert_eq!(parse_icon("\\uzzzz"), None);
+        assert_eq!(parse_icon("\\u"), None);
+        assert_eq!(parse_icon("ab"), None);
+    }
+
+    #[test]
+    fn globs_and_names() {
+        assert_eq!(IconOverrides::parse("*.rs=\\ue7a8:Makefile=\\uf728"),
+                   overrides(&[ ("*.rs", '\u{e7a8}'), ("Makefile", '\u{f728}') ]));
+    }
+
+    #[test]
+    fn invalid_entries_skipped() {
+        assert_eq!(IconOverrides::parse("*.rs=\\ud800:[=\\ue7a8:*.md=\\uf48a:*.txt="),
+                   overrides(&[ ("*.md", '\u{f48a}') ]));
+    }
+
+    /// Makes a directory with one of each kind of file that has its own
+    /// ASCII icon, returning the icon each one gets.
+    #[cfg(unix)]
+    fn ascii_icons(name: &str, overrides: &IconOverrides) -> Vec<(&'static str, char)> {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+        use std::fs;
+        use crate::fs::test::temp_dir;
+
+        let root = temp_dir(&[ "ascii-icons-", name ].concat());
+        fs::create_dir(root.join("dir")).unwrap();
+
+        for file in &[ "run", "photo.png", "film.mkv", "song.mp3", "bundle.zip", "notes.txt" ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        fs::set_permissions(root.join("run"), fs::Permissions::from_mode(0o755)).unwrap();
+        symlink("dir", root.join("link")).unwrap();
+
+        let icons = [ "dir", "link", "run", "photo.png", "film.mkv", "song.mp3", "bundle.zip", "notes.txt" ].iter()
+            .map(|name| (*name, icon_for_file(&File::from_args(root.join(name), None, None).unwrap(), overrides, IconSet::Ascii)))
+            .collect();
+
+        fs::remove_dir_all(root).unwrap();
+        icons
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn ascii_kinds() {
+        assert_eq!(ascii_icons("kinds", &IconOverrides::default()), vec![
+            ("dir", 'd'), ("link", 'l'), ("run", 'x'), ("photo.png", '~'),
+            ("film.mkv", '>'), ("song.mp3", '#'), ("bundle.zip", 'z'), ("notes.txt", '-'),
+        ]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn ascii_overridden() {
+        let icons = ascii_icons("overridden", &overrides(&[ ("*.txt", 't') ]));
+        assert_eq!(icons.last(), Some(&("notes.txt", 't')));
+    }
+}
@@ -3,6 +3,7 @@ use std::io::{self, Write};
 use ansi_term::ANSIStrings;
 
 use crate::fs::File;
+use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::output::cell::TextCellContents;
 use crate::output::file_name::{Options as FileStyle};
@@ -15,6 +16,7 @@ pub struct Render<'a> {
     pub theme: &'a Theme,
     pub file_style: &'a FileStyle,
     pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
 }
 
 impl<'a> Render<'a> {
@@ -32,6 +34,7 @@ impl<'a> Render<'a> {
         self.file_style
             .for_file(file, self.theme)
             .with_link_paths()
+            .with_git_status(self.git)
             .paint()
     }
 }
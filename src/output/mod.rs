@@ -1,6 +1,7 @@
 pub use self::cell::{TextCell, TextCellContents, DisplayWidth};
 pub use self::escape::escape;
 
+pub mod delimited;
 pub mod details;
 pub mod file_name;
 pub mod grid;
@@ -8,12 +9,14 @@ pub mod grid_details;
 pub mod icons;
 pub mod lines;
 pub mod render;
+pub mod stat;
 pub mod table;
 pub mod time;
+pub mod tree;
+pub mod zero;
 
 mod cell;
 mod escape;
-mod tree;
 
 
 /// The **view** contains all information about how to format output.
@@ -22,6 +25,35 @@ pub struct View {
     pub mode: Mode,
     pub width: TerminalWidth,
     pub file_style: file_name::Options,
+    pub paging: Paging,
+}
+
+impl View {
+
+    /// Whether file sizes are being shown as percentages, and of what. This
+    /// is only possible in a table that has a file size column.
+    pub fn size_percent(&self) -> Option<table::SizePercent> {
+        match self.mode {
+            Mode::Details(details::Options { table: Some(ref table), .. }) |
+            Mode::GridDetails(grid_details::Options { details: details::Options { table: Some(ref table), .. }, .. }) |
+            Mode::Delimited(delimited::Options { ref table, .. }) => {
+                table.size_percent.filter(|_| table.columns.filesize)
+            }
+            _ => None,
+        }
+    }
+
+    /// The format to show the total size in on the summary line, if there
+    /// should be one after the listing, which is only in a long view.
+    pub fn summary(&self) -> Option<table::SizeFormat> {
+        match self.mode {
+            Mode::Details(ref opts) |
+            Mode::GridDetails(grid_details::Options { details: ref opts, .. }) if opts.summary => {
+                Some(opts.table.as_ref().map_or_else(table::SizeFormat::default, |t| t.size_format))
+            }
+            _ => None,
+        }
+    }
 }
 
 
@@ -33,6 +65,22 @@ pub enum Mode {
     Details(details::Options),
     GridDetails(grid_details::Options),
     Lines,
+    Zero,
+    Delimited(delimited::Options),
+    Stat(stat::Options),
+}
+
+
+/// Whether to send the output through a pager.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Paging {
+
+    /// Write the output straight to the terminal.
+    Never,
+
+    /// Run the given pager command, and write the output to it instead, if
+    /// there’s too much output to fit in the terminal.
+    Auto(String),
 }
 
 
@@ -1,13 +1,25 @@
 use ansi_term::Style;
+use locale::Numeric as NumericLocale;
 
 use crate::fs::fields as f;
 use crate::output::cell::TextCell;
+use crate::output::render::SizeColours;
+use crate::output::table::{BlockSize, SizeFormat};
 
 
 impl f::Blocks {
-    pub fn render<C: Colours>(&self, colours: &C) -> TextCell {
+    pub fn render<C: Colours>(&self, colours: &C, block_size: BlockSize) -> TextCell {
         match self {
-            Self::Some(blk)  => TextCell::paint(colours.block_count(), blk.to_string()),
+            Self::Some(blk)  => TextCell::paint(colours.block_count(), block_size.scale(*blk).to_string()),
+            Self::None       => TextCell::blank(colours.no_blocks()),
+        }
+    }
+
+    /// Renders the space these blocks take up on disk, in the same way as
+    /// a file size, so it can be compared with the file’s apparent size.
+    pub fn render_human<C: Colours + SizeColours>(&self, colours: &C, size_format: SizeFormat, numerics: &NumericLocale) -> TextCell {
+        match self {
+            Self::Some(blk)  => f::Size::Some(blk.saturating_mul(BlockSize::REPORTED)).render(colours, size_format, numerics),
             Self::None       => TextCell::blank(colours.no_blocks()),
         }
     }
@@ -24,9 +36,13 @@ pub trait Colours {
 pub mod test {
     use ansi_term::Style;
     use ansi_term::Colour::*;
+    use locale::Numeric as NumericLocale;
+    use number_prefix::Prefix;
 
     use super::Colours;
     use crate::output::cell::TextCell;
+    use crate::output::render::SizeColours;
+    use crate::output::table::{BlockSize, SizeFormat};
     use crate::fs::fields as f;
 
 
@@ -37,13 +53,27 @@ pub mod test {
         fn no_blocks(&self)   -> Style { Green.italic() }
     }
 
+    impl SizeColours for TestColours {
+        fn size(&self, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
+        fn unit(&self, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
+        fn no_size(&self) -> Style { Black.italic() }
+        fn major(&self)   -> Style { Blue.on(Red) }
+        fn comma(&self)   -> Style { Green.italic() }
+        fn minor(&self)   -> Style { Cyan.on(Yellow) }
+    }
+
+    fn human(blox: f::Blocks, size_format: SizeFormat) -> String {
+        let cell = blox.render_human(&TestColours, size_format, &NumericLocale::english());
+        cell.contents.iter().map(|s| &**s).collect()
+    }
+
 
     #[test]
     fn blocklessness() {
         let blox = f::Blocks::None;
         let expected = TextCell::blank(Green.italic());
 
-        assert_eq!(expected, blox.render(&TestColours));
+        assert_eq!(expected, blox.render(&TestColours, BlockSize::default()));
     }
 
 
@@ -52,6 +82,58 @@ pub mod test {
         let blox = f::Blocks::Some(3005);
         let expected = TextCell::paint_str(Red.blink(), "3005");
 
-        assert_eq!(expected, blox.render(&TestColours));
+        assert_eq!(expected, blox.render(&TestColours, BlockSize::default()));
+    }
+
+
+    #[test]
+    fn kilobytes() {
+        let blox = f::Blocks::Some(3005);
+        let expected = TextCell::paint_str(Red.blink(), "1503");
+
+        assert_eq!(expected, blox.render(&TestColours, BlockSize(1024)));
+    }
+
+
+    #[test]
+    fn scaling() {
+        assert_eq!(BlockSize(512).scale(8), 8);
+        assert_eq!(BlockSize(1024).scale(8), 4);
+        assert_eq!(BlockSize(1024).scale(7), 4);
+        assert_eq!(BlockSize(4096).scale(1), 1);
+        assert_eq!(BlockSize(1_000_000).scale(1953), 1);
+        assert_eq!(BlockSize(1_000_000).scale(1954), 2);
+        assert_eq!(BlockSize(256).scale(3), 6);
+        assert_eq!(BlockSize(1024).scale(0), 0);
+    }
+
+
+    #[test]
+    fn raw_count() {
+        // A 4 KiB file, as most file systems would store it
+        assert_eq!(f::Blocks::Some(8).render(&TestColours, BlockSize::default()), TextCell::paint_str(Red.blink(), "8"));
+    }
+
+    #[test]
+    fn human_binary() {
+        assert_eq!(human(f::Blocks::Some(8), SizeFormat::BinaryBytes), "4.0Ki");
+        assert_eq!(human(f::Blocks::Some(2048), SizeFormat::BinaryBytes), "1.0Mi");
+    }
+
+    #[test]
+    fn human_decimal() {
+        assert_eq!(human(f::Blocks::Some(8), SizeFormat::DecimalBytes), "4.1k");
+        assert_eq!(human(f::Blocks::Some(3005), SizeFormat::DecimalBytes), "1.5M");
+    }
+
+    #[test]
+    fn human_bytes() {
+        assert_eq!(human(f::Blocks::Some(8), SizeFormat::JustBytes), "4,096");
+    }
+
+    #[test]
+    fn human_blocklessness() {
+        let blox = f::Blocks::None;
+        assert_eq!(blox.render_human(&TestColours, SizeFormat::DecimalBytes, &NumericLocale::english()), TextCell::blank(Green.italic()));
     }
 }
@@ -14,6 +14,45 @@ impl f::Git {
             ].into(),
         }
     }
+
+    /// Renders only the status of the file in the index, for when it gets
+    /// a column of its own, like the X in `git status --short`.
+    pub fn render_index(self, colours: &dyn Colours) -> TextCell {
+        TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![ self.staged.render(colours) ].into(),
+        }
+    }
+
+    /// Renders only the status of the file in the working tree, like the Y
+    /// in `git status --short`.
+    pub fn render_worktree(self, colours: &dyn Colours) -> TextCell {
+        TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![ self.unstaged.render(colours) ].into(),
+        }
+    }
+}
+
+
+impl f::GitRepo {
+    pub fn render(self, colours: &dyn Colours) -> TextCell {
+        let mut cell = if self.dirty { TextCell::paint_str(colours.modified(), "M") }
+                                else { TextCell::paint_str(colours.not_modified(), "-") };
+
+        cell.add_spaces(1);
+        cell.append(TextCell::paint(Style::default(), self.head));
+
+        if self.ahead > 0 {
+            cell.append(TextCell::paint(colours.new(), format!(" ↑{}", self.ahead)));
+        }
+
+        if self.behind > 0 {
+            cell.append(TextCell::paint(colours.deleted(), format!(" ↓{}", self.behind)));
+        }
+
+        cell
+    }
 }
 
 
@@ -28,6 +67,7 @@ impl f::GitStatus {
             Self::TypeChange   => colours.type_change().paint("T"),
             Self::Ignored      => colours.ignored().paint("I"),
             Self::Conflicted   => colours.conflicted().paint("U"),
+            Self::Unknown      => colours.unknown().paint("?"),
         }
     }
 }
@@ -43,6 +83,7 @@ pub trait Colours {
     fn type_change(&self) -> Style;
     fn ignored(&self) -> Style;
     fn conflicted(&self) -> Style;
+    fn unknown(&self) -> Style;
 }
 
 
@@ -67,6 +108,7 @@ pub mod test {
         fn type_change(&self)  -> Style { Fixed(95).normal() }
         fn ignored(&self)      -> Style { Fixed(96).normal() }
         fn conflicted(&self)   -> Style { Fixed(97).normal() }
+        fn unknown(&self)      -> Style { Fixed(98).normal() }
     }
 
 
@@ -106,4 +148,90 @@ pub mod test {
 
         assert_eq!(expected, stati.render(&TestColours))
     }
+
+
+    #[test]
+    fn git_staged_then_modified_index() {
+        let stati = f::Git {
+            staged:   f::GitStatus::New,
+            unstaged: f::GitStatus::Modified,
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![
+                Fixed(91).paint("N"),
+            ].into(),
+        };
+
+        assert_eq!(expected, stati.render_index(&TestColours));
+    }
+
+
+    #[test]
+    fn git_staged_then_modified_worktree() {
+        let stati = f::Git {
+            staged:   f::GitStatus::New,
+            unstaged: f::GitStatus::Modified,
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![
+                Fixed(92).paint("M"),
+            ].into(),
+        };
+
+        assert_eq!(expected, stati.render_worktree(&TestColours));
+    }
+
+
+    #[test]
+    fn git_unknown() {
+        let expected = TextCell {
+            width: DisplayWidth::from(2),
+            contents: vec![
+                Fixed(98).paint("?"),
+                Fixed(98).paint("?"),
+            ].into(),
+        };
+
+        assert_eq!(expected, f::Git::unknown().render(&TestColours));
+    }
+
+
+    #[test]
+    fn repo_clean() {
+        let repo = f::GitRepo { head: String::from("main"), dirty: false, ahead: 0, behind: 0 };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![
+                Fixed(90).paint("-"),
+                Style::default().paint(" "),
+                Style::default().paint("main"),
+            ].into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours))
+    }
+
+
+    #[test]
+    fn repo_dirty_diverged() {
+        let repo = f::GitRepo { head: String::from("dev"), dirty: true, ahead: 2, behind: 13 };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(12),
+            contents: vec![
+                Fixed(92).paint("M"),
+                Style::default().paint(" "),
+                Style::default().paint("dev"),
+                Fixed(91).paint(" ↑2"),
+                Fixed(93).paint(" ↓13"),
+            ].into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours))
+    }
 }
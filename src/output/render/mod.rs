@@ -26,7 +26,8 @@ pub use self::size::Colours as SizeColours;
 
 mod times;
 pub use self::times::Render as TimeRender;
-// times does too
+pub use self::times::Colours as TimeColours;
+pub use self::times::AgeRange;
 
 #[cfg(unix)]
 mod users;
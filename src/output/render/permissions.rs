@@ -15,6 +15,11 @@ impl f::PermissionsPlus {
            chars.push(colours.attribute().paint("@"));
         }
 
+        #[cfg(target_os = "linux")]
+        if self.capabilities {
+           chars.push(colours.attribute().paint("c"));
+        }
+
         // As these are all ASCII characters, we can guarantee that they’re
         // all going to be one character wide, and don’t need to compute the
         // cell’s display width.
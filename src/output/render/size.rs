@@ -17,22 +17,24 @@ impl f::Size {
             Self::DeviceIDs(ref ids)  => return ids.render(colours),
         };
 
-        let result = match size_format {
-            SizeFormat::DecimalBytes  => NumberPrefix::decimal(size as f64),
-            SizeFormat::BinaryBytes   => NumberPrefix::binary(size as f64),
+        let (result, unit) = match size_format {
+            SizeFormat::DecimalBytes  => (NumberPrefix::decimal(size as f64), ""),
+            SizeFormat::BinaryBytes   => (NumberPrefix::binary(size as f64), ""),
+            SizeFormat::SIBytes       => (NumberPrefix::decimal(size as f64), "B"),
+            SizeFormat::IECBytes      => (NumberPrefix::binary(size as f64), "B"),
             SizeFormat::JustBytes     => {
 
                 // Use the binary prefix to select a style.
-                let prefix = match NumberPrefix::binary(size as f64) {
-                    NumberPrefix::Standalone(_)   => None,
-                    NumberPrefix::Prefixed(p, _)  => Some(p),
-                };
+                let prefix = binary_prefix(size);
 
                 // But format the number directly using the locale.
                 let string = numerics.format_int(size);
 
                 return TextCell::paint(colours.size(prefix), string);
             }
+            SizeFormat::GroupedBytes  => {
+                return render_grouped(size, colours, binary_prefix(size));
+            }
         };
 
         let (prefix, n) = match result {
@@ -40,7 +42,7 @@ impl f::Size {
             NumberPrefix::Prefixed(p, n)  => (p, n),
         };
 
-        let symbol = prefix.symbol();
+        let symbol = [ prefix.symbol(), unit ].concat();
         let number = if n < 10_f64 {
             numerics.format_float(n, 1)
         } else {
@@ -56,6 +58,58 @@ impl f::Size {
             ].into(),
         }
     }
+
+    /// Renders this size as a percentage of the given total. Anything
+    /// without a size of its own, such as a directory or a device, gets a
+    /// blank cell.
+    pub fn render_percent<C: Colours>(self, colours: &C, total: u64, numerics: &NumericLocale) -> TextCell {
+        let size = match self {
+            Self::Some(s)  => s,
+            _              => return TextCell::blank(colours.no_size()),
+        };
+
+        let percent = if total == 0 { 0_f64 } else { size as f64 * 100_f64 / total as f64 };
+        let number = numerics.format_float(percent, 1);
+
+        TextCell {
+            width: DisplayWidth::from(&*number) + 1,
+            contents: vec![
+                colours.size(None).paint(number),
+                colours.unit(None).paint("%"),
+            ].into(),
+        }
+    }
+}
+
+
+/// The binary prefix a number of bytes would have, which picks the style
+/// to use for it even when it isn’t displayed with one.
+fn binary_prefix(size: u64) -> Option<Prefix> {
+    use number_prefix::NumberPrefix;
+
+    match NumberPrefix::binary(size as f64) {
+        NumberPrefix::Standalone(_)   => None,
+        NumberPrefix::Prefixed(p, _)  => Some(p),
+    }
+}
+
+/// Renders a number of bytes with a comma between each group of three
+/// digits, no matter what the locale’s separator is, so the commas can be
+/// painted in the same style as the ones between device IDs.
+fn render_grouped<C: Colours>(size: u64, colours: &C, prefix: Option<Prefix>) -> TextCell {
+    let digits = size.to_string();
+    let first = (digits.len() - 1) % 3 + 1;
+
+    let mut contents = vec![ colours.size(prefix).paint(digits[.. first].to_string()) ];
+    for start in (first .. digits.len()).step_by(3) {
+        contents.push(colours.comma().paint(","));
+        contents.push(colours.size(prefix).paint(digits[start .. start + 3].to_string()));
+    }
+
+    TextCell {
+        width: DisplayWidth::from(digits.len() + (digits.len() - 1) / 3),
+        contents: contents.into(),
+    }
 }
 
 
@@ -165,6 +219,89 @@ pub mod test {
     }
 
 
+    #[test]
+    fn file_grouped_bytes() {
+        let file = f::Size::Some(1_048_576);
+        let expected = TextCell {
+            width: DisplayWidth::from(9),
+            contents: vec![
+                Fixed(66).paint("1"),
+                Green.italic().paint(","),
+                Fixed(66).paint("048"),
+                Green.italic().paint(","),
+                Fixed(66).paint("576"),
+            ].into(),
+        };
+
+        assert_eq!(expected, file.render(&TestColours, SizeFormat::GroupedBytes, &NumericLocale::english()));
+    }
+
+
+    /// The unit a size gets displayed with, without its number.
+    fn unit(size: u64, size_format: SizeFormat) -> String {
+        let cell = f::Size::Some(size).render(&TestColours, size_format, &NumericLocale::english());
+        cell.contents.iter().skip(1).map(|s| &**s).collect::<String>()
+    }
+
+    #[test]
+    fn si_units() {
+        assert_eq!(unit(1_000, SizeFormat::SIBytes), "kB");
+        assert_eq!(unit(1_000_000, SizeFormat::SIBytes), "MB");
+        assert_eq!(unit(1_000_000_000, SizeFormat::SIBytes), "GB");
+    }
+
+    #[test]
+    fn iec_units() {
+        assert_eq!(unit(1 << 10, SizeFormat::IECBytes), "KiB");
+        assert_eq!(unit(1 << 20, SizeFormat::IECBytes), "MiB");
+        assert_eq!(unit(1 << 30, SizeFormat::IECBytes), "GiB");
+    }
+
+    #[test]
+    fn units_match_base() {
+        // A thousand bytes is a kilobyte, but not yet a kibibyte.
+        assert_eq!(unit(1_000, SizeFormat::SIBytes), "kB");
+        assert_eq!(unit(1_000, SizeFormat::IECBytes), "");
+        assert_eq!(unit(1_000_000, SizeFormat::IECBytes), "KiB");
+    }
+
+    #[test]
+    fn unit_width() {
+        let cell = f::Size::Some(1 << 20).render(&TestColours, SizeFormat::IECBytes, &NumericLocale::english());
+        assert_eq!(cell.width, DisplayWidth::from(6));
+    }
+
+
+    fn grouped(size: u64) -> String {
+        let cell = f::Size::Some(size).render(&TestColours, SizeFormat::GroupedBytes, &NumericLocale::english());
+        let string = cell.contents.iter().map(|s| &**s).collect::<String>();
+        assert_eq!(*cell.width, string.len());
+        string
+    }
+
+    #[test]
+    fn grouped_small() {
+        assert_eq!(grouped(0), "0");
+        assert_eq!(grouped(7), "7");
+        assert_eq!(grouped(42), "42");
+        assert_eq!(grouped(999), "999");
+    }
+
+    #[test]
+    fn grouped_thousands() {
+        assert_eq!(grouped(1_000), "1,000");
+        assert_eq!(grouped(12_345), "12,345");
+        assert_eq!(grouped(999_999), "999,999");
+    }
+
+    #[test]
+    fn grouped_large() {
+        assert_eq!(grouped(1_000_000), "1,000,000");
+        assert_eq!(grouped(123_456_789_012), "123,456,789,012");
+        assert_eq!(grouped(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+
     #[test]
     fn device_ids() {
         let directory = f::Size::DeviceIDs(f::DeviceIDs { major: 10, minor: 80 });
@@ -179,4 +316,42 @@ pub mod test {
 
         assert_eq!(expected, directory.render(&TestColours, SizeFormat::JustBytes, &NumericLocale::english()))
     }
+
+
+    #[test]
+    fn file_percent() {
+        let file = f::Size::Some(423);
+        let expected = TextCell {
+            width: DisplayWidth::from(5),
+            contents: vec![
+                Fixed(66).paint("42.3"),
+                Fixed(77).bold().paint("%"),
+            ].into(),
+        };
+
+        assert_eq!(expected, file.render_percent(&TestColours, 1000, &NumericLocale::english()))
+    }
+
+
+    #[test]
+    fn empty_total_percent() {
+        let file = f::Size::Some(0);
+        let expected = TextCell {
+            width: DisplayWidth::from(4),
+            contents: vec![
+                Fixed(66).paint("0.0"),
+                Fixed(77).bold().paint("%"),
+            ].into(),
+        };
+
+        assert_eq!(expected, file.render_percent(&TestColours, 0, &NumericLocale::english()))
+    }
+
+
+    #[test]
+    fn directory_percent() {
+        let directory = f::Size::None;
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(expected, directory.render_percent(&TestColours, 1000, &NumericLocale::english()))
+    }
 }
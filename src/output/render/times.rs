@@ -1,30 +1,154 @@
-use std::time::SystemTime;
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
 
 use datetime::TimeZone;
 use ansi_term::Style;
+use log::*;
 
 use crate::output::cell::TextCell;
 use crate::output::time::TimeFormat;
 
 
 pub trait Render {
-    fn render(self, style: Style, tz: &Option<TimeZone>, format: TimeFormat) -> TextCell;
+    fn render<C: Colours>(self, colours: &C, ages: Option<AgeRange>, tz: &Option<TimeZone>, format: &TimeFormat) -> TextCell;
 }
 
 impl Render for Option<SystemTime> {
-    fn render(self, style: Style, tz: &Option<TimeZone>, format: TimeFormat) -> TextCell {
-        let datestamp = if let Some(time) = self {
-            if let Some(ref tz) = tz {
+    fn render<C: Colours>(self, colours: &C, ages: Option<AgeRange>, tz: &Option<TimeZone>, format: &TimeFormat) -> TextCell {
+        if let Some(time) = self {
+            let style = match (colours.date_of_time(time), ages) {
+                (Some(style), _)    => style,
+                (None, Some(ages))  => colours.date_of_age(ages.age(time)),
+                (None, None)        => colours.date(),
+            };
+
+            let datestamp = if let Some(ref tz) = tz {
                 format.format_zoned(time, tz)
             }
             else {
                 format.format_local(time)
-            }
+            };
+
+            TextCell::paint(style, datestamp)
         }
         else {
-            String::from("-")
-        };
+            render_missing(colours)
+        }
+    }
+}
+
+/// Renders the placeholder for a timestamp that a file doesn’t have, such
+/// as a created time on a file system that doesn’t record them. This can
+/// happen for every file in a listing, so the reason only gets logged once.
+fn render_missing<C: Colours>(colours: &C) -> TextCell {
+    static WARNING: Once = Once::new();
+    WARNING.call_once(|| {
+        warn!("A requested timestamp isn't available for some files, possibly because the file system doesn't record it");
+    });
+
+    TextCell::blank(colours.no_time())
+}
+
+
+pub trait Colours {
+    fn date(&self) -> Style;
+
+    /// The style for the placeholder shown instead of a timestamp that a
+    /// file doesn’t have.
+    fn no_time(&self) -> Style;
+
+    /// The style for a timestamp of the given age, from `0.0` for the
+    /// newest in the listing to `1.0` for the oldest.
+    fn date_of_age(&self, age: f32) -> Style;
+
+    /// The style for a timestamp depending on how long ago it was, if
+    /// timestamps get coloured that way, which overrides the other two.
+    fn date_of_time(&self, time: SystemTime) -> Option<Style>;
+}
+
+
+/// The range of timestamps shown in one column of a listing, used to
+/// colour each one by how old it is compared to the others.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct AgeRange {
+    oldest: SystemTime,
+    newest: SystemTime,
+}
+
+impl AgeRange {
+
+    /// Works out the range of the given timestamps, returning nothing if
+    /// there aren’t any.
+    pub fn of<I: IntoIterator<Item = SystemTime>>(times: I) -> Option<Self> {
+        times.into_iter().fold(None, |range, time| match range {
+            None     => Some(Self { oldest: time, newest: time }),
+            Some(r)  => Some(Self { oldest: r.oldest.min(time), newest: r.newest.max(time) }),
+        })
+    }
+
+    /// How old the given time is, from `0.0` for the newest time in the
+    /// range to `1.0` for the oldest.
+    pub fn age(self, time: SystemTime) -> f32 {
+        let span = self.newest.duration_since(self.oldest).unwrap_or(Duration::ZERO);
+        if span.is_zero() {
+            return 0.0;
+        }
+
+        let age = self.newest.duration_since(time).unwrap_or(Duration::ZERO);
+        (age.as_secs_f64() / span.as_secs_f64()).min(1.0) as f32
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    struct TestColours;
+
+    impl Colours for TestColours {
+        fn date(&self)                          -> Style { Blue.normal() }
+        fn no_time(&self)                       -> Style { Black.bold() }
+        fn date_of_age(&self, _: f32)           -> Style { Green.normal() }
+        fn date_of_time(&self, _: SystemTime)   -> Option<Style> { None }
+    }
+
+    #[test]
+    fn missing_time() {
+        let time: Option<SystemTime> = None;
+        let cell = time.render(&TestColours, None, &None, &TimeFormat::ISOFormat);
+        assert_eq!(cell, TextCell::blank(Black.bold()));
+    }
+
+    #[test]
+    fn missing_time_with_ages() {
+        let time: Option<SystemTime> = None;
+        let ages = AgeRange::of(vec![ at(100), at(200) ]);
+        let cell = time.render(&TestColours, ages, &None, &TimeFormat::ISOFormat);
+        assert_eq!(cell, TextCell::blank(Black.bold()));
+    }
+
+    #[test]
+    fn no_times() {
+        assert_eq!(AgeRange::of(Vec::new()), None);
+    }
+
+    #[test]
+    fn newest_and_oldest() {
+        let range = AgeRange::of(vec![ at(200), at(100), at(300) ]).unwrap();
+        assert_eq!(range.age(at(300)), 0.0);
+        assert_eq!(range.age(at(200)), 0.5);
+        assert_eq!(range.age(at(100)), 1.0);
+    }
 
-        TextCell::paint(style, datestamp)
+    #[test]
+    fn all_the_same() {
+        let range = AgeRange::of(vec![ at(100), at(100) ]).unwrap();
+        assert_eq!(range.age(at(100)), 0.0);
     }
 }
@@ -0,0 +1,230 @@
+//! The stat view, which describes each file in a block of labelled lines.
+
+use std::io::{self, Write};
+
+use ansi_term::Style;
+
+use crate::fs::{File, FileTarget};
+use crate::fs::fields as f;
+use crate::fs::filter::FileFilter;
+use crate::output::cell::TextCell;
+use crate::output::file_name::{Absolute, Options as FileStyle};
+use crate::output::table::{Column, Options as TableOptions, Table, TimeType};
+use crate::theme::Theme;
+
+
+/// The stat view prints everything exa knows about each file as a block of
+/// lines, one for each field, like the `stat` command does, rather than as
+/// a row in a table. Directories are described themselves, rather than
+/// having their contents listed.
+///
+/// The fields are the same as the details view’s columns, and get rendered
+/// the same way, so they take the same formatting options.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Options {
+    pub table: TableOptions,
+}
+
+
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub theme: &'a Theme,
+    pub file_style: &'a FileStyle,
+    pub opts: &'a Options,
+    pub filter: &'a FileFilter,
+}
+
+/// The width of the longest label, “Permissions”, which all the others get
+/// padded to so that the values line up.
+const LABEL_WIDTH: usize = 11;
+
+impl Render<'_> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files);
+        let table = Table::new(&self.opts.table, None, self.theme);
+
+        for (i, file) in self.files.iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+
+            self.render_file(w, &table, file)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_file<W: Write>(&self, w: &mut W, table: &Table<'_>, file: &File<'_>) -> io::Result<()> {
+        let name = self.file_style.for_file(file, self.theme).paint().promote();
+        write_field(w, "File", &name)?;
+
+        if let Some(path) = Absolute::On.path(&file.path) {
+            write_field(w, "Path", &TextCell::paint(Style::default(), path.display().to_string()))?;
+        }
+
+        write_field(w, "Type", &TextCell::paint_str(Style::default(), type_name(file.type_char())))?;
+        write_field(w, "Size", &table.display(file, Column::FileSize, false))?;
+
+        #[cfg(unix)]
+        write_field(w, "On disk", &table.display(file, Column::Blocks, false))?;
+
+        let mut permissions = table.display(file, Column::Permissions, false);
+        #[cfg(unix)]
+        {
+            permissions.push(Style::default().paint(" ("), 2);
+            permissions.append(table.display(file, Column::Octal, false));
+            permissions.push(Style::default().paint(")"), 1);
+        }
+        write_field(w, "Permissions", &permissions)?;
+
+        #[cfg(unix)]
+        {
+            write_field(w, "User", &table.display(file, Column::User, false))?;
+            write_field(w, "Group", &table.display(file, Column::Group, false))?;
+            write_field(w, "Inode", &table.display(file, Column::Inode, false))?;
+            write_field(w, "Links", &table.display(file, Column::HardLinks, false))?;
+        }
+
+        write_field(w, "Modified", &table.display(file, Column::Timestamp(TimeType::Modified), false))?;
+        write_field(w, "Changed", &table.display(file, Column::Timestamp(TimeType::Changed), false))?;
+        write_field(w, "Accessed", &table.display(file, Column::Timestamp(TimeType::Accessed), false))?;
+        write_field(w, "Created", &table.display(file, Column::Timestamp(TimeType::Created), false))?;
+
+        if file.is_link() {
+            let target = match file.link_target() {
+                FileTarget::Ok(target)   => TextCell::paint(self.theme.ui.symlink_path, target.path.display().to_string()),
+                FileTarget::Broken(path) => TextCell::paint(self.theme.ui.broken_symlink, path.display().to_string()),
+                FileTarget::Err(e)       => TextCell::paint(self.theme.ui.broken_symlink, e.to_string()),
+            };
+
+            write_field(w, "Target", &target)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes one line of a file’s block, with the label padded out so that
+/// the values of every line start in the same column.
+fn write_field<W: Write>(w: &mut W, label: &str, value: &TextCell) -> io::Result<()> {
+    let padding = " ".repeat(LABEL_WIDTH - label.len() + 1);
+    writeln!(w, "{}{}", [ label, ":", &padding ].concat(), value.strings())
+}
+
+/// A description of the type of a file, for its “Type” line.
+fn type_name(file_type: f::Type) -> &'static str {
+    match file_type {
+        f::Type::Directory    => "directory",
+        f::Type::File         => "regular file",
+        f::Type::Link         => "symbolic link",
+        f::Type::Pipe         => "named pipe",
+        f::Type::Socket       => "socket",
+        f::Type::CharDevice   => "character device",
+        f::Type::BlockDevice  => "block device",
+        f::Type::Special      => "special file",
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::fs::test::temp_dir;
+    use crate::output::table::{Columns, SizeFormat, TimeTypes, UserFormat};
+    use crate::output::time::TimeFormat;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    /// Renders the file at the given path, and returns the lines of output.
+    fn stat_lines(path: PathBuf) -> Vec<String> {
+        let theme = ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false);
+
+        let file_style = FileStyle::default();
+
+        let columns = Columns {
+            time_types: TimeTypes { modified: true, changed: true, accessed: true, created: true },
+            .. Columns::default()
+        };
+
+        let opts = Options {
+            table: TableOptions {
+                size_format: SizeFormat::JustBytes,
+                human_blocks: true,
+                time_formats: vec![ TimeFormat::Precise ],
+                user_format: UserFormat::Numeric,
+                columns,
+                .. TableOptions::default()
+            },
+        };
+
+        let filter = FileFilter::default();
+
+        let files = vec![ File::from_args(path, None, None).unwrap() ];
+        let render = Render { files, theme: &theme, file_style: &file_style, opts: &opts, filter: &filter };
+        let mut output = Vec::new();
+        render.render(&mut output).unwrap();
+
+        String::from_utf8(output).unwrap().lines().map(String::from).collect()
+    }
+
+    /// Returns the value on the line with the given label, if there is one.
+    fn field<'l>(lines: &'l [String], label: &str) -> Option<&'l str> {
+        let prefix = [ label, ":" ].concat();
+        lines.iter()
+             .find(|line| line.starts_with(&prefix))
+             .map(|line| line[prefix.len() ..].trim_start())
+    }
+
+    #[test]
+    fn regular_file() {
+        let root = temp_dir("stat-file");
+        let path = root.join("notes.txt");
+        fs::write(&path, "hello").unwrap();
+
+        let lines = stat_lines(path.clone());
+        let labels = lines.iter().map(|line| line.split(':').next().unwrap()).collect::<Vec<_>>();
+        assert_eq!(labels, vec![ "File", "Path", "Type", "Size", "On disk", "Permissions", "User", "Group",
+                                 "Inode", "Links", "Modified", "Changed", "Accessed", "Created" ]);
+
+        assert_eq!(field(&lines, "File"), path.to_str());
+        assert_eq!(field(&lines, "Path").map(PathBuf::from), Some(path.canonicalize().unwrap()));
+        assert_eq!(field(&lines, "Type"), Some("regular file"));
+        assert_eq!(field(&lines, "Size"), Some("5"));
+        assert_eq!(field(&lines, "Links"), Some("1"));
+        assert!(field(&lines, "Permissions").unwrap().starts_with(".rw"));
+        assert!(field(&lines, "Permissions").unwrap().ends_with(')'));
+        assert_eq!(field(&lines, "Target"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn symlink() {
+        let root = temp_dir("stat-link");
+        fs::write(root.join("target.txt"), "hello").unwrap();
+        std::os::unix::fs::symlink("target.txt", root.join("link")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", root.join("broken")).unwrap();
+
+        let lines = stat_lines(root.join("link"));
+        assert_eq!(field(&lines, "File"), root.join("link").to_str());
+        assert_eq!(field(&lines, "Type"), Some("symbolic link"));
+        assert_eq!(field(&lines, "Target"), Some("target.txt"));
+        assert_eq!(lines.last().map(|line| line.split(':').next().unwrap()), Some("Target"));
+
+        let lines = stat_lines(root.join("broken"));
+        assert_eq!(field(&lines, "Target"), Some("missing.txt"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
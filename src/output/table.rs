@@ -1,8 +1,13 @@
 use std::cmp::max;
+use std::collections::HashMap;
 use std::env;
+use std::fs;
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 #[cfg(unix)]
-use std::sync::{Mutex, MutexGuard};
+use std::sync::MutexGuard;
 
 use datetime::TimeZone;
 use zoneinfo_compiled::{CompiledData, Result as TZResult};
@@ -12,26 +17,67 @@ use log::*;
 #[cfg(unix)]
 use users::UsersCache;
 
-use crate::fs::{File, fields as f};
-use crate::fs::feature::git::GitCache;
+use crate::fs::{Dir, DotFilter, File, fields as f};
+use crate::fs::mounts::{self, MountedFs};
+use crate::fs::feature::git::{self, GitCache};
+use crate::fs::feature::xattr::FileAttributes;
+use crate::info::mime;
 use crate::output::cell::TextCell;
-use crate::output::render::TimeRender;
+use crate::output::render::{AgeRange, TimeRender};
 use crate::output::time::TimeFormat;
 use crate::theme::Theme;
 
 
 /// Options for displaying a table.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Default)]
 pub struct Options {
     pub size_format: SizeFormat,
-    pub time_format: TimeFormat,
+    pub size_percent: Option<SizePercent>,
+    pub block_size: BlockSize,
+
+    /// Whether the blocks column should show how much space each file
+    /// takes up on disk, with the size format, rather than a block count.
+    pub human_blocks: bool,
+
+    /// How to format the timestamp columns, in the order they’re displayed.
+    /// The last format gets used for any columns beyond the end.
+    pub time_formats: Vec<TimeFormat>,
+
     pub user_format: UserFormat,
     pub columns: Columns,
+
+    /// Whether symlinks should have the details of the files they point
+    /// to listed, instead of their own.
+    pub dereference: bool,
+}
+
+impl Options {
+
+    /// The format for the timestamp column at the given position, counting
+    /// only timestamp columns, which is the last one given if there aren’t
+    /// enough to go round.
+    pub fn time_format(&self, index: usize) -> &TimeFormat {
+        self.time_formats.get(index)
+            .or_else(|| self.time_formats.last())
+            .unwrap_or(&TimeFormat::DefaultFormat)
+    }
+
+    /// The total that file sizes should be shown as a percentage of, if
+    /// they should be, given the files in the directory being listed and the
+    /// total of the whole listing if it’s been worked out.
+    pub fn size_total<'a, 'f: 'a, I>(&self, files: I, listing_total: Option<u64>) -> Option<u64>
+    where I: IntoIterator<Item = &'a File<'f>>
+    {
+        match self.size_percent? {
+            SizePercent::PerDirectory  => Some(total_size(files)),
+            SizePercent::Global        => listing_total,
+        }
+    }
 }
 
 /// Extra columns to display in the table.
 #[allow(clippy::struct_excessive_bools)]
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Columns {
 
     /// At least one of these timestamps will be shown.
@@ -40,19 +86,93 @@ pub struct Columns {
     // The rest are just on/off
     pub inode: bool,
     pub links: bool,
+    pub link_groups: bool,
     pub blocks: bool,
     pub group: bool,
     pub git: bool,
+    pub git_repos: bool,
+    pub mounts: bool,
+    pub mime: bool,
+
+    /// Whether the names of each file’s extended attributes are listed in
+    /// a column of their own, rather than on lines of their own.
+    pub xattr_names: bool,
+
     pub octal: bool,
+    pub total_size: bool,
+
+    /// Whether directories have the number of entries in them shown in
+    /// the size column, instead of nothing.
+    pub dir_counts: bool,
+
+    /// Whether the Git status is split into two columns, one for the index
+    /// and one for the working tree, instead of sharing one.
+    pub git_split: bool,
 
     // Defaults to true:
     pub permissions: bool,
     pub filesize: bool,
     pub user: bool,
+
+    /// The columns that were picked by name with `--columns`, in the order
+    /// they should be displayed, which replaces the fixed order above.
+    pub order: Option<Vec<ColumnName>>,
+}
+
+impl Default for Columns {
+
+    /// By default, display the columns that the long view has when no
+    /// other columns are asked for: the permissions, size, user, and
+    /// modified time.
+    fn default() -> Self {
+        Self {
+            time_types: TimeTypes::default(),
+            inode: false,
+            links: false,
+            link_groups: false,
+            blocks: false,
+            group: false,
+            git: false,
+            git_repos: false,
+            mounts: false,
+            mime: false,
+            xattr_names: false,
+            octal: false,
+            total_size: false,
+            dir_counts: false,
+            git_split: false,
+            permissions: true,
+            filesize: true,
+            user: true,
+            order: None,
+        }
+    }
+}
+
+/// The columns that can be picked by name, each of which turns into one or
+/// more table columns.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ColumnName {
+    Permissions,
+    Octal,
+    HardLinks,
+    Inode,
+    Blocks,
+    User,
+    Group,
+    FileSize,
+    GitStatus,
+    Timestamps,
 }
 
 impl Columns {
     pub fn collect(&self, actually_enable_git: bool) -> Vec<Column> {
+        if let Some(order) = &self.order {
+            return order.iter()
+                        .flat_map(|name| self.named(*name, actually_enable_git))
+                        .collect();
+        }
+
         let mut columns = Vec::with_capacity(4);
 
         if self.inode {
@@ -74,10 +194,19 @@ impl Columns {
             columns.push(Column::HardLinks);
         }
 
+        if self.link_groups {
+            #[cfg(unix)]
+            columns.push(Column::LinkGroup);
+        }
+
         if self.filesize {
             columns.push(Column::FileSize);
         }
 
+        if self.total_size {
+            columns.push(Column::TotalSize);
+        }
+
         if self.blocks {
             #[cfg(unix)]
             columns.push(Column::Blocks);
@@ -93,28 +222,58 @@ impl Columns {
             columns.push(Column::Group);
         }
 
-        if self.time_types.modified {
-            columns.push(Column::Timestamp(TimeType::Modified));
+        columns.extend(self.time_types.columns());
+
+        if self.git && actually_enable_git {
+            columns.extend(self.git_columns());
         }
 
-        if self.time_types.changed {
-            columns.push(Column::Timestamp(TimeType::Changed));
+        if self.git_repos && cfg!(feature = "git") {
+            columns.push(Column::GitRepo);
         }
 
-        if self.time_types.created {
-            columns.push(Column::Timestamp(TimeType::Created));
+        if self.mounts {
+            columns.push(Column::Mount);
         }
 
-        if self.time_types.accessed {
-            columns.push(Column::Timestamp(TimeType::Accessed));
+        if self.mime {
+            columns.push(Column::Mime);
         }
 
-        if self.git && actually_enable_git {
-            columns.push(Column::GitStatus);
+        if self.xattr_names {
+            columns.push(Column::XattrNames);
         }
 
         columns
     }
+
+    /// The table columns that a column picked by name turns into. Columns
+    /// that can’t be shown on this platform, or a Git column without a
+    /// repository, produce nothing.
+    fn named(&self, name: ColumnName, actually_enable_git: bool) -> Vec<Column> {
+        match name {
+            ColumnName::Permissions  => vec![ Column::Permissions ],
+            ColumnName::FileSize     => vec![ Column::FileSize ],
+            ColumnName::GitStatus    => if actually_enable_git { self.git_columns() } else { Vec::new() },
+            ColumnName::Timestamps   => self.time_types.columns(),
+
+            #[cfg(unix)] ColumnName::Octal      => vec![ Column::Octal ],
+            #[cfg(unix)] ColumnName::HardLinks  => vec![ Column::HardLinks ],
+            #[cfg(unix)] ColumnName::Inode      => vec![ Column::Inode ],
+            #[cfg(unix)] ColumnName::Blocks     => vec![ Column::Blocks ],
+            #[cfg(unix)] ColumnName::User       => vec![ Column::User ],
+            #[cfg(unix)] ColumnName::Group      => vec![ Column::Group ],
+
+            #[cfg(not(unix))]
+            _ => Vec::new(),
+        }
+    }
+
+    /// The table columns that the Git status gets shown in.
+    fn git_columns(&self) -> Vec<Column> {
+        if self.git_split { vec![ Column::GitIndex, Column::GitWorktree ] }
+                     else { vec![ Column::GitStatus ] }
+    }
 }
 
 
@@ -123,6 +282,7 @@ impl Columns {
 pub enum Column {
     Permissions,
     FileSize,
+    TotalSize,
     Timestamp(TimeType),
     #[cfg(unix)]
     Blocks,
@@ -133,8 +293,16 @@ pub enum Column {
     #[cfg(unix)]
     HardLinks,
     #[cfg(unix)]
+    LinkGroup,
+    #[cfg(unix)]
     Inode,
     GitStatus,
+    GitIndex,
+    GitWorktree,
+    GitRepo,
+    Mount,
+    Mime,
+    XattrNames,
     #[cfg(unix)]
     Octal,
 }
@@ -154,7 +322,9 @@ impl Column {
     pub fn alignment(self) -> Alignment {
         match self {
             Self::FileSize   |
+            Self::TotalSize  |
             Self::HardLinks  |
+            Self::LinkGroup  |
             Self::Inode      |
             Self::Blocks     |
             Self::GitStatus  => Alignment::Right,
@@ -166,6 +336,7 @@ impl Column {
     pub fn alignment(&self) -> Alignment {
         match self {
             Self::FileSize   |
+            Self::TotalSize  |
             Self::GitStatus  => Alignment::Right,
             _                => Alignment::Left,
         }
@@ -180,6 +351,7 @@ impl Column {
             #[cfg(windows)]
             Self::Permissions   => "Mode",
             Self::FileSize      => "Size",
+            Self::TotalSize     => "Total",
             Self::Timestamp(t)  => t.header(),
             #[cfg(unix)]
             Self::Blocks        => "Blocks",
@@ -190,8 +362,16 @@ impl Column {
             #[cfg(unix)]
             Self::HardLinks     => "Links",
             #[cfg(unix)]
+            Self::LinkGroup     => "Linked",
+            #[cfg(unix)]
             Self::Inode         => "inode",
             Self::GitStatus     => "Git",
+            Self::GitIndex      => "Index",
+            Self::GitWorktree   => "Worktree",
+            Self::GitRepo       => "Repo",
+            Self::Mount         => "Filesystem",
+            Self::Mime          => "Type",
+            Self::XattrNames    => "Xattrs",
             #[cfg(unix)]
             Self::Octal         => "Octal",
         }
@@ -212,16 +392,116 @@ pub enum SizeFormat {
     /// “mebi”, or “gibi”.
     BinaryBytes,
 
+    /// Format the file size using the decimal prefixes and the unit symbol
+    /// from the SI standard, such as “kB”, “MB”, or “GB”.
+    SIBytes,
+
+    /// Format the file size using the binary prefixes and the unit symbol
+    /// from the IEC standard, such as “KiB”, “MiB”, or “GiB”.
+    IECBytes,
+
     /// Do no formatting and just display the size as a number of bytes.
     JustBytes,
+
+    /// Display the size as a number of bytes, with a comma between each
+    /// group of three digits, whatever the locale uses.
+    GroupedBytes,
 }
 
-/// Formatting options for user and group.
+/// The number of bytes that each block in the blocks column stands for.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct BlockSize(pub u64);
+
+impl BlockSize {
+
+    /// The size of the blocks that file systems count files in, which is
+    /// what gets shown if no other size is picked.
+    pub const REPORTED: u64 = 512;
+
+    /// Converts a number of reported blocks into a number of blocks of this
+    /// size, rounding up, so a file that takes up any space at all never
+    /// shows up as taking none.
+    pub fn scale(self, blocks: u64) -> u64 {
+        let bytes = u128::from(blocks) * u128::from(Self::REPORTED);
+        let size = u128::from(self.0);
+        u64::try_from((bytes + size - 1) / size).unwrap_or(u64::MAX)
+    }
+}
+
+impl Default for BlockSize {
+    fn default() -> Self {
+        Self(Self::REPORTED)
+    }
+}
+
+/// What file sizes should be shown as a percentage of, when they are shown
+/// relative to one another rather than as absolute sizes.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SizePercent {
+
+    /// Each size is a percentage of the total size of the files in the same
+    /// directory.
+    PerDirectory,
+
+    /// Each size is a percentage of the total size of every file being
+    /// listed, across all directories.
+    Global,
+}
+
+/// Adds up the sizes of the given files, for use as the baseline when
+/// showing sizes as percentages. Directories and devices have no size, so
+/// they don’t count towards it.
+pub fn total_size<'a, 'f: 'a, I>(files: I) -> u64
+where I: IntoIterator<Item = &'a File<'f>>
+{
+    files.into_iter()
+         .map(|file| match file.size() {
+             f::Size::Some(size)  => size,
+             _                    => 0,
+         })
+         .sum()
+}
+
+/// Tags each set of hard-linked files among the given files with a number,
+/// starting from 1 in the order the sets first appear, keyed by the device
+/// and inode they share. Only files with more than one link can be in a
+/// set, and a set needs at least two of them to be listed to count.
+#[cfg(unix)]
+pub fn link_groups<'a, 'f: 'a, I>(files: I) -> HashMap<(u64, u64), usize>
+where I: IntoIterator<Item = &'a File<'f>>
+{
+    let mut keys = Vec::new();
+    let mut counts = HashMap::new();
+
+    for file in files {
+        if ! file.links().multiple {
+            continue;
+        }
+
+        if let Some(dev) = file.device_id() {
+            let key = (dev, file.inode().0);
+            let count = counts.entry(key).or_insert(0);
+            if *count == 0 {
+                keys.push(key);
+            }
+            *count += 1;
+        }
+    }
+
+    keys.into_iter()
+        .filter(|key| counts[key] > 1)
+        .enumerate()
+        .map(|(index, key)| (key, index + 1))
+        .collect()
+}
+
+/// Formatting options for user and group.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
 pub enum UserFormat {
     /// The UID / GID
     Numeric,
     /// Show the name
+    #[default]
     Name,
 }
 
@@ -261,6 +541,16 @@ impl TimeType {
             Self::Created   => "Date Created",
         }
     }
+
+    /// Returns this timestamp of the given file, if it has one.
+    pub fn of(self, file: &File<'_>) -> Option<SystemTime> {
+        match self {
+            Self::Modified  => file.modified_time(),
+            Self::Changed   => file.changed_time(),
+            Self::Accessed  => file.accessed_time(),
+            Self::Created   => file.created_time(),
+        }
+    }
 }
 
 
@@ -292,6 +582,32 @@ impl Default for TimeTypes {
     }
 }
 
+impl TimeTypes {
+
+    /// The timestamp columns to display, in the order they appear.
+    fn columns(self) -> Vec<Column> {
+        let mut columns = Vec::new();
+
+        if self.modified {
+            columns.push(Column::Timestamp(TimeType::Modified));
+        }
+
+        if self.changed {
+            columns.push(Column::Timestamp(TimeType::Changed));
+        }
+
+        if self.created {
+            columns.push(Column::Timestamp(TimeType::Created));
+        }
+
+        if self.accessed {
+            columns.push(Column::Timestamp(TimeType::Accessed));
+        }
+
+        columns
+    }
+}
+
 
 /// The **environment** struct contains any data that could change between
 /// running instances of exa, depending on the user’s computer’s configuration.
@@ -389,15 +705,101 @@ lazy_static! {
 }
 
 
+/// The recursive sizes of directories that have been worked out so far, so
+/// that a directory listed alongside (or underneath) its parent doesn’t have
+/// to be walked a second time.
+struct TotalSizes {
+
+    /// Whether files starting with a dot should be counted.
+    dotfiles: bool,
+
+    cache: Mutex<HashMap<PathBuf, u64>>,
+}
+
+impl TotalSizes {
+
+    /// Returns the total size of every file underneath the given directory,
+    /// walking it if it hasn’t been walked already. Symlinks are never
+    /// followed, so this can’t get stuck in a cycle, and anything that can’t
+    /// be read is skipped.
+    fn of(&self, path: &Path) -> u64 {
+        if let Some(total) = self.cache.lock().unwrap().get(path) {
+            return *total;
+        }
+
+        let mut total = 0;
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            if ! self.dotfiles && entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            // `DirEntry::metadata` doesn’t traverse symlinks.
+            match entry.metadata() {
+                Ok(m) if m.is_dir()  => total += self.of(&entry.path()),
+                Ok(m)                => total += m.len(),
+                Err(_)               => {},
+            }
+        }
+
+        self.cache.lock().unwrap().insert(path.to_path_buf(), total);
+        total
+    }
+}
+
+
+/// The numbers of entries in directories that have been counted so far, so
+/// that a directory listed more than once in the same table, such as in a
+/// tree that it’s both in and underneath, only gets read once.
+struct DirCounts {
+
+    /// Which files should be counted.
+    dots: DotFilter,
+
+    cache: Mutex<HashMap<PathBuf, Option<usize>>>,
+}
+
+impl DirCounts {
+
+    /// Returns the number of entries directly inside the given directory,
+    /// reading it if it hasn’t been read already, or nothing if it can’t
+    /// be read.
+    fn of(&self, path: &Path) -> Option<usize> {
+        if let Some(count) = self.cache.lock().unwrap().get(path) {
+            return *count;
+        }
+
+        let count = match Dir::read_dir(path.to_path_buf()) {
+            Ok(dir)  => Some(dir.count(self.dots)),
+            Err(e)   => {
+                debug!("Couldn't count the entries of {}: {}", path.display(), e);
+                None
+            }
+        };
+
+        self.cache.lock().unwrap().insert(path.to_path_buf(), count);
+        count
+    }
+}
+
+
 pub struct Table<'a> {
     columns: Vec<Column>,
     theme: &'a Theme,
     env: &'a Environment,
     widths: TableWidths,
-    time_format: TimeFormat,
+    time_formats: Vec<(TimeType, TimeFormat)>,
     size_format: SizeFormat,
+    block_size: BlockSize,
+    human_blocks: bool,
+    size_total: Option<u64>,
+    total_sizes: TotalSizes,
+    dir_counts: Option<DirCounts>,
     user_format: UserFormat,
     git: Option<&'a GitCache>,
+    age_ranges: Vec<(TimeType, AgeRange)>,
+    #[cfg(unix)]
+    link_groups: HashMap<(u64, u64), usize>,
+    dereference: bool,
 }
 
 #[derive(Clone)]
@@ -405,22 +807,92 @@ pub struct Row {
     cells: Vec<TextCell>,
 }
 
+impl Row {
+    pub fn cells(&self) -> &[TextCell] {
+        &self.cells
+    }
+}
+
 impl<'a, 'f> Table<'a> {
     pub fn new(options: &'a Options, git: Option<&'a GitCache>, theme: &'a Theme) -> Table<'a> {
         let columns = options.columns.collect(git.is_some());
         let widths = TableWidths::zero(columns.len());
         let env = &*ENVIRONMENT;
 
+        let time_formats = columns.iter()
+            .filter_map(|c| match c { Column::Timestamp(t) => Some(*t), _ => None })
+            .enumerate()
+            .map(|(i, t)| (t, options.time_format(i).clone()))
+            .collect();
+
         Table {
             theme,
             widths,
             columns,
             git,
             env,
-            time_format: options.time_format,
+            time_formats,
             size_format: options.size_format,
+            block_size: options.block_size,
+            human_blocks: options.human_blocks,
+            size_total: None,
+            total_sizes: TotalSizes { dotfiles: false, cache: Mutex::new(HashMap::new()) },
+            dir_counts: if options.columns.dir_counts { Some(DirCounts { dots: DotFilter::JustFiles, cache: Mutex::new(HashMap::new()) }) }
+                                                 else { None },
             user_format: options.user_format,
+            age_ranges: Vec::new(),
+            #[cfg(unix)]
+            link_groups: HashMap::new(),
+            dereference: options.dereference,
+        }
+    }
+
+    /// Sets which files get counted when working out the total sizes of
+    /// directories, or the numbers of entries in them, which should match
+    /// the files being listed.
+    pub fn set_dot_filter(&mut self, dots: DotFilter) {
+        self.total_sizes.dotfiles = dots != DotFilter::JustFiles;
+
+        if let Some(counts) = &mut self.dir_counts {
+            counts.dots = dots;
+        }
+    }
+
+    /// Sets the total that file sizes get shown as a percentage of. Until
+    /// this is called, sizes are shown as they are.
+    pub fn set_size_total(&mut self, total: u64) {
+        self.size_total = Some(total);
+    }
+
+    /// Sets the ranges that the timestamps in each column are coloured
+    /// relative to, if they’re being coloured by age, from the files in the
+    /// directory being listed.
+    pub fn set_age_ranges<'i, 'g: 'i, I>(&mut self, files: I)
+    where I: IntoIterator<Item = &'i File<'g>> + Clone
+    {
+        if ! self.theme.age_scale {
+            return;
+        }
+
+        self.age_ranges = self.columns.iter()
+            .filter_map(|c| match c { Column::Timestamp(t) => Some(*t), _ => None })
+            .filter_map(|t| AgeRange::of(files.clone().into_iter().filter_map(|f| t.of(f))).map(|r| (t, r)))
+            .collect();
+    }
+
+    /// Numbers the sets of hard-linked files among the files in the
+    /// directory being listed, if they’re being shown in a column, so that
+    /// each file can be tagged with the set it belongs to.
+    pub fn set_link_groups<'i, 'g: 'i, I>(&mut self, files: I)
+    where I: IntoIterator<Item = &'i File<'g>>
+    {
+        #[cfg(unix)]
+        if self.columns.iter().any(|c| matches!(c, Column::LinkGroup)) {
+            self.link_groups = link_groups(files);
         }
+
+        #[cfg(not(unix))]
+        let _ = files;
     }
 
     pub fn widths(&self) -> &TableWidths {
@@ -436,6 +908,9 @@ impl<'a, 'f> Table<'a> {
     }
 
     pub fn row_for_file(&self, file: &File<'_>, xattrs: bool) -> Row {
+        let target = if self.dereference { file.dereferenced() } else { None };
+        let file = target.as_ref().unwrap_or(file);
+
         let cells = self.columns.iter()
                         .map(|c| self.display(file, *c, xattrs))
                         .collect();
@@ -455,6 +930,8 @@ impl<'a, 'f> Table<'a> {
             #[cfg(windows)]
             attributes: file.attributes(),
             xattrs,
+            #[cfg(target_os = "linux")]
+            capabilities: file.has_capabilities(),
         }
     }
 
@@ -465,25 +942,43 @@ impl<'a, 'f> Table<'a> {
         }
     }
 
-    fn display(&self, file: &File<'_>, column: Column, xattrs: bool) -> TextCell {
+    pub fn display(&self, file: &File<'_>, column: Column, xattrs: bool) -> TextCell {
         match column {
             Column::Permissions => {
                 self.permissions_plus(file, xattrs).render(self.theme)
             }
-            Column::FileSize => {
-                file.size().render(self.theme, self.size_format, &self.env.numeric)
+            Column::FileSize if file.is_directory() && self.dir_counts.is_some() => {
+                self.dir_count(file)
+            }
+            Column::FileSize => match self.size_total {
+                Some(total)  => self.file_size(file).render_percent(self.theme, total, &self.env.numeric),
+                None         => self.file_size(file).render(self.theme, self.size_format, &self.env.numeric),
+            },
+            Column::TotalSize => {
+                self.total_size(file).render(self.theme, self.size_format, &self.env.numeric)
             }
             #[cfg(unix)]
             Column::HardLinks => {
                 file.links().render(self.theme, &self.env.numeric)
             }
             #[cfg(unix)]
+            Column::LinkGroup => {
+                match file.device_id().and_then(|dev| self.link_groups.get(&(dev, file.inode().0))) {
+                    Some(tag)  => TextCell::paint(self.theme.ui.links.multi_link_file, tag.to_string()),
+                    None       => TextCell::blank(self.theme.ui.punctuation),
+                }
+            }
+            #[cfg(unix)]
             Column::Inode => {
                 file.inode().render(self.theme.ui.inode)
             }
             #[cfg(unix)]
+            Column::Blocks if self.human_blocks => {
+                file.blocks().render_human(self.theme, self.size_format, &self.env.numeric)
+            }
+            #[cfg(unix)]
             Column::Blocks => {
-                file.blocks().render(self.theme)
+                file.blocks().render(self.theme, self.block_size)
             }
             #[cfg(unix)]
             Column::User => {
@@ -496,26 +991,94 @@ impl<'a, 'f> Table<'a> {
             Column::GitStatus => {
                 self.git_status(file).render(self.theme)
             }
+            Column::GitIndex => {
+                self.git_status(file).render_index(self.theme)
+            }
+            Column::GitWorktree => {
+                self.git_status(file).render_worktree(self.theme)
+            }
+            Column::GitRepo => {
+                self.git_repo(file).map(|r| r.render(self.theme)).unwrap_or_default()
+            }
+            Column::Mount => {
+                match self.mount(file) {
+                    Some(m)  => TextCell::paint(self.theme.ui.filesystem, m.fstype.clone()),
+                    None     => TextCell::blank(self.theme.ui.punctuation),
+                }
+            }
+            Column::Mime => {
+                match mime::mime_type(file) {
+                    Some(m)  => TextCell::paint_str(self.theme.ui.mime_type, m),
+                    None     => TextCell::blank(self.theme.ui.punctuation),
+                }
+            }
+            Column::XattrNames => {
+                self.xattr_names(file)
+            }
             #[cfg(unix)]
             Column::Octal => {
                 self.octal_permissions(file).render(self.theme.ui.octal)
             }
 
-            Column::Timestamp(TimeType::Modified)  => {
-                file.modified_time().render(self.theme.ui.date, &self.env.tz, self.time_format)
-            }
-            Column::Timestamp(TimeType::Changed)   => {
-                file.changed_time().render(self.theme.ui.date, &self.env.tz, self.time_format)
-            }
-            Column::Timestamp(TimeType::Created)   => {
-                file.created_time().render(self.theme.ui.date, &self.env.tz, self.time_format)
-            }
-            Column::Timestamp(TimeType::Accessed)  => {
-                file.accessed_time().render(self.theme.ui.date, &self.env.tz, self.time_format)
+            Column::Timestamp(time_type)  => {
+                let ages = self.age_ranges.iter().find(|(t, _)| *t == time_type).map(|(_, r)| *r);
+                let format = self.time_formats.iter().find(|(t, _)| *t == time_type).map_or(&TimeFormat::DefaultFormat, |(_, f)| f);
+                time_type.of(file).render(self.theme, ages, &self.env.tz, format)
             }
         }
     }
 
+    fn file_size(&self, file: &File<'_>) -> f::Size {
+        // When dereferencing, any file that’s still a link by this point is
+        // a broken one, which has no target to take a size from.
+        if self.dereference && file.is_link() {
+            f::Size::None
+        }
+        else {
+            file.size()
+        }
+    }
+
+    fn xattr_names(&self, file: &File<'_>) -> TextCell {
+        let names = match file.path.attributes() {
+            Ok(attrs)  => attrs.into_iter().map(|a| a.name).collect::<Vec<_>>(),
+            Err(_)     => Vec::new(),
+        };
+
+        if names.is_empty() {
+            TextCell::blank(self.theme.ui.punctuation)
+        }
+        else {
+            TextCell::paint(self.theme.ui.perms.attribute, names.join(","))
+        }
+    }
+
+    fn dir_count(&self, file: &File<'_>) -> TextCell {
+        let count = match (&self.dir_counts, &file.archived) {
+            (Some(counts), Some(_))  => file.to_dir().ok().map(|dir| dir.count(counts.dots)),
+            (Some(counts), None)     => counts.of(&file.path),
+            (None, _)                => None,
+        };
+
+        match count {
+            Some(1)      => TextCell::paint(self.theme.ui.dir_count, String::from("1 entry")),
+            Some(count)  => TextCell::paint(self.theme.ui.dir_count, self.env.numeric.format_int(count) + " entries"),
+            None         => TextCell::blank(self.theme.ui.punctuation),
+        }
+    }
+
+    fn total_size(&self, file: &File<'_>) -> f::Size {
+        if ! file.is_directory() {
+            f::Size::None
+        }
+        else if let Some(archived) = &file.archived {
+            f::Size::Some(archived.total_size(self.total_sizes.dotfiles))
+        }
+        else {
+            f::Size::Some(self.total_sizes.of(&file.path))
+        }
+    }
+
     fn git_status(&self, file: &File<'_>) -> f::Git {
         debug!("Getting Git status for file {:?}", file.path);
 
@@ -524,6 +1087,37 @@ impl<'a, 'f> Table<'a> {
             .unwrap_or_default()
     }
 
+    fn git_repo(&self, file: &File<'_>) -> Option<f::GitRepo> {
+        if file.is_directory() {
+            git::repo_summary(&file.path)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Finds the file system the given file is on. A symlink is on the
+    /// same one as the directory containing it, so only the parent gets
+    /// resolved to an absolute path, rather than the file itself.
+    fn mount(&self, file: &File<'_>) -> Option<&'static MountedFs> {
+        let mounts = mounts::mount_points();
+        if mounts.is_empty() {
+            return None;
+        }
+
+        let parent = match file.path.parent() {
+            Some(p) if ! p.as_os_str().is_empty()  => p,
+            _                                      => Path::new("."),
+        };
+
+        let path = match file.path.file_name() {
+            Some(name)  => fs::canonicalize(parent).ok()?.join(name),
+            None        => fs::canonicalize(&file.path).ok()?,
+        };
+
+        mounts::mount_for(mounts, &path)
+    }
+
     pub fn render(&self, row: Row) -> TextCell {
         let mut cell = TextCell::default();
 
@@ -578,3 +1172,325 @@ impl TableWidths {
         self.0.len() + self.0.iter().sum::<usize>()
     }
 }
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod link_groups_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+
+    fn tags(name: &str, links: &[(&str, &str)], files: &[&str]) -> Vec<Option<usize>> {
+        let root = temp_dir(&[ "link-groups-", name ].concat());
+
+        for file in files {
+            if ! links.iter().any(|(link, _)| link == file) {
+                fs::write(root.join(file), "").unwrap();
+            }
+        }
+
+        for (link, original) in links {
+            fs::hard_link(root.join(original), root.join(link)).unwrap();
+        }
+
+        let files = files.iter()
+                         .map(|name| File::from_args(root.join(name), None, None).unwrap())
+                         .collect::<Vec<_>>();
+
+        let groups = link_groups(&files);
+        let tags = files.iter()
+                        .map(|f| groups.get(&(f.device_id().unwrap(), f.inode().0)).copied())
+                        .collect();
+
+        fs::remove_dir_all(root).unwrap();
+        tags
+    }
+
+    #[test]
+    fn no_links() {
+        assert_eq!(tags("none", &[], &[ "a", "b" ]), vec![ None, None ]);
+    }
+
+    #[test]
+    fn one_group() {
+        assert_eq!(tags("one", &[ ("b", "a") ], &[ "a", "b", "c" ]), vec![ Some(1), Some(1), None ]);
+    }
+
+    #[test]
+    fn two_groups() {
+        assert_eq!(tags("two", &[ ("c", "a"), ("d", "b"), ("e", "a") ], &[ "a", "b", "c", "d", "e" ]),
+                   vec![ Some(1), Some(2), Some(1), Some(2), Some(1) ]);
+    }
+
+    #[test]
+    fn other_link_not_listed() {
+        assert_eq!(tags("unlisted", &[ ("b", "a") ], &[ "a" ]), vec![ None ]);
+    }
+}
+
+
+#[cfg(test)]
+mod dir_counts_test {
+    use super::*;
+    use crate::fs::test::temp_dir;
+
+    fn counts(dots: DotFilter) -> DirCounts {
+        DirCounts { dots, cache: Mutex::new(HashMap::new()) }
+    }
+
+    /// Makes a directory with two files, a dotfile, and a subdirectory
+    /// that has more files of its own.
+    fn make_dir(name: &str) -> PathBuf {
+        let root = temp_dir(&[ "dir-counts-", name ].concat());
+        fs::create_dir(root.join("sub")).unwrap();
+
+        for file in &[ "a", "b", ".hidden", "sub/c", "sub/d", "sub/e" ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn immediate_children() {
+        let root = make_dir("children");
+        assert_eq!(counts(DotFilter::JustFiles).of(&root), Some(3));
+        assert_eq!(counts(DotFilter::JustFiles).of(&root.join("sub")), Some(3));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dotfiles() {
+        let root = make_dir("dotfiles");
+        assert_eq!(counts(DotFilter::Dotfiles).of(&root), Some(4));
+        assert_eq!(counts(DotFilter::DotfilesAndDots).of(&root), Some(4));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn empty() {
+        let root = make_dir("empty");
+        fs::create_dir(root.join("nothing")).unwrap();
+        assert_eq!(counts(DotFilter::JustFiles).of(&root.join("nothing")), Some(0));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn unreadable() {
+        let root = make_dir("unreadable");
+        assert_eq!(counts(DotFilter::JustFiles).of(&root.join("missing")), None);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn read_once() {
+        let root = make_dir("once");
+        let counts = counts(DotFilter::JustFiles);
+        assert_eq!(counts.of(&root), Some(3));
+
+        fs::write(root.join("f"), "").unwrap();
+        assert_eq!(counts.of(&root), Some(3));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod time_formats_test {
+    use super::*;
+
+    fn options(time_formats: Vec<TimeFormat>) -> Options {
+        let time_types = TimeTypes { modified: true, changed: false, accessed: true, created: true };
+        Options { time_formats, columns: Columns { time_types, .. Columns::default() }, .. Options::default() }
+    }
+
+    #[test]
+    fn one_for_every_column() {
+        let options = options(vec![ TimeFormat::Relative ]);
+        assert_eq!(options.time_format(0), &TimeFormat::Relative);
+        assert_eq!(options.time_format(2), &TimeFormat::Relative);
+    }
+
+    #[test]
+    fn one_each() {
+        let options = options(vec![ TimeFormat::Relative, TimeFormat::ISOFormat, TimeFormat::FullISO ]);
+        assert_eq!(options.time_format(0), &TimeFormat::Relative);
+        assert_eq!(options.time_format(1), &TimeFormat::ISOFormat);
+        assert_eq!(options.time_format(2), &TimeFormat::FullISO);
+    }
+
+    #[test]
+    fn last_one_reused() {
+        let options = options(vec![ TimeFormat::Relative, TimeFormat::ISOFormat ]);
+        assert_eq!(options.time_format(1), &TimeFormat::ISOFormat);
+        assert_eq!(options.time_format(2), &TimeFormat::ISOFormat);
+    }
+
+    #[test]
+    fn none_given() {
+        let options = options(Vec::new());
+        assert_eq!(options.time_format(0), &TimeFormat::DefaultFormat);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(unix)]
+mod octal_test {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use crate::fs::test::temp_dir;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    fn options(order: Option<Vec<ColumnName>>) -> Options {
+        let time_types = TimeTypes { modified: false, .. TimeTypes::default() };
+        let columns = Columns { octal: true, filesize: false, user: false, time_types, order, .. Columns::default() };
+        Options { columns, .. Options::default() }
+    }
+
+    fn theme() -> Theme {
+        ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false)
+    }
+
+    /// Renders a row for a file and a directory with known modes, after
+    /// working out the column widths from both of them, and the header row
+    /// too if there is one.
+    fn rendered(name: &str, options: &Options, header: bool) -> Vec<String> {
+        let root = temp_dir(&[ "octal-", name ].concat());
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("file"), "").unwrap();
+        fs::set_permissions(root.join("file"), fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(root.join("dir"), fs::Permissions::from_mode(0o1755)).unwrap();
+
+        let theme = theme();
+        let mut table = Table::new(options, None, &theme);
+        let mut rows = if header { vec![ table.header_row() ] } else { Vec::new() };
+        rows.extend([ "file", "dir" ].iter()
+                                     .map(|name| File::from_args(root.join(name), None, None).unwrap())
+                                     .map(|file| table.row_for_file(&file, false)));
+
+        for row in &rows {
+            table.add_widths(row);
+        }
+
+        fs::remove_dir_all(root).unwrap();
+        rows.into_iter().map(|row| table.render(row).strings().to_string()).collect()
+    }
+
+    #[test]
+    fn alongside_permissions() {
+        let lines = rendered("alongside", &options(None), false);
+        assert_eq!(lines, vec![ "0644 .rw-r--r-- ", "1755 drwxr-xr-t " ]);
+    }
+
+    #[test]
+    fn widened_by_header() {
+        let lines = rendered("header", &options(None), true);
+        assert_eq!(lines, vec![ "Octal Permissions ", "0644  .rw-r--r--  ", "1755  drwxr-xr-t  " ]);
+    }
+
+    #[test]
+    fn in_column_order() {
+        let lines = rendered("order", &options(Some(vec![ ColumnName::Permissions, ColumnName::Octal ])), false);
+        assert_eq!(lines, vec![ ".rw-r--r-- 0644 ", "drwxr-xr-t 1755 " ]);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod xattr_names_test {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use crate::fs::test::temp_dir;
+    use crate::theme::{Options as ThemeOptions, UseColours, ColourScale, ThemePreset, Definitions};
+
+    fn options() -> Options {
+        let time_types = TimeTypes { modified: false, .. TimeTypes::default() };
+        let columns = Columns { xattr_names: true, permissions: false, filesize: false, user: false, time_types, .. Columns::default() };
+        Options { columns, .. Options::default() }
+    }
+
+    fn theme() -> Theme {
+        ThemeOptions {
+            use_colours: UseColours::Never,
+            colour_scale: ColourScale::default(),
+            preset: ThemePreset::Dark,
+            definitions: Definitions::default(),
+            dim_hidden: false,
+            age_colours: false,
+        }.to_theme(false)
+    }
+
+    /// Sets an extended attribute on the file at the given path, returning
+    /// whether it worked, as not every file system supports them.
+    fn set_xattr(path: &Path, name: &str) -> bool {
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let name = CString::new(name).unwrap();
+        let value = b"exa";
+
+        #[cfg(target_os = "linux")]
+        let result = unsafe { libc::setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr().cast(), value.len(), 0) };
+
+        #[cfg(target_os = "macos")]
+        let result = unsafe { libc::setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr().cast(), value.len(), 0, 0) };
+
+        result == 0
+    }
+
+    /// Renders the extended attribute column for a file with the given
+    /// attributes set on it, or `None` if they couldn’t be set.
+    fn rendered(name: &str, xattrs: &[&str]) -> Option<String> {
+        let root = temp_dir(&[ "xattr-names-", name ].concat());
+        let path = root.join("file");
+        fs::write(&path, "").unwrap();
+
+        if ! xattrs.iter().all(|x| set_xattr(&path, x)) {
+            fs::remove_dir_all(root).unwrap();
+            return None;
+        }
+
+        let options = options();
+        let theme = theme();
+        let table = Table::new(&options, None, &theme);
+        let file = File::from_args(path.clone(), None, None).unwrap();
+        let cell = table.display(&file, Column::XattrNames, false);
+
+        fs::remove_dir_all(root).unwrap();
+        Some(cell.strings().to_string())
+    }
+
+    #[test]
+    fn header() {
+        assert_eq!(Column::XattrNames.header(), "Xattrs");
+    }
+
+    #[test]
+    fn none() {
+        assert_eq!(rendered("none", &[]), Some(String::from("-")));
+    }
+
+    #[test]
+    fn one() {
+        if let Some(names) = rendered("one", &[ "user.exa.test" ]) {
+            assert_eq!(names, "user.exa.test");
+        }
+    }
+
+    #[test]
+    fn several() {
+        if let Some(names) = rendered("several", &[ "user.exa.one", "user.exa.two" ]) {
+            let mut names = names.split(',').collect::<Vec<_>>();
+            names.sort_unstable();
+            assert_eq!(names, vec![ "user.exa.one", "user.exa.two" ]);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Timestamp formatting.
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use datetime::{LocalDateTime, TimeZone, DatePiece, TimePiece};
 use datetime::fmt::DateFormat;
@@ -23,9 +23,9 @@ use unicode_width::UnicodeWidthStr;
 /// own enum variants. It’s not worth looking the locale up if the formatter
 /// prints month names as numbers.
 ///
-/// Currently exa does not support *custom* styles, where the user enters a
-/// format string in an environment variable or something. Just these four.
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+/// As well as the named styles, the user can give their own format string,
+/// in the style of `strftime`, by prefixing it with a `+`.
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub enum TimeFormat {
 
     /// The **default format** uses the user’s locale to print month names,
@@ -46,27 +46,75 @@ pub enum TimeFormat {
     /// millisecond and includes its offset down to the minute. This too uses
     /// only numbers so doesn’t require any special consideration.
     FullISO,
+
+    /// Use the **precise format**, which is like the full ISO format but
+    /// always includes the offset, even when no time zone could be found, so
+    /// the output lines up with what `stat` prints. Timestamps that have no
+    /// sub-second component are padded with zeroes.
+    Precise,
+
+    /// Use the **relative format**, which shows how long ago the timestamp
+    /// was in the largest unit that fits, such as “3 hours ago”. Every
+    /// timestamp is compared against the same moment, so all the rows in a
+    /// listing agree with each other.
+    Relative,
+
+    /// Use the **relative format** for timestamps up to the given length of
+    /// time ago, and the date in ISO format for anything older, as how long
+    /// ago something was gets less useful the longer ago it was.
+    RelativeWithin(Duration),
+
+    /// Use a **custom format** that the user has written themselves, with
+    /// `strftime`-style `%` specifiers. Only formats that contain known
+    /// specifiers can be created, using `TimeFormat::custom`.
+    Custom(String),
 }
 
 // There are two different formatting functions because local and zoned
 // timestamps are separate types.
 
 impl TimeFormat {
-    pub fn format_local(self, time: SystemTime) -> String {
+
+    /// Creates a custom time format from the given format string, returning
+    /// `None` if it contains a specifier that isn’t supported.
+    pub fn custom(format: &str) -> Option<Self> {
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            if c == '%' {
+                match chars.next() {
+                    Some(spec) if CUSTOM_SPECIFIERS.contains(spec)  => {},
+                    _                                               => return None,
+                }
+            }
+        }
+
+        Some(Self::Custom(format.into()))
+    }
+
+    pub fn format_local(&self, time: SystemTime) -> String {
         match self {
-            Self::DefaultFormat  => default_local(time),
-            Self::ISOFormat      => iso_local(time),
-            Self::LongISO        => long_local(time),
-            Self::FullISO        => full_local(time),
+            Self::DefaultFormat           => default_local(time),
+            Self::ISOFormat               => iso_local(time),
+            Self::LongISO                 => long_local(time),
+            Self::FullISO                 => full_local(time),
+            Self::Precise                 => precise_local(time),
+            Self::Relative                => relative(time, *NOW),
+            Self::RelativeWithin(cutoff)  => relative_within(time, *NOW, *cutoff).unwrap_or_else(|| date_local(time)),
+            Self::Custom(fmt)             => custom_local(fmt, time),
         }
     }
 
-    pub fn format_zoned(self, time: SystemTime, zone: &TimeZone) -> String {
+    pub fn format_zoned(&self, time: SystemTime, zone: &TimeZone) -> String {
         match self {
-            Self::DefaultFormat  => default_zoned(time, zone),
-            Self::ISOFormat      => iso_zoned(time, zone),
-            Self::LongISO        => long_zoned(time, zone),
-            Self::FullISO        => full_zoned(time, zone),
+            Self::DefaultFormat           => default_zoned(time, zone),
+            Self::ISOFormat               => iso_zoned(time, zone),
+            Self::LongISO                 => long_zoned(time, zone),
+            Self::FullISO                 => full_zoned(time, zone),
+            Self::Precise                 => full_zoned(time, zone),
+            Self::Relative                => relative(time, *NOW),
+            Self::RelativeWithin(cutoff)  => relative_within(time, *NOW, *cutoff).unwrap_or_else(|| date_zoned(time, zone)),
+            Self::Custom(fmt)             => custom_zoned(fmt, time, zone),
         }
     }
 }
@@ -134,6 +182,11 @@ fn full_zoned(time: SystemTime, zone: &TimeZone) -> String {
             offset.hours(), offset.minutes().abs())
 }
 
+fn precise_local(time: SystemTime) -> String {
+    // Without a time zone, timestamps are rendered in UTC.
+    format!("{} +0000", full_local(time))
+}
+
 #[allow(trivial_numeric_casts)]
 fn iso_local(time: SystemTime) -> String {
     let date = LocalDateTime::at(systemtime_epoch(time));
@@ -149,6 +202,22 @@ fn iso_local(time: SystemTime) -> String {
     }
 }
 
+#[allow(trivial_numeric_casts)]
+fn date_local(time: SystemTime) -> String {
+    let date = LocalDateTime::at(systemtime_epoch(time));
+
+    format!("{:04}-{:02}-{:02}",
+            date.year(), date.month() as usize, date.day())
+}
+
+#[allow(trivial_numeric_casts)]
+fn date_zoned(time: SystemTime, zone: &TimeZone) -> String {
+    let date = zone.to_zoned(LocalDateTime::at(systemtime_epoch(time)));
+
+    format!("{:04}-{:02}-{:02}",
+            date.year(), date.month() as usize, date.day())
+}
+
 #[allow(trivial_numeric_casts)]
 fn iso_zoned(time: SystemTime, zone: &TimeZone) -> String {
     let date = zone.to_zoned(LocalDateTime::at(systemtime_epoch(time)));
@@ -165,6 +234,146 @@ fn iso_zoned(time: SystemTime, zone: &TimeZone) -> String {
 }
 
 
+/// Describes how far the given time is from now, in the largest unit that
+/// it’s at least one of. Time zones don’t matter here, as both times are
+/// absolute.
+fn relative(time: SystemTime, now: SystemTime) -> String {
+    let (secs, future) = match now.duration_since(time) {
+        Ok(ago)  => (ago.as_secs(), false),
+        Err(e)   => (e.duration().as_secs(), true),
+    };
+
+    if secs == 0 {
+        return String::from("now");
+    }
+
+    let (count, unit) = RELATIVE_UNITS.iter()
+        .rev()
+        .find(|(length, _)| secs >= *length)
+        .map(|(length, unit)| (secs / length, *unit))
+        .unwrap();
+
+    let plural = if count == 1 { "" } else { "s" };
+
+    if future {
+        format!("in {} {}{}", count, unit, plural)
+    }
+    else {
+        format!("{} {}{} ago", count, unit, plural)
+    }
+}
+
+/// Describes how far the given time is from now, as above, unless it was
+/// longer ago than the cutoff, in which case it should be shown as a date.
+/// Times in the future are always relative.
+fn relative_within(time: SystemTime, now: SystemTime, cutoff: Duration) -> Option<String> {
+    match now.duration_since(time) {
+        Ok(ago) if ago > cutoff  => None,
+        _                        => Some(relative(time, now)),
+    }
+}
+
+/// The units used by the relative format, with how many seconds they last,
+/// from smallest to largest. Months and years are approximate.
+const RELATIVE_UNITS: &[(u64, &str)] = &[
+    (1,                   "second"),
+    (60,                  "minute"),
+    (60 * 60,             "hour"),
+    (60 * 60 * 24,        "day"),
+    (60 * 60 * 24 * 7,    "week"),
+    (60 * 60 * 24 * 30,   "month"),
+    (60 * 60 * 24 * 365,  "year"),
+];
+
+/// The number of seconds in a day.
+pub const DAY: u64 = 60 * 60 * 24;
+
+/// Parses a duration made up of a whole number followed by a unit: `s` for
+/// seconds, `m` for minutes, `h` for hours, `d` for days, or `w` for weeks.
+/// This is the syntax for every option that takes a length of time.
+pub fn parse_duration(word: &str) -> Option<Duration> {
+    let unit_seconds = match word.chars().last()? {
+        's'  => 1,
+        'm'  => 60,
+        'h'  => 60 * 60,
+        'd'  => DAY,
+        'w'  => DAY * 7,
+        _    => return None,
+    };
+
+    let count: u64 = word[.. word.len() - 1].parse().ok()?;
+    Some(Duration::from_secs(count.checked_mul(unit_seconds)?))
+}
+
+/// The characters that can follow a `%` in a custom time format.
+const CUSTOM_SPECIFIERS: &str = "YCymbhBdejHIMSNpaAuzFTR%";
+
+fn custom_local(format: &str, time: SystemTime) -> String {
+    // Without a time zone, timestamps are rendered in UTC.
+    let date = LocalDateTime::at(systemtime_epoch(time));
+    custom(format, &date, systemtime_nanos(time), 0)
+}
+
+fn custom_zoned(format: &str, time: SystemTime, zone: &TimeZone) -> String {
+    let local = LocalDateTime::at(systemtime_epoch(time));
+    let date = zone.to_zoned(local);
+    custom(format, &date, systemtime_nanos(time), zone.offset(local))
+}
+
+/// Formats a date using a custom format string, which has already been
+/// checked to only contain supported specifiers.
+#[allow(trivial_numeric_casts)]
+fn custom<D: DatePiece + TimePiece>(format: &str, date: &D, nanos: u32, offset: i64) -> String {
+    use std::fmt::Write;
+
+    let month = date.month() as usize;
+    let weekday = date.weekday() as usize;
+    let hour12 = match date.hour() % 12 { 0 => 12, h => h };
+
+    let mut out = String::with_capacity(format.len() * 2);
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+
+        // Writing to a string can’t fail.
+        let _ = match chars.next() {
+            Some('Y')  => write!(out, "{:04}", date.year()),
+            Some('C')  => write!(out, "{:02}", date.year().div_euclid(100)),
+            Some('y')  => write!(out, "{:02}", date.year().rem_euclid(100)),
+            Some('m')  => write!(out, "{:02}", month),
+            Some('b') |
+            Some('h')  => write!(out, "{}", LOCALE.short_month_name(month - 1)),
+            Some('B')  => write!(out, "{}", LOCALE.long_month_name(month - 1)),
+            Some('d')  => write!(out, "{:02}", date.day()),
+            Some('e')  => write!(out, "{:2}", date.day()),
+            Some('j')  => write!(out, "{:03}", date.yearday()),
+            Some('H')  => write!(out, "{:02}", date.hour()),
+            Some('I')  => write!(out, "{:02}", hour12),
+            Some('M')  => write!(out, "{:02}", date.minute()),
+            Some('S')  => write!(out, "{:02}", date.second()),
+            Some('N')  => write!(out, "{:09}", nanos),
+            Some('p')  => write!(out, "{}", if date.hour() < 12 { "AM" } else { "PM" }),
+            Some('a')  => write!(out, "{}", LOCALE.short_day_name(weekday)),
+            Some('A')  => write!(out, "{}", LOCALE.long_day_name(weekday)),
+            Some('u')  => write!(out, "{}", if weekday == 0 { 7 } else { weekday }),
+            Some('z')  => write!(out, "{}{:02}{:02}", if offset < 0 { '-' } else { '+' },
+                                 offset.abs() / 3600, offset.abs() % 3600 / 60),
+            Some('F')  => write!(out, "{:04}-{:02}-{:02}", date.year(), month, date.day()),
+            Some('T')  => write!(out, "{:02}:{:02}:{:02}", date.hour(), date.minute(), date.second()),
+            Some('R')  => write!(out, "{:02}:{:02}", date.hour(), date.minute()),
+            Some(_)    => write!(out, "%"),  // only %% is left
+            None       => Ok(()),
+        };
+    }
+
+    out
+}
+
+
 fn systemtime_epoch(time: SystemTime) -> i64 {
     time.duration_since(UNIX_EPOCH)
         .map(|t| t.as_secs() as i64)
@@ -200,6 +409,8 @@ lazy_static! {
 
     static ref CURRENT_YEAR: i64 = LocalDateTime::now().year();
 
+    static ref NOW: SystemTime = SystemTime::now();
+
     static ref LOCALE: locale::Time = {
         locale::Time::load_user_locale()
                .unwrap_or_else(|_| locale::Time::english())
@@ -241,3 +452,131 @@ lazy_static! {
         "{2>:D} {:M} {5>:Y}"
     ).unwrap();
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn billennium() -> SystemTime {
+        UNIX_EPOCH + Duration::new(1_000_000_000, 123_456_789)
+    }
+
+    #[test]
+    fn relative_now() {
+        assert_eq!(relative(billennium(), billennium()), "now");
+    }
+
+    #[test]
+    fn relative_seconds() {
+        let now = billennium() + Duration::from_secs(59);
+        assert_eq!(relative(billennium(), now), "59 seconds ago");
+    }
+
+    #[test]
+    fn relative_one_minute() {
+        let now = billennium() + Duration::from_secs(60);
+        assert_eq!(relative(billennium(), now), "1 minute ago");
+    }
+
+    #[test]
+    fn relative_days() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 6 + 1);
+        assert_eq!(relative(billennium(), now), "6 days ago");
+    }
+
+    #[test]
+    fn relative_one_week() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 7);
+        assert_eq!(relative(billennium(), now), "1 week ago");
+    }
+
+    #[test]
+    fn relative_years() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 365 * 3);
+        assert_eq!(relative(billennium(), now), "3 years ago");
+    }
+
+    #[test]
+    fn relative_within_cutoff() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 30 - 1);
+        assert_eq!(relative_within(billennium(), now, Duration::from_secs(60 * 60 * 24 * 30)), Some(String::from("4 weeks ago")));
+    }
+
+    #[test]
+    fn relative_at_cutoff() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 30);
+        assert_eq!(relative_within(billennium(), now, Duration::from_secs(60 * 60 * 24 * 30)), Some(String::from("1 month ago")));
+    }
+
+    #[test]
+    fn relative_past_cutoff() {
+        let now = billennium() + Duration::from_secs(60 * 60 * 24 * 30 + 1);
+        assert_eq!(relative_within(billennium(), now, Duration::from_secs(60 * 60 * 24 * 30)), None);
+    }
+
+    #[test]
+    fn relative_future_within_cutoff() {
+        let now = billennium() - Duration::from_secs(60 * 60 * 24 * 365);
+        assert_eq!(relative_within(billennium(), now, Duration::from_secs(60)), Some(String::from("in 1 year")));
+    }
+
+    #[test]
+    fn date_past_cutoff() {
+        assert_eq!(date_local(billennium()), "2001-09-09");
+    }
+
+    #[test]
+    fn relative_future() {
+        let now = billennium() - Duration::from_secs(60 * 60 * 2);
+        assert_eq!(relative(billennium(), now), "in 2 hours");
+    }
+
+    #[test]
+    fn custom_date() {
+        let format = TimeFormat::custom("%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(format.format_local(billennium()), "2001-09-09 01:46:40");
+    }
+
+    #[test]
+    fn custom_shorthands() {
+        let format = TimeFormat::custom("%F %T.%N %z").unwrap();
+        assert_eq!(format.format_local(billennium()), "2001-09-09 01:46:40.123456789 +0000");
+    }
+
+    #[test]
+    fn custom_numbers() {
+        let format = TimeFormat::custom("%y %C %e %j %I%p %u 100%%").unwrap();
+        assert_eq!(format.format_local(billennium()), "01 20  9 252 01AM 7 100%");
+    }
+
+    #[test]
+    fn custom_unknown() {
+        assert_eq!(TimeFormat::custom("%Y-%Q"), None);
+    }
+
+    #[test]
+    fn custom_trailing() {
+        assert_eq!(TimeFormat::custom("%Y%"), None);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("5m"),  Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("12h"), Some(Duration::from_secs(43200)));
+        assert_eq!(parse_duration("3d"),  Some(Duration::from_secs(DAY * 3)));
+        assert_eq!(parse_duration("2w"),  Some(Duration::from_secs(DAY * 14)));
+    }
+
+    #[test]
+    fn bad_durations() {
+        assert_eq!(parse_duration(""),    None);
+        assert_eq!(parse_duration("90"),  None);
+        assert_eq!(parse_duration("d"),   None);
+        assert_eq!(parse_duration("1y"),  None);
+        assert_eq!(parse_duration("-1d"), None);
+        assert_eq!(parse_duration("1.5h"), None);
+    }
+}
@@ -37,6 +37,29 @@
 //! of each file to see if it’s the last one. (As some files may not be
 //! successfully `stat`ted, we don’t know how many files are going to exist in
 //! each directory)
+//!
+//! ## Folding Extensions
+//!
+//! With `--collapse-exts`, files in a directory that share an extension get
+//! folded into one summary row, such as `15 × .hpp`, once there are enough of
+//! them. These rows go after the files that are still listed, so the last of
+//! those files is no longer the last row in its directory.
+//!
+//! ## Collapsing Single Children
+//!
+//! With `--collapse-single-child`, a directory whose only entry is another
+//! directory shares a row with it, such as `main/java`, and so on down the
+//! chain. The rows under it are those of the last directory in the chain,
+//! one level deeper than the row itself, so the depths don’t follow the
+//! directories on disk any more.
+//!
+//! ## Tree Styles
+//!
+//! The box-drawing characters don’t show up in every terminal or log file,
+//! so with `--tree-style=ascii`, the same tree gets drawn using `|--`,
+//! `` `-- ``, and `|` instead.
+
+use std::collections::BTreeMap;
 
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -57,19 +80,35 @@ pub enum TreePart {
 
 impl TreePart {
 
-    /// Turn this tree part into ASCII-licious box drawing characters!
-    /// (Warning: not actually ASCII)
-    pub fn ascii_art(self) -> &'static str {
-        match self {
-            Self::Edge    => "├──",
-            Self::Line    => "│  ",
-            Self::Corner  => "└──",
-            Self::Blank   => "   ",
+    /// Turn this tree part into box drawing characters, in the given style.
+    pub fn ascii_art(self, style: TreeStyle) -> &'static str {
+        match (style, self) {
+            (TreeStyle::Unicode, Self::Edge)    => "├──",
+            (TreeStyle::Unicode, Self::Line)    => "│  ",
+            (TreeStyle::Unicode, Self::Corner)  => "└──",
+            (TreeStyle::Ascii,   Self::Edge)    => "|--",
+            (TreeStyle::Ascii,   Self::Line)    => "|  ",
+            (TreeStyle::Ascii,   Self::Corner)  => "`--",
+            (_,                  Self::Blank)   => "   ",
         }
     }
 }
 
 
+/// The set of characters that tree parts get drawn with.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum TreeStyle {
+
+    /// Unicode box-drawing characters, such as `├──`. This is the default.
+    #[default]
+    Unicode,
+
+    /// Only ASCII characters, such as `|--`, for terminals and files that
+    /// can’t show the box-drawing ones.
+    Ascii,
+}
+
+
 /// A **tree trunk** builds up arrays of tree parts over multiple depths.
 #[derive(Debug, Default)]
 pub struct TreeTrunk {
@@ -189,6 +228,25 @@ where I: ExactSizeIterator + Iterator<Item = T>
 }
 
 
+/// Finds the extensions that at least `threshold` of the given extensions
+/// share, returning each one with the number of files that have it, in
+/// order of extension. Files with these extensions get folded into one
+/// summary row each, rather than being listed.
+pub fn folded_extensions<'e, I>(exts: I, threshold: usize) -> Vec<(String, usize)>
+where I: IntoIterator<Item = &'e str>
+{
+    let mut counts = BTreeMap::new();
+    for ext in exts {
+        *counts.entry(ext).or_insert(0) += 1;
+    }
+
+    counts.into_iter()
+          .filter(|&(_, count)| count >= threshold)
+          .map(|(ext, count)| (ext.to_string(), count))
+          .collect()
+}
+
+
 #[cfg(test)]
 mod trunk_test {
     use super::*;
@@ -246,6 +304,42 @@ mod trunk_test {
 }
 
 
+#[cfg(test)]
+mod style_test {
+    use super::*;
+
+    /// Draws the tree parts for a directory with two children, the first
+    /// of which has two children of its own, in the given style.
+    fn drawn(style: TreeStyle) -> Vec<String> {
+        let mut tt = TreeTrunk::default();
+        let rows = [ (0, true), (1, false), (2, false), (2, true), (1, true) ];
+
+        rows.iter()
+            .map(|&(depth, last)| tt.new_row(TreeParams::new(TreeDepth(depth), last)).iter().map(|part| part.ascii_art(style)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(drawn(TreeStyle::Unicode), vec![ "", "├──", "│  ├──", "│  └──", "└──" ]);
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(drawn(TreeStyle::Ascii), vec![ "", "|--", "|  |--", "|  `--", "`--" ]);
+    }
+
+    #[test]
+    fn blanks_after_last() {
+        let mut tt = TreeTrunk::default();
+        tt.new_row(TreeParams::new(TreeDepth(0), true));
+        tt.new_row(TreeParams::new(TreeDepth(1), true));
+        let parts = tt.new_row(TreeParams::new(TreeDepth(2), true));
+        assert_eq!(parts.iter().map(|part| part.ascii_art(TreeStyle::Ascii)).collect::<String>(), "   `--");
+    }
+}
+
+
 #[cfg(test)]
 mod iter_test {
     use super::*;
@@ -277,3 +371,27 @@ mod iter_test {
         assert!(iter.next().is_none());
     }
 }
+
+
+#[cfg(test)]
+mod fold_test {
+    use super::*;
+
+    #[test]
+    fn below_threshold() {
+        let exts = [ "rs", "rs", "toml" ];
+        assert_eq!(folded_extensions(exts.iter().copied(), 3), vec![]);
+    }
+
+    #[test]
+    fn at_threshold() {
+        let exts = [ "hpp", "cpp", "hpp", "hpp", "cpp" ];
+        assert_eq!(folded_extensions(exts.iter().copied(), 3), vec![ ("hpp".to_string(), 3) ]);
+    }
+
+    #[test]
+    fn several_in_order() {
+        let exts = [ "o", "c", "o", "c", "h" ];
+        assert_eq!(folded_extensions(exts.iter().copied(), 2), vec![ ("c".to_string(), 2), ("o".to_string(), 2) ]);
+    }
+}
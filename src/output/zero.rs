@@ -0,0 +1,53 @@
+use std::ffi::OsStr;
+use std::io::{self, Write};
+
+use crate::fs::File;
+use crate::fs::filter::FileFilter;
+
+
+/// The zero view displays each file’s name followed by a NUL byte, rather
+/// than a newline, so that the output can be passed to `xargs -0` or
+/// anything else that splits its input on NUL bytes. File names can contain
+/// spaces and even newlines, but they can never contain a NUL.
+///
+/// The names are written out exactly as they are, without any colours,
+/// icons, escaping, or link targets.
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub filter: &'a FileFilter,
+
+    /// Whether to write out each file’s full path rather than just its
+    /// name, which is needed when recursing for the output to be usable.
+    pub full_paths: bool,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files);
+        for file in &self.files {
+            // Files passed in as arguments have no parent directory, and
+            // their paths are exactly what the user typed.
+            if self.full_paths || file.parent_dir.is_none() {
+                write_bytes(w, file.path.as_os_str())?;
+            }
+            else {
+                write_bytes(w, file.path.file_name().unwrap_or_else(|| file.path.as_os_str()))?;
+            }
+
+            w.write_all(b"\0")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn write_bytes<W: Write>(w: &mut W, string: &OsStr) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    w.write_all(string.as_bytes())
+}
+
+#[cfg(windows)]
+fn write_bytes<W: Write>(w: &mut W, string: &OsStr) -> io::Result<()> {
+    w.write_all(string.to_string_lossy().as_bytes())
+}
@@ -0,0 +1,112 @@
+use log::*;
+
+use crate::theme::lsc::Pair;
+use crate::theme::ui_styles::UiStyles;
+
+
+// Parsing a theme file into the styles of exa’s interface.
+//
+// A theme file is a small subset of TOML: each line sets one style, with the
+// name of the style on the left and a string of ANSI codes — the same values
+// that `EXA_COLORS` takes — on the right. Styles are grouped into tables
+// named after the sections of `UiStyles`:
+//
+//     punctuation = "38;5;244"
+//
+//     [filekinds]
+//     directory = "1;34"
+//
+//     [perms]
+//     user_read = "1;33"
+//
+// Anything exa doesn’t understand gets skipped with a warning, so a theme
+// written for a newer version still works with an older one.
+
+pub struct ThemeFile<'file>(pub &'file str);
+
+impl<'file> ThemeFile<'file> {
+
+    /// Sets every style in the given set of colours that this theme file
+    /// has a value for, leaving the others alone.
+    pub fn apply(&self, colours: &mut UiStyles) {
+        let mut section = None;
+
+        for (number, line) in self.0.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.trim());
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((k, v))  => (k.trim(), unquote(v.trim())),
+                None          => {
+                    warn!("Couldn't parse line {} of theme file: {:?}", number + 1, line);
+                    continue;
+                }
+            };
+
+            let name = match section {
+                Some(s)  => format!("{}.{}", s, key),
+                None     => key.to_string(),
+            };
+
+            let pair = Pair { key, value };
+            if ! colours.set_named(&name, pair.to_style()) {
+                warn!("Unknown style in theme file: {:?}", name);
+            }
+        }
+    }
+}
+
+/// Removes the quotes around a TOML string value, if it has them.
+fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+         .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+         .unwrap_or(value)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ansi_term::Colour::*;
+    use ansi_term::Style;
+
+    #[test]
+    fn top_level() {
+        let mut colours = UiStyles::default();
+        ThemeFile("punctuation = \"31\"").apply(&mut colours);
+        assert_eq!(colours.punctuation, Red.normal());
+    }
+
+    #[test]
+    fn sections() {
+        let mut colours = UiStyles::default();
+        ThemeFile("[filekinds]\ndirectory = \"1;34\"\n\n[size]\nunit_kilo = '32'").apply(&mut colours);
+        assert_eq!(colours.filekinds.directory, Blue.bold());
+        assert_eq!(colours.size.unit_kilo, Green.normal());
+    }
+
+    #[test]
+    fn comments_and_junk() {
+        let mut colours = UiStyles::default();
+        ThemeFile("# a comment\nnonsense\ndate = 33\nsparkles = \"35\"").apply(&mut colours);
+
+        let mut expected = UiStyles::default();
+        expected.date = Yellow.normal();
+        assert_eq!(colours, expected);
+    }
+
+    #[test]
+    fn unknown_section() {
+        let mut colours = UiStyles::default();
+        ThemeFile("[nowhere]\ndate = \"33\"").apply(&mut colours);
+        assert_eq!(colours.date, Style::default());
+    }
+}
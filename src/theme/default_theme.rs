@@ -64,6 +64,15 @@ impl UiStyles {
                 typechange:  Purple.normal(),
                 ignored:     Style::default().dimmed(),
                 conflicted:  Red.normal(),
+                branch:      Green.normal(),
+            },
+
+            ages: Ages {
+                day:    Green.bold(),
+                week:   Green.normal(),
+                month:  Blue.normal(),
+                older:  Fixed(244).normal(),
+                .. Ages::default()
             },
 
             punctuation:  Fixed(244).normal(),
@@ -71,12 +80,115 @@ impl UiStyles {
             inode:        Purple.normal(),
             blocks:       Cyan.normal(),
             octal:        Purple.normal(),
+            filesystem:   Cyan.normal(),
+            mime_type:    Yellow.normal(),
+            dir_count:    Cyan.bold(),
             header:       Style::default().underline(),
 
             symlink_path:         Cyan.normal(),
             control_char:         Red.normal(),
             broken_symlink:       Red.normal(),
             broken_path_overlay:  Style::default().underline(),
+
+            dim_hidden:  false,
+        }
+    }
+
+    /// The styles for terminals with light backgrounds. The layout is the
+    /// same as the default theme, but with darker colours from the 256-colour
+    /// palette standing in for the yellows and greens that are hard to read.
+    pub fn light_theme(scale: ColourScale) -> Self {
+        let yellow = Fixed(130);
+        let red    = Fixed(124);
+        let green  = Fixed(28);
+        let blue   = Fixed(25);
+        let cyan   = Fixed(30);
+        let purple = Fixed(90);
+
+        Self {
+            colourful: true,
+
+            filekinds: FileKinds {
+                normal:       Style::default(),
+                directory:    blue.bold(),
+                symlink:      cyan.normal(),
+                pipe:         yellow.normal(),
+                block_device: yellow.bold(),
+                char_device:  yellow.bold(),
+                socket:       red.bold(),
+                special:      yellow.normal(),
+                executable:   green.bold(),
+            },
+
+            perms: Permissions {
+                user_read:           yellow.bold(),
+                user_write:          red.bold(),
+                user_execute_file:   green.bold().underline(),
+                user_execute_other:  green.bold(),
+
+                group_read:          yellow.normal(),
+                group_write:         red.normal(),
+                group_execute:       green.normal(),
+
+                other_read:          yellow.normal(),
+                other_write:         red.normal(),
+                other_execute:       green.normal(),
+
+                special_user_file:   purple.normal(),
+                special_other:       purple.normal(),
+
+                attribute:           Style::default(),
+            },
+
+            size: Size::light(scale),
+
+            users: Users {
+                user_you:           yellow.bold(),
+                user_someone_else:  Style::default(),
+                group_yours:        yellow.bold(),
+                group_not_yours:    Style::default(),
+            },
+
+            links: Links {
+                normal:          red.bold(),
+                multi_link_file: red.on(Fixed(223)),
+            },
+
+            git: Git {
+                new:         green.normal(),
+                modified:    blue.normal(),
+                deleted:     red.normal(),
+                renamed:     yellow.normal(),
+                typechange:  purple.normal(),
+                ignored:     Style::default().dimmed(),
+                conflicted:  red.normal(),
+                branch:      green.normal(),
+            },
+
+            ages: Ages {
+                day:    green.bold(),
+                week:   green.normal(),
+                month:  blue.normal(),
+                older:  Fixed(242).normal(),
+                .. Ages::default()
+            },
+
+            punctuation:  Fixed(242).normal(),
+            date:         blue.normal(),
+            inode:        purple.normal(),
+            blocks:       cyan.normal(),
+            octal:        purple.normal(),
+            filesystem:   cyan.normal(),
+            mime_type:    yellow.normal(),
+            dir_count:    cyan.bold(),
+            header:       Style::default().underline(),
+
+            symlink_path:         cyan.normal(),
+            control_char:         red.normal(),
+            broken_symlink:       red.normal(),
+            broken_path_overlay:  Style::default().underline(),
+
+            dim_hidden:  false,
         }
     }
 }
@@ -84,10 +196,8 @@ impl UiStyles {
 
 impl Size {
     pub fn colourful(scale: ColourScale) -> Self {
-        match scale {
-            ColourScale::Gradient  => Self::colourful_gradient(),
-            ColourScale::Fixed     => Self::colourful_fixed(),
-        }
+        if scale.size { Self::colourful_gradient() }
+                 else { Self::colourful_fixed() }
     }
 
     fn colourful_fixed() -> Self {
@@ -127,4 +237,74 @@ impl Size {
             unit_huge: Green.normal(),
         }
     }
+
+    pub fn light(scale: ColourScale) -> Self {
+        if scale.size { Self::light_gradient() }
+                 else { Self::light_fixed() }
+    }
+
+    fn light_fixed() -> Self {
+        Self {
+            major:  Fixed(28).bold(),
+            minor:  Fixed(28).normal(),
+
+            number_byte: Fixed(28).bold(),
+            number_kilo: Fixed(28).bold(),
+            number_mega: Fixed(28).bold(),
+            number_giga: Fixed(28).bold(),
+            number_huge: Fixed(28).bold(),
+
+            unit_byte: Fixed(28).normal(),
+            unit_kilo: Fixed(28).normal(),
+            unit_mega: Fixed(28).normal(),
+            unit_giga: Fixed(28).normal(),
+            unit_huge: Fixed(28).normal(),
+        }
+    }
+
+    fn light_gradient() -> Self {
+        Self {
+            major:  Fixed(28).bold(),
+            minor:  Fixed(28).normal(),
+
+            number_byte: Fixed(28).normal(),
+            number_kilo: Fixed(64).normal(),
+            number_mega: Fixed(136).normal(),
+            number_giga: Fixed(130).normal(),
+            number_huge: Fixed(124).normal(),
+
+            unit_byte: Fixed(28).normal(),
+            unit_kilo: Fixed(28).normal(),
+            unit_mega: Fixed(28).normal(),
+            unit_giga: Fixed(28).normal(),
+            unit_huge: Fixed(28).normal(),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::theme::ThemePreset;
+
+    #[test]
+    fn dark_is_default() {
+        assert_eq!(ThemePreset::Dark.ui_styles(ColourScale::default()), UiStyles::default_theme(ColourScale::default()));
+    }
+
+    #[test]
+    fn light_directories() {
+        let dark  = ThemePreset::Dark .ui_styles(ColourScale::default());
+        let light = ThemePreset::Light.ui_styles(ColourScale::default());
+        assert_eq!(dark.filekinds.directory,  Blue.bold());
+        assert_eq!(light.filekinds.directory, Fixed(25).bold());
+    }
+
+    #[test]
+    fn light_no_yellow() {
+        let light = ThemePreset::Light.ui_styles(ColourScale { size: true, age: false });
+        assert_eq!(light.perms.user_read, Fixed(130).bold());
+        assert_ne!(light.size.number_mega, Fixed(226).normal());
+    }
 }
@@ -44,8 +44,9 @@ impl<'var> LSColors<'var> {
 fn parse_into_high_colour<'a, I>(iter: &mut Peekable<I>) -> Option<Colour>
 where I: Iterator<Item = &'a str>
 {
-    match iter.peek() {
-        Some(&"5") => {
+    // The mode can have leading zeroes, the same as the codes before it
+    match iter.peek().map(|mode| mode.trim_start_matches('0')) {
+        Some("5") => {
             let _ = iter.next();
             if let Some(byte) = iter.next() {
                 if let Ok(num) = byte.parse() {
@@ -54,7 +55,7 @@ where I: Iterator<Item = &'a str>
             }
         }
 
-        Some(&"2") => {
+        Some("2") => {
             let _ = iter.next();
             if let Some(hexes) = iter.next() {
                 // Some terminals support R:G:B instead of R;G;B
@@ -184,6 +185,10 @@ mod ansi_test {
     test!(rgbi:  "38;2;255;100;0;3"   => Style::default().fg(RGB(255, 100, 0)).italic());
     test!(rgbbg: "48;2;255;100;0"     => Style::default().on(RGB(255, 100, 0)));
     test!(rgbbi: "48;2;255;100;0;3"   => Style::default().on(RGB(255, 100, 0)).italic());
+    test!(rgbfb: "38;2;1;2;3;48;2;4;5;6"  => RGB(1, 2, 3).on(RGB(4, 5, 6)));
+    test!(rgb0:  "038;02;255;100;0"   => Style::default().fg(RGB(255, 100, 0)));
+    test!(hi0:   "38;05;149"          => Fixed(149).normal());
+    test!(rgbhi: "38;2;256;0;0"       => Style::default());
 
     test!(fgbg:  "38;5;121;48;5;212"  => Fixed(121).on(Fixed(212)));
     test!(bgfg:  "48;5;121;38;5;212"  => Fixed(212).on(Fixed(121)));
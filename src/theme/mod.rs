@@ -1,6 +1,11 @@
-use ansi_term::Style;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use ansi_term::{Colour, Style};
 
 use crate::fs::File;
+use crate::fs::fields as f;
 use crate::output::file_name::Colours as FileNameColours;
 use crate::output::render;
 
@@ -11,6 +16,9 @@ pub use self::ui_styles::Size as SizeColours;
 mod lsc;
 pub use self::lsc::LSColors;
 
+mod config;
+use self::config::ThemeFile;
+
 mod default_theme;
 
 
@@ -21,7 +29,16 @@ pub struct Options {
 
     pub colour_scale: ColourScale,
 
+    pub preset: ThemePreset,
+
     pub definitions: Definitions,
+
+    /// Whether to dim the names of hidden files, from `--dim-hidden`.
+    pub dim_hidden: bool,
+
+    /// Whether to colour timestamps by how long ago they were, from
+    /// `--age-colours`.
+    pub age_colours: bool,
 }
 
 /// Under what circumstances we should display coloured, rather than plain,
@@ -44,26 +61,67 @@ pub enum UseColours {
     Never,
 }
 
+/// Which columns get coloured on a scale, rather than with a fixed colour.
+/// Each scale is enabled independently of the others, and the default is
+/// to use the fixed colours for everything.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct ColourScale {
+
+    /// Colour file sizes differently depending on their magnitude.
+    pub size: bool,
+
+    /// Fade timestamps from bright to dim as they get older.
+    pub age: bool,
+}
+
+/// Which set of built-in colours to start from, before any of the user’s
+/// own definitions are applied.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub enum ColourScale {
-    Fixed,
-    Gradient,
+pub enum ThemePreset {
+
+    /// Colours that show up well on a dark background. This is the default.
+    Dark,
+
+    /// Darker colours that show up well on a light background, avoiding
+    /// yellows and bright colours that would get washed out.
+    Light,
+}
+
+impl ThemePreset {
+
+    /// Returns the fully-populated styles for this preset.
+    pub fn ui_styles(self, scale: ColourScale) -> UiStyles {
+        match self {
+            Self::Dark   => UiStyles::default_theme(scale),
+            Self::Light  => UiStyles::light_theme(scale),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Default)]
 pub struct Definitions {
     pub ls: Option<String>,
     pub exa: Option<String>,
+    pub theme_file: Option<PathBuf>,
 }
 
 
 pub struct Theme {
     pub ui: UiStyles,
     pub exts: Box<dyn FileColours>,
+
+    /// Whether timestamps get faded depending on how old they are.
+    pub age_scale: bool,
+
+    /// The time to measure timestamps’ ages from, when they get coloured by
+    /// how long ago they were, rather than faded.
+    pub age_buckets: Option<SystemTime>,
 }
 
 impl Options {
 
+    /// Builds the theme to use, given whether the output should be treated
+    /// as a terminal, which should have been resolved using `Options::is_tty`.
     #[allow(trivial_casts)]   // the `as Box<_>` stuff below warns about this for some reason
     pub fn to_theme(&self, isatty: bool) -> Theme {
         use crate::info::filetype::FileExtensions;
@@ -71,13 +129,17 @@ impl Options {
         if self.use_colours == UseColours::Never || (self.use_colours == UseColours::Automatic && ! isatty) {
             let ui = UiStyles::plain();
             let exts = Box::new(NoFileColours);
-            return Theme { ui, exts };
+            return Theme { ui, exts, age_scale: false, age_buckets: None };
         }
 
         // Parse the environment variables into colours and extension mappings
-        let mut ui = UiStyles::default_theme(self.colour_scale);
+        let mut ui = self.preset.ui_styles(self.colour_scale);
         let (exts, use_default_filetypes) = self.definitions.parse_color_vars(&mut ui);
 
+        if self.dim_hidden {
+            ui.dim_hidden = true;
+        }
+
         // Use between 0 and 2 file name highlighters
         let exts = match (exts.is_non_empty(), use_default_filetypes) {
             (false, false)  => Box::new(NoFileColours)           as Box<_>,
@@ -86,7 +148,10 @@ impl Options {
             ( true,  true)  => Box::new((exts, FileExtensions))  as Box<_>,
         };
 
-        Theme { ui, exts }
+        let age_buckets = if self.age_colours { Some(SystemTime::now()) }
+                                         else { None };
+
+        Theme { ui, exts, age_scale: self.colour_scale.age, age_buckets }
     }
 }
 
@@ -96,6 +161,9 @@ impl Definitions {
     /// colours into the `ExtensionMappings` that gets returned, and using the
     /// two-character UI codes to modify the mutable `Colours`.
     ///
+    /// The theme file, if there is one, gets applied before either of the
+    /// variables, so they can still override the styles it sets.
+    ///
     /// Also returns if the `EXA_COLORS` variable should reset the existing file
     /// type mappings or not. The `reset` code needs to be the first one.
     fn parse_color_vars(&self, colours: &mut UiStyles) -> (ExtensionMappings, bool) {
@@ -103,6 +171,17 @@ impl Definitions {
 
         let mut exts = ExtensionMappings::default();
 
+        if let Some(path) = &self.theme_file {
+            match std::fs::read_to_string(path) {
+                Ok(contents) => {
+                    ThemeFile(&contents).apply(colours);
+                }
+                Err(e) => {
+                    warn!("Couldn't read theme file {:?}: {}", path, e);
+                }
+            }
+        }
+
         if let Some(lsc) = &self.ls {
             LSColors(lsc).each_pair(|pair| {
                 if ! colours.set_ls(&pair) {
@@ -175,16 +254,28 @@ where A: FileColours,
 #[derive(PartialEq, Debug, Default)]
 struct ExtensionMappings {
     mappings: Vec<(glob::Pattern, Style)>,
+
+    /// The positions of the patterns that are just `*.` followed by an
+    /// extension, keyed by that extension, which can be looked up without
+    /// running the pattern.
+    extensions: HashMap<String, usize>,
+
+    /// The positions of all the other patterns, which have to be run.
+    globs: Vec<usize>,
 }
 
 // Loop through backwards so that colours specified later in the list override
-// colours specified earlier, like we do with options and strict mode
+// colours specified earlier, like we do with options and strict mode.
+//
+// A pattern like `*.txt` matches exactly the names that end in `.txt`, so
+// rather than running every one of them against every file, the text after
+// each dot in the name gets looked up instead. Only the other patterns that
+// come after the latest extension that matched need to be run, so the same
+// pattern wins as if they had all been run in order.
 
 impl FileColours for ExtensionMappings {
     fn colour_file(&self, file: &File<'_>) -> Option<Style> {
-        self.mappings.iter().rev()
-            .find(|t| t.0.matches(&file.name))
-            .map (|t| t.1)
+        self.colour_name(&file.name)
     }
 }
 
@@ -194,8 +285,34 @@ impl ExtensionMappings {
     }
 
     fn add(&mut self, pattern: glob::Pattern, style: Style) {
-        self.mappings.push((pattern, style))
+        match literal_extension(pattern.as_str()) {
+            Some(ext)  => { self.extensions.insert(ext.to_string(), self.mappings.len()); }
+            None       => self.globs.push(self.mappings.len()),
+        }
+
+        self.mappings.push((pattern, style));
     }
+
+    fn colour_name(&self, name: &str) -> Option<Style> {
+        let extension = name.match_indices('.')
+            .filter_map(|(i, _)| self.extensions.get(&name[i + 1 ..]))
+            .max()
+            .copied();
+
+        self.globs.iter().rev()
+            .take_while(|&&i| extension.map_or(true, |e| i > e))
+            .find(|&&i| self.mappings[i].0.matches(name))
+            .copied()
+            .or(extension)
+            .map(|i| self.mappings[i].1)
+    }
+}
+
+/// The extension in a pattern of the form `*.ext`, if it has no other
+/// special characters in it.
+fn literal_extension(pattern: &str) -> Option<&str> {
+    pattern.strip_prefix("*.")
+           .filter(|ext| ! ext.contains(&['*', '?', '[', ']'][..]))
 }
 
 
@@ -227,6 +344,7 @@ impl render::GitColours for Theme {
     fn type_change(&self)   -> Style { self.ui.git.typechange }
     fn ignored(&self)       -> Style { self.ui.git.ignored }
     fn conflicted(&self)    -> Style { self.ui.git.conflicted }
+    fn unknown(&self)       -> Style { self.ui.punctuation }
 }
 
 #[cfg(unix)]
@@ -288,6 +406,27 @@ impl render::SizeColours for Theme {
     fn minor(&self)   -> Style { self.ui.size.minor }
 }
 
+impl render::TimeColours for Theme {
+    fn date(&self) -> Style { self.ui.date }
+    fn no_time(&self) -> Style { self.ui.punctuation }
+
+    fn date_of_age(&self, age: f32) -> Style {
+        if ! self.age_scale {
+            return self.ui.date;
+        }
+
+        let mut style = self.ui.date;
+        style.foreground = style.foreground.map(|c| fade(c, age));
+        style
+    }
+
+    fn date_of_time(&self, time: SystemTime) -> Option<Style> {
+        let now = self.age_buckets?;
+        let age = now.duration_since(time).unwrap_or(Duration::ZERO);
+        Some(self.ui.ages.style(age))
+    }
+}
+
 #[cfg(unix)]
 impl render::UserColours for Theme {
     fn you(&self)           -> Style { self.ui.users.user_you }
@@ -306,6 +445,29 @@ impl FileNameColours for Theme {
     fn colour_file(&self, file: &File<'_>) -> Style {
         self.exts.colour_file(file).unwrap_or(self.ui.filekinds.normal)
     }
+
+    fn hidden_file(&self, style: Style) -> Style {
+        if self.ui.dim_hidden { apply_overlay(style, Style::default().dimmed()) }
+                         else { style }
+    }
+
+    fn git_status(&self, style: Style, status: f::GitStatus) -> Style {
+        use render::GitColours;
+
+        let overlay = match status {
+            f::GitStatus::NotModified |
+            f::GitStatus::Unknown      => return style,
+            f::GitStatus::New          => self.new(),
+            f::GitStatus::Modified     => self.modified(),
+            f::GitStatus::Deleted      => self.deleted(),
+            f::GitStatus::Renamed      => self.renamed(),
+            f::GitStatus::TypeChange   => self.type_change(),
+            f::GitStatus::Ignored      => self.ignored(),
+            f::GitStatus::Conflicted   => self.conflicted(),
+        };
+
+        apply_overlay(style, overlay)
+    }
 }
 
 
@@ -339,6 +501,54 @@ fn apply_overlay(mut base: Style, overlay: Style) -> Style {
 // TODO: move this function to the ansi_term crate
 
 
+/// Fades a colour from its full brightness, for an age of `0.0`, down to
+/// [`OLDEST_BRIGHTNESS`] of it for an age of `1.0`, to show how old a
+/// timestamp is. This has to produce an RGB colour, as there’s no way to
+/// dim the terminal’s own colours by degrees.
+fn fade(colour: Colour, age: f32) -> Colour {
+    let (r, g, b) = rgb(colour);
+    let brightness = 1.0 - (1.0 - OLDEST_BRIGHTNESS) * age.clamp(0.0, 1.0);
+    let scale = |c: u8| (f32::from(c) * brightness).round() as u8;
+    Colour::RGB(scale(r), scale(g), scale(b))
+}
+
+/// How bright the oldest timestamp is, compared to the newest.
+const OLDEST_BRIGHTNESS: f32 = 0.4;
+
+/// Approximates the RGB value of a colour, using xterm’s defaults for the
+/// sixteen basic colours and the 256-colour palette.
+fn rgb(colour: Colour) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (  0,   0,   0), (205,   0,   0), (  0, 205,   0), (205, 205,   0),
+        (  0,   0, 238), (205,   0, 205), (  0, 205, 205), (229, 229, 229),
+        (127, 127, 127), (255,   0,   0), (  0, 255,   0), (255, 255,   0),
+        ( 92,  92, 255), (255,   0, 255), (  0, 255, 255), (255, 255, 255),
+    ];
+
+    match colour {
+        Colour::Black      => BASIC[0],
+        Colour::Red        => BASIC[1],
+        Colour::Green      => BASIC[2],
+        Colour::Yellow     => BASIC[3],
+        Colour::Blue       => BASIC[4],
+        Colour::Purple     => BASIC[5],
+        Colour::Cyan       => BASIC[6],
+        Colour::White      => BASIC[7],
+        Colour::RGB(r, g, b)  => (r, g, b),
+        Colour::Fixed(n) if n < 16  => BASIC[usize::from(n)],
+        Colour::Fixed(n) if n < 232 => {
+            let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+            let n = n - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        Colour::Fixed(n) => {
+            let grey = 8 + (n - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+
 #[cfg(test)]
 mod customs_test {
     use super::*;
@@ -355,6 +565,7 @@ mod customs_test {
                 let definitions = Definitions {
                     ls:  Some($ls.into()),
                     exa: Some($exa.into()),
+                    theme_file: None,
                 };
 
                 let mut result = UiStyles::default();
@@ -365,18 +576,19 @@ mod customs_test {
         ($name:ident:  ls $ls:expr, exa $exa:expr  =>  exts $mappings:expr) => {
             #[test]
             fn $name() {
-                let mappings: Vec<(glob::Pattern, Style)>
-                    = $mappings.iter()
-                               .map(|t| (glob::Pattern::new(t.0).unwrap(), t.1))
-                               .collect();
+                let mut mappings = ExtensionMappings::default();
+                for t in $mappings.iter() {
+                    mappings.add(glob::Pattern::new(t.0).unwrap(), t.1);
+                }
 
                 let definitions = Definitions {
                     ls:  Some($ls.into()),
                     exa: Some($exa.into()),
+                    theme_file: None,
                 };
 
                 let (result, _reset) = definitions.parse_color_vars(&mut UiStyles::default());
-                assert_eq!(ExtensionMappings { mappings }, result);
+                assert_eq!(mappings, result);
             }
         };
         ($name:ident:  ls $ls:expr, exa $exa:expr  =>  colours $expected:ident -> $process_expected:expr, exts $mappings:expr) => {
@@ -385,19 +597,20 @@ mod customs_test {
                 let mut $expected = UiStyles::colourful(false);
                 $process_expected();
 
-                let mappings: Vec<(glob::Pattern, Style)>
-                    = $mappings.into_iter()
-                               .map(|t| (glob::Pattern::new(t.0).unwrap(), t.1))
-                               .collect();
+                let mut mappings = ExtensionMappings::default();
+                for t in $mappings.into_iter() {
+                    mappings.add(glob::Pattern::new(t.0).unwrap(), t.1);
+                }
 
                 let definitions = Definitions {
                     ls:  Some($ls.into()),
                     exa: Some($exa.into()),
+                    theme_file: None,
                 };
 
                 let mut meh = UiStyles::colourful(false);
                 let (result, _reset) = definitions.parse_color_vars(&vars, &mut meh);
-                assert_eq!(ExtensionMappings { mappings }, result);
+                assert_eq!(mappings, result);
                 assert_eq!($expected, meh);
             }
         };
@@ -489,6 +702,7 @@ mod customs_test {
     test!(exa_gd:  ls "", exa "gd=38;5;125"  =>  colours c -> { c.git.deleted               = Fixed(125).normal(); });
     test!(exa_gv:  ls "", exa "gv=38;5;126"  =>  colours c -> { c.git.renamed               = Fixed(126).normal(); });
     test!(exa_gt:  ls "", exa "gt=38;5;127"  =>  colours c -> { c.git.typechange            = Fixed(127).normal(); });
+    test!(exa_gb:  ls "", exa "gb=38;5;127"  =>  colours c -> { c.git.branch                = Fixed(127).normal(); });
 
     test!(exa_xx:  ls "", exa "xx=38;5;128"  =>  colours c -> { c.punctuation               = Fixed(128).normal(); });
     test!(exa_da:  ls "", exa "da=38;5;129"  =>  colours c -> { c.date                      = Fixed(129).normal(); });
@@ -498,6 +712,27 @@ mod customs_test {
     test!(exa_lp:  ls "", exa "lp=38;5;133"  =>  colours c -> { c.symlink_path              = Fixed(133).normal(); });
     test!(exa_cc:  ls "", exa "cc=38;5;134"  =>  colours c -> { c.control_char              = Fixed(134).normal(); });
     test!(exa_bo:  ls "", exa "bO=4"         =>  colours c -> { c.broken_path_overlay       = Style::default().underline(); });
+    test!(exa_dc:  ls "", exa "dc=38;5;139"  =>  colours c -> { c.dir_count                 = Fixed(139).normal(); });
+    test!(exa_dh:  ls "", exa "dh=1"         =>  colours c -> { c.dim_hidden                = true; });
+    test!(exa_dh0: ls "", exa "dh=1:dh=0"    =>  colours c -> { c.dim_hidden                = false; });
+
+    test!(exa_ad:  ls "", exa "ad=38;5;135"  =>  colours c -> { c.ages.day                  = Fixed(135).normal(); });
+    test!(exa_aw:  ls "", exa "aw=38;5;136"  =>  colours c -> { c.ages.week                 = Fixed(136).normal(); });
+    test!(exa_am:  ls "", exa "am=38;5;137"  =>  colours c -> { c.ages.month                = Fixed(137).normal(); });
+    test!(exa_ao:  ls "", exa "ao=38;5;138"  =>  colours c -> { c.ages.older                = Fixed(138).normal(); });
+
+    // The limits between the periods take durations rather than colours:
+    test!(exa_ad_limit:  ls "", exa "Ad=12h"      =>  colours c -> { c.ages.day_limit   = Duration::from_secs(43200);   });
+    test!(exa_aw_limit:  ls "", exa "Aw=2w"       =>  colours c -> { c.ages.week_limit  = Duration::from_secs(60 * 60 * 24 * 14); });
+    test!(exa_am_limit:  ls "", exa "Am=60d"       =>  colours c -> { c.ages.month_limit = Duration::from_secs(60 * 60 * 24 * 60); });
+    test!(exa_bad_limit: ls "", exa "Ad=soon"     =>  colours c -> { let _ = &mut c; });
+
+    // Both variables accept 24-bit colours, mixed in with other attributes:
+    test!(exa_rgb_fg: ls "", exa "da=38;2;255;100;0"      =>  colours c -> { c.date = RGB(255, 100, 0).normal(); });
+    test!(exa_rgb_bg: ls "", exa "in=48;2;10;20;30"       =>  colours c -> { c.inode = Style::default().on(RGB(10, 20, 30)); });
+    test!(exa_rgb_bu: ls "", exa "hd=1;38;2;1;2;3;4"      =>  colours c -> { c.header = RGB(1, 2, 3).bold().underline(); });
+    test!(ls_rgb_all: ls "di=4;48;2;0;0;0;38;2;9;9;9;1", exa ""  =>  colours c -> { c.filekinds.directory = RGB(9, 9, 9).on(RGB(0, 0, 0)).bold().underline(); });
+    test!(ls_rgb_ext: ls "*.rs=48;2;1;2;3;1", exa ""  =>  exts [ ("*.rs", Style::default().on(RGB(1, 2, 3)).bold()) ]);
 
     // All the while, LS_COLORS treats them as filenames:
     test!(ls_uu:   ls "uu=38;5;117", exa ""  =>  exts [ ("uu", Fixed(117).normal()) ]);
@@ -528,3 +763,280 @@ mod customs_test {
     test!(ls_overwrite:  ls "pi=31:pi=32:pi=33", exa ""  =>  colours c -> { c.filekinds.pipe = Yellow.normal(); });
     test!(exa_overwrite: ls "", exa "da=36:da=35:da=34"  =>  colours c -> { c.date = Blue.normal(); });
 }
+
+
+#[cfg(test)]
+mod extension_mappings_test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    fn mappings(patterns: &[(&str, Style)]) -> ExtensionMappings {
+        let mut mappings = ExtensionMappings::default();
+        for (pattern, style) in patterns {
+            mappings.add(glob::Pattern::new(pattern).unwrap(), *style);
+        }
+        mappings
+    }
+
+    /// Runs every pattern against the name, last first, the way they all
+    /// got run before extensions could be looked up.
+    fn run_every_glob(mappings: &ExtensionMappings, name: &str) -> Option<Style> {
+        mappings.mappings.iter().rev()
+            .find(|t| t.0.matches(name))
+            .map(|t| t.1)
+    }
+
+    #[test]
+    fn literal_extensions() {
+        assert_eq!(literal_extension("*.txt"), Some("txt"));
+        assert_eq!(literal_extension("*.tar.gz"), Some("tar.gz"));
+        assert_eq!(literal_extension("*."), Some(""));
+        assert_eq!(literal_extension("*.t?t"), None);
+        assert_eq!(literal_extension("*.[ch]"), None);
+        assert_eq!(literal_extension("*.*"), None);
+        assert_eq!(literal_extension("Makefile"), None);
+        assert_eq!(literal_extension("README.*"), None);
+    }
+
+    #[test]
+    fn extension() {
+        let exts = mappings(&[ ("*.txt", Red.normal()) ]);
+        assert_eq!(exts.colour_name("notes.txt"), Some(Red.normal()));
+        assert_eq!(exts.colour_name("notes.txt.bak"), None);
+        assert_eq!(exts.colour_name("notes.TXT"), None);
+        assert_eq!(exts.colour_name("txt"), None);
+    }
+
+    #[test]
+    fn extension_with_dots() {
+        let exts = mappings(&[ ("*.tar.gz", Red.normal()), ("*.gz", Green.normal()) ]);
+        assert_eq!(exts.colour_name("source.tar.gz"), Some(Green.normal()));
+        assert_eq!(exts.colour_name("source.gz"), Some(Green.normal()));
+
+        let exts = mappings(&[ ("*.gz", Green.normal()), ("*.tar.gz", Red.normal()) ]);
+        assert_eq!(exts.colour_name("source.tar.gz"), Some(Red.normal()));
+    }
+
+    #[test]
+    fn later_glob_wins() {
+        let exts = mappings(&[ ("*.txt", Red.normal()), ("notes*", Green.normal()) ]);
+        assert_eq!(exts.colour_name("notes.txt"), Some(Green.normal()));
+        assert_eq!(exts.colour_name("other.txt"), Some(Red.normal()));
+    }
+
+    #[test]
+    fn later_extension_wins() {
+        let exts = mappings(&[ ("notes*", Green.normal()), ("*.txt", Red.normal()) ]);
+        assert_eq!(exts.colour_name("notes.txt"), Some(Red.normal()));
+        assert_eq!(exts.colour_name("notes.md"), Some(Green.normal()));
+    }
+
+    #[test]
+    fn same_as_every_glob() {
+        let exts = mappings(&[
+            ("*.txt", Red.normal()), ("*.t?t", Green.normal()), ("*.tar.gz", Blue.normal()),
+            ("Makefile*", Yellow.normal()), ("*.gz", Purple.normal()), ("*.", Cyan.normal()),
+            ("*.[ch]", White.normal()), ("*.txt", Fixed(1).normal()), ("*.in", Fixed(2).normal()),
+        ]);
+
+        let names = [
+            "notes.txt", "notes.tst", "a.tar.gz", "b.gz", "Makefile", "Makefile.in",
+            "trailing.", "main.c", "main.h", "main.rs", "README", ".txt", "..", "x.TXT",
+        ];
+
+        for name in &names {
+            assert_eq!((name, exts.colour_name(name)), (name, run_every_glob(&exts, name)));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod hidden_test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    fn theme(dim_hidden: bool) -> Theme {
+        let ui = UiStyles { dim_hidden, ..UiStyles::default_theme(ColourScale::default()) };
+        Theme { ui, exts: Box::new(NoFileColours), age_scale: false, age_buckets: None }
+    }
+
+    #[test]
+    fn dims_when_enabled() {
+        let style = theme(true).hidden_file(Yellow.normal());
+        assert!(style.is_dimmed);
+        assert_eq!(style.foreground, Some(Yellow));
+    }
+
+    #[test]
+    fn keeps_other_attributes() {
+        assert_eq!(theme(true).hidden_file(Blue.bold().underline()), Blue.bold().underline().dimmed());
+    }
+
+    #[test]
+    fn unchanged_when_disabled() {
+        assert_eq!(theme(false).hidden_file(Fixed(208).normal()), Fixed(208).normal());
+    }
+}
+
+
+#[cfg(test)]
+mod git_status_test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    fn theme() -> Theme {
+        let ui = UiStyles::default_theme(ColourScale::default());
+        Theme { ui, exts: Box::new(NoFileColours), age_scale: false, age_buckets: None }
+    }
+
+    #[test]
+    fn overrides_extension_colour() {
+        // The modified colour replaces the colour from the file’s extension
+        assert_eq!(theme().git_status(Purple.normal(), f::GitStatus::Modified), Blue.normal());
+    }
+
+    #[test]
+    fn keeps_attributes() {
+        assert_eq!(theme().git_status(Fixed(208).bold().underline(), f::GitStatus::New), Green.bold().underline());
+    }
+
+    #[test]
+    fn unmodified_is_unchanged() {
+        assert_eq!(theme().git_status(Yellow.bold(), f::GitStatus::NotModified), Yellow.bold());
+    }
+
+    #[test]
+    fn ignored_dims() {
+        assert_eq!(theme().git_status(Cyan.normal(), f::GitStatus::Ignored), Cyan.dimmed());
+    }
+}
+
+
+#[cfg(test)]
+mod age_buckets_test {
+    use super::*;
+    use ansi_term::Colour::*;
+    use render::TimeColours;
+
+    fn theme(age_buckets: Option<SystemTime>) -> Theme {
+        let ui = UiStyles::default_theme(ColourScale::default());
+        Theme { ui, exts: Box::new(NoFileColours), age_scale: true, age_buckets }
+    }
+
+    /// Some time well after the epoch to measure ages from.
+    const NOW: u64 = 1_000_000_000;
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(NOW)
+    }
+
+    fn hours_ago(hours: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(NOW - hours * 60 * 60)
+    }
+
+    #[test]
+    fn off_by_default() {
+        assert_eq!(theme(None).date_of_time(now()), None);
+    }
+
+    #[test]
+    fn buckets() {
+        assert_eq!(theme(Some(now())).date_of_time(hours_ago(23)),       Some(Green.bold()));
+        assert_eq!(theme(Some(now())).date_of_time(hours_ago(24)),       Some(Green.normal()));
+        assert_eq!(theme(Some(now())).date_of_time(hours_ago(24 * 7)),   Some(Blue.normal()));
+        assert_eq!(theme(Some(now())).date_of_time(hours_ago(24 * 30)),  Some(Fixed(244).normal()));
+    }
+
+    #[test]
+    fn future_is_recent() {
+        let future = now() + Duration::from_secs(60);
+        assert_eq!(theme(Some(now())).date_of_time(future), Some(Green.bold()));
+    }
+}
+
+
+#[cfg(test)]
+mod colour_scale_test {
+    use super::*;
+
+    fn theme(size: bool, age: bool) -> Theme {
+        let options = Options {
+            use_colours:  UseColours::Always,
+            colour_scale: ColourScale { size, age },
+            preset:       ThemePreset::Dark,
+            definitions:  Definitions::default(),
+            dim_hidden:   false,
+            age_colours:  false,
+        };
+
+        options.to_theme(true)
+    }
+
+    fn gradient_sizes() -> SizeColours {
+        UiStyles::default_theme(ColourScale { size: true, age: false }).size
+    }
+
+    fn fixed_sizes() -> SizeColours {
+        UiStyles::default_theme(ColourScale::default()).size
+    }
+
+    #[test]
+    fn neither() {
+        let theme = theme(false, false);
+        assert_eq!(theme.ui.size, fixed_sizes());
+        assert!(! theme.age_scale);
+    }
+
+    #[test]
+    fn size_only() {
+        let theme = theme(true, false);
+        assert_eq!(theme.ui.size, gradient_sizes());
+        assert!(! theme.age_scale);
+    }
+
+    #[test]
+    fn age_only() {
+        let theme = theme(false, true);
+        assert_eq!(theme.ui.size, fixed_sizes());
+        assert!(theme.age_scale);
+    }
+
+    #[test]
+    fn size_and_age() {
+        let theme = theme(true, true);
+        assert_eq!(theme.ui.size, gradient_sizes());
+        assert!(theme.age_scale);
+    }
+}
+
+
+#[cfg(test)]
+mod fade_test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    #[test]
+    fn newest_is_unchanged() {
+        assert_eq!(fade(RGB(200, 100, 50), 0.0), RGB(200, 100, 50));
+    }
+
+    #[test]
+    fn oldest_is_dim() {
+        assert_eq!(fade(RGB(200, 100, 50), 1.0), RGB(80, 40, 20));
+    }
+
+    #[test]
+    fn basic_colours() {
+        assert_eq!(fade(Blue, 0.0), RGB(0, 0, 238));
+        assert_eq!(fade(Fixed(12), 0.0), RGB(92, 92, 255));
+    }
+
+    #[test]
+    fn palette_colours() {
+        assert_eq!(rgb(Fixed(16)), (0, 0, 0));
+        assert_eq!(rgb(Fixed(33)), (0, 135, 255));
+        assert_eq!(rgb(Fixed(231)), (255, 255, 255));
+        assert_eq!(rgb(Fixed(244)), (128, 128, 128));
+    }
+}
@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use ansi_term::Style;
+use log::*;
 
+use crate::output::time::{parse_duration, DAY};
 use crate::theme::lsc::Pair;
 
 
@@ -13,6 +17,7 @@ pub struct UiStyles {
     pub users:      Users,
     pub links:      Links,
     pub git:        Git,
+    pub ages:       Ages,
 
     pub punctuation:  Style,
     pub date:         Style,
@@ -20,11 +25,18 @@ pub struct UiStyles {
     pub blocks:       Style,
     pub header:       Style,
     pub octal:        Style,
+    pub filesystem:   Style,
+    pub mime_type:    Style,
+    pub dir_count:    Style,
 
     pub symlink_path:         Style,
     pub control_char:         Style,
     pub broken_symlink:       Style,
     pub broken_path_overlay:  Style,
+
+    /// Whether the names of hidden files get dimmed, on top of whichever
+    /// colour they would otherwise be.
+    pub dim_hidden: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -102,6 +114,56 @@ pub struct Git {
     pub typechange: Style,
     pub ignored: Style,
     pub conflicted: Style,
+    pub branch: Style,
+}
+
+/// The styles for timestamps when they get coloured by how long ago they
+/// were, with `--age-colours`, along with the limits of each period.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ages {
+    pub day: Style,
+    pub week: Style,
+    pub month: Style,
+    pub older: Style,
+
+    pub day_limit: Duration,
+    pub week_limit: Duration,
+    pub month_limit: Duration,
+}
+
+impl Default for Ages {
+    fn default() -> Self {
+        Self {
+            day:    Style::default(),
+            week:   Style::default(),
+            month:  Style::default(),
+            older:  Style::default(),
+
+            day_limit:    Duration::from_secs(DAY),
+            week_limit:   Duration::from_secs(DAY * 7),
+            month_limit:  Duration::from_secs(DAY * 30),
+        }
+    }
+}
+
+impl Ages {
+
+    /// The style for a timestamp from the given amount of time ago. Each
+    /// limit is the first age that falls into the next period.
+    pub fn style(&self, age: Duration) -> Style {
+        if age < self.day_limit {
+            self.day
+        }
+        else if age < self.week_limit {
+            self.week
+        }
+        else if age < self.month_limit {
+            self.month
+        }
+        else {
+            self.older
+        }
+    }
 }
 
 impl UiStyles {
@@ -183,6 +245,7 @@ impl UiStyles {
             "gd" => self.git.deleted              = pair.to_style(),
             "gv" => self.git.renamed              = pair.to_style(),
             "gt" => self.git.typechange           = pair.to_style(),
+            "gb" => self.git.branch               = pair.to_style(),
 
             "xx" => self.punctuation              = pair.to_style(),
             "da" => self.date                     = pair.to_style(),
@@ -192,6 +255,16 @@ impl UiStyles {
             "lp" => self.symlink_path             = pair.to_style(),
             "cc" => self.control_char             = pair.to_style(),
             "bO" => self.broken_path_overlay      = pair.to_style(),
+            "dc" => self.dir_count                = pair.to_style(),
+            "dh" => self.dim_hidden               = pair.value != "0",
+
+            "ad" => self.ages.day                 = pair.to_style(),
+            "aw" => self.ages.week                = pair.to_style(),
+            "am" => self.ages.month               = pair.to_style(),
+            "ao" => self.ages.older               = pair.to_style(),
+            "Ad" => set_limit(&mut self.ages.day_limit,   pair.value),
+            "Aw" => set_limit(&mut self.ages.week_limit,  pair.value),
+            "Am" => set_limit(&mut self.ages.month_limit, pair.value),
 
              _   => return false,
         }
@@ -199,6 +272,90 @@ impl UiStyles {
         true
     }
 
+    /// Sets a value on this set of colours using the name of its field, such
+    /// as `filekinds.directory` or `date`, which is how theme files refer to
+    /// them. Invalid names set nothing, but return false.
+    pub fn set_named(&mut self, name: &str, style: Style) -> bool {
+        match name {
+            "filekinds.normal"        => self.filekinds.normal          = style,
+            "filekinds.directory"     => self.filekinds.directory       = style,
+            "filekinds.symlink"       => self.filekinds.symlink         = style,
+            "filekinds.pipe"          => self.filekinds.pipe            = style,
+            "filekinds.block_device"  => self.filekinds.block_device    = style,
+            "filekinds.char_device"   => self.filekinds.char_device     = style,
+            "filekinds.socket"        => self.filekinds.socket          = style,
+            "filekinds.special"       => self.filekinds.special         = style,
+            "filekinds.executable"    => self.filekinds.executable      = style,
+
+            "perms.user_read"           => self.perms.user_read          = style,
+            "perms.user_write"          => self.perms.user_write         = style,
+            "perms.user_execute_file"   => self.perms.user_execute_file  = style,
+            "perms.user_execute_other"  => self.perms.user_execute_other = style,
+            "perms.group_read"          => self.perms.group_read         = style,
+            "perms.group_write"         => self.perms.group_write        = style,
+            "perms.group_execute"       => self.perms.group_execute      = style,
+            "perms.other_read"          => self.perms.other_read         = style,
+            "perms.other_write"         => self.perms.other_write        = style,
+            "perms.other_execute"       => self.perms.other_execute      = style,
+            "perms.special_user_file"   => self.perms.special_user_file  = style,
+            "perms.special_other"       => self.perms.special_other      = style,
+            "perms.attribute"           => self.perms.attribute          = style,
+
+            "size.major"        => self.size.major        = style,
+            "size.minor"        => self.size.minor        = style,
+            "size.number_byte"  => self.size.number_byte  = style,
+            "size.number_kilo"  => self.size.number_kilo  = style,
+            "size.number_mega"  => self.size.number_mega  = style,
+            "size.number_giga"  => self.size.number_giga  = style,
+            "size.number_huge"  => self.size.number_huge  = style,
+            "size.unit_byte"    => self.size.unit_byte    = style,
+            "size.unit_kilo"    => self.size.unit_kilo    = style,
+            "size.unit_mega"    => self.size.unit_mega    = style,
+            "size.unit_giga"    => self.size.unit_giga    = style,
+            "size.unit_huge"    => self.size.unit_huge    = style,
+
+            "users.user_you"           => self.users.user_you          = style,
+            "users.user_someone_else"  => self.users.user_someone_else = style,
+            "users.group_yours"        => self.users.group_yours       = style,
+            "users.group_not_yours"    => self.users.group_not_yours   = style,
+
+            "links.normal"           => self.links.normal          = style,
+            "links.multi_link_file"  => self.links.multi_link_file = style,
+
+            "git.new"         => self.git.new        = style,
+            "git.modified"    => self.git.modified   = style,
+            "git.deleted"     => self.git.deleted    = style,
+            "git.renamed"     => self.git.renamed    = style,
+            "git.typechange"  => self.git.typechange = style,
+            "git.ignored"     => self.git.ignored    = style,
+            "git.conflicted"  => self.git.conflicted = style,
+            "git.branch"      => self.git.branch     = style,
+
+            "ages.day"    => self.ages.day   = style,
+            "ages.week"   => self.ages.week  = style,
+            "ages.month"  => self.ages.month = style,
+            "ages.older"  => self.ages.older = style,
+
+            "punctuation"          => self.punctuation         = style,
+            "date"                 => self.date                = style,
+            "inode"                => self.inode               = style,
+            "blocks"               => self.blocks              = style,
+            "header"               => self.header              = style,
+            "octal"                => self.octal               = style,
+            "filesystem"           => self.filesystem          = style,
+            "mime_type"            => self.mime_type           = style,
+            "dir_count"            => self.dir_count           = style,
+            "symlink_path"         => self.symlink_path        = style,
+            "control_char"         => self.control_char        = style,
+            "broken_symlink"       => self.broken_symlink      = style,
+            "broken_path_overlay"  => self.broken_path_overlay = style,
+
+             _  => return false,
+        }
+
+        true
+    }
+
     pub fn set_number_style(&mut self, style: Style) {
         self.size.number_byte = style;
         self.size.number_kilo = style;
@@ -215,3 +372,72 @@ impl UiStyles {
         self.size.unit_huge = style;
     }
 }
+
+
+/// Sets one of the limits between periods of time from an `EXA_COLORS`
+/// value, leaving it alone if the value isn’t a valid duration.
+fn set_limit(limit: &mut Duration, value: &str) {
+    match parse_duration(value) {
+        Some(duration) => *limit = duration,
+        None           => warn!("Couldn't parse duration {:?}", value),
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ansi_term::Colour::*;
+
+    fn ages() -> Ages {
+        Ages {
+            day:    Green.normal(),
+            week:   Yellow.normal(),
+            month:  Blue.normal(),
+            older:  Red.normal(),
+            .. Ages::default()
+        }
+    }
+
+    fn days(n: u64) -> Duration {
+        Duration::from_secs(DAY * n)
+    }
+
+    fn just_under(days: u64) -> Duration {
+        Duration::from_secs(DAY * days - 1)
+    }
+
+    #[test]
+    fn just_now() {
+        assert_eq!(ages().style(Duration::ZERO), Green.normal());
+    }
+
+    #[test]
+    fn day_boundary() {
+        assert_eq!(ages().style(just_under(1)), Green.normal());
+        assert_eq!(ages().style(days(1)), Yellow.normal());
+    }
+
+    #[test]
+    fn week_boundary() {
+        assert_eq!(ages().style(just_under(7)), Yellow.normal());
+        assert_eq!(ages().style(days(7)), Blue.normal());
+    }
+
+    #[test]
+    fn month_boundary() {
+        assert_eq!(ages().style(just_under(30)), Blue.normal());
+        assert_eq!(ages().style(days(30)), Red.normal());
+        assert_eq!(ages().style(days(3650)), Red.normal());
+    }
+
+    #[test]
+    fn changed_limits() {
+        let ages = Ages { day_limit: Duration::from_secs(3600), week_limit: days(1), ..ages() };
+        assert_eq!(ages.style(Duration::from_secs(3599)), Green.normal());
+        assert_eq!(ages.style(Duration::from_secs(3600)), Yellow.normal());
+        assert_eq!(ages.style(days(1)), Blue.normal());
+    }
+
+}